@@ -0,0 +1,7 @@
+-- Site URL Configuration Migration
+-- Version: 009_site_url
+-- Description: Add the public site URL used to build absolute links in RSS/Atom/JSON feeds
+
+INSERT INTO site_config (config_key, config_value, config_type, description)
+VALUES ('site_url', '', 'string', '站点访问地址(用于生成订阅源中的绝对链接)')
+ON CONFLICT (config_key) DO NOTHING;
@@ -0,0 +1,9 @@
+-- Sitemap and robots.txt Configuration Migration
+-- Version: 010_sitemap_robots
+-- Description: Add settings used to generate the XML sitemap and robots.txt
+
+INSERT INTO site_config (config_key, config_value, config_type, description) VALUES
+('sitemap_base_url', '', 'string', '站点地图对外访问前缀(为空时使用站点访问地址)'),
+('robots_disallow', '/admin', 'string', 'robots.txt 禁止抓取的路径(每行一个)'),
+('robots_extra', '', 'string', 'robots.txt 追加的自定义规则')
+ON CONFLICT (config_key) DO NOTHING;
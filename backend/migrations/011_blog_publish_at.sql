@@ -0,0 +1,11 @@
+-- Blog Scheduled Publishing Migration
+-- Version: 011_blog_publish_at
+-- Description: Add publish_at to blogs so drafts can be queued for automatic publishing
+
+ALTER TABLE blogs ADD COLUMN IF NOT EXISTS publish_at TIMESTAMPTZ DEFAULT NULL;
+
+-- Partial index for the scheduler, which only looks at queued drafts
+CREATE INDEX IF NOT EXISTS idx_blogs_publish_at ON blogs(publish_at)
+    WHERE is_published = false AND publish_at IS NOT NULL;
+
+COMMENT ON COLUMN blogs.publish_at IS '定时发布时间 - 到期后由后台任务自动发布';
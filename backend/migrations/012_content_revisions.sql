@@ -0,0 +1,37 @@
+-- Content Revisions Migration
+-- Version: 012_content_revisions
+-- Description: Keep a snapshot of blogs and documents on every update so edits can be diffed and restored
+
+CREATE TABLE IF NOT EXISTS blog_revisions (
+    id BIGSERIAL PRIMARY KEY,
+    blog_id BIGINT NOT NULL REFERENCES blogs(id) ON DELETE CASCADE,
+    title VARCHAR(255) NOT NULL,
+    content TEXT NOT NULL,
+    summary TEXT,
+    "references" JSONB,
+    source VARCHAR(20) NOT NULL,
+    actor_id BIGINT,
+    actor_name VARCHAR(255),
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_blog_revisions_blog_id ON blog_revisions(blog_id, id DESC);
+
+CREATE TABLE IF NOT EXISTS document_revisions (
+    id BIGSERIAL PRIMARY KEY,
+    document_id BIGINT NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    content TEXT NOT NULL,
+    "references" JSONB,
+    source VARCHAR(20) NOT NULL,
+    actor_id BIGINT,
+    actor_name VARCHAR(255),
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_revisions_document_id ON document_revisions(document_id, id DESC);
+
+COMMENT ON TABLE blog_revisions IS '博客修订历史 - 每次更新后的内容快照';
+COMMENT ON TABLE document_revisions IS '文档修订历史 - 每次更新后的内容快照';
+COMMENT ON COLUMN blog_revisions.source IS '修改来源: baseline/admin/mcp/ai_batch/restore';
+COMMENT ON COLUMN document_revisions.source IS '修改来源: baseline/admin/mcp/ai_batch/restore';
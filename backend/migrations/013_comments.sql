@@ -0,0 +1,27 @@
+-- Comments Migration
+-- Version: 013_comments
+-- Description: Threaded reader comments on blogs and documents with a moderation queue
+
+CREATE TABLE IF NOT EXISTS comments (
+    id BIGSERIAL PRIMARY KEY,
+    blog_id BIGINT REFERENCES blogs(id) ON DELETE CASCADE,
+    document_id BIGINT REFERENCES documents(id) ON DELETE CASCADE,
+    parent_id BIGINT REFERENCES comments(id) ON DELETE CASCADE,
+    author_name VARCHAR(100) NOT NULL,
+    author_email VARCHAR(255),
+    author_url VARCHAR(500),
+    content TEXT NOT NULL,
+    status SMALLINT DEFAULT 0, -- 0: 待审核, 1: 已通过, 2: 垃圾评论
+    ip_address VARCHAR(64),
+    user_agent VARCHAR(500),
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    CONSTRAINT comments_single_target CHECK ((blog_id IS NULL) <> (document_id IS NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_comments_blog_id ON comments(blog_id, status);
+CREATE INDEX IF NOT EXISTS idx_comments_document_id ON comments(document_id, status);
+CREATE INDEX IF NOT EXISTS idx_comments_status ON comments(status, created_at DESC);
+
+COMMENT ON TABLE comments IS '评论表 - 博客与文档的读者评论，支持嵌套回复';
+COMMENT ON COLUMN comments.status IS '0: 待审核, 1: 已通过, 2: 垃圾评论';
+COMMENT ON COLUMN comments.parent_id IS '父评论ID，为空表示顶层评论';
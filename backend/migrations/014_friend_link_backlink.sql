@@ -0,0 +1,11 @@
+-- Friend Link Backlink Check Migration
+-- Version: 014_friend_link_backlink
+-- Description: Record whether an applicant's site links back to us, for evidence during moderation
+
+ALTER TABLE friend_links ADD COLUMN IF NOT EXISTS backlink_found BOOLEAN DEFAULT NULL;
+ALTER TABLE friend_links ADD COLUMN IF NOT EXISTS backlink_error VARCHAR(500) DEFAULT NULL;
+ALTER TABLE friend_links ADD COLUMN IF NOT EXISTS backlink_checked_at TIMESTAMPTZ DEFAULT NULL;
+
+COMMENT ON COLUMN friend_links.backlink_found IS '对方页面是否包含指向本站的链接，NULL 表示尚未检查';
+COMMENT ON COLUMN friend_links.backlink_error IS '最近一次回链检查失败的原因';
+COMMENT ON COLUMN friend_links.backlink_checked_at IS '最近一次回链检查时间';
@@ -0,0 +1,26 @@
+-- Link Checks Migration
+-- Version: 015_link_checks
+-- Description: Results of the periodic dead-link check over approved friend links and blog outbound links
+
+CREATE TABLE IF NOT EXISTS link_checks (
+    id BIGSERIAL PRIMARY KEY,
+    source_type VARCHAR(20) NOT NULL, -- friend_link / blog
+    source_id BIGINT NOT NULL,
+    url VARCHAR(2000) NOT NULL,
+    http_status INTEGER,
+    redirect_url VARCHAR(2000),
+    error VARCHAR(500),
+    consecutive_failures INTEGER NOT NULL DEFAULT 0,
+    last_checked_at TIMESTAMPTZ,
+    last_ok_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    CONSTRAINT link_checks_source_url_unique UNIQUE (source_type, source_id, url)
+);
+
+CREATE INDEX IF NOT EXISTS idx_link_checks_failures ON link_checks(consecutive_failures DESC)
+    WHERE consecutive_failures > 0;
+
+COMMENT ON TABLE link_checks IS '死链检查结果 - 友链与博客外链';
+COMMENT ON COLUMN link_checks.source_type IS '链接来源: friend_link / blog';
+COMMENT ON COLUMN link_checks.redirect_url IS '跟随重定向后的最终地址（与原地址相同时为空）';
+COMMENT ON COLUMN link_checks.consecutive_failures IS '连续检查失败次数，成功后清零';
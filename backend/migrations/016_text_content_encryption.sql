@@ -0,0 +1,9 @@
+-- Text Content Encryption Migration
+-- Version: 016_text_content_encryption
+-- Description: Store the content of password-protected texts encrypted at rest
+
+ALTER TABLE texts ADD COLUMN IF NOT EXISTS content_kdf VARCHAR(255) DEFAULT NULL;
+ALTER TABLE texts ADD COLUMN IF NOT EXISTS content_nonce VARCHAR(64) DEFAULT NULL;
+
+COMMENT ON COLUMN texts.content_kdf IS '内容密钥派生参数（HKDF-SHA256 与随机盐，如 $hkdf-sha256$<salt>），NULL 表示 content 为明文';
+COMMENT ON COLUMN texts.content_nonce IS 'XChaCha20-Poly1305 随机数（base64），content 为 base64 密文';
@@ -0,0 +1,29 @@
+-- User Roles Migration
+-- Version: 017_user_roles
+-- Description: Admin / editor / author roles for users, and blogs linked to the user who wrote them
+
+-- Accounts that exist before roles had full access, so they become admins
+ALTER TABLE users ADD COLUMN IF NOT EXISTS role VARCHAR(20) NOT NULL DEFAULT 'admin';
+ALTER TABLE users ALTER COLUMN role SET DEFAULT 'author';
+DO $$
+BEGIN
+    IF NOT EXISTS (SELECT 1 FROM pg_constraint WHERE conname = 'users_role_check') THEN
+        ALTER TABLE users ADD CONSTRAINT users_role_check CHECK (role IN ('admin', 'editor', 'author'));
+    END IF;
+END
+$$;
+
+ALTER TABLE blogs ADD COLUMN IF NOT EXISTS author_id BIGINT REFERENCES users(id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS idx_blogs_author_id ON blogs(author_id);
+
+-- Link existing blogs to the user whose username or nickname matches the byline,
+-- falling back to the first account (the former single admin)
+UPDATE blogs b SET author_id = u.id
+FROM users u
+WHERE b.author_id IS NULL AND b.author IS NOT NULL AND (u.username = b.author OR u.nickname = b.author);
+
+UPDATE blogs SET author_id = (SELECT id FROM users ORDER BY id LIMIT 1)
+WHERE author_id IS NULL;
+
+COMMENT ON COLUMN users.role IS '用户角色: admin 管理员 / editor 编辑 / author 作者';
+COMMENT ON COLUMN blogs.author_id IS '撰写该博客的用户，author 字段保留为显示署名';
@@ -0,0 +1,58 @@
+-- MCP Tokens Migration
+-- Version: 018_mcp_tokens
+-- Description: Multiple named MCP tokens with scopes and expiry, plus an audit log of tool calls
+
+CREATE TABLE IF NOT EXISTS mcp_tokens (
+    id BIGSERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL UNIQUE,
+    token_prefix VARCHAR(32),
+    token_hash VARCHAR(255) NOT NULL,
+    token_last_four VARCHAR(4) NOT NULL,
+    scopes TEXT[] NOT NULL DEFAULT '{}', -- read / write / destructive
+    expires_at TIMESTAMPTZ,
+    last_used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_mcp_tokens_prefix ON mcp_tokens(token_prefix);
+
+-- Carry the single token from site_config over as the "default" token with every scope
+INSERT INTO mcp_tokens (name, token_hash, token_last_four, scopes, created_at)
+SELECT
+    'default',
+    hash.config_value,
+    COALESCE(NULLIF(last_four.config_value, ''), '????'),
+    ARRAY['read', 'write', 'destructive'],
+    COALESCE(NULLIF(rotated_at.config_value, '')::TIMESTAMPTZ, NOW())
+FROM site_config hash
+LEFT JOIN site_config last_four ON last_four.config_key = 'mcp_token_last_four'
+LEFT JOIN site_config rotated_at ON rotated_at.config_key = 'mcp_token_rotated_at'
+WHERE hash.config_key = 'mcp_token_hash' AND hash.config_value <> ''
+ON CONFLICT (name) DO NOTHING;
+
+DELETE FROM site_config
+WHERE config_key IN ('mcp_token_hash', 'mcp_token_last_four', 'mcp_token_rotated_at');
+
+CREATE TABLE IF NOT EXISTS mcp_audit_logs (
+    id BIGSERIAL PRIMARY KEY,
+    token_id BIGINT REFERENCES mcp_tokens(id) ON DELETE SET NULL,
+    token_name VARCHAR(100),
+    tool VARCHAR(100) NOT NULL,
+    arguments JSONB,
+    status VARCHAR(20) NOT NULL, -- ok / error / denied
+    error VARCHAR(1000),
+    duration_ms BIGINT,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_mcp_audit_logs_created_at ON mcp_audit_logs(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_mcp_audit_logs_token_id ON mcp_audit_logs(token_id);
+
+COMMENT ON TABLE mcp_tokens IS 'MCP访问令牌';
+COMMENT ON COLUMN mcp_tokens.token_prefix IS '令牌明文前缀，用于查找候选令牌（旧令牌为空）';
+COMMENT ON COLUMN mcp_tokens.scopes IS '权限范围: read 只读 / write 内容写入 / destructive 删除';
+COMMENT ON COLUMN mcp_tokens.expires_at IS '过期时间，为空表示永不过期';
+COMMENT ON TABLE mcp_audit_logs IS 'MCP工具调用审计日志';
+COMMENT ON COLUMN mcp_audit_logs.token_name IS '调用时的令牌名称（令牌删除后保留）';
+COMMENT ON COLUMN mcp_audit_logs.arguments IS '调用参数（已脱敏，长字符串已截断）';
+COMMENT ON COLUMN mcp_audit_logs.status IS '调用结果: ok / error / denied';
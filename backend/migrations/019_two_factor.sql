@@ -0,0 +1,24 @@
+-- Two-Factor Authentication Migration
+-- Version: 019_two_factor
+-- Description: Optional TOTP second factor for user login with one-time recovery codes
+
+ALTER TABLE users ADD COLUMN IF NOT EXISTS totp_secret VARCHAR(64);
+ALTER TABLE users ADD COLUMN IF NOT EXISTS totp_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS totp_last_step BIGINT;
+
+CREATE TABLE IF NOT EXISTS user_recovery_codes (
+    id BIGSERIAL PRIMARY KEY,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    code_hash VARCHAR(255) NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_recovery_codes_user_id ON user_recovery_codes(user_id);
+
+COMMENT ON COLUMN users.totp_secret IS 'TOTP密钥（Base32），确认绑定前为待定状态';
+COMMENT ON COLUMN users.totp_enabled IS '是否已启用两步验证';
+COMMENT ON COLUMN users.totp_last_step IS '最近一次使用的TOTP时间步，防止验证码重放';
+COMMENT ON TABLE user_recovery_codes IS '两步验证一次性恢复码';
+COMMENT ON COLUMN user_recovery_codes.code_hash IS '恢复码哈希（Argon2）';
+COMMENT ON COLUMN user_recovery_codes.used_at IS '使用时间，为空表示未使用';
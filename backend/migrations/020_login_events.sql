@@ -0,0 +1,32 @@
+-- Login Events Migration
+-- Version: 020_login_events
+-- Description: Record failed logins and add brute-force protection settings
+
+CREATE TABLE IF NOT EXISTS login_events (
+    id BIGSERIAL PRIMARY KEY,
+    username VARCHAR(255) NOT NULL,
+    user_id BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    ip VARCHAR(64),
+    user_agent TEXT,
+    reason VARCHAR(32) NOT NULL,
+    locked_out BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_login_events_created_at ON login_events(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_login_events_username ON login_events(username);
+CREATE INDEX IF NOT EXISTS idx_login_events_ip ON login_events(ip);
+
+COMMENT ON TABLE login_events IS '登录失败记录';
+COMMENT ON COLUMN login_events.username IS '尝试登录的用户名';
+COMMENT ON COLUMN login_events.user_id IS '对应用户（用户名不存在时为空）';
+COMMENT ON COLUMN login_events.reason IS '失败原因: unknown_user, invalid_password, invalid_two_factor';
+COMMENT ON COLUMN login_events.locked_out IS '本次失败是否触发了账号临时锁定';
+
+INSERT INTO site_config (config_key, config_value, config_type, description) VALUES
+('login_window_minutes', '15', 'number', '登录失败统计的滑动窗口(分钟)'),
+('login_max_failures_per_ip', '20', 'number', '窗口内单个IP允许的最大登录失败次数'),
+('login_max_failures_per_user', '5', 'number', '窗口内单个用户名允许的最大登录失败次数，超过后临时锁定'),
+('login_lockout_minutes', '15', 'number', '账号临时锁定时长(分钟)'),
+('login_backoff_base_seconds', '1', 'number', '登录失败后的退避基数(秒)，每次失败翻倍')
+ON CONFLICT (config_key) DO NOTHING;
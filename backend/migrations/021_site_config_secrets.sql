@@ -0,0 +1,10 @@
+-- Site Config Secrets Migration
+-- Version: 021_site_config_secrets
+-- Description: Mark credential settings as sensitive so they are masked and encrypted at rest
+
+ALTER TABLE site_config ADD COLUMN IF NOT EXISTS is_sensitive BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE site_config SET is_sensitive = TRUE
+WHERE config_key IN ('s3_access_key', 's3_secret_key', 'ai_api_key');
+
+COMMENT ON COLUMN site_config.is_sensitive IS '是否为敏感配置（接口中脱敏显示，只写，配置主密钥时加密存储）';
@@ -0,0 +1,29 @@
+-- File Variants Migration
+-- Version: 022_file_variants
+-- Description: Store thumbnails and responsive variants generated for uploaded images
+
+CREATE TABLE IF NOT EXISTS file_variants (
+    id BIGSERIAL PRIMARY KEY,
+    file_id BIGINT NOT NULL REFERENCES files(id) ON DELETE CASCADE,
+    kind VARCHAR(20) NOT NULL,
+    format VARCHAR(10) NOT NULL,
+    width INT NOT NULL,
+    height INT NOT NULL,
+    file_size BIGINT NOT NULL,
+    url TEXT NOT NULL,
+    object_key TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_file_variants_file_id ON file_variants(file_id);
+
+COMMENT ON TABLE file_variants IS '图片上传生成的缩略图及响应式尺寸变体';
+COMMENT ON COLUMN file_variants.kind IS '变体类型: thumbnail, responsive';
+COMMENT ON COLUMN file_variants.format IS '编码格式: jpeg, png, webp';
+
+INSERT INTO site_config (config_key, config_value, config_type, description) VALUES
+('image_thumbnail_size', '320', 'number', '图片缩略图的最大宽高(像素)'),
+('image_variant_widths', '640,1280,1920', 'string', '响应式图片变体宽度(像素，逗号分隔，大于原图的宽度会跳过)'),
+('image_webp_enabled', 'true', 'boolean', '是否额外生成 WebP 格式的图片变体'),
+('image_strip_metadata', 'true', 'boolean', '上传图片时是否去除 EXIF/XMP 元数据(含 GPS 位置)')
+ON CONFLICT (config_key) DO NOTHING;
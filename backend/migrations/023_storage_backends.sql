@@ -0,0 +1,12 @@
+-- Storage Backends Migration
+-- Version: 023_storage_backends
+-- Description: Record the storage backend of each file and add local filesystem storage settings
+
+ALTER TABLE files ADD COLUMN IF NOT EXISTS storage_backend VARCHAR(20) NOT NULL DEFAULT 's3';
+
+COMMENT ON COLUMN files.storage_backend IS '文件所在的存储后端: s3, local';
+
+INSERT INTO site_config (config_key, config_value, config_type, description) VALUES
+('storage_backend', 's3', 'string', '新上传文件的存储后端: s3, local(保存在服务器 STORAGE_LOCAL_DIR 目录)'),
+('local_storage_public_url', '', 'string', '本地存储文件的公开访问地址前缀(如 https://api.example.com/files)，留空使用 /files')
+ON CONFLICT (config_key) DO NOTHING;
@@ -0,0 +1,12 @@
+-- File Deduplication Migration
+-- Version: 024_file_dedup
+-- Description: Store the SHA-256 of uploaded files and count the uploads sharing each stored file
+
+ALTER TABLE files ADD COLUMN IF NOT EXISTS content_hash VARCHAR(64);
+ALTER TABLE files ADD COLUMN IF NOT EXISTS ref_count INT NOT NULL DEFAULT 1;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_files_content_hash ON files(content_hash)
+    WHERE content_hash IS NOT NULL;
+
+COMMENT ON COLUMN files.content_hash IS '上传内容的 SHA-256(十六进制)，相同内容的上传复用同一文件；历史文件为空';
+COMMENT ON COLUMN files.ref_count IS '引用该文件的上传次数，删除时递减，归零才删除存储中的对象';
@@ -0,0 +1,7 @@
+-- Direct Uploads Migration
+-- Version: 025_direct_uploads
+-- Description: Limit the size of files uploaded directly to S3 with presigned URLs
+
+INSERT INTO site_config (config_key, config_value, config_type, description) VALUES
+('direct_upload_max_size_mb', '4096', 'number', '浏览器通过预签名地址直传 S3 的单个文件大小上限(MB)，超过 64MB 的文件使用分片上传')
+ON CONFLICT (config_key) DO NOTHING;
@@ -0,0 +1,7 @@
+-- Sitemap Base URL Default Migration
+-- Version: 026_sitemap_base_default
+-- Description: Sitemap URLs now default to the API prefix under the site URL, where the routes are mounted
+
+UPDATE site_config
+SET description = '站点地图对外访问前缀(为空时使用站点访问地址加 /api/v1)'
+WHERE config_key = 'sitemap_base_url';
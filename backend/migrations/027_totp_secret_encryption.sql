@@ -0,0 +1,7 @@
+-- TOTP Secret Encryption Migration
+-- Version: 027_totp_secret_encryption
+-- Description: Widen the TOTP secret column to hold values encrypted with the master key
+
+ALTER TABLE users ALTER COLUMN totp_secret TYPE TEXT;
+
+COMMENT ON COLUMN users.totp_secret IS 'TOTP密钥（Base32，配置主密钥时加密存储），确认绑定前为待定状态';
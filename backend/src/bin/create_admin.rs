@@ -16,5 +16,5 @@ fn main() {
 
     println!("Password hash for '{}': {}", password, password_hash);
     println!("\nSQL to insert admin user:");
-    println!("INSERT INTO users (username, password_hash, nickname) VALUES ('admin', '{}', 'Administrator');", password_hash);
+    println!("INSERT INTO users (username, password_hash, nickname, role) VALUES ('admin', '{}', 'Administrator', 'admin');", password_hash);
 }
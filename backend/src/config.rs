@@ -2,8 +2,10 @@
 //!
 //! Reads configuration from environment variables.
 
+use ipnet::IpNet;
 use serde::Deserialize;
 use std::env;
+use std::net::IpAddr;
 
 /// Application configuration
 #[derive(Debug, Clone, Deserialize)]
@@ -13,6 +15,9 @@ pub struct Config {
     pub redis: RedisConfig,
     pub jwt: JwtConfig,
     pub s3: S3Config,
+    pub encryption: EncryptionConfig,
+    pub storage: StorageConfig,
+    pub rate_limit: RateLimitConfig,
 }
 
 /// Server configuration
@@ -21,6 +26,8 @@ pub struct ServerConfig {
     pub host: String,
     pub port: u16,
     pub max_body_size_mb: usize,
+    /// Proxies whose `Forwarded` / `X-Forwarded-For` / `X-Real-IP` headers are believed
+    pub trusted_proxies: Vec<IpNet>,
 }
 
 /// Database configuration
@@ -59,6 +66,38 @@ pub struct S3Config {
     pub public_url: String,
 }
 
+/// File storage configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct StorageConfig {
+    /// Directory of the `local` storage backend, served under `/files`
+    pub local_dir: String,
+}
+
+/// At-rest encryption configuration
+#[derive(Debug, Clone, Deserialize)]
+pub struct EncryptionConfig {
+    /// Server master key; protected text content can't be stored without it
+    pub master_key: Option<String>,
+}
+
+/// Token bucket limit for one route group
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct RateLimit {
+    /// Requests a client may make in a burst
+    pub burst: u32,
+    /// Sustained requests per minute
+    pub per_minute: u32,
+}
+
+/// API rate limiting configuration, per route group
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateLimitConfig {
+    pub enabled: bool,
+    pub public: RateLimit,
+    pub admin: RateLimit,
+    pub mcp: RateLimit,
+}
+
 impl Config {
     /// Load configuration from environment variables
     pub fn from_env() -> Result<Self, ConfigError> {
@@ -75,6 +114,10 @@ impl Config {
                     .map_err(|_| {
                         ConfigError::InvalidValue("SERVER_MAX_BODY_SIZE_MB".to_string())
                     })?,
+                trusted_proxies: parse_trusted_proxies(
+                    &env::var("TRUSTED_PROXIES").unwrap_or_default(),
+                )
+                .ok_or_else(|| ConfigError::InvalidValue("TRUSTED_PROXIES".to_string()))?,
             },
             database: DatabaseConfig {
                 url: env::var("DATABASE_URL")
@@ -139,10 +182,61 @@ impl Config {
                 secret_key: env::var("S3_SECRET_KEY").unwrap_or_else(|_| "minioadmin".to_string()),
                 public_url: env::var("S3_PUBLIC_URL").unwrap_or_default(),
             },
+            encryption: EncryptionConfig {
+                master_key: env::var("ENCRYPTION_MASTER_KEY")
+                    .ok()
+                    .filter(|key| !key.is_empty()),
+            },
+            storage: StorageConfig {
+                local_dir: env::var("STORAGE_LOCAL_DIR")
+                    .unwrap_or_else(|_| "./uploads".to_string()),
+            },
+            rate_limit: RateLimitConfig {
+                enabled: env::var("RATE_LIMIT_ENABLED")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .map_err(|_| ConfigError::InvalidValue("RATE_LIMIT_ENABLED".to_string()))?,
+                public: rate_limit_from_env("PUBLIC", 60, 120)?,
+                admin: rate_limit_from_env("ADMIN", 120, 600)?,
+                mcp: rate_limit_from_env("MCP", 30, 60)?,
+            },
         })
     }
 }
 
+/// Read `RATE_LIMIT_{group}_BURST` and `RATE_LIMIT_{group}_PER_MINUTE`
+fn rate_limit_from_env(group: &str, burst: u32, per_minute: u32) -> Result<RateLimit, ConfigError> {
+    let read = |suffix: &str, default: u32| {
+        let name = format!("RATE_LIMIT_{}_{}", group, suffix);
+        env::var(&name)
+            .map(|value| value.parse::<u32>())
+            .unwrap_or(Ok(default))
+            .ok()
+            .filter(|value| *value > 0)
+            .ok_or(ConfigError::InvalidValue(name))
+    };
+
+    Ok(RateLimit {
+        burst: read("BURST", burst)?,
+        per_minute: read("PER_MINUTE", per_minute)?,
+    })
+}
+
+/// Parse a comma-separated list of CIDRs; bare addresses trust that single host
+fn parse_trusted_proxies(value: &str) -> Option<Vec<IpNet>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            entry
+                .parse::<IpNet>()
+                .ok()
+                .or_else(|| entry.parse::<IpAddr>().ok().map(IpNet::from))
+        })
+        .collect()
+}
+
 /// Configuration errors
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
@@ -95,6 +95,9 @@ pub enum ApiError {
     #[error("Not found: {0}")]
     NotFound(String),
 
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
+
     #[error("Internal server error: {0}")]
     InternalError(String),
 
@@ -119,6 +122,7 @@ pub enum ErrorCode {
     Unauthorized = 401,
     Forbidden = 403,
     NotFound = 404,
+    TooManyRequests = 429,
     InternalError = 500,
     DatabaseError = 1001,
     CacheError = 1002,
@@ -134,6 +138,7 @@ impl ApiError {
             ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
             ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
             ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
             ApiError::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::CacheError(_) => StatusCode::INTERNAL_SERVER_ERROR,
@@ -149,6 +154,7 @@ impl ApiError {
             ApiError::Unauthorized(_) => ErrorCode::Unauthorized as i32,
             ApiError::Forbidden(_) => ErrorCode::Forbidden as i32,
             ApiError::NotFound(_) => ErrorCode::NotFound as i32,
+            ApiError::TooManyRequests(_) => ErrorCode::TooManyRequests as i32,
             ApiError::InternalError(_) => ErrorCode::InternalError as i32,
             ApiError::DatabaseError(_) => ErrorCode::DatabaseError as i32,
             ApiError::CacheError(_) => ErrorCode::CacheError as i32,
@@ -6,6 +6,9 @@ use axum::{extract::State, Json};
 use serde::{Deserialize, Serialize};
 
 use crate::error::{ApiError, ApiResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::revision::{RevisionActor, RevisionTarget};
+use crate::repositories::revision_repo::RevisionRepository;
 use crate::repositories::site_config_repo::SiteConfigRepo;
 use crate::services::ai_service::AiService;
 use crate::AppState;
@@ -97,7 +100,12 @@ async fn get_ai_service(state: &AppState) -> Result<AiService, ApiError> {
         return Err(ApiError::ValidationError("AI功能未启用".to_string()));
     }
 
-    let api_key: Option<String> = SiteConfigRepo::get_value(&state.db, "ai_api_key").await?;
+    let api_key: Option<String> = SiteConfigRepo::get_secret(
+        &state.db,
+        "ai_api_key",
+        state.config.encryption.master_key.as_deref(),
+    )
+    .await?;
     let api_key =
         api_key.ok_or_else(|| ApiError::ValidationError("AI API密钥未配置".to_string()))?;
 
@@ -258,6 +266,7 @@ pub struct BatchSummarizeAllResponse {
 /// - concurrency: number of concurrent requests (default: 1, max: 10)
 pub async fn batch_summarize_all(
     State(state): State<AppState>,
+    auth_user: AuthUser,
     Json(req): Json<BatchSummarizeAllRequest>,
 ) -> Result<Json<ApiResponse<BatchSummarizeAllResponse>>, ApiError> {
     use futures::stream::{self, StreamExt};
@@ -290,6 +299,7 @@ pub async fn batch_summarize_all(
 
     // Limit concurrency to 1-10
     let concurrency = req.concurrency.clamp(1, 10);
+    let actor = RevisionActor::ai_batch(auth_user.user_id, &auth_user.username);
 
     let success = Arc::new(Mutex::new(0i64));
     let errors = Arc::new(Mutex::new(Vec::new()));
@@ -300,20 +310,19 @@ pub async fn batch_summarize_all(
             let ai_service = &ai_service;
             let summary_prompt = &summary_prompt;
             let db = &state.db;
+            let actor = &actor;
             let success = Arc::clone(&success);
             let errors = Arc::clone(&errors);
 
             async move {
                 match ai_service.summarize_text(&content, summary_prompt).await {
                     Ok(summary) => {
-                        match sqlx::query(
+                        let write = sqlx::query(
                             "UPDATE blogs SET summary = $1, updated_at = NOW() WHERE id = $2",
                         )
                         .bind(&summary)
-                        .bind(blog_id)
-                        .execute(db)
-                        .await
-                        {
+                        .bind(blog_id);
+                        match write_with_revision(db, blog_id, actor, write).await {
                             Ok(_) => {
                                 *success.lock().await += 1;
                                 tracing::info!("Generated summary for blog {}: {}", blog_id, title);
@@ -356,32 +365,33 @@ pub async fn batch_summarize_all(
 /// Confirm and save batch AI processing results
 pub async fn batch_confirm(
     State(state): State<AppState>,
+    auth_user: AuthUser,
     Json(req): Json<BatchConfirmRequest>,
 ) -> Result<Json<ApiResponse<BatchConfirmResponse>>, ApiError> {
     let mut updated = 0i64;
     let mut errors = Vec::new();
+    let actor = RevisionActor::ai_batch(auth_user.user_id, &auth_user.username);
 
     for item in req.items {
         let result = match item.action.as_str() {
             "polish" => {
                 // Update content and regenerate HTML
                 let html = crate::utils::markdown::render_markdown(&item.result);
-                sqlx::query(
+                let write = sqlx::query(
                     "UPDATE blogs SET content = $1, html = $2, updated_at = NOW() WHERE id = $3",
                 )
                 .bind(&item.result)
-                .bind(&html)
-                .bind(item.blog_id)
-                .execute(&state.db)
-                .await
+                .bind(html)
+                .bind(item.blog_id);
+                write_with_revision(&state.db, item.blog_id, &actor, write).await
             }
             "summarize" => {
                 // Update summary field
-                sqlx::query("UPDATE blogs SET summary = $1, updated_at = NOW() WHERE id = $2")
-                    .bind(&item.result)
-                    .bind(item.blog_id)
-                    .execute(&state.db)
-                    .await
+                let write =
+                    sqlx::query("UPDATE blogs SET summary = $1, updated_at = NOW() WHERE id = $2")
+                        .bind(&item.result)
+                        .bind(item.blog_id);
+                write_with_revision(&state.db, item.blog_id, &actor, write).await
             }
             _ => {
                 errors.push(format!("Blog {}: 无效的操作类型", item.blog_id));
@@ -403,3 +413,33 @@ pub async fn batch_confirm(
         errors,
     })))
 }
+
+/// Run a raw blog write-back and record it in the blog's revision history
+///
+/// The blog row is locked and the current state snapshotted first, all in one
+/// transaction, so the pre-AI version can always be restored.
+async fn write_with_revision(
+    db: &sqlx::PgPool,
+    blog_id: i64,
+    actor: &RevisionActor,
+    write: sqlx::query::Query<'_, sqlx::Postgres, sqlx::postgres::PgArguments>,
+) -> Result<(), ApiError> {
+    let mut tx = db.begin().await?;
+    if !RevisionRepository::lock_entity(&mut tx, RevisionTarget::Blog, blog_id).await? {
+        return Err(ApiError::NotFound(format!(
+            "Blog with id {} not found",
+            blog_id
+        )));
+    }
+    RevisionRepository::snapshot(
+        &mut tx,
+        RevisionTarget::Blog,
+        blog_id,
+        &RevisionActor::baseline(),
+    )
+    .await?;
+    write.execute(&mut *tx).await?;
+    RevisionRepository::snapshot(&mut tx, RevisionTarget::Blog, blog_id, actor).await?;
+    tx.commit().await?;
+    Ok(())
+}
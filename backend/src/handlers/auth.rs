@@ -1,20 +1,79 @@
 //! Authentication handlers
 
-use axum::{extract::State, Json};
+use axum::{
+    extract::State,
+    http::{header, HeaderMap},
+    Json,
+};
+use chrono::Utc;
 
 use crate::error::{ApiError, ApiResponse};
+use crate::middleware::client_ip::ClientIp;
+use crate::models::login_event::LoginFailureReason;
+use crate::models::session::{LogoutRequest, SessionClient};
+use crate::models::two_factor::{LoginOutcome, TwoFactorChallenge, TwoFactorLoginRequest};
 use crate::models::user::{LoginRequest, LoginResponse, RefreshTokenRequest, RefreshTokenResponse};
 use crate::repositories::user_repo::UserRepository;
-use crate::services::auth_service::AuthService;
+use crate::services::auth_service::{AuthService, TWO_FACTOR_TOKEN_EXPIRE_SECONDS};
+use crate::services::login_guard_service::{LoginAttempt, LoginGuardService, LoginLimits};
+use crate::services::session_service::SessionService;
+use crate::services::two_factor_service::TwoFactorService;
 use crate::AppState;
 
+/// Describe the client starting a session
+fn session_client(client_ip: ClientIp, headers: &HeaderMap) -> SessionClient {
+    SessionClient {
+        ip: Some(client_ip.to_string()),
+        user_agent: headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(ToOwned::to_owned),
+    }
+}
+
+/// Count a failed login and build the error returned for it
+async fn reject_login(
+    state: &AppState,
+    limits: &LoginLimits,
+    attempt: &LoginAttempt<'_>,
+    user_id: Option<i64>,
+    reason: LoginFailureReason,
+) -> ApiError {
+    if let Err(e) = LoginGuardService::record_failure(
+        &state.db,
+        &state.cache,
+        limits,
+        attempt,
+        user_id,
+        reason,
+        Utc::now(),
+    )
+    .await
+    {
+        tracing::warn!("Failed to record failed login: {}", e);
+    }
+
+    match reason {
+        LoginFailureReason::InvalidTwoFactor => {
+            ApiError::Unauthorized("Invalid verification code".to_string())
+        }
+        _ => ApiError::Unauthorized("Invalid username or password".to_string()),
+    }
+}
+
 /// POST /api/v1/auth/login
 ///
-/// Authenticate user and return JWT tokens
+/// Authenticate user, start a session and return JWT tokens
+///
+/// Users with two-factor authentication get a short-lived challenge token
+/// instead, to be exchanged at `/auth/login/2fa`. Failed attempts are throttled
+/// per IP and per username, see `LoginGuardService`.
 pub async fn login(
     State(state): State<AppState>,
+    client_ip: ClientIp,
+    headers: HeaderMap,
     Json(req): Json<LoginRequest>,
-) -> Result<Json<ApiResponse<LoginResponse>>, ApiError> {
+) -> Result<Json<ApiResponse<LoginOutcome>>, ApiError> {
     // Validate input
     if req.username.trim().is_empty() {
         return Err(ApiError::ValidationError(
@@ -27,31 +86,119 @@ pub async fn login(
         ));
     }
 
+    let client = session_client(client_ip, &headers);
+    let attempt = LoginAttempt {
+        username: &req.username,
+        client: &client,
+    };
+    let limits = LoginLimits::load(&state.db).await?;
+    LoginGuardService::check(&state.cache, &limits, &attempt, Utc::now()).await?;
+
     // Find user by username
-    let user = UserRepository::find_by_username(&state.db, &req.username)
-        .await?
-        .ok_or_else(|| ApiError::Unauthorized("Invalid username or password".to_string()))?;
+    let Some(user) = UserRepository::find_by_username(&state.db, &req.username).await? else {
+        let reason = LoginFailureReason::UnknownUser;
+        return Err(reject_login(&state, &limits, &attempt, None, reason).await);
+    };
 
     // Verify password
     let is_valid = UserRepository::verify_password(&req.password, &user.password_hash)?;
     if !is_valid {
-        return Err(ApiError::Unauthorized(
-            "Invalid username or password".to_string(),
-        ));
+        let reason = LoginFailureReason::InvalidPassword;
+        return Err(reject_login(&state, &limits, &attempt, Some(user.id), reason).await);
     }
 
-    // Generate tokens
     let auth_service = AuthService::new(state.config.jwt.clone());
-    let response = auth_service.create_login_response(user)?;
+
+    if user.totp_enabled {
+        let challenge_token = auth_service.generate_two_factor_token(user.id)?;
+        tracing::debug!("User '{}' passed the password step", req.username);
+
+        return Ok(Json(ApiResponse::success(LoginOutcome::TwoFactor(
+            TwoFactorChallenge {
+                two_factor_required: true,
+                challenge_token,
+                expires_in: TWO_FACTOR_TOKEN_EXPIRE_SECONDS,
+            },
+        ))));
+    }
+
+    // Start a session and generate tokens
+    LoginGuardService::record_success(&state.cache, &req.username).await?;
+    let response = SessionService::login(&state.cache, &auth_service, user, client).await?;
 
     tracing::info!("User '{}' logged in successfully", req.username);
 
+    Ok(Json(ApiResponse::success(LoginOutcome::Tokens(response))))
+}
+
+/// POST /api/v1/auth/login/2fa
+///
+/// Answer a login challenge with an authenticator or recovery code, start a
+/// session and return JWT tokens
+pub async fn login_two_factor(
+    State(state): State<AppState>,
+    client_ip: ClientIp,
+    headers: HeaderMap,
+    Json(req): Json<TwoFactorLoginRequest>,
+) -> Result<Json<ApiResponse<LoginResponse>>, ApiError> {
+    if req.code.trim().is_empty() {
+        return Err(ApiError::ValidationError(
+            "Verification code is required".to_string(),
+        ));
+    }
+
+    let auth_service = AuthService::new(state.config.jwt.clone());
+    let user_id = auth_service.verify_two_factor_token(&req.challenge_token)?;
+
+    // The user may have been deleted or turned 2FA off since the password step
+    let user = UserRepository::find_by_id(&state.db, user_id)
+        .await?
+        .filter(|user| user.totp_enabled)
+        .ok_or_else(|| ApiError::Unauthorized("Login challenge is no longer valid".to_string()))?;
+
+    let client = session_client(client_ip, &headers);
+    let attempt = LoginAttempt {
+        username: &user.username,
+        client: &client,
+    };
+    let limits = LoginLimits::load(&state.db).await?;
+    LoginGuardService::check(&state.cache, &limits, &attempt, Utc::now()).await?;
+
+    match TwoFactorService::verify(
+        &state.db,
+        &state.cache,
+        state.config.encryption.master_key.as_deref(),
+        &user,
+        &req.code,
+        Utc::now(),
+    )
+    .await
+    {
+        Ok(()) => {}
+        Err(ApiError::Unauthorized(_)) => {
+            let reason = LoginFailureReason::InvalidTwoFactor;
+            return Err(reject_login(&state, &limits, &attempt, Some(user.id), reason).await);
+        }
+        Err(e) => return Err(e),
+    }
+
+    let username = user.username.clone();
+    LoginGuardService::record_success(&state.cache, &username).await?;
+    let response = SessionService::login(&state.cache, &auth_service, user, client).await?;
+
+    tracing::info!(
+        "User '{}' logged in with two-factor authentication",
+        username
+    );
+
     Ok(Json(ApiResponse::success(response)))
 }
 
 /// POST /api/v1/auth/refresh
 ///
-/// Refresh access token using refresh token
+/// Rotate a refresh token, returning a new access token and refresh token
+///
+/// Reusing a refresh token that was already rotated revokes its whole session.
 pub async fn refresh_token(
     State(state): State<AppState>,
     Json(req): Json<RefreshTokenRequest>,
@@ -63,24 +210,39 @@ pub async fn refresh_token(
         ));
     }
 
-    // Verify refresh token
     let auth_service = AuthService::new(state.config.jwt.clone());
-    let claims = auth_service.verify_refresh_token(&req.refresh_token)?;
+    let response =
+        SessionService::refresh(&state.db, &state.cache, &auth_service, &req.refresh_token).await?;
 
-    // Find user to ensure they still exist
-    let user = UserRepository::find_by_id(&state.db, claims.sub)
-        .await?
-        .ok_or_else(|| ApiError::Unauthorized("User not found".to_string()))?;
+    tracing::debug!("Token refreshed");
 
-    // Generate new access token
-    let response = auth_service.create_refresh_response(&user)?;
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// POST /api/v1/auth/logout
+///
+/// End the session of a refresh token; its access tokens stop working too
+pub async fn logout(
+    State(state): State<AppState>,
+    Json(req): Json<LogoutRequest>,
+) -> Result<Json<ApiResponse<()>>, ApiError> {
+    if req.refresh_token.trim().is_empty() {
+        return Err(ApiError::ValidationError(
+            "Refresh token is required".to_string(),
+        ));
+    }
 
-    tracing::debug!("Token refreshed for user '{}'", user.username);
+    let auth_service = AuthService::new(state.config.jwt.clone());
+    SessionService::logout(&state.cache, &auth_service, &req.refresh_token).await?;
 
-    Ok(Json(ApiResponse::success(response)))
+    Ok(Json(ApiResponse {
+        code: 0,
+        message: "Logged out successfully".to_string(),
+        data: None,
+    }))
 }
 
-use crate::models::user::CreateUserRequest;
+use crate::models::user::{CreateUserRequest, UserRole};
 use serde::Serialize;
 
 /// Check if any admin exists response
@@ -110,6 +272,8 @@ pub async fn check_admin_exists(
 /// Register the first admin user (only works when no users exist)
 pub async fn setup_admin(
     State(state): State<AppState>,
+    client_ip: ClientIp,
+    headers: HeaderMap,
     Json(req): Json<CreateUserRequest>,
 ) -> Result<Json<ApiResponse<LoginResponse>>, ApiError> {
     // Check if any user already exists
@@ -136,11 +300,17 @@ pub async fn setup_admin(
     }
 
     // Create the admin user
-    let user = UserRepository::create(&state.db, &req).await?;
+    let user = UserRepository::create(&state.db, &req, UserRole::Admin).await?;
 
     // Generate tokens and return login response
     let auth_service = AuthService::new(state.config.jwt.clone());
-    let response = auth_service.create_login_response(user)?;
+    let response = SessionService::login(
+        &state.cache,
+        &auth_service,
+        user,
+        session_client(client_ip, &headers),
+    )
+    .await?;
 
     tracing::info!("First admin user '{}' created successfully", req.username);
 
@@ -1,45 +1,24 @@
 //! Blog handlers
 
 use axum::{
-    extract::{ConnectInfo, Path, Query, State},
-    http::HeaderMap,
+    extract::{Path, Query, State},
     Json,
 };
-use std::net::SocketAddr;
 
 use crate::error::{ApiError, ApiResponse, PaginatedData};
+use crate::middleware::auth::AuthUser;
+use crate::middleware::client_ip::ClientIp;
 use crate::models::blog::{
     BlogDetail, BlogListItem, BlogQueryParams, BlogResponse, CreateBlogRequest, UpdateBlogRequest,
 };
+use crate::models::revision::RevisionActor;
 use crate::repositories::blog_repo::BlogRepository;
+use crate::repositories::user_repo::UserRepository;
 use crate::services::blog_service::BlogService;
 use crate::services::cache_service::{cache_keys, cache_ttl};
 use crate::utils::markdown::render_markdown;
 use crate::AppState;
 
-/// Extract client IP from request headers or connection info
-fn get_client_ip_from_headers(headers: &HeaderMap, addr: &SocketAddr) -> String {
-    // Try X-Forwarded-For header first (for proxied requests)
-    if let Some(forwarded) = headers.get("x-forwarded-for") {
-        if let Ok(value) = forwarded.to_str() {
-            // Take the first IP in the chain
-            if let Some(ip) = value.split(',').next() {
-                return ip.trim().to_string();
-            }
-        }
-    }
-
-    // Try X-Real-IP header
-    if let Some(real_ip) = headers.get("x-real-ip") {
-        if let Ok(value) = real_ip.to_str() {
-            return value.to_string();
-        }
-    }
-
-    // Fall back to connection info
-    addr.ip().to_string()
-}
-
 /// GET /api/v1/blogs
 ///
 /// Get paginated list of published blogs (public endpoint)
@@ -108,9 +87,8 @@ pub async fn list_blogs(
 /// Get a single blog by ID (public endpoint)
 pub async fn get_blog(
     State(state): State<AppState>,
-    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    client_ip: ClientIp,
     Path(id): Path<i64>,
-    headers: HeaderMap,
 ) -> Result<Json<ApiResponse<BlogResponse>>, ApiError> {
     let cache_key = cache_keys::blog_detail(id);
 
@@ -119,8 +97,9 @@ pub async fn get_blog(
         tracing::debug!("Cache hit for blog detail (id {})", id);
 
         // Still increment view count even for cached responses
-        let client_ip = get_client_ip_from_headers(&headers, &addr);
-        let _ = BlogService::increment_view_count(&state.db, &state.cache, id, &client_ip).await;
+        let _ =
+            BlogService::increment_view_count(&state.db, &state.cache, id, &client_ip.to_string())
+                .await;
 
         return Ok(Json(ApiResponse::success(cached)));
     }
@@ -130,7 +109,7 @@ pub async fn get_blog(
         .ok_or_else(|| ApiError::NotFound(format!("Blog with id {} not found", id)))?;
 
     // Only return published blogs for public endpoint
-    if !blog.is_published {
+    if !blog.is_publicly_visible() {
         return Err(ApiError::NotFound(format!("Blog with id {} not found", id)));
     }
 
@@ -146,8 +125,8 @@ pub async fn get_blog(
     }
 
     // Increment view count with rate limiting
-    let client_ip = get_client_ip_from_headers(&headers, &addr);
-    let _ = BlogService::increment_view_count(&state.db, &state.cache, id, &client_ip).await;
+    let _ = BlogService::increment_view_count(&state.db, &state.cache, id, &client_ip.to_string())
+        .await;
 
     Ok(Json(ApiResponse::success(response)))
 }
@@ -157,9 +136,8 @@ pub async fn get_blog(
 /// Get a single blog by slug (public endpoint)
 pub async fn get_blog_by_slug(
     State(state): State<AppState>,
-    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    client_ip: ClientIp,
     Path(slug): Path<String>,
-    headers: HeaderMap,
 ) -> Result<Json<ApiResponse<BlogResponse>>, ApiError> {
     let cache_key = cache_keys::blog_slug(&slug);
 
@@ -168,9 +146,13 @@ pub async fn get_blog_by_slug(
         tracing::debug!("Cache hit for blog by slug (slug {})", slug);
 
         // Still increment view count even for cached responses
-        let client_ip = get_client_ip_from_headers(&headers, &addr);
-        let _ =
-            BlogService::increment_view_count(&state.db, &state.cache, cached.id, &client_ip).await;
+        let _ = BlogService::increment_view_count(
+            &state.db,
+            &state.cache,
+            cached.id,
+            &client_ip.to_string(),
+        )
+        .await;
 
         return Ok(Json(ApiResponse::success(cached)));
     }
@@ -180,7 +162,7 @@ pub async fn get_blog_by_slug(
         .ok_or_else(|| ApiError::NotFound(format!("Blog with slug '{}' not found", slug)))?;
 
     // Only return published blogs for public endpoint
-    if !blog.is_published {
+    if !blog.is_publicly_visible() {
         return Err(ApiError::NotFound(format!(
             "Blog with slug '{}' not found",
             slug
@@ -199,9 +181,13 @@ pub async fn get_blog_by_slug(
     }
 
     // Increment view count with rate limiting
-    let client_ip = get_client_ip_from_headers(&headers, &addr);
-    let _ =
-        BlogService::increment_view_count(&state.db, &state.cache, response.id, &client_ip).await;
+    let _ = BlogService::increment_view_count(
+        &state.db,
+        &state.cache,
+        response.id,
+        &client_ip.to_string(),
+    )
+    .await;
 
     Ok(Json(ApiResponse::success(response)))
 }
@@ -254,11 +240,16 @@ pub async fn admin_get_blog(
 
 /// POST /api/v1/admin/blogs
 ///
-/// Create a new blog (admin endpoint)
+/// Create a new blog owned by the current user (admin endpoint)
+///
+/// Authors may only create drafts; the byline defaults to the user's display name.
 pub async fn create_blog(
     State(state): State<AppState>,
-    Json(req): Json<CreateBlogRequest>,
+    auth_user: AuthUser,
+    Json(mut req): Json<CreateBlogRequest>,
 ) -> Result<Json<ApiResponse<BlogResponse>>, ApiError> {
+    BlogService::ensure_can_publish(&auth_user, false, req.is_published, req.publish_at)?;
+
     // Validate input
     if req.title.trim().is_empty() {
         return Err(ApiError::ValidationError(
@@ -282,11 +273,22 @@ pub async fn create_blog(
         }
     }
 
+    // Default the byline to the author's display name
+    if req
+        .author
+        .as_deref()
+        .is_none_or(|author| author.trim().is_empty())
+    {
+        req.author = UserRepository::find_by_id(&state.db, auth_user.user_id)
+            .await?
+            .map(|user| user.display_name());
+    }
+
     // Render markdown to HTML
     let html = render_markdown(&req.content);
 
     // Create blog
-    let blog = BlogRepository::create(&state.db, &req, Some(html)).await?;
+    let blog = BlogRepository::create(&state.db, &req, Some(html), Some(auth_user.user_id)).await?;
 
     // Fetch the full blog detail with category and tags
     let blog_detail = BlogRepository::find_detail_by_id(&state.db, blog.id)
@@ -310,6 +312,7 @@ pub async fn create_blog(
 /// Update an existing blog (admin endpoint)
 pub async fn update_blog(
     State(state): State<AppState>,
+    auth_user: AuthUser,
     Path(id): Path<i64>,
     Json(req): Json<UpdateBlogRequest>,
 ) -> Result<Json<ApiResponse<BlogResponse>>, ApiError> {
@@ -318,6 +321,14 @@ pub async fn update_blog(
         .await?
         .ok_or_else(|| ApiError::NotFound(format!("Blog with id {} not found", id)))?;
 
+    BlogService::ensure_can_edit(&auth_user, &old_blog)?;
+    BlogService::ensure_can_publish(
+        &auth_user,
+        old_blog.is_published,
+        req.is_published,
+        req.publish_at,
+    )?;
+
     // Validate title if provided
     if let Some(ref title) = req.title {
         if title.trim().is_empty() {
@@ -341,7 +352,8 @@ pub async fn update_blog(
     let html = req.content.as_ref().map(|content| render_markdown(content));
 
     // Update blog
-    let blog = BlogRepository::update(&state.db, id, &req, html)
+    let actor = RevisionActor::admin(auth_user.user_id, &auth_user.username);
+    let blog = BlogRepository::update(&state.db, id, &req, html, &actor)
         .await?
         .ok_or_else(|| ApiError::NotFound(format!("Blog with id {} not found", id)))?;
 
@@ -376,6 +388,7 @@ pub async fn update_blog(
 /// Delete a blog (admin endpoint)
 pub async fn delete_blog(
     State(state): State<AppState>,
+    auth_user: AuthUser,
     Path(id): Path<i64>,
 ) -> Result<Json<ApiResponse<()>>, ApiError> {
     // Check if blog exists
@@ -383,6 +396,8 @@ pub async fn delete_blog(
         .await?
         .ok_or_else(|| ApiError::NotFound(format!("Blog with id {} not found", id)))?;
 
+    BlogService::ensure_can_delete(&auth_user, &blog)?;
+
     // Delete blog (tags will be automatically removed due to CASCADE)
     let deleted = BlogRepository::delete(&state.db, id).await?;
     if !deleted {
@@ -0,0 +1,161 @@
+//! Comment handlers
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap},
+    Json,
+};
+
+use crate::error::{ApiError, ApiResponse, PaginatedData};
+use crate::middleware::client_ip::ClientIp;
+use crate::models::comment::{
+    Comment, CommentNode, CommentQueryParams, CommentStatus, CommentTarget, CreateCommentRequest,
+    PublicComment, UpdateCommentStatusRequest,
+};
+use crate::repositories::comment_repo::CommentRepository;
+use crate::services::comment_service::CommentService;
+use crate::AppState;
+
+/// GET /api/v1/blogs/:id/comments
+///
+/// Get approved comments of a blog as a reply tree (public endpoint)
+pub async fn list_blog_comments(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<ApiResponse<Vec<CommentNode>>>, ApiError> {
+    let thread = CommentService::list_thread(&state.db, CommentTarget::Blog(id)).await?;
+    Ok(Json(ApiResponse::success(thread)))
+}
+
+/// GET /api/v1/documents/:id/comments
+///
+/// Get approved comments of a document as a reply tree (public endpoint)
+pub async fn list_document_comments(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<ApiResponse<Vec<CommentNode>>>, ApiError> {
+    let thread = CommentService::list_thread(&state.db, CommentTarget::Document(id)).await?;
+    Ok(Json(ApiResponse::success(thread)))
+}
+
+/// POST /api/v1/blogs/:id/comments
+///
+/// Submit a comment or reply on a blog (public endpoint, rate limited per IP)
+pub async fn create_blog_comment(
+    State(state): State<AppState>,
+    client_ip: ClientIp,
+    Path(id): Path<i64>,
+    headers: HeaderMap,
+    Json(req): Json<CreateCommentRequest>,
+) -> Result<Json<ApiResponse<PublicComment>>, ApiError> {
+    submit_comment(&state, CommentTarget::Blog(id), client_ip, &headers, &req).await
+}
+
+/// POST /api/v1/documents/:id/comments
+///
+/// Submit a comment or reply on a document (public endpoint, rate limited per IP)
+pub async fn create_document_comment(
+    State(state): State<AppState>,
+    client_ip: ClientIp,
+    Path(id): Path<i64>,
+    headers: HeaderMap,
+    Json(req): Json<CreateCommentRequest>,
+) -> Result<Json<ApiResponse<PublicComment>>, ApiError> {
+    submit_comment(
+        &state,
+        CommentTarget::Document(id),
+        client_ip,
+        &headers,
+        &req,
+    )
+    .await
+}
+
+async fn submit_comment(
+    state: &AppState,
+    target: CommentTarget,
+    client_ip: ClientIp,
+    headers: &HeaderMap,
+    req: &CreateCommentRequest,
+) -> Result<Json<ApiResponse<PublicComment>>, ApiError> {
+    let client_ip = client_ip.to_string();
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|value| value.to_str().ok());
+
+    let comment =
+        CommentService::submit(&state.db, &state.cache, target, &client_ip, user_agent, req)
+            .await?;
+
+    // Honeypot hits get the same answer as real submissions
+    Ok(Json(ApiResponse {
+        code: 0,
+        message: "Comment submitted and awaiting moderation".to_string(),
+        data: comment.map(PublicComment::from),
+    }))
+}
+
+/// GET /api/v1/admin/comments
+///
+/// Get paginated comments, optionally filtered by status (admin endpoint)
+pub async fn list_comments(
+    State(state): State<AppState>,
+    Query(params): Query<CommentQueryParams>,
+) -> Result<Json<ApiResponse<PaginatedData<Comment>>>, ApiError> {
+    let page = params.page();
+    let page_size = params.page_size();
+
+    let (comments, total) =
+        CommentRepository::find_with_filters(&state.db, page, page_size, params.status).await?;
+
+    Ok(Json(ApiResponse::success(PaginatedData::new(
+        comments, total, page, page_size,
+    ))))
+}
+
+/// PUT /api/v1/admin/comments/:id/status
+///
+/// Approve a comment, mark it as spam or move it back to pending (admin endpoint)
+pub async fn update_comment_status(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(req): Json<UpdateCommentStatusRequest>,
+) -> Result<Json<ApiResponse<Comment>>, ApiError> {
+    if !(0..=2).contains(&req.status) {
+        return Err(ApiError::ValidationError(
+            "Invalid status value. Must be 0 (pending), 1 (approved), or 2 (spam)".to_string(),
+        ));
+    }
+
+    let comment = CommentRepository::update_status(&state.db, id, CommentStatus::from(req.status))
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Comment with id {} not found", id)))?;
+
+    tracing::info!("Set comment {} status to {}", comment.id, comment.status);
+
+    Ok(Json(ApiResponse::success(comment)))
+}
+
+/// DELETE /api/v1/admin/comments/:id
+///
+/// Delete a comment together with its replies (admin endpoint)
+pub async fn delete_comment(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<ApiResponse<()>>, ApiError> {
+    let deleted = CommentRepository::delete(&state.db, id).await?;
+    if !deleted {
+        return Err(ApiError::NotFound(format!(
+            "Comment with id {} not found",
+            id
+        )));
+    }
+
+    tracing::info!("Deleted comment {}", id);
+
+    Ok(Json(ApiResponse {
+        code: 0,
+        message: "Comment deleted successfully".to_string(),
+        data: None,
+    }))
+}
@@ -1,10 +1,31 @@
 //! Data import/export handlers
 
-use axum::{extract::State, Json};
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Multipart, Path, Query, State},
+    http::{header, HeaderMap, HeaderName},
+    response::IntoResponse,
+    Json,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 
 use crate::error::{ApiError, ApiResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::data_export::{
+    DataExportQuery, DataImportQuery, DataImportReport, RowData, EXPORT_SCHEMA_VERSION,
+};
+use crate::models::import::{ImportQuery, ImportReport, ImportSource};
+use crate::models::site_export::SiteExportQuery;
+use crate::models::text::TextContent;
+use crate::repositories::user_repo::UserRepository;
+use crate::services::blog_service::BlogService;
+use crate::services::data_export_service::DataExportService;
+use crate::services::import_service::ImportService;
+use crate::services::site_export_service::SiteExportService;
+use crate::utils::crypto::{self, SealedContent};
 use crate::AppState;
+use argon2::password_hash::PasswordHash;
 
 /// Export data response
 #[derive(Debug, Serialize)]
@@ -100,13 +121,68 @@ pub struct TextExport {
     pub id: i64,
     pub name: String,
     pub intro: Option<String>,
+    /// Base64 ciphertext when `content_kdf` is set, plaintext otherwise
     pub content: String,
     pub is_encrypted: Option<bool>,
+    /// Argon2id parameters and salt used to derive the content key
+    #[serde(default)]
+    pub content_kdf: Option<String>,
+    /// XChaCha20-Poly1305 nonce of the content
+    #[serde(default)]
+    pub content_nonce: Option<String>,
+    /// Argon2 hash of the view password
+    #[serde(default)]
+    pub view_password_hash: Option<String>,
+    /// Plaintext view password from backups made before passwords were hashed (import only)
+    #[serde(default, skip_serializing)]
     pub view_password: Option<String>,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
 }
 
+impl TextExport {
+    /// Hash to store on import, hashing a legacy plaintext password if that's all the backup has
+    fn password_hash_for_import(&self) -> Result<Option<String>, ApiError> {
+        match (&self.view_password_hash, &self.view_password) {
+            (Some(hash), _) if PasswordHash::new(hash).is_ok() => Ok(Some(hash.clone())),
+            (Some(_), _) => Err(ApiError::ValidationError(
+                "view_password_hash is not a valid password hash".to_string(),
+            )),
+            (None, Some(password)) => UserRepository::hash_password(password).map(Some),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Content to store on import; protected texts from older backups are encrypted here
+    fn content_for_import(&self, master_key: Option<&str>) -> Result<TextContent, ApiError> {
+        match (&self.content_kdf, &self.content_nonce) {
+            (Some(kdf), Some(nonce)) => {
+                crypto::validate_kdf(kdf)?;
+                Ok(TextContent::Sealed(SealedContent {
+                    ciphertext: self.content.clone(),
+                    kdf: kdf.clone(),
+                    nonce: nonce.clone(),
+                }))
+            }
+            (None, None) if self.is_encrypted.unwrap_or(false) => {
+                let master_key = master_key.ok_or_else(|| {
+                    ApiError::ValidationError(
+                        "ENCRYPTION_MASTER_KEY is required to import protected texts".to_string(),
+                    )
+                })?;
+                Ok(TextContent::Sealed(crypto::seal(
+                    master_key,
+                    &self.content,
+                )?))
+            }
+            (None, None) => Ok(TextContent::Plain(self.content.clone())),
+            _ => Err(ApiError::ValidationError(
+                "content_kdf and content_nonce must be given together".to_string(),
+            )),
+        }
+    }
+}
+
 /// Import data request
 #[derive(Debug, Deserialize)]
 pub struct ImportData {
@@ -121,25 +197,83 @@ pub struct ImportData {
     pub texts: Option<Vec<TextExport>>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct ImportResult {
-    pub categories: ImportStats,
-    pub tags: ImportStats,
-    pub blogs: ImportStats,
-    pub blog_tags: ImportStats,
-    pub friend_links: ImportStats,
-    pub projects: ImportStats,
-    pub directories: ImportStats,
-    pub documents: ImportStats,
-    pub texts: ImportStats,
-}
+impl ImportData {
+    /// Rows in the column layout of the NDJSON export
+    fn into_rows(self, master_key: Option<&str>) -> Result<Vec<(&'static str, RowData)>, ApiError> {
+        fn object<T: Serialize>(value: &T) -> Result<RowData, ApiError> {
+            match serde_json::to_value(value) {
+                Ok(JsonValue::Object(mut row)) => {
+                    // Older backups have no timestamps; leave them to the defaults
+                    for key in ["created_at", "updated_at"] {
+                        if row.get(key).is_some_and(JsonValue::is_null) {
+                            row.remove(key);
+                        }
+                    }
+                    Ok(row)
+                }
+                _ => Err(ApiError::InternalError(
+                    "Failed to convert backup row".to_string(),
+                )),
+            }
+        }
+        fn rows<T: Serialize>(
+            table: &'static str,
+            items: Option<Vec<T>>,
+        ) -> Result<Vec<(&'static str, RowData)>, ApiError> {
+            items
+                .unwrap_or_default()
+                .iter()
+                .map(|item| Ok((table, object(item)?)))
+                .collect()
+        }
 
-#[derive(Debug, Serialize, Default)]
-pub struct ImportStats {
-    pub total: i64,
-    pub success: i64,
-    pub failed: i64,
-    pub errors: Vec<String>,
+        let mut all = rows("categories", self.categories)?;
+        all.extend(rows("tags", self.tags)?);
+        for blog in self.blogs.unwrap_or_default() {
+            let mut row = object(&blog)?;
+            // Generate slug if empty
+            if blog.slug.as_deref().is_none_or(str::is_empty) {
+                row.insert(
+                    "slug".to_string(),
+                    JsonValue::from(format!("blog-{}", blog.id)),
+                );
+            }
+            all.push(("blogs", row));
+        }
+        all.extend(rows("blog_tags", self.blog_tags)?);
+        all.extend(rows("friend_links", self.friend_links)?);
+        all.extend(rows("projects", self.projects)?);
+        all.extend(rows("directories", self.directories)?);
+        all.extend(rows("documents", self.documents)?);
+        for text in self.texts.unwrap_or_default() {
+            let password_hash = text.password_hash_for_import()?;
+            let content = text.content_for_import(master_key)?;
+            let (content, content_kdf, content_nonce) = content.columns();
+            let mut row = serde_json::json!({
+                "id": text.id,
+                "name": text.name,
+                "intro": text.intro,
+                "content": content,
+                "is_encrypted": content_kdf.is_some(),
+                "view_password": password_hash,
+                "content_kdf": content_kdf,
+                "content_nonce": content_nonce,
+            });
+            for (key, value) in [
+                ("created_at", &text.created_at),
+                ("updated_at", &text.updated_at),
+            ] {
+                if let Some(value) = value {
+                    row[key] = JsonValue::from(value.clone());
+                }
+            }
+            if let JsonValue::Object(row) = row {
+                all.push(("texts", row));
+            }
+        }
+
+        Ok(all)
+    }
 }
 
 /// GET /api/v1/admin/data/export
@@ -268,26 +402,52 @@ pub async fn export_data(
     )
     .collect();
 
-    // Export texts
-    let texts = sqlx::query_as::<_, (i64, String, Option<String>, String, bool, Option<String>)>(
-        "SELECT id, name, intro, content, is_encrypted, view_password FROM texts ORDER BY id",
+    // Export texts (legacy plaintext passwords are hashed so they never leave the database;
+    // protected content is exported as stored, i.e. encrypted)
+    let texts = sqlx::query_as::<
+        _,
+        (
+            i64,
+            String,
+            Option<String>,
+            String,
+            bool,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+        ),
+    >(
+        "SELECT id, name, intro, content, is_encrypted, view_password, content_kdf, content_nonce
+         FROM texts ORDER BY id",
     )
     .fetch_all(&state.db)
     .await?
     .into_iter()
     .map(
-        |(id, name, intro, content, is_encrypted, view_password)| TextExport {
-            id,
-            name,
-            intro,
-            content,
-            is_encrypted: Some(is_encrypted),
-            view_password,
-            created_at: None,
-            updated_at: None,
+        |(id, name, intro, content, is_encrypted, view_password, content_kdf, content_nonce)| {
+            let view_password_hash = match view_password {
+                Some(stored) if PasswordHash::new(&stored).is_err() => {
+                    Some(UserRepository::hash_password(&stored)?)
+                }
+                other => other,
+            };
+
+            Ok(TextExport {
+                id,
+                name,
+                intro,
+                content,
+                is_encrypted: Some(is_encrypted),
+                content_kdf,
+                content_nonce,
+                view_password_hash,
+                view_password: None,
+                created_at: None,
+                updated_at: None,
+            })
         },
     )
-    .collect();
+    .collect::<Result<Vec<_>, ApiError>>()?;
 
     let data = ExportData {
         categories,
@@ -305,326 +465,96 @@ pub async fn export_data(
     Ok(Json(ApiResponse::success(data)))
 }
 
-/// POST /api/v1/admin/data/import
-pub async fn import_data(
+/// GET /api/v1/admin/data/export/site
+///
+/// Download the blogs and documents as a zip of Markdown files with front
+/// matter; `assets=true` also copies referenced uploads into the archive,
+/// listing the ones left out (too large, unreadable) in `skipped-assets.txt`.
+pub async fn export_site(
     State(state): State<AppState>,
-    Json(data): Json<ImportData>,
-) -> Result<Json<ApiResponse<ImportResult>>, ApiError> {
-    let mut result = ImportResult {
-        categories: ImportStats::default(),
-        tags: ImportStats::default(),
-        blogs: ImportStats::default(),
-        blog_tags: ImportStats::default(),
-        friend_links: ImportStats::default(),
-        projects: ImportStats::default(),
-        directories: ImportStats::default(),
-        documents: ImportStats::default(),
-        texts: ImportStats::default(),
-    };
-
-    // Import categories
-    if let Some(categories) = data.categories {
-        result.categories.total = categories.len() as i64;
-        for cat in categories {
-            match sqlx::query(
-                r#"INSERT INTO categories (id, name, intro, logo) VALUES ($1, $2, $3, $4)
-                   ON CONFLICT (id) DO UPDATE SET name = $2, intro = $3, logo = $4"#,
-            )
-            .bind(cat.id)
-            .bind(&cat.name)
-            .bind(&cat.intro)
-            .bind(&cat.logo)
-            .execute(&state.db)
-            .await
-            {
-                Ok(_) => result.categories.success += 1,
-                Err(e) => {
-                    result.categories.failed += 1;
-                    result
-                        .categories
-                        .errors
-                        .push(format!("Category {}: {}", cat.id, e));
-                }
-            }
-        }
-        // Update sequence
-        let _ = sqlx::query(
-            "SELECT setval('categories_id_seq', (SELECT COALESCE(MAX(id), 1) FROM categories))",
-        )
-        .execute(&state.db)
-        .await;
-    }
-
-    // Import tags
-    if let Some(tags) = data.tags {
-        result.tags.total = tags.len() as i64;
-        for tag in tags {
-            match sqlx::query(
-                r#"INSERT INTO tags (id, name) VALUES ($1, $2)
-                   ON CONFLICT (id) DO UPDATE SET name = $2"#,
-            )
-            .bind(tag.id)
-            .bind(&tag.name)
-            .execute(&state.db)
-            .await
-            {
-                Ok(_) => result.tags.success += 1,
-                Err(e) => {
-                    result.tags.failed += 1;
-                    result.tags.errors.push(format!("Tag {}: {}", tag.id, e));
-                }
-            }
-        }
-        let _ =
-            sqlx::query("SELECT setval('tags_id_seq', (SELECT COALESCE(MAX(id), 1) FROM tags))")
-                .execute(&state.db)
-                .await;
-    }
-
-    // Import blogs
-    if let Some(blogs) = data.blogs {
-        result.blogs.total = blogs.len() as i64;
-        for blog in blogs {
-            // Generate slug if empty
-            let slug = blog
-                .slug
-                .clone()
-                .filter(|s| !s.is_empty())
-                .unwrap_or_else(|| format!("blog-{}", blog.id));
-
-            match sqlx::query(
-                r#"INSERT INTO blogs (id, title, slug, author, content, html, thumbnail, category_id, view_count, is_published, created_at, updated_at)
-                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, NOW(), NOW())
-                   ON CONFLICT (id) DO UPDATE SET 
-                     title = $2, slug = $3, author = $4, content = $5, html = $6,
-                     thumbnail = $7, category_id = $8, view_count = $9, is_published = $10"#,
-            )
-            .bind(blog.id)
-            .bind(&blog.title)
-            .bind(&slug)
-            .bind(&blog.author)
-            .bind(&blog.content)
-            .bind(&blog.html)
-            .bind(&blog.thumbnail)
-            .bind(blog.category_id)
-            .bind(blog.view_count)
-            .bind(blog.is_published)
-            .execute(&state.db)
-            .await
-            {
-                Ok(_) => result.blogs.success += 1,
-                Err(e) => {
-                    result.blogs.failed += 1;
-                    result.blogs.errors.push(format!("Blog {}: {}", blog.id, e));
-                }
-            }
-        }
-        let _ =
-            sqlx::query("SELECT setval('blogs_id_seq', (SELECT COALESCE(MAX(id), 1) FROM blogs))")
-                .execute(&state.db)
-                .await;
-    }
-
-    // Import blog_tags
-    if let Some(blog_tags) = data.blog_tags {
-        result.blog_tags.total = blog_tags.len() as i64;
-        for bt in blog_tags {
-            match sqlx::query(
-                r#"INSERT INTO blog_tags (blog_id, tag_id) VALUES ($1, $2)
-                   ON CONFLICT (blog_id, tag_id) DO NOTHING"#,
-            )
-            .bind(bt.blog_id)
-            .bind(bt.tag_id)
-            .execute(&state.db)
-            .await
-            {
-                Ok(_) => result.blog_tags.success += 1,
-                Err(e) => {
-                    result.blog_tags.failed += 1;
-                    result
-                        .blog_tags
-                        .errors
-                        .push(format!("BlogTag ({}, {}): {}", bt.blog_id, bt.tag_id, e));
-                }
-            }
-        }
-    }
+    Query(query): Query<SiteExportQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let data =
+        SiteExportService::export(&state.db, &state.config, query.assets.unwrap_or(false)).await?;
+    let disposition = format!(
+        "attachment; filename=\"site-export-{}.zip\"",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    );
 
-    // Import friend_links
-    if let Some(friend_links) = data.friend_links {
-        result.friend_links.total = friend_links.len() as i64;
-        for link in friend_links {
-            match sqlx::query(
-                r#"INSERT INTO friend_links (id, name, url, logo, intro, email, status)
-                   VALUES ($1, $2, $3, $4, $5, $6, $7)
-                   ON CONFLICT (id) DO UPDATE SET 
-                     name = $2, url = $3, logo = $4, intro = $5, email = $6, status = $7"#,
-            )
-            .bind(link.id)
-            .bind(&link.name)
-            .bind(&link.url)
-            .bind(&link.logo)
-            .bind(&link.intro)
-            .bind(&link.email)
-            .bind(link.status)
-            .execute(&state.db)
-            .await
-            {
-                Ok(_) => result.friend_links.success += 1,
-                Err(e) => {
-                    result.friend_links.failed += 1;
-                    result
-                        .friend_links
-                        .errors
-                        .push(format!("FriendLink {}: {}", link.id, e));
-                }
-            }
-        }
-        let _ = sqlx::query(
-            "SELECT setval('friend_links_id_seq', (SELECT COALESCE(MAX(id), 1) FROM friend_links))",
-        )
-        .execute(&state.db)
-        .await;
-    }
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        data,
+    ))
+}
 
-    // Import projects
-    if let Some(projects) = data.projects {
-        result.projects.total = projects.len() as i64;
-        for proj in projects {
-            match sqlx::query(
-                r#"INSERT INTO projects (id, name, description, logo, github_url, preview_url, download_url)
-                   VALUES ($1, $2, $3, $4, $5, $6, $7)
-                   ON CONFLICT (id) DO UPDATE SET 
-                     name = $2, description = $3, logo = $4, github_url = $5, preview_url = $6, download_url = $7"#,
-            )
-            .bind(proj.id)
-            .bind(&proj.name)
-            .bind(&proj.description)
-            .bind(&proj.logo)
-            .bind(&proj.github_url)
-            .bind(&proj.preview_url)
-            .bind(&proj.download_url)
-            .execute(&state.db)
-            .await
-            {
-                Ok(_) => result.projects.success += 1,
-                Err(e) => {
-                    result.projects.failed += 1;
-                    result.projects.errors.push(format!("Project {}: {}", proj.id, e));
-                }
-            }
-        }
-        let _ = sqlx::query(
-            "SELECT setval('projects_id_seq', (SELECT COALESCE(MAX(id), 1) FROM projects))",
-        )
-        .execute(&state.db)
-        .await;
-    }
+/// GET /api/v1/admin/data/export/ndjson
+///
+/// Stream a versioned NDJSON export of every table; `since` limits tables
+/// that track modification time to rows changed from then on.
+pub async fn export_ndjson(
+    State(state): State<AppState>,
+    Query(query): Query<DataExportQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let lines = DataExportService::export(&state.db, query.since).await?;
+    let disposition = format!(
+        "attachment; filename=\"backup-{}.ndjson\"",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    );
 
-    // Import directories (parent_id NULL first)
-    if let Some(mut directories) = data.directories {
-        // Sort: parent_id NULL first
-        directories.sort_by(|a, b| match (&a.parent_id, &b.parent_id) {
-            (None, Some(_)) => std::cmp::Ordering::Less,
-            (Some(_), None) => std::cmp::Ordering::Greater,
-            _ => a.id.cmp(&b.id),
-        });
-
-        result.directories.total = directories.len() as i64;
-        for dir in directories {
-            match sqlx::query(
-                r#"INSERT INTO directories (id, name, intro, parent_id)
-                   VALUES ($1, $2, $3, $4)
-                   ON CONFLICT (id) DO UPDATE SET name = $2, intro = $3, parent_id = $4"#,
-            )
-            .bind(dir.id)
-            .bind(&dir.name)
-            .bind(&dir.intro)
-            .bind(dir.parent_id)
-            .execute(&state.db)
-            .await
-            {
-                Ok(_) => result.directories.success += 1,
-                Err(e) => {
-                    result.directories.failed += 1;
-                    result
-                        .directories
-                        .errors
-                        .push(format!("Directory {}: {}", dir.id, e));
-                }
-            }
-        }
-        let _ = sqlx::query(
-            "SELECT setval('directories_id_seq', (SELECT COALESCE(MAX(id), 1) FROM directories))",
-        )
-        .execute(&state.db)
-        .await;
-    }
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+            (
+                HeaderName::from_static("x-export-schema-version"),
+                EXPORT_SCHEMA_VERSION.to_string(),
+            ),
+        ],
+        Body::from_stream(lines),
+    ))
+}
 
-    // Import documents
-    if let Some(documents) = data.documents {
-        result.documents.total = documents.len() as i64;
-        for doc in documents {
-            match sqlx::query(
-                r#"INSERT INTO documents (id, name, filename, content, directory_id)
-                   VALUES ($1, $2, $3, $4, $5)
-                   ON CONFLICT (id) DO UPDATE SET name = $2, filename = $3, content = $4, directory_id = $5"#,
-            )
-            .bind(doc.id)
-            .bind(&doc.name)
-            .bind(&doc.filename)
-            .bind(&doc.content)
-            .bind(doc.directory_id)
-            .execute(&state.db)
-            .await
-            {
-                Ok(_) => result.documents.success += 1,
-                Err(e) => {
-                    result.documents.failed += 1;
-                    result.documents.errors.push(format!("Document {}: {}", doc.id, e));
-                }
-            }
-        }
-        let _ = sqlx::query(
-            "SELECT setval('documents_id_seq', (SELECT COALESCE(MAX(id), 1) FROM documents))",
+/// POST /api/v1/admin/data/import
+///
+/// Import an NDJSON export (`Content-Type: application/x-ndjson`) or a
+/// legacy JSON backup in a single transaction; `policy` decides what
+/// happens to rows that already exist.
+pub async fn import_data(
+    State(state): State<AppState>,
+    Query(query): Query<DataImportQuery>,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<Json<ApiResponse<DataImportReport>>, ApiError> {
+    let policy = query.policy.unwrap_or_default();
+    let is_ndjson = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/x-ndjson"));
+
+    let report = if is_ndjson {
+        DataExportService::import_ndjson(
+            &state.db,
+            state.config.encryption.master_key.as_deref(),
+            body.into_data_stream(),
+            policy,
         )
-        .execute(&state.db)
-        .await;
-    }
-
-    // Import texts
-    if let Some(texts) = data.texts {
-        result.texts.total = texts.len() as i64;
-        for text in texts {
-            match sqlx::query(
-                r#"INSERT INTO texts (id, name, intro, content, is_encrypted, view_password)
-                   VALUES ($1, $2, $3, $4, $5, $6)
-                   ON CONFLICT (id) DO UPDATE SET name = $2, intro = $3, content = $4, is_encrypted = $5, view_password = $6"#,
-            )
-            .bind(text.id)
-            .bind(&text.name)
-            .bind(&text.intro)
-            .bind(&text.content)
-            .bind(text.is_encrypted.unwrap_or(false))
-            .bind(&text.view_password)
-            .execute(&state.db)
+        .await?
+    } else {
+        let limit = state.config.server.max_body_size_mb * 1024 * 1024;
+        let bytes = to_bytes(body, limit)
             .await
-            {
-                Ok(_) => result.texts.success += 1,
-                Err(e) => {
-                    result.texts.failed += 1;
-                    result.texts.errors.push(format!("Text {}: {}", text.id, e));
-                }
-            }
-        }
-        let _ =
-            sqlx::query("SELECT setval('texts_id_seq', (SELECT COALESCE(MAX(id), 1) FROM texts))")
-                .execute(&state.db)
-                .await;
-    }
+            .map_err(|e| ApiError::BadRequest(format!("Failed to read import: {}", e)))?;
+        let data: ImportData = serde_json::from_slice(&bytes)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid backup: {}", e)))?;
+        let master_key = state.config.encryption.master_key.as_deref();
+        let rows = data.into_rows(master_key)?;
+        DataExportService::import_rows(&state.db, master_key, rows, policy).await?
+    };
 
-    tracing::info!("Imported data: {:?}", result);
-    Ok(Json(ApiResponse::success(result)))
+    Ok(Json(ApiResponse::success(report)))
 }
 
 /// SQL import request
@@ -641,6 +571,63 @@ pub struct SqlImportResult {
     pub errors: Vec<String>,
 }
 
+/// POST /api/v1/admin/data/import/:source
+///
+/// Import posts from a WordPress WXR file, a zip of front matter Markdown or a
+/// Ghost JSON export, sent as the multipart field "file". Runs as a dry run
+/// unless `dry_run=false` is given.
+pub async fn import_posts(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(source): Path<String>,
+    Query(query): Query<ImportQuery>,
+    mut multipart: Multipart,
+) -> Result<Json<ApiResponse<ImportReport>>, ApiError> {
+    let source = ImportSource::parse(&source).ok_or_else(|| {
+        ApiError::BadRequest(format!(
+            "Unknown import source '{}', expected wordpress, markdown or ghost",
+            source
+        ))
+    })?;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::FileUploadError(format!("Failed to read multipart: {}", e)))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| ApiError::FileUploadError(format!("Failed to read file data: {}", e)))?;
+
+        let report =
+            ImportService::import(&state.db, source, &data, query.dry_run(), auth_user.user_id)
+                .await?;
+
+        if let Some(blog) = report.posts.iter().find(|post| post.blog_id.is_some()) {
+            if let Err(e) = BlogService::invalidate_blog_cache(
+                &state.cache,
+                blog.blog_id.unwrap_or_default(),
+                blog.slug.as_deref(),
+            )
+            .await
+            {
+                tracing::warn!("Failed to invalidate blog cache: {}", e);
+            }
+        }
+
+        return Ok(Json(ApiResponse::success(report)));
+    }
+
+    Err(ApiError::FileUploadError(
+        "No file field found in request".to_string(),
+    ))
+}
+
 /// POST /api/v1/admin/data/import-sql
 ///
 /// Execute raw SQL statements for data import
@@ -6,9 +6,11 @@ use axum::{
 };
 
 use crate::error::{ApiError, ApiResponse};
+use crate::middleware::auth::AuthUser;
 use crate::models::document::{
     CreateDocumentRequest, DocumentListItem, DocumentResponse, UpdateDocumentRequest,
 };
+use crate::models::revision::RevisionActor;
 use crate::repositories::document_repo::DocumentRepository;
 use crate::services::cache_service::cache_keys;
 use crate::utils::markdown::render_markdown;
@@ -83,6 +85,7 @@ pub async fn create_document(
 /// Update an existing document (admin endpoint)
 pub async fn update_document(
     State(state): State<AppState>,
+    auth_user: AuthUser,
     Path(id): Path<i64>,
     Json(req): Json<UpdateDocumentRequest>,
 ) -> Result<Json<ApiResponse<DocumentResponse>>, ApiError> {
@@ -110,7 +113,8 @@ pub async fn update_document(
     }
 
     // Update document (directory validation is done in repository)
-    let document = DocumentRepository::update(&state.db, id, &req)
+    let actor = RevisionActor::admin(auth_user.user_id, &auth_user.username);
+    let document = DocumentRepository::update(&state.db, id, &req, &actor)
         .await?
         .ok_or_else(|| ApiError::NotFound(format!("Document with id {} not found", id)))?;
 
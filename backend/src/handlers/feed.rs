@@ -0,0 +1,62 @@
+//! Feed handlers
+
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+};
+
+use crate::error::ApiError;
+use crate::services::feed_service::{FeedFormat, FeedScope, FeedService};
+use crate::AppState;
+
+/// Render a feed and wrap it with the matching Content-Type
+async fn feed_response(
+    state: &AppState,
+    format: FeedFormat,
+    scope: FeedScope,
+) -> Result<impl IntoResponse, ApiError> {
+    let body = FeedService::get_feed(&state.db, &state.cache, format, scope).await?;
+    Ok(([(header::CONTENT_TYPE, format.content_type())], body))
+}
+
+/// GET /api/v1/feed.xml
+///
+/// RSS 2.0 feed of the latest published blogs (public endpoint)
+pub async fn rss_feed(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    feed_response(&state, FeedFormat::Rss, FeedScope::All).await
+}
+
+/// GET /api/v1/atom.xml
+///
+/// Atom 1.0 feed of the latest published blogs (public endpoint)
+pub async fn atom_feed(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    feed_response(&state, FeedFormat::Atom, FeedScope::All).await
+}
+
+/// GET /api/v1/feed.json
+///
+/// JSON Feed 1.1 of the latest published blogs (public endpoint)
+pub async fn json_feed(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    feed_response(&state, FeedFormat::Json, FeedScope::All).await
+}
+
+/// GET /api/v1/categories/:id/feed.xml
+///
+/// RSS 2.0 feed of the latest published blogs in a category (public endpoint)
+pub async fn category_rss_feed(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    feed_response(&state, FeedFormat::Rss, FeedScope::Category(id)).await
+}
+
+/// GET /api/v1/tags/:id/feed.xml
+///
+/// RSS 2.0 feed of the latest published blogs with a tag (public endpoint)
+pub async fn tag_rss_feed(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    feed_response(&state, FeedFormat::Rss, FeedScope::Tag(id)).await
+}
@@ -8,34 +8,27 @@ use axum::{
 };
 
 use crate::error::{ApiError, ApiResponse, PaginatedData};
-use crate::models::file::{CreateFileRequest, FileQueryParams, FileResponse};
+use crate::models::file::{
+    AbortUploadRequest, CompleteUploadRequest, FileQueryParams, FileResponse, OrphanPurgeSummary,
+    OrphanReport, OrphanScanParams, PresignUploadRequest, PresignedUpload,
+};
 use crate::repositories::file_repo::FileRepository;
-use crate::repositories::site_config_repo::SiteConfigRepo;
-use crate::services::s3_service::S3Service;
+use crate::repositories::file_variant_repo::FileVariantRepository;
+use crate::services::direct_upload_service::DirectUploadService;
+use crate::services::file_gc_service::FileGcService;
+use crate::services::file_service::FileService;
+use crate::services::storage::{StorageBackend, StorageService};
 use crate::AppState;
 
 /// POST /api/v1/admin/files/upload
 ///
-/// Upload a file to S3 storage (admin endpoint)
+/// Upload a file to the configured storage backend (admin endpoint)
 pub async fn upload_file(
     State(state): State<AppState>,
     mut multipart: Multipart,
 ) -> Result<Json<ApiResponse<FileResponse>>, ApiError> {
-    // Get S3 config from database
-    let db_s3_config = SiteConfigRepo::get_s3_config(&state.db).await?;
-    let s3_config = crate::config::S3Config {
-        endpoint: db_s3_config.endpoint,
-        region: db_s3_config.region,
-        bucket: db_s3_config.bucket,
-        access_key: db_s3_config.access_key,
-        secret_key: db_s3_config.secret_key,
-        public_url: db_s3_config.public_url,
-    };
-
-    // Initialize S3 service
-    let s3_service = S3Service::new(&s3_config)
-        .await
-        .map_err(|e| ApiError::FileUploadError(format!("Failed to initialize S3: {}", e)))?;
+    // Storage backend selected in site config
+    let storage = StorageService::active(&state.db, &state.config).await?;
 
     // Process multipart form data
     while let Some(field) = multipart
@@ -67,48 +60,16 @@ pub async fn upload_file(
             .await
             .map_err(|e| ApiError::FileUploadError(format!("Failed to read file data: {}", e)))?;
 
-        let file_size = data.len() as i64;
-
-        // Upload to S3
-        let upload_result = s3_service
-            .upload_file(data.to_vec(), &original_filename, &content_type)
-            .await?;
-
-        // Extract file type from content type (e.g., "image/png" -> "image")
-        let file_type = content_type.split('/').next().map(|s| s.to_string());
-
-        // Generate filename from object key
-        let filename = upload_result
-            .object_key
-            .split('/')
-            .last()
-            .unwrap_or(&upload_result.object_key)
-            .to_string();
-
-        // Create file record in database
-        let create_req = CreateFileRequest {
-            filename,
-            original_filename: Some(original_filename.clone()),
-            file_type,
-            file_size: Some(file_size),
-            url: upload_result.url,
-            thumbnail_url: None, // TODO: Generate thumbnail for images
-            width: None,         // TODO: Extract dimensions for images
-            height: None,
-            bucket_name: Some(upload_result.bucket),
-            object_key: Some(upload_result.object_key),
-        };
-
-        let file = FileRepository::create(&state.db, &create_req).await?;
-
-        tracing::info!(
-            "File uploaded: {} (id: {}, size: {} bytes)",
-            original_filename,
-            file.id,
-            file_size
-        );
+        let file = FileService::upload(
+            &state.db,
+            storage.as_ref(),
+            &original_filename,
+            &content_type,
+            data.to_vec(),
+        )
+        .await?;
 
-        return Ok(Json(ApiResponse::success(FileResponse::from(file))));
+        return Ok(Json(ApiResponse::success(file)));
     }
 
     Err(ApiError::FileUploadError(
@@ -116,6 +77,44 @@ pub async fn upload_file(
     ))
 }
 
+/// POST /api/v1/admin/files/presign
+///
+/// Issue presigned URLs for uploading a large file straight to S3 (admin endpoint)
+pub async fn presign_upload(
+    State(state): State<AppState>,
+    Json(req): Json<PresignUploadRequest>,
+) -> Result<Json<ApiResponse<PresignedUpload>>, ApiError> {
+    let upload = DirectUploadService::presign(&state.db, &state.config, &req).await?;
+    Ok(Json(ApiResponse::success(upload)))
+}
+
+/// POST /api/v1/admin/files/presign/complete
+///
+/// Verify a presigned upload and record the file (admin endpoint)
+pub async fn complete_direct_upload(
+    State(state): State<AppState>,
+    Json(req): Json<CompleteUploadRequest>,
+) -> Result<Json<ApiResponse<FileResponse>>, ApiError> {
+    let file = DirectUploadService::complete(&state.db, &state.config, &req).await?;
+    Ok(Json(ApiResponse::success(file)))
+}
+
+/// POST /api/v1/admin/files/presign/abort
+///
+/// Abandon a multipart presigned upload (admin endpoint)
+pub async fn abort_direct_upload(
+    State(state): State<AppState>,
+    Json(req): Json<AbortUploadRequest>,
+) -> Result<Json<ApiResponse<()>>, ApiError> {
+    DirectUploadService::abort(&state.db, &state.config, &req).await?;
+
+    Ok(Json(ApiResponse {
+        code: 0,
+        message: "Upload aborted".to_string(),
+        data: None,
+    }))
+}
+
 /// GET /api/v1/admin/files
 ///
 /// Get paginated list of files (admin endpoint)
@@ -129,7 +128,7 @@ pub async fn list_files(
     let (files, total) =
         FileRepository::find_all(&state.db, page, page_size, params.file_type.as_deref()).await?;
 
-    let file_responses: Vec<FileResponse> = files.into_iter().map(FileResponse::from).collect();
+    let file_responses = FileService::to_responses(&state.db, files).await?;
 
     tracing::debug!(
         "Retrieved {} files (page {}, total {})",
@@ -148,43 +147,55 @@ pub async fn list_files(
 
 /// DELETE /api/v1/admin/files/:id
 ///
-/// Delete a file (admin endpoint)
+/// Delete a file (admin endpoint). Files shared by several identical uploads
+/// lose one reference; the objects are removed with the last one.
 pub async fn delete_file(
     State(state): State<AppState>,
     Path(id): Path<i64>,
 ) -> Result<Json<ApiResponse<()>>, ApiError> {
-    // Find the file first to get the object key
+    // Find the file and its variants first, they go with the record
     let file = FileRepository::find_by_id(&state.db, id)
         .await?
         .ok_or_else(|| ApiError::NotFound(format!("File with id {} not found", id)))?;
+    let variants = FileVariantRepository::find_by_file_id(&state.db, id).await?;
 
-    // Delete from S3 if object key exists
-    if let Some(object_key) = &file.object_key {
-        // Get S3 config from database
-        let db_s3_config = SiteConfigRepo::get_s3_config(&state.db).await?;
-        let s3_config = crate::config::S3Config {
-            endpoint: db_s3_config.endpoint,
-            region: db_s3_config.region,
-            bucket: db_s3_config.bucket,
-            access_key: db_s3_config.access_key,
-            secret_key: db_s3_config.secret_key,
-            public_url: db_s3_config.public_url,
-        };
-
-        let s3_service = S3Service::new(&s3_config)
-            .await
-            .map_err(|e| ApiError::FileUploadError(format!("Failed to initialize S3: {}", e)))?;
+    let remaining = FileRepository::release(&state.db, id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("File with id {} not found", id)))?;
+    if remaining > 0 {
+        tracing::info!(
+            "File reference released: {} (id: {}, {} references left)",
+            file.original_filename
+                .unwrap_or_else(|| file.filename.clone()),
+            id,
+            remaining
+        );
 
-        // Try to delete from S3, but don't fail if it doesn't exist
-        if let Err(e) = s3_service.delete_file(object_key).await {
-            tracing::warn!("Failed to delete file from S3: {}", e);
-        }
+        return Ok(Json(ApiResponse {
+            code: 0,
+            message: format!(
+                "File reference removed, still used by {} other upload(s)",
+                remaining
+            ),
+            data: None,
+        }));
     }
 
-    // Delete from database
-    let deleted = FileRepository::delete(&state.db, id).await?;
-    if !deleted {
-        return Err(ApiError::NotFound(format!("File with id {} not found", id)));
+    // Last reference: delete the original and its variants from the backend holding them
+    if let Some(object_key) = &file.object_key {
+        let backend = StorageBackend::parse(&file.storage_backend).ok_or_else(|| {
+            ApiError::InternalError(format!(
+                "Unknown storage backend '{}'",
+                file.storage_backend
+            ))
+        })?;
+        let storage = StorageService::open(&state.db, &state.config, backend).await?;
+
+        // Try to delete, but don't fail if the objects don't exist
+        let object_keys: Vec<String> = std::iter::once(object_key.clone())
+            .chain(variants.into_iter().map(|v| v.object_key))
+            .collect();
+        FileService::delete_objects(storage.as_ref(), &object_keys).await;
     }
 
     tracing::info!(
@@ -200,3 +211,26 @@ pub async fn delete_file(
         data: None,
     }))
 }
+
+/// GET /api/v1/admin/files/orphans?min_age_hours=24
+///
+/// Dry run of the orphaned file scan (admin endpoint)
+pub async fn scan_orphaned_files(
+    State(state): State<AppState>,
+    Query(params): Query<OrphanScanParams>,
+) -> Result<Json<ApiResponse<OrphanReport>>, ApiError> {
+    let report = FileGcService::scan(&state.db, &state.config, params.min_age_hours()).await?;
+    Ok(Json(ApiResponse::success(report)))
+}
+
+/// POST /api/v1/admin/files/orphans/purge
+///
+/// Scan again and delete the orphans found (admin endpoint)
+pub async fn purge_orphaned_files(
+    State(state): State<AppState>,
+    Json(params): Json<OrphanScanParams>,
+) -> Result<Json<ApiResponse<OrphanPurgeSummary>>, ApiError> {
+    let (_, summary) =
+        FileGcService::purge(&state.db, &state.config, params.min_age_hours()).await?;
+    Ok(Json(ApiResponse::success(summary)))
+}
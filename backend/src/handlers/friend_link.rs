@@ -7,11 +7,14 @@ use axum::{
 use std::time::Duration;
 
 use crate::error::{ApiError, ApiResponse};
+use crate::middleware::client_ip::ClientIp;
 use crate::models::friend_link::{
-    CreateFriendLinkRequest, FriendLinkResponse, UpdateFriendLinkRequest,
+    ApplyFriendLinkRequest, CreateFriendLinkRequest, FriendLinkResponse, FriendLinkStatus,
+    UpdateFriendLinkRequest, UpdateFriendLinkStatusRequest,
 };
 use crate::repositories::friend_link_repo::FriendLinkRepository;
 use crate::services::cache_service::cache_keys;
+use crate::services::friend_link_service::FriendLinkService;
 use crate::AppState;
 
 const CACHE_TTL: Duration = Duration::from_secs(10 * 60); // 10 minutes
@@ -46,6 +49,27 @@ pub async fn list_friend_links(
     Ok(Json(ApiResponse::success(responses)))
 }
 
+/// POST /api/v1/friend-links/apply
+///
+/// Apply for a friend link (public endpoint, rate limited per IP)
+/// Always created as pending; the applicant's page is checked for a backlink in the background
+pub async fn apply_friend_link(
+    State(state): State<AppState>,
+    client_ip: ClientIp,
+    Json(req): Json<ApplyFriendLinkRequest>,
+) -> Result<Json<ApiResponse<FriendLinkResponse>>, ApiError> {
+    let link = FriendLinkService::apply(
+        &state.db,
+        &state.cache,
+        state.page_fetcher.clone(),
+        &client_ip.to_string(),
+        &req,
+    )
+    .await?;
+
+    Ok(Json(ApiResponse::success(FriendLinkResponse::from(link))))
+}
+
 /// GET /api/v1/admin/friend-links
 ///
 /// Get all friend links (admin endpoint)
@@ -172,6 +196,56 @@ pub async fn update_friend_link(
     Ok(Json(ApiResponse::success(FriendLinkResponse::from(link))))
 }
 
+/// PUT /api/v1/admin/friend-links/:id/status
+///
+/// Approve or reject a friend link (admin endpoint)
+/// The response includes the latest backlink check as evidence
+pub async fn update_friend_link_status(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(req): Json<UpdateFriendLinkStatusRequest>,
+) -> Result<Json<ApiResponse<FriendLinkResponse>>, ApiError> {
+    if !(0..=2).contains(&req.status) {
+        return Err(ApiError::ValidationError(
+            "Invalid status value. Must be 0 (pending), 1 (approved), or 2 (rejected)".to_string(),
+        ));
+    }
+
+    let link = FriendLinkRepository::update_status(&state.db, id, req.status)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Friend link with id {} not found", id)))?;
+
+    // Invalidate cache
+    let _ = state.cache.delete(&cache_keys::friend_link_list()).await;
+
+    tracing::info!(
+        "Set friend link {} status to {:?} (backlink found: {:?})",
+        link.id,
+        FriendLinkStatus::from(link.status),
+        link.backlink_found
+    );
+
+    Ok(Json(ApiResponse::success(FriendLinkResponse::from(link))))
+}
+
+/// POST /api/v1/admin/friend-links/:id/check-backlink
+///
+/// Re-run the backlink check for a friend link now (admin endpoint)
+pub async fn check_friend_link_backlink(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<ApiResponse<FriendLinkResponse>>, ApiError> {
+    let link = FriendLinkRepository::find_by_id(&state.db, id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Friend link with id {} not found", id)))?;
+
+    let link = FriendLinkService::check_backlink(&state.db, state.page_fetcher.as_ref(), &link)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Friend link with id {} not found", id)))?;
+
+    Ok(Json(ApiResponse::success(FriendLinkResponse::from(link))))
+}
+
 /// DELETE /api/v1/admin/friend-links/:id
 ///
 /// Delete a friend link (admin endpoint)
@@ -0,0 +1,42 @@
+//! Dead-link check handlers
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+
+use crate::error::{ApiError, ApiResponse};
+use crate::models::link_check::{BrokenLinkQuery, LinkCheck};
+use crate::repositories::link_check_repo::LinkCheckRepository;
+use crate::services::link_check_service::LinkCheckService;
+use crate::AppState;
+
+/// GET /api/v1/admin/link-checks/broken?min_failures=1
+///
+/// Report of links whose latest checks failed (admin endpoint)
+pub async fn list_broken_links(
+    State(state): State<AppState>,
+    Query(query): Query<BrokenLinkQuery>,
+) -> Result<Json<ApiResponse<Vec<LinkCheck>>>, ApiError> {
+    let links = LinkCheckRepository::find_broken(&state.db, query.min_failures()).await?;
+    Ok(Json(ApiResponse::success(links)))
+}
+
+/// POST /api/v1/admin/link-checks/run
+///
+/// Start a dead-link check in the background without waiting for the schedule (admin endpoint)
+pub async fn run_link_check(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<()>>, ApiError> {
+    tokio::spawn(async move {
+        if let Err(e) = LinkCheckService::run_once(&state.db, state.page_fetcher.as_ref()).await {
+            tracing::warn!("Manual dead-link check failed: {}", e);
+        }
+    });
+
+    Ok(Json(ApiResponse {
+        code: 0,
+        message: "Link check started".to_string(),
+        data: None,
+    }))
+}
@@ -0,0 +1,28 @@
+//! Login event handlers
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+
+use crate::error::{ApiError, ApiResponse, PaginatedData};
+use crate::models::login_event::{LoginEvent, LoginEventQuery};
+use crate::repositories::login_event_repo::LoginEventRepository;
+use crate::AppState;
+
+/// GET /api/v1/admin/login-events
+///
+/// Paginated failed logins, filterable by username and IP (admin endpoint)
+pub async fn list_login_events(
+    State(state): State<AppState>,
+    Query(query): Query<LoginEventQuery>,
+) -> Result<Json<ApiResponse<PaginatedData<LoginEvent>>>, ApiError> {
+    let (events, total) = LoginEventRepository::find_with_filters(&state.db, &query).await?;
+
+    Ok(Json(ApiResponse::success(PaginatedData::new(
+        events,
+        total,
+        query.page(),
+        query.page_size(),
+    ))))
+}
@@ -1,29 +1,35 @@
-use axum::{extract::State, http::HeaderMap, Json};
-use chrono::{DateTime, Utc};
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use chrono::Utc;
 
-use crate::error::{ApiError, ApiResponse};
+use crate::error::{ApiError, ApiResponse, PaginatedData};
 use crate::mcp::auth::{
     generate_mcp_token, get_mcp_runtime_config, hash_secret, invalidate_mcp_runtime_config_cache,
-    mask_token,
+    token_last_four, token_lookup_prefix,
+};
+use crate::models::mcp::{
+    CreateMcpTokenRequest, CreateMcpTokenResponse, McpAuditLog, McpAuditLogQuery, McpScope,
+    McpSettingsResponse, McpToken, McpTokenResponse, RotateMcpTokenResponse,
+    UpdateMcpSettingsRequest,
 };
-use crate::models::mcp::{McpSettingsResponse, RotateMcpTokenResponse, UpdateMcpSettingsRequest};
+use crate::repositories::mcp_audit_repo::McpAuditRepository;
+use crate::repositories::mcp_token_repo::McpTokenRepository;
 use crate::repositories::site_config_repo::SiteConfigRepo;
 use crate::AppState;
 
+/// Name of the token managed by the single-token settings endpoints
+const DEFAULT_TOKEN_NAME: &str = "default";
+
 pub async fn get_mcp_settings(
     State(state): State<AppState>,
     headers: HeaderMap,
 ) -> Result<Json<ApiResponse<McpSettingsResponse>>, ApiError> {
-    let config = get_mcp_runtime_config(&state).await?;
-    let endpoint = resolve_mcp_endpoint(&headers, &state);
-
-    Ok(Json(ApiResponse::success(McpSettingsResponse {
-        enabled: config.enabled,
-        endpoint,
-        token_initialized: config.token_initialized(),
-        token_masked: config.token_last_four.as_deref().map(mask_token),
-        token_last_rotated_at: parse_rotated_at(config.token_rotated_at.as_deref()),
-    })))
+    Ok(Json(ApiResponse::success(
+        settings_response(&state, &headers).await?,
+    )))
 }
 
 pub async fn update_mcp_settings(
@@ -40,59 +46,175 @@ pub async fn update_mcp_settings(
 
     invalidate_mcp_runtime_config_cache(&state.cache).await;
 
-    let config = get_mcp_runtime_config(&state).await?;
-    let endpoint = resolve_mcp_endpoint(&headers, &state);
-
-    Ok(Json(ApiResponse::success(McpSettingsResponse {
-        enabled: config.enabled,
-        endpoint,
-        token_initialized: config.token_initialized(),
-        token_masked: config.token_last_four.as_deref().map(mask_token),
-        token_last_rotated_at: parse_rotated_at(config.token_rotated_at.as_deref()),
-    })))
+    Ok(Json(ApiResponse::success(
+        settings_response(&state, &headers).await?,
+    )))
 }
 
+/// POST /api/v1/admin/mcp/token/rotate
+///
+/// Replace the "default" token with a new one holding every scope
 pub async fn rotate_mcp_token(
     State(state): State<AppState>,
     headers: HeaderMap,
 ) -> Result<Json<ApiResponse<RotateMcpTokenResponse>>, ApiError> {
-    let token = generate_mcp_token();
-    let token_hash = hash_secret(&token)?;
-    let last_four = token
-        .chars()
-        .rev()
-        .take(4)
-        .collect::<String>()
-        .chars()
-        .rev()
-        .collect::<String>();
-    let rotated_at = Utc::now();
-
-    SiteConfigRepo::batch_update(
-        &state.db,
-        &[
-            ("mcp_token_hash".to_string(), token_hash),
-            ("mcp_token_last_four".to_string(), last_four.clone()),
-            ("mcp_token_rotated_at".to_string(), rotated_at.to_rfc3339()),
-        ],
-    )
-    .await?;
+    if let Some(existing) = McpTokenRepository::find_by_name(&state.db, DEFAULT_TOKEN_NAME).await? {
+        McpTokenRepository::delete(&state.db, existing.id).await?;
+    }
 
-    invalidate_mcp_runtime_config_cache(&state.cache).await;
+    let (token, record) = issue_token(&state, DEFAULT_TOKEN_NAME, &McpScope::ALL, None).await?;
+    let info = McpTokenResponse::from(record);
 
     Ok(Json(ApiResponse::success(RotateMcpTokenResponse {
         endpoint: resolve_mcp_endpoint(&headers, &state),
         token,
-        token_masked: mask_token(&last_four),
-        token_last_rotated_at: rotated_at,
+        token_masked: info.token_masked,
+        token_last_rotated_at: info.created_at.unwrap_or_else(Utc::now),
     })))
 }
 
-fn parse_rotated_at(value: Option<&str>) -> Option<DateTime<Utc>> {
-    value
-        .filter(|v| !v.is_empty())
-        .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
-        .map(|dt| dt.with_timezone(&Utc))
+/// GET /api/v1/admin/mcp/tokens
+///
+/// List all MCP tokens (admin endpoint)
+pub async fn list_mcp_tokens(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<McpTokenResponse>>>, ApiError> {
+    let tokens = McpTokenRepository::find_all(&state.db).await?;
+
+    Ok(Json(ApiResponse::success(
+        tokens.into_iter().map(McpTokenResponse::from).collect(),
+    )))
+}
+
+/// POST /api/v1/admin/mcp/tokens
+///
+/// Create a named MCP token; the secret is only returned in this response
+pub async fn create_mcp_token(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<CreateMcpTokenRequest>,
+) -> Result<Json<ApiResponse<CreateMcpTokenResponse>>, ApiError> {
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::ValidationError(
+            "Token name is required".to_string(),
+        ));
+    }
+    if req.scopes.is_empty() {
+        return Err(ApiError::ValidationError(
+            "At least one scope is required".to_string(),
+        ));
+    }
+    if req.expires_at.is_some_and(|at| at <= Utc::now()) {
+        return Err(ApiError::ValidationError(
+            "Expiry must be in the future".to_string(),
+        ));
+    }
+    if McpTokenRepository::find_by_name(&state.db, name)
+        .await?
+        .is_some()
+    {
+        return Err(ApiError::ValidationError(format!(
+            "MCP token '{}' already exists",
+            name
+        )));
+    }
+
+    let (token, record) = issue_token(&state, name, &req.scopes, req.expires_at).await?;
+
+    tracing::info!("Created MCP token: {} (id: {})", record.name, record.id);
+
+    Ok(Json(ApiResponse::success(CreateMcpTokenResponse {
+        endpoint: resolve_mcp_endpoint(&headers, &state),
+        token,
+        info: McpTokenResponse::from(record),
+    })))
+}
+
+/// DELETE /api/v1/admin/mcp/tokens/:id
+///
+/// Revoke an MCP token (admin endpoint)
+pub async fn delete_mcp_token(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<ApiResponse<()>>, ApiError> {
+    let deleted = McpTokenRepository::delete(&state.db, id).await?;
+    if !deleted {
+        return Err(ApiError::NotFound(format!(
+            "MCP token with id {} not found",
+            id
+        )));
+    }
+
+    tracing::info!("Revoked MCP token (id: {})", id);
+
+    Ok(Json(ApiResponse {
+        code: 0,
+        message: "MCP token revoked successfully".to_string(),
+        data: None,
+    }))
+}
+
+/// GET /api/v1/admin/mcp/audit-logs
+///
+/// Paginated MCP tool call audit log, filterable by token, tool and status (admin endpoint)
+pub async fn list_mcp_audit_logs(
+    State(state): State<AppState>,
+    Query(query): Query<McpAuditLogQuery>,
+) -> Result<Json<ApiResponse<PaginatedData<McpAuditLog>>>, ApiError> {
+    let (logs, total) = McpAuditRepository::find_with_filters(&state.db, &query).await?;
+
+    Ok(Json(ApiResponse::success(PaginatedData::new(
+        logs,
+        total,
+        query.page(),
+        query.page_size(),
+    ))))
+}
+
+/// Generate, hash and store a new token, returning its secret
+async fn issue_token(
+    state: &AppState,
+    name: &str,
+    scopes: &[McpScope],
+    expires_at: Option<chrono::DateTime<Utc>>,
+) -> Result<(String, McpToken), ApiError> {
+    let token = generate_mcp_token();
+    let token_hash = hash_secret(&token)?;
+    let prefix = token_lookup_prefix(&token).unwrap_or_default();
+
+    let record = McpTokenRepository::create(
+        &state.db,
+        name,
+        prefix,
+        &token_hash,
+        &token_last_four(&token),
+        scopes,
+        expires_at,
+    )
+    .await?;
+
+    Ok((token, record))
+}
+
+async fn settings_response(
+    state: &AppState,
+    headers: &HeaderMap,
+) -> Result<McpSettingsResponse, ApiError> {
+    let config = get_mcp_runtime_config(state).await?;
+    let default_token = McpTokenRepository::find_by_name(&state.db, DEFAULT_TOKEN_NAME)
+        .await?
+        .map(McpTokenResponse::from);
+
+    Ok(McpSettingsResponse {
+        enabled: config.enabled,
+        endpoint: resolve_mcp_endpoint(headers, state),
+        token_initialized: McpTokenRepository::any_active(&state.db).await?,
+        token_masked: default_token
+            .as_ref()
+            .map(|token| token.token_masked.clone()),
+        token_last_rotated_at: default_token.and_then(|token| token.created_at),
+    })
 }
 
 fn resolve_mcp_endpoint(headers: &HeaderMap, state: &AppState) -> String {
@@ -5,15 +5,24 @@ pub mod archive;
 pub mod auth;
 pub mod blog;
 pub mod category;
+pub mod comment;
 pub mod data;
 pub mod directory;
 pub mod document;
+pub mod feed;
 pub mod file;
 pub mod friend_link;
+pub mod link_check;
+pub mod login_event;
 pub mod mcp;
 pub mod project;
+pub mod revision;
 pub mod search;
+pub mod session;
 pub mod site_config;
+pub mod sitemap;
 pub mod stats;
 pub mod tag;
 pub mod text;
+pub mod two_factor;
+pub mod user;
@@ -0,0 +1,268 @@
+//! Revision history handlers for blogs and documents
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+
+use crate::error::{ApiError, ApiResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::blog::{Blog, BlogResponse};
+use crate::models::document::DocumentResponse;
+use crate::models::revision::{
+    Revision, RevisionActor, RevisionDiffQuery, RevisionDiffResponse, RevisionListItem,
+    RevisionTarget,
+};
+use crate::repositories::blog_repo::BlogRepository;
+use crate::repositories::document_repo::DocumentRepository;
+use crate::repositories::revision_repo::RevisionRepository;
+use crate::services::blog_service::BlogService;
+use crate::services::cache_service::cache_keys;
+use crate::utils::diff::line_diff;
+use crate::utils::markdown::render_markdown;
+use crate::AppState;
+
+/// GET /api/v1/admin/blogs/:id/revisions
+///
+/// List revisions of a blog, newest first (admin endpoint)
+pub async fn list_blog_revisions(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+) -> Result<Json<ApiResponse<Vec<RevisionListItem>>>, ApiError> {
+    find_editable_blog(&state, &auth_user, id).await?;
+    let revisions = RevisionRepository::find_by_entity(&state.db, RevisionTarget::Blog, id).await?;
+    Ok(Json(ApiResponse::success(revisions)))
+}
+
+/// GET /api/v1/admin/blogs/:id/revisions/:revision_id
+///
+/// Get a single blog revision with its full content (admin endpoint)
+pub async fn get_blog_revision(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((id, revision_id)): Path<(i64, i64)>,
+) -> Result<Json<ApiResponse<Revision>>, ApiError> {
+    find_editable_blog(&state, &auth_user, id).await?;
+    let revision = find_revision(&state, RevisionTarget::Blog, id, revision_id).await?;
+    Ok(Json(ApiResponse::success(revision)))
+}
+
+/// GET /api/v1/admin/blogs/:id/revisions/diff?from=1&to=2
+///
+/// Line diff of the content between two blog revisions (admin endpoint)
+pub async fn diff_blog_revisions(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+    Query(query): Query<RevisionDiffQuery>,
+) -> Result<Json<ApiResponse<RevisionDiffResponse>>, ApiError> {
+    find_editable_blog(&state, &auth_user, id).await?;
+    let diff = diff_revisions(&state, RevisionTarget::Blog, id, &query).await?;
+    Ok(Json(ApiResponse::success(diff)))
+}
+
+/// POST /api/v1/admin/blogs/:id/revisions/:revision_id/restore
+///
+/// Restore a blog's title, content, summary and references from a revision (admin endpoint)
+///
+/// The restore is itself recorded as a new revision, so it can be undone too.
+pub async fn restore_blog_revision(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((id, revision_id)): Path<(i64, i64)>,
+) -> Result<Json<ApiResponse<BlogResponse>>, ApiError> {
+    let existing = find_editable_blog(&state, &auth_user, id).await?;
+
+    let revision = find_revision(&state, RevisionTarget::Blog, id, revision_id).await?;
+
+    let html = render_markdown(&revision.content);
+    let actor = RevisionActor::restore(auth_user.user_id, &auth_user.username);
+    if !RevisionRepository::restore(
+        &state.db,
+        RevisionTarget::Blog,
+        &revision,
+        Some(&html),
+        &actor,
+    )
+    .await?
+    {
+        return Err(ApiError::NotFound(format!("Blog with id {} not found", id)));
+    }
+
+    let blog_detail = BlogRepository::find_detail_by_id(&state.db, id)
+        .await?
+        .ok_or_else(|| ApiError::InternalError("Failed to fetch restored blog".to_string()))?;
+
+    if let Err(e) =
+        BlogService::invalidate_blog_cache(&state.cache, id, existing.slug.as_deref()).await
+    {
+        tracing::warn!("Failed to invalidate blog cache: {}", e);
+    }
+
+    tracing::info!(
+        "Restored blog {} to revision {} by {}",
+        id,
+        revision_id,
+        auth_user.username
+    );
+
+    Ok(Json(ApiResponse::success(BlogResponse::from(blog_detail))))
+}
+
+/// GET /api/v1/admin/documents/:id/revisions
+///
+/// List revisions of a document, newest first (admin endpoint)
+pub async fn list_document_revisions(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<ApiResponse<Vec<RevisionListItem>>>, ApiError> {
+    ensure_document_exists(&state, id).await?;
+    let revisions =
+        RevisionRepository::find_by_entity(&state.db, RevisionTarget::Document, id).await?;
+    Ok(Json(ApiResponse::success(revisions)))
+}
+
+/// GET /api/v1/admin/documents/:id/revisions/:revision_id
+///
+/// Get a single document revision with its full content (admin endpoint)
+pub async fn get_document_revision(
+    State(state): State<AppState>,
+    Path((id, revision_id)): Path<(i64, i64)>,
+) -> Result<Json<ApiResponse<Revision>>, ApiError> {
+    let revision = find_revision(&state, RevisionTarget::Document, id, revision_id).await?;
+    Ok(Json(ApiResponse::success(revision)))
+}
+
+/// GET /api/v1/admin/documents/:id/revisions/diff?from=1&to=2
+///
+/// Line diff of the content between two document revisions (admin endpoint)
+pub async fn diff_document_revisions(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Query(query): Query<RevisionDiffQuery>,
+) -> Result<Json<ApiResponse<RevisionDiffResponse>>, ApiError> {
+    let diff = diff_revisions(&state, RevisionTarget::Document, id, &query).await?;
+    Ok(Json(ApiResponse::success(diff)))
+}
+
+/// POST /api/v1/admin/documents/:id/revisions/:revision_id/restore
+///
+/// Restore a document's name, content and references from a revision (admin endpoint)
+pub async fn restore_document_revision(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((id, revision_id)): Path<(i64, i64)>,
+) -> Result<Json<ApiResponse<DocumentResponse>>, ApiError> {
+    let revision = find_revision(&state, RevisionTarget::Document, id, revision_id).await?;
+
+    let actor = RevisionActor::restore(auth_user.user_id, &auth_user.username);
+    if !RevisionRepository::restore(&state.db, RevisionTarget::Document, &revision, None, &actor)
+        .await?
+    {
+        return Err(ApiError::NotFound(format!(
+            "Document with id {} not found",
+            id
+        )));
+    }
+    let document = DocumentRepository::find_by_id(&state.db, id)
+        .await?
+        .ok_or_else(|| ApiError::InternalError("Failed to fetch restored document".to_string()))?;
+
+    // Document name might have changed
+    let _ = state.cache.delete(&cache_keys::directory_tree()).await;
+
+    let html = render_markdown(&document.content);
+
+    tracing::info!(
+        "Restored document {} to revision {} by {}",
+        document.id,
+        revision_id,
+        auth_user.username
+    );
+
+    Ok(Json(ApiResponse::success(document.to_response(Some(html)))))
+}
+
+/// Load a blog, returning 404 if it doesn't exist and 403 if the user may not edit it
+///
+/// Revisions hold earlier drafts, so reading them needs the same rights as editing.
+async fn find_editable_blog(
+    state: &AppState,
+    auth_user: &AuthUser,
+    id: i64,
+) -> Result<Blog, ApiError> {
+    let blog = BlogRepository::find_by_id(&state.db, id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Blog with id {} not found", id)))?;
+    BlogService::ensure_can_edit(auth_user, &blog)?;
+    Ok(blog)
+}
+
+/// Return 404 if the document doesn't exist
+async fn ensure_document_exists(state: &AppState, id: i64) -> Result<(), ApiError> {
+    if DocumentRepository::find_by_id(&state.db, id)
+        .await?
+        .is_some()
+    {
+        Ok(())
+    } else {
+        Err(ApiError::NotFound(format!(
+            "Document with id {} not found",
+            id
+        )))
+    }
+}
+
+async fn find_revision(
+    state: &AppState,
+    target: RevisionTarget,
+    id: i64,
+    revision_id: i64,
+) -> Result<Revision, ApiError> {
+    RevisionRepository::find_by_id(&state.db, target, id, revision_id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::NotFound(format!(
+                "Revision {} of {} {} not found",
+                revision_id,
+                target_name(target).to_lowercase(),
+                id
+            ))
+        })
+}
+
+async fn diff_revisions(
+    state: &AppState,
+    target: RevisionTarget,
+    id: i64,
+    query: &RevisionDiffQuery,
+) -> Result<RevisionDiffResponse, ApiError> {
+    let from = find_revision(state, target, id, query.from).await?;
+    let to = find_revision(state, target, id, query.to).await?;
+
+    let diff = line_diff(
+        &from.content,
+        &to.content,
+        &format!("revision {}", from.id),
+        &format!("revision {}", to.id),
+    );
+
+    Ok(RevisionDiffResponse {
+        from: RevisionListItem::from(&from),
+        to: RevisionListItem::from(&to),
+        title_changed: from.title != to.title,
+        summary_changed: from.summary != to.summary,
+        references_changed: from.references != to.references,
+        additions: diff.additions,
+        deletions: diff.deletions,
+        diff: diff.unified,
+    })
+}
+
+fn target_name(target: RevisionTarget) -> &'static str {
+    match target {
+        RevisionTarget::Blog => "Blog",
+        RevisionTarget::Document => "Document",
+    }
+}
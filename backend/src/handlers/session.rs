@@ -0,0 +1,53 @@
+//! Login session handlers
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+use crate::error::{ApiError, ApiResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::session::SessionResponse;
+use crate::services::session_service::SessionService;
+use crate::AppState;
+
+/// GET /api/v1/admin/sessions
+///
+/// List all active login sessions (admin endpoint)
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> Result<Json<ApiResponse<Vec<SessionResponse>>>, ApiError> {
+    let sessions = SessionService::list(&state.cache).await?;
+
+    Ok(Json(ApiResponse::success(
+        sessions
+            .into_iter()
+            .map(|session| SessionResponse::new(session, &auth_user.session_id))
+            .collect(),
+    )))
+}
+
+/// DELETE /api/v1/admin/sessions/:session_id
+///
+/// Revoke a login session; its refresh and access tokens stop working (admin endpoint)
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(session_id): Path<String>,
+) -> Result<Json<ApiResponse<()>>, ApiError> {
+    if !SessionService::revoke(&state.cache, &session_id).await? {
+        return Err(ApiError::NotFound(format!(
+            "Session {} not found",
+            session_id
+        )));
+    }
+
+    tracing::info!("Session {} revoked by '{}'", session_id, auth_user.username);
+
+    Ok(Json(ApiResponse {
+        code: 0,
+        message: "Session revoked successfully".to_string(),
+        data: None,
+    }))
+}
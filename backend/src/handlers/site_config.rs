@@ -39,7 +39,9 @@ pub async fn get_public_config(
 pub async fn get_all_config(
     State(state): State<AppState>,
 ) -> Result<Json<ApiResponse<Vec<SiteConfig>>>, ApiError> {
-    let configs = SiteConfigRepo::get_all(&state.db).await?;
+    let configs =
+        SiteConfigRepo::get_all_redacted(&state.db, state.config.encryption.master_key.as_deref())
+            .await?;
     Ok(Json(ApiResponse::success(configs)))
 }
 
@@ -55,7 +57,12 @@ pub async fn update_config(
         .map(|c| (c.key, c.value))
         .collect();
 
-    SiteConfigRepo::batch_update(&state.db, &configs).await?;
+    SiteConfigRepo::batch_update(
+        &state.db,
+        &configs,
+        state.config.encryption.master_key.as_deref(),
+    )
+    .await?;
 
     // 清除配置缓存
     let cache_key = cache_keys::site_config();
@@ -63,6 +70,16 @@ pub async fn update_config(
         tracing::warn!("Failed to clear site config cache: {}", e);
     }
 
+    // 订阅源包含站点标题等信息，同步清除
+    if let Err(e) = state.cache.delete_pattern("feed:*").await {
+        tracing::warn!("Failed to clear feed cache: {}", e);
+    }
+
+    // 站点地图与 robots.txt 依赖站点地址配置，同步清除
+    if let Err(e) = state.cache.delete_pattern("sitemap:*").await {
+        tracing::warn!("Failed to clear sitemap cache: {}", e);
+    }
+
     tracing::info!("Site config updated, cache cleared");
 
     Ok(Json(ApiResponse::success(serde_json::json!({
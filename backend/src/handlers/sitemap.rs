@@ -0,0 +1,53 @@
+//! Sitemap and robots.txt handlers
+
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+};
+
+use crate::error::ApiError;
+use crate::models::sitemap::SitemapKind;
+use crate::services::sitemap_service::SitemapService;
+use crate::AppState;
+
+const XML_CONTENT_TYPE: &str = "application/xml; charset=utf-8";
+const TEXT_CONTENT_TYPE: &str = "text/plain; charset=utf-8";
+
+/// GET /api/v1/sitemap.xml
+///
+/// Sitemap index pointing at the paged sub-sitemaps (public endpoint)
+pub async fn get_sitemap_index(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, ApiError> {
+    let body = SitemapService::get_index(&state.db, &state.cache).await?;
+    Ok(([(header::CONTENT_TYPE, XML_CONTENT_TYPE)], body))
+}
+
+/// GET /api/v1/sitemaps/:kind/:page
+///
+/// One page of the blogs/categories/tags/documents sitemap, e.g.
+/// `/sitemaps/blogs/1.xml` (public endpoint)
+pub async fn get_sitemap_page(
+    State(state): State<AppState>,
+    Path((kind, page)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let kind = SitemapKind::parse(&kind)
+        .ok_or_else(|| ApiError::NotFound(format!("Sitemap '{}' not found", kind)))?;
+    let page = page
+        .strip_suffix(".xml")
+        .unwrap_or(&page)
+        .parse::<i64>()
+        .map_err(|_| ApiError::BadRequest(format!("Invalid sitemap page '{}'", page)))?;
+
+    let body = SitemapService::get_page(&state.db, &state.cache, kind, page).await?;
+    Ok(([(header::CONTENT_TYPE, XML_CONTENT_TYPE)], body))
+}
+
+/// GET /api/v1/robots.txt
+///
+/// robots.txt generated from site config (public endpoint)
+pub async fn get_robots(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let body = SitemapService::get_robots(&state.db, &state.cache).await?;
+    Ok(([(header::CONTENT_TYPE, TEXT_CONTENT_TYPE)], body))
+}
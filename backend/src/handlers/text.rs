@@ -2,31 +2,56 @@
 
 use axum::{
     extract::{Path, State},
+    http::HeaderMap,
     Json,
 };
 
 use crate::error::{ApiError, ApiResponse};
+use crate::middleware::client_ip::ClientIp;
 use crate::models::text::{
     CreateTextRequest, TextAdminResponse, TextResponse, UpdateTextRequest, VerifyPasswordRequest,
+    VerifyTextResponse,
 };
 use crate::repositories::text_repo::TextRepository;
+use crate::services::auth_service::{AuthService, TEXT_UNLOCK_TOKEN_EXPIRE_SECONDS};
+use crate::services::text_service::{PasswordRequester, TextService};
 use crate::AppState;
 
+/// Header carrying the unlock token returned by `POST /texts/{id}/verify`
+const UNLOCK_TOKEN_HEADER: &str = "x-text-unlock-token";
+
 /// GET /api/v1/texts/:id
 ///
 /// Get a text by ID (public endpoint)
-/// For encrypted texts, content is hidden until password is verified
+/// For encrypted texts, content is hidden unless a valid `X-Text-Unlock-Token` is sent
 pub async fn get_text(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    headers: HeaderMap,
 ) -> Result<Json<ApiResponse<TextResponse>>, ApiError> {
     let text = TextRepository::find_by_id(&state.db, id)
         .await?
         .ok_or_else(|| ApiError::NotFound(format!("Text with id {} not found", id)))?;
 
-    // For public access, hide content if encrypted
+    // For public access, hide content if encrypted and not unlocked
     let is_encrypted = text.is_encrypted.unwrap_or(false);
-    let response = text.to_public_response(!is_encrypted);
+    let unlocked = is_encrypted
+        && headers
+            .get(UNLOCK_TOKEN_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|token| {
+                AuthService::new(state.config.jwt.clone()).verify_text_unlock_token(
+                    token,
+                    id,
+                    &text.password_fingerprint(),
+                )
+            });
+    let response = if unlocked {
+        TextService::decrypt(state.config.encryption.master_key.as_deref(), text)?
+            .to_public_response(true)
+    } else {
+        text.to_public_response(!is_encrypted)
+    };
 
     Ok(Json(ApiResponse::success(response)))
 }
@@ -34,30 +59,34 @@ pub async fn get_text(
 /// POST /api/v1/texts/:id/verify
 ///
 /// Verify password for encrypted text (public endpoint)
-/// Returns the text content if password is correct
+/// Returns the text content and an unlock token if password is correct
 pub async fn verify_text_password(
     State(state): State<AppState>,
     Path(id): Path<i64>,
+    client_ip: ClientIp,
     Json(req): Json<VerifyPasswordRequest>,
-) -> Result<Json<ApiResponse<TextResponse>>, ApiError> {
+) -> Result<Json<ApiResponse<VerifyTextResponse>>, ApiError> {
     let text = TextRepository::find_by_id(&state.db, id)
         .await?
         .ok_or_else(|| ApiError::NotFound(format!("Text with id {} not found", id)))?;
 
-    // Check if text is encrypted
-    let is_encrypted = text.is_encrypted.unwrap_or(false);
-    if !is_encrypted {
-        // Not encrypted, return content directly
-        return Ok(Json(ApiResponse::success(text.to_public_response(true))));
-    }
-
-    // Verify password
-    if !text.verify_password(&req.password) {
-        return Err(ApiError::Unauthorized("Invalid password".to_string()));
-    }
-
-    // Password correct, return content
-    Ok(Json(ApiResponse::success(text.to_public_response(true))))
+    let text = TextService::verify_text_password(
+        &state.db,
+        &state.cache,
+        state.config.encryption.master_key.as_deref(),
+        text,
+        &req.password,
+        PasswordRequester::Ip(&client_ip.to_string()),
+    )
+    .await?;
+    let unlock_token = AuthService::new(state.config.jwt.clone())
+        .generate_text_unlock_token(id, &text.password_fingerprint())?;
+
+    Ok(Json(ApiResponse::success(VerifyTextResponse {
+        text: text.to_public_response(true),
+        unlock_token,
+        unlock_expires_in: TEXT_UNLOCK_TOKEN_EXPIRE_SECONDS,
+    })))
 }
 
 /// GET /api/v1/admin/texts
@@ -85,6 +114,7 @@ pub async fn get_text_admin(
     let text = TextRepository::find_by_id(&state.db, id)
         .await?
         .ok_or_else(|| ApiError::NotFound(format!("Text with id {} not found", id)))?;
+    let text = TextService::decrypt(state.config.encryption.master_key.as_deref(), text)?;
 
     Ok(Json(ApiResponse::success(text.to_admin_response())))
 }
@@ -117,7 +147,12 @@ pub async fn create_text(
     }
 
     // Create text
-    let text = TextRepository::create(&state.db, &req).await?;
+    let text = TextService::create(
+        &state.db,
+        state.config.encryption.master_key.as_deref(),
+        &req,
+    )
+    .await?;
 
     tracing::info!("Created text: {} (id: {})", text.name, text.id);
 
@@ -156,9 +191,14 @@ pub async fn update_text(
     }
 
     // Update text
-    let text = TextRepository::update(&state.db, id, &req)
-        .await?
-        .ok_or_else(|| ApiError::NotFound(format!("Text with id {} not found", id)))?;
+    let text = TextService::update(
+        &state.db,
+        state.config.encryption.master_key.as_deref(),
+        id,
+        &req,
+    )
+    .await?
+    .ok_or_else(|| ApiError::NotFound(format!("Text with id {} not found", id)))?;
 
     tracing::info!("Updated text: {} (id: {})", text.name, text.id);
 
@@ -0,0 +1,138 @@
+//! Two-factor authentication handlers for the signed-in user
+
+use axum::{extract::State, Json};
+use chrono::Utc;
+
+use crate::error::{ApiError, ApiResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::two_factor::{
+    DisableTwoFactorRequest, RecoveryCodesResponse, TwoFactorCodeRequest, TwoFactorSetupResponse,
+    TwoFactorStatus,
+};
+use crate::models::user::User;
+use crate::repositories::site_config_repo::SiteConfigRepo;
+use crate::repositories::user_repo::UserRepository;
+use crate::services::two_factor_service::TwoFactorService;
+use crate::AppState;
+
+/// Issuer shown in authenticator apps when the site has no title
+const DEFAULT_ISSUER: &str = "Blog";
+
+/// GET /api/v1/admin/auth/2fa
+///
+/// Get the two-factor status of the current user
+pub async fn get_status(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> Result<Json<ApiResponse<TwoFactorStatus>>, ApiError> {
+    let user = current_user(&state, &auth_user).await?;
+    let status = TwoFactorService::status(&state.db, &user).await?;
+
+    Ok(Json(ApiResponse::success(status)))
+}
+
+/// POST /api/v1/admin/auth/2fa/setup
+///
+/// Start enrollment, returning the secret and an `otpauth://` URI for a QR code
+pub async fn setup(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> Result<Json<ApiResponse<TwoFactorSetupResponse>>, ApiError> {
+    let user = current_user(&state, &auth_user).await?;
+    let issuer = SiteConfigRepo::get_value(&state.db, "site_title")
+        .await?
+        .filter(|title| !title.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_ISSUER.to_string());
+
+    let response = TwoFactorService::setup(
+        &state.db,
+        state.config.encryption.master_key.as_deref(),
+        &user,
+        &issuer,
+    )
+    .await?;
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// POST /api/v1/admin/auth/2fa/confirm
+///
+/// Confirm enrollment with an authenticator code and receive recovery codes
+pub async fn confirm(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(req): Json<TwoFactorCodeRequest>,
+) -> Result<Json<ApiResponse<RecoveryCodesResponse>>, ApiError> {
+    let user = current_user(&state, &auth_user).await?;
+    let recovery_codes = TwoFactorService::confirm(
+        &state.db,
+        state.config.encryption.master_key.as_deref(),
+        &user,
+        &req.code,
+        Utc::now(),
+    )
+    .await?;
+
+    Ok(Json(ApiResponse::success(RecoveryCodesResponse {
+        recovery_codes,
+    })))
+}
+
+/// POST /api/v1/admin/auth/2fa/disable
+///
+/// Turn off two-factor authentication (requires password and a code)
+pub async fn disable(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(req): Json<DisableTwoFactorRequest>,
+) -> Result<Json<ApiResponse<()>>, ApiError> {
+    let user = current_user(&state, &auth_user).await?;
+    TwoFactorService::disable(
+        &state.db,
+        &state.cache,
+        state.config.encryption.master_key.as_deref(),
+        &user,
+        &req.password,
+        &req.code,
+        Utc::now(),
+    )
+    .await?;
+
+    Ok(Json(ApiResponse {
+        code: 0,
+        message: "Two-factor authentication disabled".to_string(),
+        data: None,
+    }))
+}
+
+/// POST /api/v1/admin/auth/2fa/recovery-codes
+///
+/// Replace all recovery codes; the old ones stop working
+pub async fn regenerate_recovery_codes(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(req): Json<TwoFactorCodeRequest>,
+) -> Result<Json<ApiResponse<RecoveryCodesResponse>>, ApiError> {
+    let user = current_user(&state, &auth_user).await?;
+    let recovery_codes = TwoFactorService::regenerate_recovery_codes(
+        &state.db,
+        &state.cache,
+        state.config.encryption.master_key.as_deref(),
+        &user,
+        &req.code,
+        Utc::now(),
+    )
+    .await?;
+
+    tracing::info!("User '{}' regenerated recovery codes", user.username);
+
+    Ok(Json(ApiResponse::success(RecoveryCodesResponse {
+        recovery_codes,
+    })))
+}
+
+async fn current_user(state: &AppState, auth_user: &AuthUser) -> Result<User, ApiError> {
+    UserRepository::find_by_id(&state.db, auth_user.user_id)
+        .await?
+        .ok_or_else(|| ApiError::Unauthorized("User not found".to_string()))
+}
@@ -0,0 +1,165 @@
+//! User management handlers
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+use crate::error::{ApiError, ApiResponse};
+use crate::middleware::auth::AuthUser;
+use crate::models::user::{CreateUserRequest, UpdateUserRequest, User, UserResponse, UserRole};
+use crate::repositories::user_repo::UserRepository;
+use crate::services::session_service::SessionService;
+use crate::AppState;
+
+/// GET /api/v1/admin/users
+///
+/// Get all users (admin endpoint)
+pub async fn list_users(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<Vec<UserResponse>>>, ApiError> {
+    let users = UserRepository::find_all(&state.db).await?;
+
+    let responses: Vec<UserResponse> = users.into_iter().map(UserResponse::from).collect();
+
+    Ok(Json(ApiResponse::success(responses)))
+}
+
+/// GET /api/v1/admin/users/:id
+///
+/// Get a single user by ID (admin endpoint)
+pub async fn get_user(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<ApiResponse<UserResponse>>, ApiError> {
+    let user = find_user(&state, id).await?;
+
+    Ok(Json(ApiResponse::success(UserResponse::from(user))))
+}
+
+/// POST /api/v1/admin/users
+///
+/// Create a new user; the role defaults to author (admin endpoint)
+pub async fn create_user(
+    State(state): State<AppState>,
+    Json(req): Json<CreateUserRequest>,
+) -> Result<Json<ApiResponse<UserResponse>>, ApiError> {
+    // Validate input
+    if req.username.trim().is_empty() {
+        return Err(ApiError::ValidationError(
+            "Username is required".to_string(),
+        ));
+    }
+    validate_password(&req.password)?;
+
+    if UserRepository::username_exists(&state.db, &req.username).await? {
+        return Err(ApiError::ValidationError(format!(
+            "User '{}' already exists",
+            req.username
+        )));
+    }
+
+    let role = req.role.unwrap_or(UserRole::Author);
+    let user = UserRepository::create(&state.db, &req, role).await?;
+
+    tracing::info!(
+        "Created user: {} (id: {}, role: {})",
+        user.username,
+        user.id,
+        role.as_str()
+    );
+
+    Ok(Json(ApiResponse::success(UserResponse::from(user))))
+}
+
+/// PUT /api/v1/admin/users/:id
+///
+/// Update a user's profile, role or password (admin endpoint)
+pub async fn update_user(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(req): Json<UpdateUserRequest>,
+) -> Result<Json<ApiResponse<UserResponse>>, ApiError> {
+    let existing = find_user(&state, id).await?;
+
+    if let Some(ref password) = req.password {
+        validate_password(password)?;
+    }
+
+    // Never demote the last admin
+    if req.role.is_some_and(|role| role != UserRole::Admin) {
+        ensure_not_last_admin(&state, &existing).await?;
+    }
+
+    let user = UserRepository::update(&state.db, id, &req)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("User with id {} not found", id)))?;
+
+    // A new password signs the user out everywhere
+    if req.password.is_some() {
+        SessionService::revoke_user(&state.cache, id).await?;
+    }
+
+    tracing::info!("Updated user: {} (id: {})", user.username, user.id);
+
+    Ok(Json(ApiResponse::success(UserResponse::from(user))))
+}
+
+/// DELETE /api/v1/admin/users/:id
+///
+/// Delete a user; their blogs are kept without an owner (admin endpoint)
+pub async fn delete_user(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<i64>,
+) -> Result<Json<ApiResponse<()>>, ApiError> {
+    if id == auth_user.user_id {
+        return Err(ApiError::BadRequest(
+            "You cannot delete your own account".to_string(),
+        ));
+    }
+
+    let user = find_user(&state, id).await?;
+    ensure_not_last_admin(&state, &user).await?;
+
+    let deleted = UserRepository::delete(&state.db, id).await?;
+    if !deleted {
+        return Err(ApiError::NotFound(format!("User with id {} not found", id)));
+    }
+    SessionService::revoke_user(&state.cache, id).await?;
+
+    tracing::info!("Deleted user: {} (id: {})", user.username, id);
+
+    Ok(Json(ApiResponse {
+        code: 0,
+        message: "User deleted successfully".to_string(),
+        data: None,
+    }))
+}
+
+async fn find_user(state: &AppState, id: i64) -> Result<User, ApiError> {
+    UserRepository::find_by_id(&state.db, id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("User with id {} not found", id)))
+}
+
+fn validate_password(password: &str) -> Result<(), ApiError> {
+    if password.len() < 6 {
+        return Err(ApiError::ValidationError(
+            "Password must be at least 6 characters".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Refuse to remove the admin role from the only remaining admin
+async fn ensure_not_last_admin(state: &AppState, user: &User) -> Result<(), ApiError> {
+    if user.role() == UserRole::Admin && UserRepository::count_admins(&state.db).await? <= 1 {
+        return Err(ApiError::BadRequest(
+            "At least one admin must remain".to_string(),
+        ));
+    }
+
+    Ok(())
+}
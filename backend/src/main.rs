@@ -22,7 +22,16 @@ use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::config::Config;
+use crate::middleware::rate_limit::{with_rate_limit, RouteGroup};
+use crate::repositories::site_config_repo::SiteConfigRepo;
+use crate::services::blog_service::BlogService;
 use crate::services::cache_service::CacheService;
+use crate::services::friend_link_service::FriendLinkService;
+use crate::services::link_check_service::LinkCheckService;
+use crate::services::page_fetcher::{HttpPageFetcher, PageFetcher};
+use crate::services::storage::LOCAL_FILES_ROUTE;
+use crate::services::text_service::TextService;
+use crate::services::two_factor_service::TwoFactorService;
 
 /// Application state shared across handlers
 #[derive(Clone)]
@@ -30,6 +39,8 @@ pub struct AppState {
     pub db: sqlx::PgPool,
     pub cache: Arc<CacheService>,
     pub config: Arc<Config>,
+    /// HTTP client for fetching third-party pages (mockable in tests)
+    pub page_fetcher: Arc<dyn PageFetcher>,
 }
 
 #[tokio::main]
@@ -79,24 +90,89 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     tracing::info!("Redis connection established");
 
+    // Encrypt protected texts, sensitive settings and two-factor secrets stored
+    // before encryption existed
+    match config.encryption.master_key.as_deref() {
+        Some(master_key) => {
+            match TextService::seal_legacy_texts(&db, master_key).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Encrypted content of {} protected texts", count),
+                Err(e) => tracing::error!("Failed to encrypt protected texts: {}", e),
+            }
+            match SiteConfigRepo::seal_legacy_secrets(&db, master_key).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Encrypted {} sensitive site config values", count),
+                Err(e) => tracing::error!("Failed to encrypt sensitive site config: {}", e),
+            }
+            match TwoFactorService::seal_legacy_secrets(&db, master_key).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Encrypted {} two-factor secrets", count),
+                Err(e) => tracing::error!("Failed to encrypt two-factor secrets: {}", e),
+            }
+        }
+        None => tracing::warn!(
+            "ENCRYPTION_MASTER_KEY is not set; protected texts can't be created or read \
+             and sensitive settings and two-factor secrets are stored unencrypted"
+        ),
+    }
+
     // Create application state
     let state = AppState {
         db,
         cache,
         config: config.clone(),
+        page_fetcher: Arc::new(HttpPageFetcher::new()?),
     };
 
+    // Start scheduled publishing task
+    tokio::spawn(BlogService::run_publish_scheduler(
+        state.db.clone(),
+        state.cache.clone(),
+    ));
+
+    tracing::info!("Scheduled publishing task started");
+
+    // Start friend link backlink checker
+    tokio::spawn(FriendLinkService::run_backlink_checker(
+        state.db.clone(),
+        state.page_fetcher.clone(),
+    ));
+
+    // Start dead-link checker
+    tokio::spawn(LinkCheckService::run_link_checker(
+        state.db.clone(),
+        state.page_fetcher.clone(),
+    ));
+
     // Build CORS layer
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
         .allow_headers(Any);
 
-    // Build router with public and admin routes
+    // Build router with public and admin routes, each group with its own rate limit
     let app = Router::new()
-        .merge(mcp::router(state.clone()))
-        .nest("/api/v1", routes::create_routes())
-        .nest("/api/v1/admin", routes::create_admin_routes(state.clone()))
+        .merge(with_rate_limit(
+            mcp::router(state.clone()),
+            &state,
+            RouteGroup::Mcp,
+        ))
+        .nest(
+            routes::API_PREFIX,
+            with_rate_limit(routes::create_routes(), &state, RouteGroup::Public),
+        )
+        .nest(
+            "/api/v1/admin",
+            with_rate_limit(
+                routes::create_admin_routes(state.clone()),
+                &state,
+                RouteGroup::Admin,
+            ),
+        )
+        .nest(
+            LOCAL_FILES_ROUTE,
+            routes::create_local_files_routes(&config.storage.local_dir),
+        )
         .layer(DefaultBodyLimit::max(
             config.server.max_body_size_mb * 1024 * 1024,
         ))
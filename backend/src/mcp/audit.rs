@@ -0,0 +1,101 @@
+//! MCP tool call auditing
+
+use serde_json::{Map, Value};
+
+use crate::models::mcp::{McpCallStatus, McpGrant};
+use crate::repositories::mcp_audit_repo::McpAuditRepository;
+use crate::AppState;
+
+/// Longest string argument stored verbatim in the audit log
+const MAX_AUDIT_STRING_CHARS: usize = 200;
+
+/// Argument keys whose values are never stored
+const SECRET_KEYS: [&str; 3] = ["password", "token", "secret"];
+
+/// Prepare tool arguments for the audit log
+///
+/// Secrets are replaced and long strings (markdown bodies, base64 uploads) truncated.
+pub fn redact_arguments(arguments: &Map<String, Value>) -> Value {
+    Value::Object(
+        arguments
+            .iter()
+            .map(|(key, value)| {
+                let lower = key.to_lowercase();
+                let value = if SECRET_KEYS.iter().any(|secret| lower.contains(secret)) {
+                    Value::String("[redacted]".to_string())
+                } else {
+                    redact_value(value)
+                };
+                (key.clone(), value)
+            })
+            .collect(),
+    )
+}
+
+fn redact_value(value: &Value) -> Value {
+    match value {
+        Value::String(text) if text.chars().count() > MAX_AUDIT_STRING_CHARS => {
+            let head: String = text.chars().take(MAX_AUDIT_STRING_CHARS).collect();
+            Value::String(format!("{}… ({} chars)", head, text.chars().count()))
+        }
+        Value::Array(items) => Value::Array(items.iter().map(redact_value).collect()),
+        Value::Object(map) => redact_arguments(map),
+        other => other.clone(),
+    }
+}
+
+/// Write an audit entry; failures are logged and never fail the tool call
+pub async fn record_call(
+    state: &AppState,
+    grant: &McpGrant,
+    tool: &str,
+    arguments: Option<&Map<String, Value>>,
+    status: McpCallStatus,
+    error: Option<&str>,
+    duration_ms: Option<i64>,
+) {
+    let arguments = arguments.map(redact_arguments);
+    if let Err(e) = McpAuditRepository::record(
+        &state.db,
+        grant,
+        tool,
+        arguments.as_ref(),
+        status,
+        error,
+        duration_ms,
+    )
+    .await
+    {
+        tracing::warn!("Failed to record MCP audit entry for {}: {}", tool, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn secrets_are_redacted_and_long_strings_truncated() {
+        let arguments = json!({
+            "text_id": 3,
+            "password": "hunter2",
+            "content": "x".repeat(500),
+            "tags": ["rust", "y".repeat(300)],
+        });
+
+        let redacted = redact_arguments(arguments.as_object().unwrap());
+
+        assert_eq!(redacted["text_id"], 3);
+        assert_eq!(redacted["password"], "[redacted]");
+        assert!(redacted["content"]
+            .as_str()
+            .unwrap()
+            .ends_with("… (500 chars)"));
+        assert_eq!(redacted["tags"][0], "rust");
+        assert!(redacted["tags"][1]
+            .as_str()
+            .unwrap()
+            .ends_with("(300 chars)"));
+    }
+}
@@ -14,25 +14,21 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::error::ApiResponse;
+use crate::models::mcp::{McpGrant, McpToken};
+use crate::repositories::mcp_token_repo::McpTokenRepository;
 use crate::repositories::site_config_repo::SiteConfigRepo;
 use crate::services::cache_service::{cache_keys, cache_ttl, CacheService};
 use crate::AppState;
 
+/// Prefix of every MCP token secret
+const MCP_TOKEN_PREFIX: &str = "ddb_mcp_";
+
+/// Length of the plain lookup prefix stored next to the token hash
+const MCP_TOKEN_LOOKUP_LEN: usize = 12;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpRuntimeConfig {
     pub enabled: bool,
-    pub token_hash: Option<String>,
-    pub token_last_four: Option<String>,
-    pub token_rotated_at: Option<String>,
-}
-
-impl McpRuntimeConfig {
-    pub fn token_initialized(&self) -> bool {
-        self.token_hash
-            .as_deref()
-            .map(|value| !value.is_empty())
-            .unwrap_or(false)
-    }
 }
 
 pub async fn get_mcp_runtime_config(
@@ -48,16 +44,8 @@ pub async fn get_mcp_runtime_config(
         .await?
         .unwrap_or_else(|| "true".to_string())
         == "true";
-    let token_hash = SiteConfigRepo::get_value(&state.db, "mcp_token_hash").await?;
-    let token_last_four = SiteConfigRepo::get_value(&state.db, "mcp_token_last_four").await?;
-    let token_rotated_at = SiteConfigRepo::get_value(&state.db, "mcp_token_rotated_at").await?;
-
-    let runtime = McpRuntimeConfig {
-        enabled,
-        token_hash,
-        token_last_four,
-        token_rotated_at,
-    };
+
+    let runtime = McpRuntimeConfig { enabled };
 
     if let Err(error) = state
         .cache
@@ -78,12 +66,42 @@ pub async fn invalidate_mcp_runtime_config_cache(cache: &CacheService) {
 
 pub fn generate_mcp_token() -> String {
     format!(
-        "ddb_mcp_{}{}",
+        "{}{}{}",
+        MCP_TOKEN_PREFIX,
         Uuid::new_v4().simple(),
         Uuid::new_v4().simple()
     )
 }
 
+/// Plain part of a token used to look it up; the rest is only stored hashed
+pub fn token_lookup_prefix(token: &str) -> Option<&str> {
+    token
+        .strip_prefix(MCP_TOKEN_PREFIX)
+        .and_then(|secret| secret.get(..MCP_TOKEN_LOOKUP_LEN))
+}
+
+pub fn token_last_four(token: &str) -> String {
+    let start = token.len().saturating_sub(4);
+    token.get(start..).unwrap_or_default().to_string()
+}
+
+/// Find the token matching a presented secret
+pub async fn authenticate_token(
+    state: &AppState,
+    token: &str,
+) -> Result<Option<McpToken>, crate::error::ApiError> {
+    let prefix = token_lookup_prefix(token).unwrap_or_default();
+    let candidates = McpTokenRepository::find_candidates(&state.db, prefix).await?;
+
+    for candidate in candidates {
+        if verify_secret(token, &candidate.token_hash)? {
+            return Ok(Some(candidate));
+        }
+    }
+
+    Ok(None)
+}
+
 pub fn hash_secret(secret: &str) -> Result<String, crate::error::ApiError> {
     let salt = SaltString::generate(&mut OsRng);
     let hash = Argon2::default()
@@ -112,9 +130,10 @@ pub fn mask_token(last_four: &str) -> String {
     format!("********{}", last_four)
 }
 
+/// Authenticate MCP requests and attach the token's grant to the request
 pub async fn mcp_auth_middleware(
     State(state): State<AppState>,
-    request: Request<Body>,
+    mut request: Request<Body>,
     next: Next,
 ) -> Response {
     let runtime = match get_mcp_runtime_config(&state).await {
@@ -140,58 +159,59 @@ pub async fn mcp_auth_middleware(
             .into_response();
     }
 
-    let Some(token_hash) = runtime
-        .token_hash
-        .as_deref()
-        .filter(|value| !value.is_empty())
-    else {
-        return (
-            StatusCode::UNAUTHORIZED,
-            Json(ApiResponse::<()>::error(401, "MCP token not initialized")),
-        )
-            .into_response();
-    };
-
     let auth_header = request
         .headers()
         .get(header::AUTHORIZATION)
         .and_then(|value| value.to_str().ok());
 
     let Some(token) = auth_header.and_then(|header| header.strip_prefix("Bearer ")) else {
-        return (
-            StatusCode::UNAUTHORIZED,
-            Json(ApiResponse::<()>::error(
-                401,
-                "Missing or invalid Authorization header",
-            )),
-        )
-            .into_response();
+        return unauthorized_response("Missing or invalid Authorization header");
     };
 
-    match verify_secret(token, token_hash) {
-        Ok(true) => next.run(request).await,
-        Ok(false) => (
-            StatusCode::UNAUTHORIZED,
-            Json(ApiResponse::<()>::error(401, "Invalid MCP token")),
-        )
-            .into_response(),
+    let token = match authenticate_token(&state, token).await {
+        Ok(Some(token)) => token,
+        Ok(None) => return unauthorized_response("Invalid MCP token"),
         Err(error) => {
             tracing::error!("Failed to verify MCP token: {}", error);
-            (
+            return (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ApiResponse::<()>::error(
                     500,
                     "MCP token verification failed",
                 )),
             )
-                .into_response()
+                .into_response();
         }
+    };
+
+    if token.is_expired() {
+        return unauthorized_response("MCP token expired");
+    }
+
+    if let Err(error) = McpTokenRepository::touch(&state.db, token.id).await {
+        tracing::warn!("Failed to record MCP token use: {}", error);
     }
+
+    // Tool dispatch checks each call against the token's scopes
+    request.extensions_mut().insert(McpGrant::from(&token));
+
+    next.run(request).await
+}
+
+fn unauthorized_response(message: &str) -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(ApiResponse::<()>::error(401, message)),
+    )
+        .into_response()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{hash_secret, mask_token, verify_secret, McpRuntimeConfig};
+    use super::{
+        generate_mcp_token, hash_secret, mask_token, token_last_four, token_lookup_prefix,
+        verify_secret,
+    };
 
     #[test]
     fn token_mask_uses_last_four_digits() {
@@ -207,21 +227,19 @@ mod tests {
     }
 
     #[test]
-    fn runtime_config_detects_initialized_token() {
-        let config = McpRuntimeConfig {
-            enabled: true,
-            token_hash: Some("hash".to_string()),
-            token_last_four: Some("1234".to_string()),
-            token_rotated_at: None,
-        };
-        assert!(config.token_initialized());
-
-        let empty = McpRuntimeConfig {
-            enabled: true,
-            token_hash: Some(String::new()),
-            token_last_four: None,
-            token_rotated_at: None,
-        };
-        assert!(!empty.token_initialized());
+    fn lookup_prefix_comes_from_generated_secret() {
+        let token = generate_mcp_token();
+        let prefix = token_lookup_prefix(&token).expect("generated token has a prefix");
+
+        assert_eq!(prefix.len(), 12);
+        assert!(token.starts_with(&format!("ddb_mcp_{}", prefix)));
+        assert_eq!(token_lookup_prefix("ddb_mcp_short"), None);
+        assert_eq!(token_lookup_prefix("other_token_value"), None);
+    }
+
+    #[test]
+    fn last_four_is_token_suffix() {
+        assert_eq!(token_last_four("ddb_mcp_0123456789"), "6789");
+        assert_eq!(token_last_four("ab"), "ab");
     }
 }
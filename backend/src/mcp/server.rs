@@ -6,40 +6,55 @@ use axum::{
     Router,
 };
 use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine as _};
+use chrono::{DateTime, Utc};
 use rmcp::{
-    handler::server::{router::tool::ToolRouter, wrapper::Parameters},
-    model::{ServerCapabilities, ServerInfo},
+    handler::server::{router::tool::ToolRouter, tool::ToolCallContext, wrapper::Parameters},
+    model::{
+        CallToolRequestParams, CallToolResult, ListToolsResult, PaginatedRequestParams,
+        ServerCapabilities, ServerInfo,
+    },
+    service::RequestContext,
     tool, tool_handler, tool_router,
     transport::streamable_http_server::{
         session::local::LocalSessionManager, StreamableHttpServerConfig, StreamableHttpService,
     },
-    Json as McpJson, ServerHandler,
+    ErrorData, Json as McpJson, RoleServer, ServerHandler,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::BTreeMap;
+use std::time::Instant;
 
 use crate::error::ApiError;
+use crate::mcp::audit;
 use crate::mcp::auth::mcp_auth_middleware;
 use crate::models::blog::{CreateBlogRequest, UpdateBlogRequest};
 use crate::models::category::{CreateCategoryRequest, UpdateCategoryRequest};
+use crate::models::comment::CommentStatus;
 use crate::models::directory::{CreateDirectoryRequest, UpdateDirectoryRequest};
 use crate::models::document::{CreateDocumentRequest, UpdateDocumentRequest};
-use crate::models::file::{CreateFileRequest, FileResponse};
+use crate::models::file::{FileResponse, OrphanScanParams};
 use crate::models::friend_link::{CreateFriendLinkRequest, UpdateFriendLinkRequest};
+use crate::models::mcp::{McpCallStatus, McpGrant, McpScope};
 use crate::models::project::{CreateProjectRequest, UpdateProjectRequest};
+use crate::models::revision::RevisionActor;
 use crate::models::tag::{CreateTagRequest, UpdateTagRequest};
 use crate::repositories::{
-    blog_repo::BlogRepository, category_repo::CategoryRepository,
+    blog_repo::BlogRepository, category_repo::CategoryRepository, comment_repo::CommentRepository,
     directory_repo::DirectoryRepository, document_repo::DocumentRepository,
-    file_repo::FileRepository, friend_link_repo::FriendLinkRepository,
+    friend_link_repo::FriendLinkRepository, link_check_repo::LinkCheckRepository,
     project_repo::ProjectRepository, search_repo::SearchRepository,
     site_config_repo::SiteConfigRepo, tag_repo::TagRepository, text_repo::TextRepository,
 };
 use crate::services::{
-    ai_service::AiService, blog_service::BlogService, cache_service::cache_keys,
-    s3_service::S3Service,
+    ai_service::AiService,
+    blog_service::BlogService,
+    cache_service::cache_keys,
+    file_gc_service::FileGcService,
+    file_service::FileService,
+    storage::StorageService,
+    text_service::{PasswordRequester, TextService},
 };
 use crate::utils::markdown::render_markdown;
 use crate::AppState;
@@ -157,6 +172,39 @@ struct ListFriendLinksArgs {
     status: Option<i16>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct ListPendingCommentsArgs {
+    page: Option<i64>,
+    page_size: Option<i64>,
+}
+
+impl ListPendingCommentsArgs {
+    fn page(&self) -> i64 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    fn page_size(&self) -> i64 {
+        self.page_size.unwrap_or(20).clamp(1, 100)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct OrphanedFilesArgs {
+    /// 只处理创建超过该小时数的文件和对象，默认 24
+    min_age_hours: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct GetBrokenLinksArgs {
+    /// 最少连续失败次数，默认 1
+    min_failures: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct CommentIdArgs {
+    comment_id: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 struct ListBlogsArgs {
     page: Option<i64>,
@@ -319,6 +367,8 @@ struct CreateBlogDraftArgs {
     category_id: Option<i64>,
     tag_ids: Option<Vec<i64>>,
     references: Option<McpReferences>,
+    /// 定时发布时间（RFC 3339），到期后自动发布
+    publish_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -333,6 +383,8 @@ struct UpdateBlogArgs {
     category_id: Option<i64>,
     tag_ids: Option<Vec<i64>>,
     references: Option<McpReferences>,
+    /// 定时发布时间（RFC 3339），到期后自动发布
+    publish_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -474,7 +526,8 @@ impl BlogMcpServer {
             return Err("AI功能未启用".to_string());
         }
 
-        let api_key = SiteConfigRepo::get_value(&self.state.db, "ai_api_key")
+        let master_key = self.state.config.encryption.master_key.as_deref();
+        let api_key = SiteConfigRepo::get_secret(&self.state.db, "ai_api_key", master_key)
             .await
             .map_err(Self::api_error_to_string)?
             .filter(|value| !value.is_empty())
@@ -493,24 +546,6 @@ impl BlogMcpServer {
         Ok(AiService::new(&api_key, &base_url, &model))
     }
 
-    async fn get_s3_service(&self) -> Result<S3Service, String> {
-        let db_s3_config = SiteConfigRepo::get_s3_config(&self.state.db)
-            .await
-            .map_err(Self::api_error_to_string)?;
-        let s3_config = crate::config::S3Config {
-            endpoint: db_s3_config.endpoint,
-            region: db_s3_config.region,
-            bucket: db_s3_config.bucket,
-            access_key: db_s3_config.access_key,
-            secret_key: db_s3_config.secret_key,
-            public_url: db_s3_config.public_url,
-        };
-
-        S3Service::new(&s3_config)
-            .await
-            .map_err(Self::api_error_to_string)
-    }
-
     fn normalize_upload_filename(filename: &str) -> Result<String, String> {
         let filename = filename.trim();
         if filename.is_empty() {
@@ -553,45 +588,38 @@ impl BlogMcpServer {
         data: Vec<u8>,
         content_type: &str,
     ) -> Result<FileResponse, String> {
-        let s3_service = self.get_s3_service().await?;
-        let file_size = data.len() as i64;
-        let upload_result = s3_service
-            .upload_file(data, filename, content_type)
+        let storage = StorageService::active(&self.state.db, &self.state.config)
             .await
             .map_err(Self::api_error_to_string)?;
-
-        let file_type = content_type.split('/').next().map(ToOwned::to_owned);
-        let stored_filename = upload_result
-            .object_key
-            .split('/')
-            .last()
-            .unwrap_or(&upload_result.object_key)
-            .to_string();
-
-        let file = FileRepository::create(
+        FileService::upload(
             &self.state.db,
-            &CreateFileRequest {
-                filename: stored_filename,
-                original_filename: Some(filename.to_string()),
-                file_type,
-                file_size: Some(file_size),
-                url: upload_result.url,
-                thumbnail_url: None,
-                width: None,
-                height: None,
-                bucket_name: Some(upload_result.bucket),
-                object_key: Some(upload_result.object_key),
-            },
+            storage.as_ref(),
+            filename,
+            content_type,
+            data,
         )
         .await
-        .map_err(Self::api_error_to_string)?;
-
-        Ok(FileResponse::from(file))
+        .map_err(Self::api_error_to_string)
     }
 
     fn api_error_to_string(error: ApiError) -> String {
         error.to_string()
     }
+
+    /// Token grant attached by `mcp_auth_middleware` to the HTTP request
+    fn grant(context: &RequestContext<RoleServer>) -> Option<McpGrant> {
+        context
+            .extensions
+            .get::<axum::http::request::Parts>()
+            .and_then(|parts| parts.extensions.get::<McpGrant>())
+            .cloned()
+    }
+
+    /// Actor recorded on revisions created through MCP tools: the calling token
+    fn revision_actor(context: &RequestContext<RoleServer>) -> Result<RevisionActor, String> {
+        let grant = Self::grant(context).ok_or("MCP token not authenticated")?;
+        Ok(RevisionActor::mcp(&grant.token_name))
+    }
 }
 
 #[tool_router(router = tool_router)]
@@ -643,6 +671,7 @@ impl BlogMcpServer {
     async fn set_blog_category(
         &self,
         Parameters(args): Parameters<SetBlogCategoryArgs>,
+        context: RequestContext<RoleServer>,
     ) -> Result<McpJson<Value>, String> {
         self.ensure_category_exists(args.category_id).await?;
 
@@ -665,8 +694,10 @@ impl BlogMcpServer {
                 tag_ids: None,
                 is_published: None,
                 references: None,
+                publish_at: None,
             },
             None,
+            &Self::revision_actor(&context)?,
         )
         .await
         .map_err(Self::api_error_to_string)?
@@ -851,6 +882,7 @@ impl BlogMcpServer {
     async fn get_text_content(
         &self,
         Parameters(args): Parameters<GetTextContentArgs>,
+        context: RequestContext<RoleServer>,
     ) -> Result<McpJson<Value>, String> {
         let text = TextRepository::find_by_id(&self.state.db, args.text_id)
             .await
@@ -873,13 +905,28 @@ impl BlogMcpServer {
             }));
         };
 
-        if !text.verify_password(password) {
-            return Self::json_result(json!({
-                "status": "INVALID_PASSWORD",
-                "text_id": text.id,
-                "name": text.name,
-            }));
-        }
+        let grant = Self::grant(&context).ok_or("MCP token not authenticated")?;
+        let (text_id, name) = (text.id, text.name.clone());
+        let text = match TextService::verify_text_password(
+            &self.state.db,
+            &self.state.cache,
+            self.state.config.encryption.master_key.as_deref(),
+            text,
+            password,
+            PasswordRequester::McpToken(grant.token_id),
+        )
+        .await
+        {
+            Ok(text) => text,
+            Err(ApiError::Unauthorized(_)) => {
+                return Self::json_result(json!({
+                    "status": "INVALID_PASSWORD",
+                    "text_id": text_id,
+                    "name": name,
+                }));
+            }
+            Err(error) => return Err(Self::api_error_to_string(error)),
+        };
 
         Self::json_result(json!({
             "status": "OK",
@@ -955,6 +1002,64 @@ impl BlogMcpServer {
         Self::json_result(links)
     }
 
+    #[tool(
+        name = "list_pending_comments",
+        description = "获取待审核评论列表（最新在前），包含评论者邮箱与 IP"
+    )]
+    async fn list_pending_comments(
+        &self,
+        Parameters(args): Parameters<ListPendingCommentsArgs>,
+    ) -> Result<McpJson<Value>, String> {
+        let (items, total) = CommentRepository::find_with_filters(
+            &self.state.db,
+            args.page(),
+            args.page_size(),
+            Some(CommentStatus::Pending.into()),
+        )
+        .await
+        .map_err(Self::api_error_to_string)?;
+
+        Self::json_result(json!({
+            "items": items,
+            "total": total,
+            "page": args.page(),
+            "page_size": args.page_size(),
+        }))
+    }
+
+    #[tool(
+        name = "approve_comment",
+        description = "审核通过指定评论，使其公开显示"
+    )]
+    async fn approve_comment(
+        &self,
+        Parameters(CommentIdArgs { comment_id }): Parameters<CommentIdArgs>,
+    ) -> Result<McpJson<Value>, String> {
+        let comment =
+            CommentRepository::update_status(&self.state.db, comment_id, CommentStatus::Approved)
+                .await
+                .map_err(Self::api_error_to_string)?
+                .ok_or_else(|| format!("评论 {} 不存在", comment_id))?;
+
+        Self::json_result(comment)
+    }
+
+    #[tool(
+        name = "get_broken_links",
+        description = "获取死链报告：已通过友链与博客正文外链中连续检查失败的链接，含 HTTP 状态、重定向地址与连续失败次数"
+    )]
+    async fn get_broken_links(
+        &self,
+        Parameters(args): Parameters<GetBrokenLinksArgs>,
+    ) -> Result<McpJson<Value>, String> {
+        let links =
+            LinkCheckRepository::find_broken(&self.state.db, args.min_failures.unwrap_or(1).max(1))
+                .await
+                .map_err(Self::api_error_to_string)?;
+
+        Self::json_result(links)
+    }
+
     #[tool(name = "list_projects", description = "获取项目列表")]
     async fn list_projects(&self) -> Result<McpJson<Value>, String> {
         let projects = ProjectRepository::find_all(&self.state.db)
@@ -1051,6 +1156,7 @@ impl BlogMcpServer {
     async fn update_document(
         &self,
         Parameters(args): Parameters<UpdateDocumentArgs>,
+        context: RequestContext<RoleServer>,
     ) -> Result<McpJson<Value>, String> {
         DocumentRepository::find_by_id(&self.state.db, args.document_id)
             .await
@@ -1079,6 +1185,7 @@ impl BlogMcpServer {
                 sort_order: args.sort_order,
                 references: Self::references_to_value(args.references)?,
             },
+            &Self::revision_actor(&context)?,
         )
         .await
         .map_err(Self::api_error_to_string)?
@@ -1496,7 +1603,7 @@ impl BlogMcpServer {
 
     #[tool(
         name = "upload_file",
-        description = "上传文件到站点 S3 存储并返回可访问直链。content_base64 传文件二进制内容的 base64 字符串"
+        description = "上传文件到站点存储(S3 或本地)并返回可访问直链。content_base64 传文件二进制内容的 base64 字符串"
     )]
     async fn upload_file(
         &self,
@@ -1524,7 +1631,7 @@ impl BlogMcpServer {
 
     #[tool(
         name = "upload_image",
-        description = "上传图片到站点 S3 存储并返回可访问直链。content_base64 支持纯 base64 或 data:image/...;base64,..."
+        description = "上传图片到站点存储(S3 或本地)并返回可访问直链。content_base64 支持纯 base64 或 data:image/...;base64,..."
     )]
     async fn upload_image(
         &self,
@@ -1556,9 +1663,50 @@ impl BlogMcpServer {
         }))
     }
 
+    #[tool(
+        name = "get_orphaned_files",
+        description = "扫描孤立文件(只读预览)：没有任何博客、文档、分类、项目等内容引用的文件，存储中已不存在对象的文件记录，以及存储中没有文件记录的对象"
+    )]
+    async fn get_orphaned_files(
+        &self,
+        Parameters(args): Parameters<OrphanedFilesArgs>,
+    ) -> Result<McpJson<Value>, String> {
+        let params = OrphanScanParams {
+            min_age_hours: args.min_age_hours,
+        };
+        let report =
+            FileGcService::scan(&self.state.db, &self.state.config, params.min_age_hours())
+                .await
+                .map_err(Self::api_error_to_string)?;
+
+        Self::json_result(report)
+    }
+
+    #[tool(
+        name = "delete_orphaned_files",
+        description = "清理孤立文件：重新扫描后删除未被引用的文件及其对象、缺失对象的文件记录和无记录的存储对象。建议先调用 get_orphaned_files 预览"
+    )]
+    async fn delete_orphaned_files(
+        &self,
+        Parameters(args): Parameters<OrphanedFilesArgs>,
+    ) -> Result<McpJson<Value>, String> {
+        let params = OrphanScanParams {
+            min_age_hours: args.min_age_hours,
+        };
+        let (report, summary) =
+            FileGcService::purge(&self.state.db, &self.state.config, params.min_age_hours())
+                .await
+                .map_err(Self::api_error_to_string)?;
+
+        Self::json_result(json!({
+            "report": report,
+            "summary": summary,
+        }))
+    }
+
     #[tool(
         name = "create_blog_draft",
-        description = "创建博客草稿，始终保存为未发布状态"
+        description = "创建博客草稿，始终保存为未发布状态；传 publish_at 可设置定时发布时间"
     )]
     async fn create_blog_draft(
         &self,
@@ -1592,10 +1740,11 @@ impl BlogMcpServer {
             tag_ids: args.tag_ids,
             is_published: Some(false),
             references: Self::references_to_value(args.references)?,
+            publish_at: args.publish_at,
         };
 
         let html = render_markdown(&args.content);
-        let blog = BlogRepository::create(&self.state.db, &create_req, Some(html))
+        let blog = BlogRepository::create(&self.state.db, &create_req, Some(html), None)
             .await
             .map_err(Self::api_error_to_string)?;
         let detail = BlogRepository::find_detail_by_id(&self.state.db, blog.id)
@@ -1610,10 +1759,14 @@ impl BlogMcpServer {
         Self::json_result(detail)
     }
 
-    #[tool(name = "update_blog", description = "更新博客草稿或已发布博客内容")]
+    #[tool(
+        name = "update_blog",
+        description = "更新博客草稿或已发布博客内容；传未来的 publish_at 会撤回发布并排入定时发布"
+    )]
     async fn update_blog(
         &self,
         Parameters(args): Parameters<UpdateBlogArgs>,
+        context: RequestContext<RoleServer>,
     ) -> Result<McpJson<Value>, String> {
         let existing = BlogRepository::find_by_id(&self.state.db, args.blog_id)
             .await
@@ -1647,16 +1800,23 @@ impl BlogMcpServer {
             tag_ids: args.tag_ids,
             is_published: None,
             references: Self::references_to_value(args.references)?,
+            publish_at: args.publish_at,
         };
 
         let html = args
             .content
             .as_ref()
             .map(|content| render_markdown(content));
-        let blog = BlogRepository::update(&self.state.db, args.blog_id, &update_req, html)
-            .await
-            .map_err(Self::api_error_to_string)?
-            .ok_or_else(|| format!("博客 {} 不存在", args.blog_id))?;
+        let blog = BlogRepository::update(
+            &self.state.db,
+            args.blog_id,
+            &update_req,
+            html,
+            &Self::revision_actor(&context)?,
+        )
+        .await
+        .map_err(Self::api_error_to_string)?
+        .ok_or_else(|| format!("博客 {} 不存在", args.blog_id))?;
         let detail = BlogRepository::find_detail_by_id(&self.state.db, blog.id)
             .await
             .map_err(Self::api_error_to_string)?
@@ -1686,6 +1846,7 @@ impl BlogMcpServer {
     async fn publish_blog(
         &self,
         Parameters(BlogIdArgs { blog_id }): Parameters<BlogIdArgs>,
+        context: RequestContext<RoleServer>,
     ) -> Result<McpJson<Value>, String> {
         let update_req = UpdateBlogRequest {
             title: None,
@@ -1698,11 +1859,18 @@ impl BlogMcpServer {
             tag_ids: None,
             is_published: Some(true),
             references: None,
+            publish_at: None,
         };
-        let blog = BlogRepository::update(&self.state.db, blog_id, &update_req, None)
-            .await
-            .map_err(Self::api_error_to_string)?
-            .ok_or_else(|| format!("博客 {} 不存在", blog_id))?;
+        let blog = BlogRepository::update(
+            &self.state.db,
+            blog_id,
+            &update_req,
+            None,
+            &Self::revision_actor(&context)?,
+        )
+        .await
+        .map_err(Self::api_error_to_string)?
+        .ok_or_else(|| format!("博客 {} 不存在", blog_id))?;
         let detail = BlogRepository::find_detail_by_id(&self.state.db, blog.id)
             .await
             .map_err(Self::api_error_to_string)?
@@ -1718,6 +1886,7 @@ impl BlogMcpServer {
     async fn unpublish_blog(
         &self,
         Parameters(BlogIdArgs { blog_id }): Parameters<BlogIdArgs>,
+        context: RequestContext<RoleServer>,
     ) -> Result<McpJson<Value>, String> {
         let update_req = UpdateBlogRequest {
             title: None,
@@ -1730,11 +1899,18 @@ impl BlogMcpServer {
             tag_ids: None,
             is_published: Some(false),
             references: None,
+            publish_at: None,
         };
-        let blog = BlogRepository::update(&self.state.db, blog_id, &update_req, None)
-            .await
-            .map_err(Self::api_error_to_string)?
-            .ok_or_else(|| format!("博客 {} 不存在", blog_id))?;
+        let blog = BlogRepository::update(
+            &self.state.db,
+            blog_id,
+            &update_req,
+            None,
+            &Self::revision_actor(&context)?,
+        )
+        .await
+        .map_err(Self::api_error_to_string)?
+        .ok_or_else(|| format!("博客 {} 不存在", blog_id))?;
         let detail = BlogRepository::find_detail_by_id(&self.state.db, blog.id)
             .await
             .map_err(Self::api_error_to_string)?
@@ -2055,9 +2231,103 @@ impl BlogMcpServer {
 
 #[tool_handler(router = self.tool_router)]
 impl ServerHandler for BlogMcpServer {
+    /// Run a tool if the calling token has its scope, auditing every call
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let grant = Self::grant(&context)
+            .ok_or_else(|| ErrorData::invalid_request("MCP token not authenticated", None))?;
+        let tool = request.name.to_string();
+        let arguments = request.arguments.clone();
+
+        if !grant.allows_tool(&tool) {
+            let message = format!(
+                "Token '{}' lacks the '{}' scope required by {}",
+                grant.token_name,
+                McpScope::for_tool(&tool).as_str(),
+                tool
+            );
+            audit::record_call(
+                &self.state,
+                &grant,
+                &tool,
+                arguments.as_ref(),
+                McpCallStatus::Denied,
+                Some(&message),
+                None,
+            )
+            .await;
+            return Err(ErrorData::invalid_request(message, None));
+        }
+
+        let started = Instant::now();
+        let result = self
+            .tool_router
+            .call(ToolCallContext::new(self, request, context))
+            .await;
+        let duration_ms = started.elapsed().as_millis() as i64;
+
+        let error = match &result {
+            Ok(result) if result.is_error == Some(true) => Some(
+                result
+                    .content
+                    .iter()
+                    .find_map(|content| content.as_text())
+                    .map(|text| text.text.clone())
+                    .unwrap_or_default(),
+            ),
+            Ok(_) => None,
+            Err(error) => Some(error.message.to_string()),
+        };
+        let status = if error.is_some() {
+            McpCallStatus::Error
+        } else {
+            McpCallStatus::Ok
+        };
+        audit::record_call(
+            &self.state,
+            &grant,
+            &tool,
+            arguments.as_ref(),
+            status,
+            error.as_deref(),
+            Some(duration_ms),
+        )
+        .await;
+
+        result
+    }
+
+    /// List only the tools the calling token may use
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        let grant = Self::grant(&context);
+        let tools = self
+            .tool_router
+            .list_all()
+            .into_iter()
+            .filter(|tool| {
+                grant
+                    .as_ref()
+                    .is_some_and(|grant| grant.allows_tool(&tool.name))
+            })
+            .collect();
+
+        Ok(ListToolsResult {
+            tools,
+            meta: None,
+            next_cursor: None,
+        })
+    }
+
     fn get_info(&self) -> ServerInfo {
         ServerInfo::new(ServerCapabilities::builder().enable_tools().build()).with_instructions(
-            "典典博客 MCP：支持博客正文模糊检索、文件上传、字典文本、文档与目录、分类、标签、友链、项目、博客管理和 AI 文本处理。"
+            "典典博客 MCP：支持博客正文模糊检索、文件上传、字典文本、文档与目录、分类、标签、评论审核、友链、项目、博客管理和 AI 文本处理。"
                 .to_string(),
         )
     }
@@ -11,8 +11,11 @@ use axum::{
     Json,
 };
 
-use crate::error::ApiResponse;
-use crate::services::auth_service::{AuthService, Claims};
+use crate::error::{ApiError, ApiResponse};
+use crate::models::user::{User, UserRole};
+use crate::repositories::user_repo::UserRepository;
+use crate::services::auth_service::AuthService;
+use crate::services::session_service::SessionService;
 use crate::AppState;
 
 /// Extension type for storing authenticated user info in request
@@ -20,13 +23,30 @@ use crate::AppState;
 pub struct AuthUser {
     pub user_id: i64,
     pub username: String,
+    pub role: UserRole,
+    /// Login session the access token belongs to
+    pub session_id: String,
 }
 
-impl From<Claims> for AuthUser {
-    fn from(claims: Claims) -> Self {
+impl AuthUser {
+    pub fn new(user: User, session_id: String) -> Self {
         Self {
-            user_id: claims.sub,
-            username: claims.username,
+            role: user.role(),
+            user_id: user.id,
+            username: user.username,
+            session_id,
+        }
+    }
+
+    /// Fail with 403 unless the user has at least `role`
+    pub fn require(&self, role: UserRole) -> Result<(), ApiError> {
+        if self.role.allows(role) {
+            Ok(())
+        } else {
+            Err(ApiError::Forbidden(format!(
+                "This action requires the {} role",
+                role.as_str()
+            )))
         }
     }
 }
@@ -34,6 +54,8 @@ impl From<Claims> for AuthUser {
 /// Authentication middleware
 ///
 /// Verifies JWT token from Authorization header and injects user info into request extensions.
+/// The user and session are looked up on every request so role changes, deletions,
+/// logouts and session revocations apply immediately.
 /// Returns 401 Unauthorized if token is missing, invalid, or expired.
 pub async fn auth_middleware(
     State(state): State<AppState>,
@@ -63,14 +85,51 @@ pub async fn auth_middleware(
         }
     };
 
+    // Reject tokens of sessions that were logged out or revoked
+    let Some(session_id) = claims.sid else {
+        return unauthorized_response("Session expired, please log in again");
+    };
+    match SessionService::is_active(&state.cache, claims.sub, &session_id).await {
+        Ok(true) => {}
+        Ok(false) => return unauthorized_response("Session has been revoked"),
+        Err(e) => return e.into_response(),
+    }
+
+    // Load the current user (and role) behind the token
+    let user = match UserRepository::find_by_id(&state.db, claims.sub).await {
+        Ok(Some(user)) => user,
+        Ok(None) => return unauthorized_response("User not found"),
+        Err(e) => return e.into_response(),
+    };
+
     // Insert authenticated user into request extensions
-    let auth_user = AuthUser::from(claims);
+    let auth_user = AuthUser::new(user, session_id);
     request.extensions_mut().insert(auth_user);
 
     // Continue to the next handler
     next.run(request).await
 }
 
+/// Role middleware for admin route groups
+///
+/// Must run after `auth_middleware`. Returns 403 Forbidden if the user's role is below `required`.
+pub async fn require_role(
+    State(required): State<UserRole>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let allowed = request
+        .extensions()
+        .get::<AuthUser>()
+        .map(|user| user.require(required));
+
+    match allowed {
+        Some(Ok(())) => next.run(request).await,
+        Some(Err(e)) => e.into_response(),
+        None => unauthorized_response("Not authenticated"),
+    }
+}
+
 /// Create an unauthorized response
 fn unauthorized_response(message: &str) -> Response {
     let body = Json(ApiResponse::<()>::error(401, message));
@@ -0,0 +1,248 @@
+//! Client IP extraction
+//!
+//! Forwarding headers are only honoured when the direct peer is one of the
+//! trusted proxies in `ServerConfig::trusted_proxies`. The chain is then walked
+//! from the nearest hop outwards, skipping trusted proxies, so a client can't
+//! pick its own IP by prepending entries to `X-Forwarded-For`.
+
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{request::Parts, HeaderMap},
+};
+use ipnet::IpNet;
+
+use crate::error::ApiError;
+use crate::AppState;
+
+/// IP address of the client that sent the request
+///
+/// Use this in handlers instead of reading forwarding headers directly:
+/// ```rust
+/// async fn handler(client_ip: ClientIp) -> impl IntoResponse {
+///     // client_ip.to_string() is the address to rate limit or log
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+impl fmt::Display for ClientIp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromRequestParts<AppState> for ClientIp {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let ConnectInfo(addr) = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .copied()
+            .ok_or_else(|| ApiError::InternalError("Missing connection info".to_string()))?;
+
+        Ok(ClientIp(resolve_client_ip(
+            &parts.headers,
+            addr.ip(),
+            &state.config.server.trusted_proxies,
+        )))
+    }
+}
+
+/// Resolve the client IP of a request received from `peer`
+pub fn resolve_client_ip(headers: &HeaderMap, peer: IpAddr, trusted_proxies: &[IpNet]) -> IpAddr {
+    let peer = peer.to_canonical();
+    let is_trusted = |ip: &IpAddr| trusted_proxies.iter().any(|net| net.contains(ip));
+    if !is_trusted(&peer) {
+        return peer;
+    }
+
+    let Some(chain) = forwarded_chain(headers) else {
+        return peer;
+    };
+
+    // Nearest hop first; stop at the first address no trusted proxy vouches for
+    let mut client = peer;
+    for hop in chain.into_iter().rev() {
+        let Some(ip) = hop else {
+            break;
+        };
+        client = ip;
+        if !is_trusted(&ip) {
+            break;
+        }
+    }
+
+    client
+}
+
+/// Addresses recorded by proxies, client first; `None` for hops that can't be parsed
+///
+/// `Forwarded` wins over `X-Forwarded-For`, which wins over `X-Real-IP`.
+fn forwarded_chain(headers: &HeaderMap) -> Option<Vec<Option<IpAddr>>> {
+    let values = |name: &str| -> Vec<String> {
+        headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .map(ToOwned::to_owned)
+            .collect()
+    };
+
+    let forwarded = values("forwarded");
+    if !forwarded.is_empty() {
+        let chain = forwarded
+            .iter()
+            .flat_map(|value| value.split(','))
+            .map(|element| {
+                element
+                    .split(';')
+                    .filter_map(|pair| pair.split_once('='))
+                    .find(|(key, _)| key.trim().eq_ignore_ascii_case("for"))
+                    .and_then(|(_, node)| parse_node(node))
+            })
+            .collect();
+        return Some(chain);
+    }
+
+    let forwarded_for = values("x-forwarded-for");
+    if !forwarded_for.is_empty() {
+        let chain = forwarded_for
+            .iter()
+            .flat_map(|value| value.split(','))
+            .map(parse_node)
+            .collect();
+        return Some(chain);
+    }
+
+    headers
+        .get("x-real-ip")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| vec![parse_node(value)])
+}
+
+/// Parse a node such as `192.0.2.1`, `192.0.2.1:4711` or `"[2001:db8::1]:4711"`
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+
+    if let Ok(ip) = node.parse::<IpAddr>() {
+        return Some(ip.to_canonical());
+    }
+    if let Ok(addr) = node.parse::<SocketAddr>() {
+        return Some(addr.ip().to_canonical());
+    }
+
+    // Bracketed IPv6 without a port
+    node.strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .and_then(|ip| ip.parse::<IpAddr>().ok())
+        .map(|ip| ip.to_canonical())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    fn trusted() -> Vec<IpNet> {
+        vec!["10.0.0.0/8".parse().unwrap(), "::1/128".parse().unwrap()]
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn untrusted_peer_cannot_spoof_headers() {
+        let headers = headers(&[
+            ("x-forwarded-for", "1.1.1.1"),
+            ("x-real-ip", "1.1.1.1"),
+            ("forwarded", "for=1.1.1.1"),
+        ]);
+
+        assert_eq!(
+            resolve_client_ip(&headers, ip("203.0.113.9"), &trusted()),
+            ip("203.0.113.9")
+        );
+        assert_eq!(
+            resolve_client_ip(&headers, ip("10.0.0.2"), &[]),
+            ip("10.0.0.2")
+        );
+    }
+
+    #[test]
+    fn forwarded_for_skips_trusted_hops_only() {
+        // The client prepended a fake address; the first untrusted hop from the right wins
+        let headers = headers(&[("x-forwarded-for", "1.1.1.1, 203.0.113.9, 10.0.0.5")]);
+
+        assert_eq!(
+            resolve_client_ip(&headers, ip("10.0.0.2"), &trusted()),
+            ip("203.0.113.9")
+        );
+    }
+
+    #[test]
+    fn forwarded_header_takes_precedence() {
+        let headers = headers(&[
+            (
+                "forwarded",
+                r#"for=198.51.100.7;proto=https, for="[2001:db8::17]:4711";by=10.0.0.1"#,
+            ),
+            ("x-forwarded-for", "1.1.1.1"),
+        ]);
+
+        assert_eq!(
+            resolve_client_ip(&headers, ip("10.0.0.2"), &trusted()),
+            ip("2001:db8::17")
+        );
+    }
+
+    #[test]
+    fn real_ip_and_fallbacks() {
+        assert_eq!(
+            resolve_client_ip(
+                &headers(&[("x-real-ip", "198.51.100.7")]),
+                ip("::1"),
+                &trusted()
+            ),
+            ip("198.51.100.7")
+        );
+
+        // Unparseable or missing entries leave the nearest known address
+        assert_eq!(
+            resolve_client_ip(
+                &headers(&[("forwarded", "for=unknown")]),
+                ip("10.0.0.2"),
+                &trusted()
+            ),
+            ip("10.0.0.2")
+        );
+        assert_eq!(
+            resolve_client_ip(&HeaderMap::new(), ip("10.0.0.2"), &trusted()),
+            ip("10.0.0.2")
+        );
+    }
+
+    #[test]
+    fn mapped_ipv4_peer_matches_ipv4_ranges() {
+        let headers = headers(&[("x-forwarded-for", "198.51.100.7:5000")]);
+
+        assert_eq!(
+            resolve_client_ip(&headers, ip("::ffff:10.0.0.2"), &trusted()),
+            ip("198.51.100.7")
+        );
+    }
+}
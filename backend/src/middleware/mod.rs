@@ -1,3 +1,5 @@
 //! Middleware components
 
 pub mod auth;
+pub mod client_ip;
+pub mod rate_limit;
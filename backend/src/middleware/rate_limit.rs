@@ -0,0 +1,214 @@
+//! Rate limiting middleware
+//!
+//! Every route group (public API, admin API, MCP) gets a token bucket per client
+//! IP in Redis. Responses carry `RateLimit-Limit`, `RateLimit-Remaining` and
+//! `RateLimit-Reset` headers; rejected requests get `429` with `Retry-After`.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, State},
+    http::{HeaderMap, HeaderName, HeaderValue, Request},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Router,
+};
+use chrono::Utc;
+use ipnet::IpNet;
+
+use crate::config::RateLimit;
+use crate::error::ApiError;
+use crate::middleware::client_ip::resolve_client_ip;
+use crate::services::cache_service::{cache_keys, CacheService};
+use crate::AppState;
+
+/// Route groups with separate limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteGroup {
+    Public,
+    Admin,
+    Mcp,
+}
+
+impl RouteGroup {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RouteGroup::Public => "public",
+            RouteGroup::Admin => "admin",
+            RouteGroup::Mcp => "mcp",
+        }
+    }
+}
+
+/// State of the rate limiting middleware for one route group
+#[derive(Clone)]
+pub struct RateLimitState {
+    cache: Arc<CacheService>,
+    group: RouteGroup,
+    limit: RateLimit,
+    trusted_proxies: Arc<[IpNet]>,
+}
+
+/// Apply the configured limit of `group` to every route of `router`
+pub fn with_rate_limit(
+    router: Router<AppState>,
+    state: &AppState,
+    group: RouteGroup,
+) -> Router<AppState> {
+    let config = &state.config.rate_limit;
+    if !config.enabled {
+        return router;
+    }
+
+    let limit = match group {
+        RouteGroup::Public => config.public,
+        RouteGroup::Admin => config.admin,
+        RouteGroup::Mcp => config.mcp,
+    };
+    let rate_limit_state = RateLimitState {
+        cache: state.cache.clone(),
+        group,
+        limit,
+        trusted_proxies: state.config.server.trusted_proxies.clone().into(),
+    };
+
+    router.layer(middleware::from_fn_with_state(rate_limit_state, rate_limit))
+}
+
+/// Take a token for the client, rejecting the request when its bucket is empty
+///
+/// Fails open: a Redis outage must not take the API down with it.
+pub async fn rate_limit(
+    State(state): State<RateLimitState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let client =
+        resolve_client_ip(request.headers(), addr.ip(), &state.trusted_proxies).to_string();
+    let key = cache_keys::rate_limit_bucket(state.group.as_str(), &client);
+
+    let taken = state
+        .cache
+        .take_token(
+            &key,
+            state.limit.burst as f64,
+            refill_per_second(&state.limit),
+            Utc::now().timestamp_millis(),
+        )
+        .await;
+    let decision = match taken {
+        Ok((allowed, tokens)) => RateLimitDecision::new(&state.limit, allowed, tokens),
+        Err(e) => {
+            tracing::warn!("Rate limiting unavailable: {}", e);
+            return next.run(request).await;
+        }
+    };
+
+    let mut response = if decision.allowed {
+        next.run(request).await
+    } else {
+        tracing::debug!(
+            "Rate limited {} request from {}",
+            state.group.as_str(),
+            client
+        );
+        ApiError::TooManyRequests("Too many requests, please slow down".to_string()).into_response()
+    };
+    decision.apply_headers(response.headers_mut());
+
+    response
+}
+
+fn refill_per_second(limit: &RateLimit) -> f64 {
+    limit.per_minute as f64 / 60.0
+}
+
+/// Outcome of taking a token, as reported to the client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RateLimitDecision {
+    allowed: bool,
+    limit: u32,
+    remaining: u32,
+    /// Seconds until the bucket is full again
+    reset: u64,
+    /// Seconds until the next token, for rejected requests
+    retry_after: Option<u64>,
+}
+
+impl RateLimitDecision {
+    fn new(limit: &RateLimit, allowed: bool, tokens: f64) -> Self {
+        let rate = refill_per_second(limit);
+        let tokens = tokens.clamp(0.0, limit.burst as f64);
+
+        Self {
+            allowed,
+            limit: limit.burst,
+            remaining: tokens.floor() as u32,
+            reset: ((limit.burst as f64 - tokens) / rate).ceil() as u64,
+            retry_after: (!allowed).then(|| ((1.0 - tokens) / rate).ceil().max(1.0) as u64),
+        }
+    }
+
+    fn apply_headers(&self, headers: &mut HeaderMap) {
+        let mut set = |name: &'static str, value: u64| {
+            headers.insert(HeaderName::from_static(name), HeaderValue::from(value));
+        };
+
+        set("ratelimit-limit", self.limit as u64);
+        set("ratelimit-remaining", self.remaining as u64);
+        set("ratelimit-reset", self.reset);
+        if let Some(retry_after) = self.retry_after {
+            set("retry-after", retry_after);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMIT: RateLimit = RateLimit {
+        burst: 10,
+        per_minute: 60,
+    };
+
+    #[test]
+    fn allowed_request_reports_remaining_tokens() {
+        let decision = RateLimitDecision::new(&LIMIT, true, 7.4);
+
+        assert_eq!(decision.remaining, 7);
+        assert_eq!(decision.reset, 3);
+        assert_eq!(decision.retry_after, None);
+    }
+
+    #[test]
+    fn rejected_request_waits_for_next_token() {
+        let decision = RateLimitDecision::new(&LIMIT, false, 0.25);
+        assert_eq!(decision.remaining, 0);
+        assert_eq!(decision.retry_after, Some(1));
+        assert_eq!(decision.reset, 10);
+
+        let slow = RateLimit {
+            burst: 5,
+            per_minute: 6,
+        };
+        assert_eq!(
+            RateLimitDecision::new(&slow, false, 0.0).retry_after,
+            Some(10)
+        );
+    }
+
+    #[test]
+    fn headers_follow_ratelimit_draft() {
+        let mut headers = HeaderMap::new();
+        RateLimitDecision::new(&LIMIT, false, 0.0).apply_headers(&mut headers);
+
+        assert_eq!(headers["ratelimit-limit"], "10");
+        assert_eq!(headers["ratelimit-remaining"], "0");
+        assert_eq!(headers["ratelimit-reset"], "10");
+        assert_eq!(headers["retry-after"], "1");
+    }
+}
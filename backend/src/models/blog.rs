@@ -34,6 +34,11 @@ pub struct Blog {
     pub updated_at: Option<DateTime<Utc>>,
     #[sqlx(default)]
     pub references: Option<JsonValue>,
+    #[sqlx(default)]
+    pub publish_at: Option<DateTime<Utc>>,
+    /// Owning user; `author` stays the displayed byline
+    #[sqlx(default)]
+    pub author_id: Option<i64>,
 }
 
 /// Blog list item (without full content) for list display
@@ -50,6 +55,7 @@ pub struct BlogListItem {
     pub view_count: i64,
     pub is_published: bool,
     pub created_at: Option<DateTime<Utc>>,
+    pub publish_at: Option<DateTime<Utc>>,
 }
 
 /// Blog detail response with category and tags
@@ -70,6 +76,16 @@ pub struct BlogDetail {
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
     pub references: Option<JsonValue>,
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+impl BlogDetail {
+    /// Whether the blog may be shown on public endpoints
+    ///
+    /// A published blog whose `publish_at` is still in the future stays hidden.
+    pub fn is_publicly_visible(&self) -> bool {
+        self.is_published && self.publish_at.is_none_or(|at| at <= Utc::now())
+    }
 }
 
 /// Create blog request DTO
@@ -85,6 +101,8 @@ pub struct CreateBlogRequest {
     pub tag_ids: Option<Vec<i64>>,
     pub is_published: Option<bool>,
     pub references: Option<JsonValue>,
+    /// Scheduled publish time; a future value keeps the blog unpublished until then
+    pub publish_at: Option<DateTime<Utc>>,
 }
 
 /// Update blog request DTO
@@ -100,6 +118,8 @@ pub struct UpdateBlogRequest {
     pub tag_ids: Option<Vec<i64>>,
     pub is_published: Option<bool>,
     pub references: Option<JsonValue>,
+    /// Scheduled publish time; a future value keeps the blog unpublished until then
+    pub publish_at: Option<DateTime<Utc>>,
 }
 
 /// Blog query parameters for list endpoint
@@ -140,6 +160,7 @@ pub struct BlogResponse {
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
     pub references: Option<JsonValue>,
+    pub publish_at: Option<DateTime<Utc>>,
 }
 
 impl From<BlogDetail> for BlogResponse {
@@ -160,6 +181,49 @@ impl From<BlogDetail> for BlogResponse {
             created_at: blog.created_at,
             updated_at: blog.updated_at,
             references: blog.references,
+            publish_at: blog.publish_at,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::BlogDetail;
+    use chrono::{Duration, Utc};
+
+    fn sample_blog(is_published: bool, publish_at: Option<chrono::DateTime<Utc>>) -> BlogDetail {
+        BlogDetail {
+            id: 1,
+            title: "sample".to_string(),
+            slug: None,
+            author: None,
+            content: "content".to_string(),
+            html: None,
+            summary: None,
+            thumbnail: None,
+            category: None,
+            tags: Vec::new(),
+            view_count: 0,
+            is_published,
+            created_at: None,
+            updated_at: None,
+            references: None,
+            publish_at,
+        }
+    }
+
+    #[test]
+    fn published_blog_without_schedule_is_visible() {
+        assert!(sample_blog(true, None).is_publicly_visible());
+        assert!(!sample_blog(false, None).is_publicly_visible());
+    }
+
+    #[test]
+    fn future_publish_at_hides_published_blog() {
+        let future = Utc::now() + Duration::hours(1);
+        let past = Utc::now() - Duration::hours(1);
+
+        assert!(!sample_blog(true, Some(future)).is_publicly_visible());
+        assert!(sample_blog(true, Some(past)).is_publicly_visible());
+    }
+}
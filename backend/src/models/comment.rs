@@ -0,0 +1,149 @@
+//! Comment models and DTOs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Comment status enum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(i16)]
+pub enum CommentStatus {
+    Pending = 0,  // 待审核
+    Approved = 1, // 已通过
+    Spam = 2,     // 垃圾评论
+}
+
+impl From<i16> for CommentStatus {
+    fn from(value: i16) -> Self {
+        match value {
+            1 => CommentStatus::Approved,
+            2 => CommentStatus::Spam,
+            _ => CommentStatus::Pending,
+        }
+    }
+}
+
+impl From<CommentStatus> for i16 {
+    fn from(status: CommentStatus) -> Self {
+        status as i16
+    }
+}
+
+/// What a comment is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentTarget {
+    Blog(i64),
+    Document(i64),
+}
+
+impl CommentTarget {
+    pub fn blog_id(&self) -> Option<i64> {
+        match self {
+            CommentTarget::Blog(id) => Some(*id),
+            CommentTarget::Document(_) => None,
+        }
+    }
+
+    pub fn document_id(&self) -> Option<i64> {
+        match self {
+            CommentTarget::Blog(_) => None,
+            CommentTarget::Document(id) => Some(*id),
+        }
+    }
+}
+
+/// Comment entity from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Comment {
+    pub id: i64,
+    pub blog_id: Option<i64>,
+    pub document_id: Option<i64>,
+    pub parent_id: Option<i64>,
+    pub author_name: String,
+    pub author_email: Option<String>,
+    pub author_url: Option<String>,
+    pub content: String,
+    pub status: i16,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl Comment {
+    pub fn target(&self) -> Option<CommentTarget> {
+        match (self.blog_id, self.document_id) {
+            (Some(id), _) => Some(CommentTarget::Blog(id)),
+            (None, Some(id)) => Some(CommentTarget::Document(id)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Public comment submission DTO
+#[derive(Debug, Deserialize)]
+pub struct CreateCommentRequest {
+    pub author_name: String,
+    pub author_email: Option<String>,
+    pub author_url: Option<String>,
+    pub content: String,
+    pub parent_id: Option<i64>,
+    /// Honeypot field - hidden in the comment form, so only bots fill it in
+    #[serde(default)]
+    pub website: Option<String>,
+}
+
+/// Admin comment list query parameters
+#[derive(Debug, Deserialize)]
+pub struct CommentQueryParams {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+    pub status: Option<i16>,
+}
+
+impl CommentQueryParams {
+    pub fn page(&self) -> i64 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn page_size(&self) -> i64 {
+        self.page_size.unwrap_or(20).clamp(1, 100)
+    }
+}
+
+/// Update comment status request DTO
+#[derive(Debug, Deserialize)]
+pub struct UpdateCommentStatusRequest {
+    pub status: i16,
+}
+
+/// Public comment DTO (without email, IP and user agent)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicComment {
+    pub id: i64,
+    pub parent_id: Option<i64>,
+    pub author_name: String,
+    pub author_url: Option<String>,
+    pub content: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<Comment> for PublicComment {
+    fn from(comment: Comment) -> Self {
+        Self {
+            id: comment.id,
+            parent_id: comment.parent_id,
+            author_name: comment.author_name,
+            author_url: comment.author_url,
+            content: comment.content,
+            created_at: comment.created_at,
+        }
+    }
+}
+
+/// Public comment with its nested replies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentNode {
+    #[serde(flatten)]
+    pub comment: PublicComment,
+    pub replies: Vec<CommentNode>,
+}
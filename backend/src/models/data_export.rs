@@ -0,0 +1,98 @@
+//! Versioned data export / import models
+//!
+//! An export is NDJSON: an `ExportHeader` line, one `row` line per table row
+//! and an `end` line with the row count of each table, so a truncated
+//! download is detected instead of half-imported.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+
+/// Identifies exports made by this backend
+pub const EXPORT_FORMAT: &str = "blog-backend-export";
+
+/// Version of the export layout; bumped when rows change incompatibly
+pub const EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// Column name to value of one table row
+pub type RowData = Map<String, JsonValue>;
+
+/// Export query parameters
+#[derive(Debug, Deserialize)]
+pub struct DataExportQuery {
+    /// Only export rows changed at or after this time
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// First line of an export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportHeader {
+    pub format: String,
+    pub schema_version: u32,
+    /// Latest database migration of the exporting instance
+    pub migration: String,
+    pub exported_at: DateTime<Utc>,
+    /// Set for incremental exports
+    pub since: Option<DateTime<Utc>>,
+    /// Tables in the order their rows follow
+    pub tables: Vec<String>,
+}
+
+/// One line of an export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExportRecord {
+    Header(ExportHeader),
+    Row {
+        table: String,
+        /// As produced by `row_to_json`
+        data: RowData,
+    },
+    End {
+        /// Rows written per table
+        rows: BTreeMap<String, u64>,
+    },
+}
+
+/// What to do with an imported row whose key already exists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Overwrite the columns present in the import, keep the others
+    #[default]
+    Upsert,
+    /// Keep the existing row
+    Skip,
+    /// Overwrite the whole row; columns missing from the import get their defaults
+    Replace,
+}
+
+/// Import query parameters
+#[derive(Debug, Deserialize)]
+pub struct DataImportQuery {
+    pub policy: Option<ConflictPolicy>,
+}
+
+/// Rows changed in one table
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TableChanges {
+    pub table: String,
+    pub inserted: u64,
+    pub updated: u64,
+    /// Existing rows left alone, by the skip policy or because nothing could change
+    pub skipped: u64,
+}
+
+/// Report of an import, written in a single transaction
+#[derive(Debug, Clone, Serialize)]
+pub struct DataImportReport {
+    /// `None` for the legacy JSON format
+    pub schema_version: Option<u32>,
+    pub policy: ConflictPolicy,
+    /// Base time of an incremental export
+    pub since: Option<DateTime<Utc>>,
+    pub tables: Vec<TableChanges>,
+    pub warnings: Vec<String>,
+}
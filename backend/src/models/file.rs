@@ -18,6 +18,12 @@ pub struct File {
     pub height: Option<i32>,
     pub bucket_name: Option<String>,
     pub object_key: Option<String>,
+    /// Backend holding the file and its variants: s3 or local
+    pub storage_backend: String,
+    /// SHA-256 of the uploaded content, hex encoded
+    pub content_hash: Option<String>,
+    /// Number of uploads sharing this file
+    pub ref_count: i32,
     pub created_at: Option<DateTime<Utc>>,
 }
 
@@ -33,6 +39,11 @@ pub struct FileResponse {
     pub thumbnail_url: Option<String>,
     pub width: Option<i32>,
     pub height: Option<i32>,
+    pub storage_backend: String,
+    pub content_hash: Option<String>,
+    pub ref_count: i32,
+    /// Thumbnail and responsive variants of images
+    pub variants: Vec<FileVariantResponse>,
     pub created_at: Option<DateTime<Utc>>,
 }
 
@@ -48,11 +59,79 @@ impl From<File> for FileResponse {
             thumbnail_url: file.thumbnail_url,
             width: file.width,
             height: file.height,
+            storage_backend: file.storage_backend,
+            content_hash: file.content_hash,
+            ref_count: file.ref_count,
+            variants: Vec::new(),
             created_at: file.created_at,
         }
     }
 }
 
+impl FileResponse {
+    /// Attach the variants generated for the file
+    pub fn with_variants(mut self, variants: Vec<FileVariant>) -> Self {
+        self.variants = variants
+            .into_iter()
+            .map(FileVariantResponse::from)
+            .collect();
+        self
+    }
+}
+
+/// Generated image variant entity from database
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FileVariant {
+    pub id: i64,
+    pub file_id: i64,
+    /// thumbnail or responsive
+    pub kind: String,
+    /// jpeg, png or webp
+    pub format: String,
+    pub width: i32,
+    pub height: i32,
+    pub file_size: i64,
+    pub url: String,
+    pub object_key: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// File variant response DTO
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileVariantResponse {
+    pub kind: String,
+    pub format: String,
+    pub width: i32,
+    pub height: i32,
+    pub file_size: i64,
+    pub url: String,
+}
+
+impl From<FileVariant> for FileVariantResponse {
+    fn from(variant: FileVariant) -> Self {
+        Self {
+            kind: variant.kind,
+            format: variant.format,
+            width: variant.width,
+            height: variant.height,
+            file_size: variant.file_size,
+            url: variant.url,
+        }
+    }
+}
+
+/// Create file variant request DTO (internal use for repository)
+#[derive(Debug, Clone)]
+pub struct CreateFileVariantRequest {
+    pub kind: String,
+    pub format: String,
+    pub width: i32,
+    pub height: i32,
+    pub file_size: i64,
+    pub url: String,
+    pub object_key: String,
+}
+
 /// Create file request DTO (internal use for repository)
 #[derive(Debug, Clone)]
 pub struct CreateFileRequest {
@@ -66,6 +145,8 @@ pub struct CreateFileRequest {
     pub height: Option<i32>,
     pub bucket_name: Option<String>,
     pub object_key: Option<String>,
+    pub storage_backend: String,
+    pub content_hash: Option<String>,
 }
 
 /// File query parameters for list endpoint
@@ -85,3 +166,138 @@ impl FileQueryParams {
         self.page_size.unwrap_or(20).clamp(1, 100)
     }
 }
+
+/// Orphaned file scan parameters
+#[derive(Debug, Default, Deserialize)]
+pub struct OrphanScanParams {
+    /// Ignore files and objects younger than this, so uploads for content that
+    /// hasn't been saved yet are left alone
+    pub min_age_hours: Option<i64>,
+}
+
+impl OrphanScanParams {
+    pub fn min_age_hours(&self) -> i64 {
+        self.min_age_hours.unwrap_or(24).max(0)
+    }
+}
+
+/// File record reported by the orphan scan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanedFile {
+    pub id: i64,
+    pub filename: String,
+    pub original_filename: Option<String>,
+    pub url: String,
+    pub object_key: Option<String>,
+    pub storage_backend: String,
+    pub file_size: Option<i64>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<&File> for OrphanedFile {
+    fn from(file: &File) -> Self {
+        Self {
+            id: file.id,
+            filename: file.filename.clone(),
+            original_filename: file.original_filename.clone(),
+            url: file.url.clone(),
+            object_key: file.object_key.clone(),
+            storage_backend: file.storage_backend.clone(),
+            file_size: file.file_size,
+            created_at: file.created_at,
+        }
+    }
+}
+
+/// Stored object no file record points to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UntrackedObject {
+    pub storage_backend: String,
+    pub object_key: String,
+    pub size: i64,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+/// Result of cross-referencing files, content and storage
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OrphanReport {
+    /// Files no blog, document, category, project or other content refers to
+    pub unreferenced_files: Vec<OrphanedFile>,
+    /// Files only unreadable content could refer to: protected texts that
+    /// couldn't be decrypted may use them, so they are never purged
+    pub undecidable_files: Vec<OrphanedFile>,
+    /// Protected texts whose content couldn't be decrypted for the scan
+    pub unreadable_texts: usize,
+    /// File records whose object is gone from storage
+    pub missing_objects: Vec<OrphanedFile>,
+    /// Objects in storage without a file record
+    pub untracked_objects: Vec<UntrackedObject>,
+    /// Backends that couldn't be listed, so their objects weren't checked
+    pub unavailable_backends: Vec<String>,
+}
+
+/// What a purge removed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OrphanPurgeSummary {
+    pub deleted_files: usize,
+    pub deleted_objects: usize,
+    pub freed_bytes: i64,
+}
+
+/// Request for presigned URLs to upload a file directly to S3
+#[derive(Debug, Clone, Deserialize)]
+pub struct PresignUploadRequest {
+    pub filename: String,
+    pub content_type: Option<String>,
+    /// Size of the file in bytes
+    pub size: i64,
+}
+
+/// Presigned upload issued to the client
+///
+/// Small files are sent with one `PUT` to `url`; large ones are split into
+/// `part_size` chunks, each sent to the URL of its part, and need the
+/// `upload_id` and the returned ETags to complete.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresignedUpload {
+    pub object_key: String,
+    /// Content-Type header the upload must be sent with
+    pub content_type: String,
+    pub url: Option<String>,
+    pub upload_id: Option<String>,
+    pub part_size: Option<i64>,
+    pub parts: Vec<PresignedPart>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Presigned URL of one multipart upload part
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresignedPart {
+    pub part_number: i32,
+    pub url: String,
+}
+
+/// Uploaded part, with the ETag S3 returned for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletedUploadPart {
+    pub part_number: i32,
+    pub etag: String,
+}
+
+/// Request to record a file uploaded with presigned URLs
+#[derive(Debug, Clone, Deserialize)]
+pub struct CompleteUploadRequest {
+    pub object_key: String,
+    pub original_filename: Option<String>,
+    /// Set for multipart uploads
+    pub upload_id: Option<String>,
+    #[serde(default)]
+    pub parts: Vec<CompletedUploadPart>,
+}
+
+/// Request to abandon a multipart upload
+#[derive(Debug, Clone, Deserialize)]
+pub struct AbortUploadRequest {
+    pub object_key: String,
+    pub upload_id: String,
+}
@@ -40,6 +40,9 @@ pub struct FriendLink {
     pub email: Option<String>,
     pub status: i16,
     pub created_at: Option<DateTime<Utc>>,
+    pub backlink_found: Option<bool>,
+    pub backlink_error: Option<String>,
+    pub backlink_checked_at: Option<DateTime<Utc>>,
 }
 
 /// Create friend link request DTO
@@ -64,6 +67,22 @@ pub struct UpdateFriendLinkRequest {
     pub status: Option<i16>,
 }
 
+/// Public friend link application DTO (always created as pending)
+#[derive(Debug, Deserialize)]
+pub struct ApplyFriendLinkRequest {
+    pub name: String,
+    pub url: String,
+    pub logo: Option<String>,
+    pub intro: Option<String>,
+    pub email: Option<String>,
+}
+
+/// Update friend link status request DTO
+#[derive(Debug, Deserialize)]
+pub struct UpdateFriendLinkStatusRequest {
+    pub status: i16,
+}
+
 /// Friend link response DTO
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FriendLinkResponse {
@@ -75,6 +94,9 @@ pub struct FriendLinkResponse {
     pub email: Option<String>,
     pub status: i16,
     pub created_at: Option<DateTime<Utc>>,
+    pub backlink_found: Option<bool>,
+    pub backlink_error: Option<String>,
+    pub backlink_checked_at: Option<DateTime<Utc>>,
 }
 
 impl From<FriendLink> for FriendLinkResponse {
@@ -88,6 +110,9 @@ impl From<FriendLink> for FriendLinkResponse {
             email: link.email,
             status: link.status,
             created_at: link.created_at,
+            backlink_found: link.backlink_found,
+            backlink_error: link.backlink_error,
+            backlink_checked_at: link.backlink_checked_at,
         }
     }
 }
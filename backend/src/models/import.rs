@@ -0,0 +1,132 @@
+//! Content import models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+/// Blog engine an import comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportSource {
+    /// WordPress WXR export (XML)
+    WordPress,
+    /// Zip of Hexo, Hugo or Jekyll Markdown files with front matter
+    Markdown,
+    /// Ghost JSON export
+    Ghost,
+}
+
+impl ImportSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportSource::WordPress => "wordpress",
+            ImportSource::Markdown => "markdown",
+            ImportSource::Ghost => "ghost",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "wordpress" => Some(ImportSource::WordPress),
+            "markdown" => Some(ImportSource::Markdown),
+            "ghost" => Some(ImportSource::Ghost),
+            _ => None,
+        }
+    }
+}
+
+/// Post read from an export, before it is matched against existing content
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportedPost {
+    pub title: String,
+    pub slug: Option<String>,
+    /// Markdown, or HTML for engines that store rendered posts
+    pub content: String,
+    pub summary: Option<String>,
+    pub thumbnail: Option<String>,
+    pub author: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub is_published: bool,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Inline references, as stored in `blogs.references`
+    pub references: Option<JsonValue>,
+}
+
+/// Posts parsed from an export, with notes about what was left out
+#[derive(Debug, Clone, Default)]
+pub struct ParsedImport {
+    pub posts: Vec<ImportedPost>,
+    pub warnings: Vec<String>,
+}
+
+/// Import query parameters
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    /// Only report what would happen; defaults to true so conflicts are seen first
+    pub dry_run: Option<bool>,
+}
+
+impl ImportQuery {
+    pub fn dry_run(&self) -> bool {
+        self.dry_run.unwrap_or(true)
+    }
+}
+
+/// What the import does with one post
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportAction {
+    Create,
+    Skip,
+}
+
+/// Outcome of one imported post
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedPostResult {
+    pub title: String,
+    pub slug: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub is_published: bool,
+    pub created_at: Option<DateTime<Utc>>,
+    pub action: ImportAction,
+    /// Why the post is skipped
+    pub conflict: Option<String>,
+    /// ID of the created blog, once written
+    pub blog_id: Option<i64>,
+}
+
+/// Report of an import run
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub source: ImportSource,
+    pub dry_run: bool,
+    pub posts: Vec<ImportedPostResult>,
+    /// Categories that don't exist yet and are (or would be) created
+    pub new_categories: Vec<String>,
+    /// Tags that don't exist yet and are (or would be) created
+    pub new_tags: Vec<String>,
+    pub created: usize,
+    pub skipped: usize,
+    pub warnings: Vec<String>,
+}
+
+/// Blog row written by an import, with category and tags by their stored names
+#[derive(Debug, Clone)]
+pub struct NewImportedBlog {
+    pub title: String,
+    pub slug: String,
+    pub author: Option<String>,
+    pub content: String,
+    pub html: String,
+    pub summary: Option<String>,
+    pub thumbnail: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub is_published: bool,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub references: Option<JsonValue>,
+}
@@ -0,0 +1,76 @@
+//! Dead-link check models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Where a checked link was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkSourceType {
+    FriendLink,
+    Blog,
+}
+
+impl LinkSourceType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LinkSourceType::FriendLink => "friend_link",
+            LinkSourceType::Blog => "blog",
+        }
+    }
+}
+
+/// Stored result of the latest check of one link
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LinkCheck {
+    pub id: i64,
+    pub source_type: String,
+    pub source_id: i64,
+    /// Blog title or friend link name
+    pub source_title: Option<String>,
+    pub url: String,
+    pub http_status: Option<i32>,
+    pub redirect_url: Option<String>,
+    pub error: Option<String>,
+    pub consecutive_failures: i32,
+    pub last_checked_at: Option<DateTime<Utc>>,
+    pub last_ok_at: Option<DateTime<Utc>>,
+}
+
+/// Result of checking a single URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkCheckOutcome {
+    pub http_status: Option<i32>,
+    pub redirect_url: Option<String>,
+    pub error: Option<String>,
+}
+
+impl LinkCheckOutcome {
+    /// A link is healthy when it answered with a non-error status
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none() && self.http_status.is_some_and(|status| status < 400)
+    }
+}
+
+/// Broken link report query parameters
+#[derive(Debug, Deserialize)]
+pub struct BrokenLinkQuery {
+    pub min_failures: Option<i32>,
+}
+
+impl BrokenLinkQuery {
+    pub fn min_failures(&self) -> i32 {
+        self.min_failures.unwrap_or(1).max(1)
+    }
+}
+
+/// Summary of one checker run
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkCheckSummary {
+    /// Distinct URLs requested
+    pub checked_urls: usize,
+    /// Links recorded (a URL used by several sources counts once per source)
+    pub links: usize,
+    /// Links that failed in this run
+    pub broken: usize,
+}
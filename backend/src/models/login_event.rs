@@ -0,0 +1,56 @@
+//! Login event models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Why a login attempt failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginFailureReason {
+    UnknownUser,
+    InvalidPassword,
+    InvalidTwoFactor,
+}
+
+impl LoginFailureReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoginFailureReason::UnknownUser => "unknown_user",
+            LoginFailureReason::InvalidPassword => "invalid_password",
+            LoginFailureReason::InvalidTwoFactor => "invalid_two_factor",
+        }
+    }
+}
+
+/// Failed login attempt from database
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct LoginEvent {
+    pub id: i64,
+    pub username: String,
+    pub user_id: Option<i64>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub reason: String,
+    /// Whether this failure locked the account
+    pub locked_out: bool,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Login event query parameters
+#[derive(Debug, Deserialize)]
+pub struct LoginEventQuery {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+    pub username: Option<String>,
+    pub ip: Option<String>,
+}
+
+impl LoginEventQuery {
+    pub fn page(&self) -> i64 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn page_size(&self) -> i64 {
+        self.page_size.unwrap_or(20).clamp(1, 100)
+    }
+}
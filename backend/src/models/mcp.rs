@@ -1,5 +1,214 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::FromRow;
+
+use crate::mcp::auth::mask_token;
+
+/// What an MCP token may do
+///
+/// Scopes are independent: a token needs the scope of every tool it calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum McpScope {
+    /// Search, list and read content
+    Read,
+    /// Create and update content, upload files, publish
+    Write,
+    /// Delete content
+    Destructive,
+}
+
+impl McpScope {
+    pub const ALL: [McpScope; 3] = [McpScope::Read, McpScope::Write, McpScope::Destructive];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            McpScope::Read => "read",
+            McpScope::Write => "write",
+            McpScope::Destructive => "destructive",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "read" => Some(McpScope::Read),
+            "write" => Some(McpScope::Write),
+            "destructive" => Some(McpScope::Destructive),
+            _ => None,
+        }
+    }
+
+    /// Scope required to call an MCP tool, derived from its name
+    pub fn for_tool(tool: &str) -> Self {
+        const READ_PREFIXES: [&str; 3] = ["search_", "get_", "list_"];
+        // AI helpers only transform the text they are given
+        const READ_TOOLS: [&str; 2] = ["polish_markdown", "summarize_markdown"];
+
+        if tool.starts_with("delete_") {
+            McpScope::Destructive
+        } else if READ_PREFIXES.iter().any(|prefix| tool.starts_with(prefix))
+            || READ_TOOLS.contains(&tool)
+        {
+            McpScope::Read
+        } else {
+            McpScope::Write
+        }
+    }
+}
+
+/// MCP token entity from database
+#[derive(Debug, Clone, FromRow)]
+pub struct McpToken {
+    pub id: i64,
+    pub name: String,
+    pub token_hash: String,
+    pub token_last_four: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl McpToken {
+    pub fn scopes(&self) -> Vec<McpScope> {
+        self.scopes
+            .iter()
+            .filter_map(|scope| McpScope::parse(scope))
+            .collect()
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|at| at <= Utc::now())
+    }
+}
+
+/// Token authenticated by the MCP middleware, attached to the request
+#[derive(Debug, Clone)]
+pub struct McpGrant {
+    pub token_id: i64,
+    pub token_name: String,
+    pub scopes: Vec<McpScope>,
+}
+
+impl McpGrant {
+    pub fn allows(&self, scope: McpScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    pub fn allows_tool(&self, tool: &str) -> bool {
+        self.allows(McpScope::for_tool(tool))
+    }
+}
+
+impl From<&McpToken> for McpGrant {
+    fn from(token: &McpToken) -> Self {
+        Self {
+            token_id: token.id,
+            token_name: token.name.clone(),
+            scopes: token.scopes(),
+        }
+    }
+}
+
+/// MCP token response DTO (never includes the secret)
+#[derive(Debug, Clone, Serialize)]
+pub struct McpTokenResponse {
+    pub id: i64,
+    pub name: String,
+    pub token_masked: String,
+    pub scopes: Vec<McpScope>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub expired: bool,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<McpToken> for McpTokenResponse {
+    fn from(token: McpToken) -> Self {
+        Self {
+            token_masked: mask_token(&token.token_last_four),
+            scopes: token.scopes(),
+            expired: token.is_expired(),
+            id: token.id,
+            name: token.name,
+            expires_at: token.expires_at,
+            last_used_at: token.last_used_at,
+            created_at: token.created_at,
+        }
+    }
+}
+
+/// Create MCP token request DTO
+#[derive(Debug, Deserialize)]
+pub struct CreateMcpTokenRequest {
+    pub name: String,
+    pub scopes: Vec<McpScope>,
+    /// None for a token that never expires
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Newly created MCP token; the secret is only ever shown here
+#[derive(Debug, Serialize)]
+pub struct CreateMcpTokenResponse {
+    pub endpoint: String,
+    pub token: String,
+    #[serde(flatten)]
+    pub info: McpTokenResponse,
+}
+
+/// Outcome of an audited MCP tool call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum McpCallStatus {
+    Ok,
+    Error,
+    /// Rejected because the token lacks the tool's scope
+    Denied,
+}
+
+impl McpCallStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            McpCallStatus::Ok => "ok",
+            McpCallStatus::Error => "error",
+            McpCallStatus::Denied => "denied",
+        }
+    }
+}
+
+/// MCP audit log entry from database
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct McpAuditLog {
+    pub id: i64,
+    pub token_id: Option<i64>,
+    pub token_name: Option<String>,
+    pub tool: String,
+    pub arguments: Option<JsonValue>,
+    pub status: String,
+    pub error: Option<String>,
+    pub duration_ms: Option<i64>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// MCP audit log query parameters
+#[derive(Debug, Deserialize)]
+pub struct McpAuditLogQuery {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+    pub token_id: Option<i64>,
+    pub tool: Option<String>,
+    pub status: Option<String>,
+}
+
+impl McpAuditLogQuery {
+    pub fn page(&self) -> i64 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn page_size(&self) -> i64 {
+        self.page_size.unwrap_or(20).clamp(1, 100)
+    }
+}
 
 #[derive(Debug, Clone, Serialize)]
 pub struct McpSettingsResponse {
@@ -22,3 +231,59 @@ pub struct RotateMcpTokenResponse {
     pub token_masked: String,
     pub token_last_rotated_at: DateTime<Utc>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_scopes_follow_tool_names() {
+        assert_eq!(McpScope::for_tool("search_blogs"), McpScope::Read);
+        assert_eq!(McpScope::for_tool("get_text_content"), McpScope::Read);
+        assert_eq!(McpScope::for_tool("list_pending_comments"), McpScope::Read);
+        assert_eq!(McpScope::for_tool("summarize_markdown"), McpScope::Read);
+        assert_eq!(McpScope::for_tool("create_blog_draft"), McpScope::Write);
+        assert_eq!(McpScope::for_tool("publish_blog"), McpScope::Write);
+        assert_eq!(
+            McpScope::for_tool("set_blog_global_summary"),
+            McpScope::Write
+        );
+        assert_eq!(
+            McpScope::for_tool("delete_directory"),
+            McpScope::Destructive
+        );
+    }
+
+    #[test]
+    fn grant_only_allows_its_scopes() {
+        let grant = McpGrant {
+            token_id: 1,
+            token_name: "reader".to_string(),
+            scopes: vec![McpScope::Read],
+        };
+
+        assert!(grant.allows_tool("list_blogs"));
+        assert!(!grant.allows_tool("update_blog"));
+        assert!(!grant.allows_tool("delete_blog"));
+    }
+
+    #[test]
+    fn token_ignores_unknown_scopes_and_reports_expiry() {
+        let mut token = McpToken {
+            id: 1,
+            name: "agent".to_string(),
+            token_hash: String::new(),
+            token_last_four: "abcd".to_string(),
+            scopes: vec!["read".to_string(), "admin".to_string()],
+            expires_at: None,
+            last_used_at: None,
+            created_at: None,
+        };
+        assert_eq!(token.scopes(), vec![McpScope::Read]);
+        assert!(!token.is_expired());
+
+        token.expires_at = Some(Utc::now() - chrono::Duration::minutes(1));
+        assert!(token.is_expired());
+        assert_eq!(McpTokenResponse::from(token).token_masked, "********abcd");
+    }
+}
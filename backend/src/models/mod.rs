@@ -3,14 +3,24 @@
 pub mod archive;
 pub mod blog;
 pub mod category;
+pub mod comment;
+pub mod data_export;
 pub mod directory;
 pub mod document;
 pub mod file;
 pub mod friend_link;
+pub mod import;
+pub mod link_check;
+pub mod login_event;
 pub mod mcp;
 pub mod project;
+pub mod revision;
 pub mod search;
+pub mod session;
 pub mod site_config;
+pub mod site_export;
+pub mod sitemap;
 pub mod tag;
 pub mod text;
+pub mod two_factor;
 pub mod user;
@@ -0,0 +1,157 @@
+//! Revision history models for blogs and documents
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::FromRow;
+
+/// Content types that keep a revision history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevisionTarget {
+    Blog,
+    Document,
+}
+
+/// Where a revision came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevisionSource {
+    /// State found before a tracked change that no earlier revision recorded
+    Baseline,
+    /// Admin panel edit made by a JWT-authenticated user
+    Admin,
+    /// Edit made through an MCP tool call
+    Mcp,
+    /// AI batch summarize / polish results written back to the blog
+    AiBatch,
+    /// An older revision restored by an admin
+    Restore,
+}
+
+impl RevisionSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RevisionSource::Baseline => "baseline",
+            RevisionSource::Admin => "admin",
+            RevisionSource::Mcp => "mcp",
+            RevisionSource::AiBatch => "ai_batch",
+            RevisionSource::Restore => "restore",
+        }
+    }
+}
+
+/// Who or what made a change, recorded alongside each revision
+#[derive(Debug, Clone)]
+pub struct RevisionActor {
+    pub source: RevisionSource,
+    pub actor_id: Option<i64>,
+    pub actor_name: Option<String>,
+}
+
+impl RevisionActor {
+    /// Snapshot of pre-existing state, not attributed to anyone
+    pub fn baseline() -> Self {
+        Self {
+            source: RevisionSource::Baseline,
+            actor_id: None,
+            actor_name: None,
+        }
+    }
+
+    /// Change made by an admin user
+    pub fn admin(user_id: i64, username: &str) -> Self {
+        Self::user(RevisionSource::Admin, user_id, username)
+    }
+
+    /// Change made through an MCP token
+    pub fn mcp(token_name: &str) -> Self {
+        Self {
+            source: RevisionSource::Mcp,
+            actor_id: None,
+            actor_name: Some(token_name.to_string()),
+        }
+    }
+
+    /// AI batch results confirmed by an admin user
+    pub fn ai_batch(user_id: i64, username: &str) -> Self {
+        Self::user(RevisionSource::AiBatch, user_id, username)
+    }
+
+    /// Older revision restored by an admin user
+    pub fn restore(user_id: i64, username: &str) -> Self {
+        Self::user(RevisionSource::Restore, user_id, username)
+    }
+
+    fn user(source: RevisionSource, user_id: i64, username: &str) -> Self {
+        Self {
+            source,
+            actor_id: Some(user_id),
+            actor_name: Some(username.to_string()),
+        }
+    }
+}
+
+/// Full revision snapshot
+///
+/// For documents `title` holds the document name and `summary` is always empty.
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct Revision {
+    pub id: i64,
+    pub entity_id: i64,
+    pub title: String,
+    pub content: String,
+    pub summary: Option<String>,
+    pub references: Option<JsonValue>,
+    pub source: String,
+    pub actor_id: Option<i64>,
+    pub actor_name: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Revision list item (without content)
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct RevisionListItem {
+    pub id: i64,
+    pub entity_id: i64,
+    pub title: String,
+    pub content_length: i32,
+    pub source: String,
+    pub actor_id: Option<i64>,
+    pub actor_name: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+impl From<&Revision> for RevisionListItem {
+    fn from(revision: &Revision) -> Self {
+        Self {
+            id: revision.id,
+            entity_id: revision.entity_id,
+            title: revision.title.clone(),
+            content_length: revision.content.chars().count() as i32,
+            source: revision.source.clone(),
+            actor_id: revision.actor_id,
+            actor_name: revision.actor_name.clone(),
+            created_at: revision.created_at,
+        }
+    }
+}
+
+/// Query parameters for diffing two revisions
+#[derive(Debug, Deserialize)]
+pub struct RevisionDiffQuery {
+    pub from: i64,
+    pub to: i64,
+}
+
+/// Line diff between two revisions
+#[derive(Debug, Serialize)]
+pub struct RevisionDiffResponse {
+    pub from: RevisionListItem,
+    pub to: RevisionListItem,
+    pub title_changed: bool,
+    pub summary_changed: bool,
+    pub references_changed: bool,
+    pub additions: usize,
+    pub deletions: usize,
+    /// Unified diff of the Markdown content
+    pub diff: String,
+}
@@ -0,0 +1,62 @@
+//! Login session models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Server-side record of a login session, stored in Redis
+///
+/// A session is the family of refresh tokens descending from one login.
+/// Only the latest refresh token (`refresh_jti`) may be used.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserSession {
+    pub session_id: String,
+    pub user_id: i64,
+    pub username: String,
+    pub refresh_jti: String,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_refreshed_at: Option<DateTime<Utc>>,
+}
+
+/// Where a login came from
+#[derive(Debug, Clone, Default)]
+pub struct SessionClient {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/// Active session response DTO (without the refresh token ID)
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionResponse {
+    pub session_id: String,
+    pub user_id: i64,
+    pub username: String,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_refreshed_at: Option<DateTime<Utc>>,
+    /// Whether this is the session making the request
+    pub current: bool,
+}
+
+impl SessionResponse {
+    pub fn new(session: UserSession, current_session_id: &str) -> Self {
+        Self {
+            current: session.session_id == current_session_id,
+            session_id: session.session_id,
+            user_id: session.user_id,
+            username: session.username,
+            ip: session.ip,
+            user_agent: session.user_agent,
+            created_at: session.created_at,
+            last_refreshed_at: session.last_refreshed_at,
+        }
+    }
+}
+
+/// Logout request DTO
+#[derive(Debug, Deserialize)]
+pub struct LogoutRequest {
+    pub refresh_token: String,
+}
@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use crate::mcp::auth::mask_token;
+
+/// 脱敏后的敏感配置前缀，提交该前缀开头的值表示保持原值不变
+pub const SECRET_MASK: &str = "********";
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct SiteConfig {
     pub id: i64,
@@ -8,10 +13,31 @@ pub struct SiteConfig {
     pub config_value: Option<String>,
     pub config_type: Option<String>,
     pub description: Option<String>,
+    /// 敏感配置：接口中脱敏显示且只写，配置主密钥时加密存储
+    #[sqlx(default)]
+    pub is_sensitive: bool,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+/// 脱敏显示敏感配置的明文，较长的值保留末四位便于辨认
+pub fn mask_secret(plaintext: &str) -> String {
+    if plaintext.is_empty() {
+        return String::new();
+    }
+
+    let chars: Vec<char> = plaintext.chars().collect();
+    if chars.len() < 12 {
+        return SECRET_MASK.to_string();
+    }
+    mask_token(&chars[chars.len() - 4..].iter().collect::<String>())
+}
+
+/// 提交的值是否为脱敏后的原值
+pub fn is_masked_secret(value: &str) -> bool {
+    value.starts_with(SECRET_MASK)
+}
+
 #[derive(Debug, Deserialize)]
 pub struct UpdateConfigRequest {
     pub configs: Vec<ConfigItem>,
@@ -31,6 +57,7 @@ pub struct SiteConfigResponse {
     pub site_subtitle: String,
     pub site_description: String,
     pub site_keywords: String,
+    pub site_url: String,
     pub blog_global_summary: String,
 
     // 站长信息
@@ -67,3 +94,23 @@ pub struct S3Config {
     pub secret_key: String,
     pub public_url: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mask_secret_keeps_last_four_of_long_values() {
+        assert_eq!(mask_secret("sk-1234567890abcd"), "********abcd");
+        assert_eq!(mask_secret("short"), "********");
+        assert_eq!(mask_secret(""), "");
+    }
+
+    #[test]
+    fn masked_values_are_recognized() {
+        assert!(is_masked_secret(&mask_secret("sk-1234567890abcd")));
+        assert!(is_masked_secret("********"));
+        assert!(!is_masked_secret("sk-new-key"));
+        assert!(!is_masked_secret(""));
+    }
+}
@@ -0,0 +1,32 @@
+//! Static-site export models
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use sqlx::FromRow;
+
+/// Static-site export query parameters
+#[derive(Debug, Deserialize)]
+pub struct SiteExportQuery {
+    /// Download referenced uploads into `assets/` and point links at them
+    pub assets: Option<bool>,
+}
+
+/// Blog with its category and tag names, as written to `posts/<slug>.md`
+#[derive(Debug, Clone, FromRow)]
+pub struct SiteExportBlog {
+    pub id: i64,
+    pub title: String,
+    pub slug: Option<String>,
+    pub author: Option<String>,
+    pub content: String,
+    pub summary: Option<String>,
+    pub thumbnail: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub is_published: bool,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub references: Option<JsonValue>,
+}
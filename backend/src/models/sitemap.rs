@@ -0,0 +1,45 @@
+//! Sitemap models
+
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+/// Content types that get their own paged sub-sitemaps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SitemapKind {
+    Blogs,
+    Categories,
+    Tags,
+    Documents,
+}
+
+impl SitemapKind {
+    /// All kinds, in the order they appear in the sitemap index
+    pub const ALL: [SitemapKind; 4] = [
+        SitemapKind::Blogs,
+        SitemapKind::Categories,
+        SitemapKind::Tags,
+        SitemapKind::Documents,
+    ];
+
+    /// Path segment used in sub-sitemap URLs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SitemapKind::Blogs => "blogs",
+            SitemapKind::Categories => "categories",
+            SitemapKind::Tags => "tags",
+            SitemapKind::Documents => "documents",
+        }
+    }
+
+    /// Parse a sub-sitemap path segment
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+}
+
+/// A single sitemap row: site-relative path plus last modification time
+#[derive(Debug, Clone, FromRow)]
+pub struct SitemapUrl {
+    pub path: String,
+    pub lastmod: Option<DateTime<Utc>>,
+}
@@ -1,9 +1,16 @@
 //! Text models and DTOs for dictionary text management
 
+use argon2::{
+    password_hash::{PasswordHash, PasswordVerifier},
+    Argon2,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use sqlx::FromRow;
 
+use crate::utils::crypto::SealedContent;
+
 /// Text entity from database
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Text {
@@ -12,11 +19,38 @@ pub struct Text {
     pub intro: Option<String>,
     pub content: String,
     pub is_encrypted: Option<bool>,
+    /// Argon2 hash of the view password (plaintext in rows written before hashing was introduced)
+    #[serde(skip_serializing)]
     pub view_password: Option<String>,
+    /// Key derivation parameters when `content` is ciphertext
+    #[serde(skip_serializing)]
+    pub content_kdf: Option<String>,
+    /// Cipher nonce when `content` is ciphertext
+    #[serde(skip_serializing)]
+    pub content_nonce: Option<String>,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
 }
 
+/// Text content as written to the database
+#[derive(Debug, Clone)]
+pub enum TextContent {
+    Plain(String),
+    Sealed(SealedContent),
+}
+
+impl TextContent {
+    /// Column values: content, content_kdf, content_nonce
+    pub fn columns(&self) -> (&str, Option<&str>, Option<&str>) {
+        match self {
+            TextContent::Plain(content) => (content, None, None),
+            TextContent::Sealed(sealed) => {
+                (&sealed.ciphertext, Some(&sealed.kdf), Some(&sealed.nonce))
+            }
+        }
+    }
+}
+
 /// Create text request DTO
 #[derive(Debug, Deserialize)]
 pub struct CreateTextRequest {
@@ -55,6 +89,17 @@ pub struct TextResponse {
     pub updated_at: Option<DateTime<Utc>>,
 }
 
+/// Password verification response DTO: the text plus a token that unlocks it for a while
+#[derive(Debug, Serialize)]
+pub struct VerifyTextResponse {
+    #[serde(flatten)]
+    pub text: TextResponse,
+    /// Send back as `X-Text-Unlock-Token` on `GET /texts/{id}` instead of the password
+    pub unlock_token: String,
+    /// Seconds until the unlock token expires
+    pub unlock_expires_in: i64,
+}
+
 /// Text response DTO for admin (includes all fields except password)
 #[derive(Debug, Serialize)]
 pub struct TextAdminResponse {
@@ -69,14 +114,26 @@ pub struct TextAdminResponse {
 }
 
 impl Text {
-    /// Convert to public response (hides content if encrypted)
+    /// Ciphertext with its KDF parameters, if the content is stored encrypted
+    pub fn sealed_content(&self) -> Option<SealedContent> {
+        match (&self.content_kdf, &self.content_nonce) {
+            (Some(kdf), Some(nonce)) => Some(SealedContent {
+                ciphertext: self.content.clone(),
+                kdf: kdf.clone(),
+                nonce: nonce.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Convert to public response (hides content if encrypted or still ciphertext)
     pub fn to_public_response(&self, include_content: bool) -> TextResponse {
         let is_encrypted = self.is_encrypted.unwrap_or(false);
         TextResponse {
             id: self.id,
             name: self.name.clone(),
             intro: self.intro.clone(),
-            content: if include_content || !is_encrypted {
+            content: if (include_content || !is_encrypted) && self.content_kdf.is_none() {
                 Some(self.content.clone())
             } else {
                 None
@@ -87,13 +144,17 @@ impl Text {
         }
     }
 
-    /// Convert to admin response
+    /// Convert to admin response (content is empty while still ciphertext)
     pub fn to_admin_response(&self) -> TextAdminResponse {
         TextAdminResponse {
             id: self.id,
             name: self.name.clone(),
             intro: self.intro.clone(),
-            content: self.content.clone(),
+            content: if self.content_kdf.is_none() {
+                self.content.clone()
+            } else {
+                String::new()
+            },
             is_encrypted: self.is_encrypted.unwrap_or(false),
             has_password: self.view_password.is_some(),
             created_at: self.created_at,
@@ -104,15 +165,36 @@ impl Text {
     /// Verify password
     pub fn verify_password(&self, password: &str) -> bool {
         match &self.view_password {
-            Some(stored_password) => stored_password == password,
+            Some(stored) => match PasswordHash::new(stored) {
+                Ok(hash) => Argon2::default()
+                    .verify_password(password.as_bytes(), &hash)
+                    .is_ok(),
+                // Legacy plaintext row, rehashed after the first successful verify
+                Err(_) => stored == password,
+            },
             None => true, // No password set, always allow
         }
     }
+
+    /// Whether the view password is still stored in plaintext
+    pub fn has_legacy_password(&self) -> bool {
+        self.view_password
+            .as_deref()
+            .is_some_and(|stored| PasswordHash::new(stored).is_err())
+    }
+
+    /// Short digest of the stored view password, embedded in unlock tokens so
+    /// that changing or removing the password revokes them
+    pub fn password_fingerprint(&self) -> String {
+        let digest = Sha256::digest(self.view_password.as_deref().unwrap_or("").as_bytes());
+        format!("{:x}", digest)[..16].to_string()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::Text;
+    use crate::repositories::user_repo::UserRepository;
 
     fn sample_text(is_encrypted: bool, password: Option<&str>) -> Text {
         Text {
@@ -122,6 +204,8 @@ mod tests {
             content: "secret content".to_string(),
             is_encrypted: Some(is_encrypted),
             view_password: password.map(ToOwned::to_owned),
+            content_kdf: None,
+            content_nonce: None,
             created_at: None,
             updated_at: None,
         }
@@ -144,6 +228,19 @@ mod tests {
         assert_eq!(response.content.as_deref(), Some("secret content"));
     }
 
+    #[test]
+    fn responses_never_expose_ciphertext() {
+        let sealed = crate::utils::crypto::seal("master", "secret content").unwrap();
+        let mut text = sample_text(true, Some("123456"));
+        text.content = sealed.ciphertext.clone();
+        text.content_kdf = Some(sealed.kdf.clone());
+        text.content_nonce = Some(sealed.nonce.clone());
+
+        assert_eq!(text.sealed_content(), Some(sealed));
+        assert_eq!(text.to_public_response(true).content, None);
+        assert_eq!(text.to_admin_response().content, "");
+    }
+
     #[test]
     fn verify_password_accepts_matching_secret() {
         let text = sample_text(true, Some("123456"));
@@ -151,6 +248,43 @@ mod tests {
         assert!(!text.verify_password("bad-password"));
     }
 
+    #[test]
+    fn verify_password_checks_argon2_hash() {
+        let hash = UserRepository::hash_password("123456").unwrap();
+        let text = sample_text(true, Some(&hash));
+
+        assert!(text.verify_password("123456"));
+        assert!(!text.verify_password("bad-password"));
+        assert!(!text.verify_password(&hash));
+        assert!(!text.has_legacy_password());
+    }
+
+    #[test]
+    fn plaintext_password_is_reported_as_legacy() {
+        assert!(sample_text(true, Some("123456")).has_legacy_password());
+        assert!(!sample_text(false, None).has_legacy_password());
+    }
+
+    #[test]
+    fn serialized_text_omits_password() {
+        let value = serde_json::to_value(sample_text(true, Some("123456"))).unwrap();
+        assert!(value.get("view_password").is_none());
+    }
+
+    #[test]
+    fn password_fingerprint_changes_with_the_password() {
+        let fingerprint = sample_text(true, Some("123456")).password_fingerprint();
+        assert_eq!(
+            fingerprint,
+            sample_text(true, Some("123456")).password_fingerprint()
+        );
+        assert_ne!(
+            fingerprint,
+            sample_text(true, Some("654321")).password_fingerprint()
+        );
+        assert_ne!(fingerprint, sample_text(true, None).password_fingerprint());
+    }
+
     #[test]
     fn verify_password_allows_plain_text_without_password() {
         let text = sample_text(false, None);
@@ -0,0 +1,80 @@
+//! Two-factor authentication models and DTOs
+
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::models::user::LoginResponse;
+
+/// Unused one-time recovery code, stored hashed
+#[derive(Debug, Clone, FromRow)]
+pub struct RecoveryCode {
+    pub id: i64,
+    pub code_hash: String,
+}
+
+/// Result of the password step of a login
+///
+/// Users with two-factor authentication get a challenge to answer at
+/// `/auth/login/2fa` instead of tokens.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum LoginOutcome {
+    Tokens(LoginResponse),
+    TwoFactor(TwoFactorChallenge),
+}
+
+/// Challenge returned when a login needs a second factor
+#[derive(Debug, Serialize)]
+pub struct TwoFactorChallenge {
+    /// Always true, lets clients tell a challenge from tokens
+    pub two_factor_required: bool,
+    pub challenge_token: String,
+    /// Seconds until the challenge token expires
+    pub expires_in: i64,
+}
+
+/// Second login step request DTO
+#[derive(Debug, Deserialize)]
+pub struct TwoFactorLoginRequest {
+    pub challenge_token: String,
+    /// Authenticator code or recovery code
+    pub code: String,
+}
+
+/// Two-factor status of the current user
+#[derive(Debug, Serialize)]
+pub struct TwoFactorStatus {
+    pub enabled: bool,
+    /// Enrollment started but not yet confirmed
+    pub pending: bool,
+    pub recovery_codes_remaining: i64,
+}
+
+/// Enrollment response DTO
+#[derive(Debug, Serialize)]
+pub struct TwoFactorSetupResponse {
+    /// Base32 secret for manual entry
+    pub secret: String,
+    /// `otpauth://` URI to show as a QR code
+    pub provisioning_uri: String,
+}
+
+/// Request carrying an authenticator or recovery code
+#[derive(Debug, Deserialize)]
+pub struct TwoFactorCodeRequest {
+    pub code: String,
+}
+
+/// Disable request DTO
+#[derive(Debug, Deserialize)]
+pub struct DisableTwoFactorRequest {
+    pub password: String,
+    /// Authenticator code or recovery code
+    pub code: String,
+}
+
+/// Freshly generated recovery codes, shown only once
+#[derive(Debug, Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
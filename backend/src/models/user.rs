@@ -4,6 +4,42 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+/// User role, from least to most privileged
+///
+/// Authors write their own blogs, editors publish and manage all content,
+/// admins also manage users, site config and MCP tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UserRole {
+    Author,
+    Editor,
+    Admin,
+}
+
+impl UserRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserRole::Author => "author",
+            UserRole::Editor => "editor",
+            UserRole::Admin => "admin",
+        }
+    }
+
+    /// Parse a stored role; unknown values get the least privileges
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "admin" => UserRole::Admin,
+            "editor" => UserRole::Editor,
+            _ => UserRole::Author,
+        }
+    }
+
+    /// Whether this role grants everything `required` does
+    pub fn allows(&self, required: UserRole) -> bool {
+        *self >= required
+    }
+}
+
 /// User entity from database
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct User {
@@ -14,10 +50,35 @@ pub struct User {
     pub email: Option<String>,
     pub nickname: Option<String>,
     pub avatar: Option<String>,
+    pub role: String,
+    /// Base32 TOTP secret; set but not enabled while enrollment is pending
+    #[serde(skip_serializing)]
+    #[sqlx(default)]
+    pub totp_secret: Option<String>,
+    #[sqlx(default)]
+    pub totp_enabled: bool,
+    /// Last TOTP step accepted, so a code can't be replayed
+    #[serde(skip_serializing)]
+    #[sqlx(default)]
+    pub totp_last_step: Option<i64>,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
 }
 
+impl User {
+    pub fn role(&self) -> UserRole {
+        UserRole::parse(&self.role)
+    }
+
+    /// Name shown as the byline of blogs this user writes
+    pub fn display_name(&self) -> String {
+        self.nickname
+            .clone()
+            .filter(|nickname| !nickname.trim().is_empty())
+            .unwrap_or_else(|| self.username.clone())
+    }
+}
+
 /// User response DTO (without sensitive data)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserResponse {
@@ -26,17 +87,21 @@ pub struct UserResponse {
     pub email: Option<String>,
     pub nickname: Option<String>,
     pub avatar: Option<String>,
+    pub role: UserRole,
+    pub totp_enabled: bool,
     pub created_at: Option<DateTime<Utc>>,
 }
 
 impl From<User> for UserResponse {
     fn from(user: User) -> Self {
         Self {
+            role: user.role(),
             id: user.id,
             username: user.username,
             email: user.email,
             nickname: user.nickname,
             avatar: user.avatar,
+            totp_enabled: user.totp_enabled,
             created_at: user.created_at,
         }
     }
@@ -69,6 +134,8 @@ pub struct RefreshTokenRequest {
 #[derive(Debug, Serialize)]
 pub struct RefreshTokenResponse {
     pub access_token: String,
+    /// Replaces the refresh token that was used; the old one is now invalid
+    pub refresh_token: String,
     pub token_type: String,
     pub expires_in: i64,
 }
@@ -81,4 +148,46 @@ pub struct CreateUserRequest {
     pub email: Option<String>,
     pub nickname: Option<String>,
     pub avatar: Option<String>,
+    /// Defaults to author; ignored by the first-admin setup
+    pub role: Option<UserRole>,
+}
+
+/// Update user request DTO (for admin use)
+#[derive(Debug, Deserialize)]
+pub struct UpdateUserRequest {
+    pub email: Option<String>,
+    pub nickname: Option<String>,
+    pub avatar: Option<String>,
+    pub role: Option<UserRole>,
+    /// New password
+    pub password: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UserRole;
+
+    #[test]
+    fn roles_are_ordered_by_privilege() {
+        assert!(UserRole::Admin.allows(UserRole::Editor));
+        assert!(UserRole::Editor.allows(UserRole::Author));
+        assert!(UserRole::Editor.allows(UserRole::Editor));
+        assert!(!UserRole::Author.allows(UserRole::Editor));
+        assert!(!UserRole::Editor.allows(UserRole::Admin));
+    }
+
+    #[test]
+    fn unknown_role_gets_least_privileges() {
+        assert_eq!(UserRole::parse("admin"), UserRole::Admin);
+        assert_eq!(UserRole::parse("editor"), UserRole::Editor);
+        assert_eq!(UserRole::parse("root"), UserRole::Author);
+    }
+
+    #[test]
+    fn role_serializes_lowercase() {
+        assert_eq!(
+            serde_json::to_string(&UserRole::Editor).unwrap(),
+            "\"editor\""
+        );
+    }
 }
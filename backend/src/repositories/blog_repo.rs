@@ -3,6 +3,8 @@
 use crate::error::ApiError;
 use crate::models::blog::{Blog, BlogDetail, BlogListItem, CreateBlogRequest, UpdateBlogRequest};
 use crate::models::category::Category;
+use crate::models::revision::{RevisionActor, RevisionTarget};
+use crate::repositories::revision_repo::RevisionRepository;
 use crate::repositories::tag_repo::TagRepository;
 use chrono::{DateTime, Utc};
 use sqlx::PgPool;
@@ -56,6 +58,10 @@ impl BlogRepository {
         }
         if let Some(published) = is_published {
             where_clauses.push(format!("b.is_published = {}", published));
+            if published {
+                // Never leak blogs scheduled for a future publish time
+                where_clauses.push("(b.publish_at IS NULL OR b.publish_at <= NOW())".to_string());
+            }
         }
 
         if !where_clauses.is_empty() {
@@ -80,6 +86,7 @@ impl BlogRepository {
                 b.view_count,
                 b.is_published,
                 b.created_at,
+                b.publish_at,
                 c.id as category_id,
                 c.name as category_name,
                 c.intro as category_intro,
@@ -116,6 +123,7 @@ impl BlogRepository {
                 i64,
                 bool,
                 Option<DateTime<Utc>>,
+                Option<DateTime<Utc>>,
                 Option<i64>,
                 Option<String>,
                 Option<String>,
@@ -139,6 +147,7 @@ impl BlogRepository {
                 view_count,
                 is_published,
                 created_at,
+                publish_at,
                 category_id,
                 category_name,
                 category_intro,
@@ -169,6 +178,7 @@ impl BlogRepository {
                 view_count,
                 is_published,
                 created_at,
+                publish_at,
             });
         }
 
@@ -180,7 +190,8 @@ impl BlogRepository {
         let blog = sqlx::query_as::<_, Blog>(
             r#"
             SELECT id, title, slug, author, content, html, summary, thumbnail, 
-                   category_id, view_count, is_published, created_at, updated_at, "references"
+                   category_id, view_count, is_published, created_at, updated_at, "references", publish_at,
+                   author_id
             FROM blogs
             WHERE id = $1
             "#,
@@ -197,7 +208,8 @@ impl BlogRepository {
         let blog = sqlx::query_as::<_, Blog>(
             r#"
             SELECT id, title, slug, author, content, html, summary, thumbnail, 
-                   category_id, view_count, is_published, created_at, updated_at, "references"
+                   category_id, view_count, is_published, created_at, updated_at, "references", publish_at,
+                   author_id
             FROM blogs
             WHERE slug = $1
             "#,
@@ -244,6 +256,7 @@ impl BlogRepository {
                     created_at: b.created_at,
                     updated_at: b.updated_at,
                     references: b.references,
+                    publish_at: b.publish_at,
                 }))
             }
             None => Ok(None),
@@ -288,24 +301,27 @@ impl BlogRepository {
                     created_at: b.created_at,
                     updated_at: b.updated_at,
                     references: b.references,
+                    publish_at: b.publish_at,
                 }))
             }
             None => Ok(None),
         }
     }
 
-    /// Create a new blog
+    /// Create a new blog owned by `author_id` (None for blogs not written by a user)
     pub async fn create(
         pool: &PgPool,
         req: &CreateBlogRequest,
         html: Option<String>,
+        author_id: Option<i64>,
     ) -> Result<Blog, ApiError> {
         let blog = sqlx::query_as::<_, Blog>(
             r#"
-            INSERT INTO blogs (title, slug, author, content, html, summary, thumbnail, category_id, is_published, "references")
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            INSERT INTO blogs (title, slug, author, content, html, summary, thumbnail, category_id, is_published, "references", publish_at, author_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, CASE WHEN $11 > NOW() THEN false ELSE $9 END, $10, $11, $12)
             RETURNING id, title, slug, author, content, html, summary, thumbnail, 
-                      category_id, view_count, is_published, created_at, updated_at, "references"
+                      category_id, view_count, is_published, created_at, updated_at, "references", publish_at,
+                      author_id
             "#,
         )
         .bind(&req.title)
@@ -318,6 +334,8 @@ impl BlogRepository {
         .bind(req.category_id)
         .bind(req.is_published.unwrap_or(false))
         .bind(&req.references)
+        .bind(req.publish_at)
+        .bind(author_id)
         .fetch_one(pool)
         .await?;
 
@@ -330,18 +348,32 @@ impl BlogRepository {
     }
 
     /// Update an existing blog
+    ///
+    /// A future `publish_at` always keeps the blog unpublished until the
+    /// scheduler flips it. Setting `is_published` without a `publish_at`
+    /// clears any pending schedule (publish now / cancel the schedule).
     pub async fn update(
         pool: &PgPool,
         id: i64,
         req: &UpdateBlogRequest,
         html: Option<String>,
+        actor: &RevisionActor,
     ) -> Result<Option<Blog>, ApiError> {
-        // First check if blog exists
-        let existing = Self::find_by_id(pool, id).await?;
-        if existing.is_none() {
+        // Lock the blog so the snapshots below bracket exactly this update
+        let mut tx = pool.begin().await?;
+        if !RevisionRepository::lock_entity(&mut tx, RevisionTarget::Blog, id).await? {
             return Ok(None);
         }
 
+        // Capture any state the history doesn't have yet (e.g. the original version)
+        RevisionRepository::snapshot(
+            &mut tx,
+            RevisionTarget::Blog,
+            id,
+            &RevisionActor::baseline(),
+        )
+        .await?;
+
         let blog = sqlx::query_as::<_, Blog>(
             r#"
             UPDATE blogs
@@ -354,12 +386,21 @@ impl BlogRepository {
                 summary = COALESCE($7, summary),
                 thumbnail = COALESCE($8, thumbnail),
                 category_id = COALESCE($9, category_id),
-                is_published = COALESCE($10, is_published),
+                is_published = CASE
+                    WHEN (CASE WHEN $12::TIMESTAMPTZ IS NOT NULL THEN $12
+                               WHEN $10::BOOLEAN IS NOT NULL THEN NULL
+                               ELSE publish_at END) > NOW() THEN false
+                    ELSE COALESCE($10, is_published)
+                END,
                 "references" = COALESCE($11, "references"),
+                publish_at = CASE WHEN $12::TIMESTAMPTZ IS NOT NULL THEN $12
+                                  WHEN $10::BOOLEAN IS NOT NULL THEN NULL
+                                  ELSE publish_at END,
                 updated_at = NOW()
             WHERE id = $1
             RETURNING id, title, slug, author, content, html, summary, thumbnail, 
-                      category_id, view_count, is_published, created_at, updated_at, "references"
+                      category_id, view_count, is_published, created_at, updated_at, "references", publish_at,
+                      author_id
             "#,
         )
         .bind(id)
@@ -373,9 +414,13 @@ impl BlogRepository {
         .bind(req.category_id)
         .bind(req.is_published)
         .bind(&req.references)
-        .fetch_optional(pool)
+        .bind(req.publish_at)
+        .fetch_optional(&mut *tx)
         .await?;
 
+        RevisionRepository::snapshot(&mut tx, RevisionTarget::Blog, id, actor).await?;
+        tx.commit().await?;
+
         // Update tags if provided
         if let Some(tag_ids) = &req.tag_ids {
             TagRepository::set_blog_tags(pool, id, tag_ids).await?;
@@ -400,6 +445,39 @@ impl BlogRepository {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Get the ID and markdown content of every blog
+    pub async fn find_all_contents(pool: &PgPool) -> Result<Vec<(i64, String)>, ApiError> {
+        let rows = sqlx::query_as::<_, (i64, String)>(
+            r#"
+            SELECT id, content FROM blogs ORDER BY id
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Publish every draft whose scheduled `publish_at` has passed
+    ///
+    /// Returns the ID and slug of each blog that was published.
+    pub async fn publish_due(pool: &PgPool) -> Result<Vec<(i64, Option<String>)>, ApiError> {
+        let published = sqlx::query_as::<_, (i64, Option<String>)>(
+            r#"
+            UPDATE blogs
+            SET is_published = true
+            WHERE is_published = false
+              AND publish_at IS NOT NULL
+              AND publish_at <= NOW()
+            RETURNING id, slug
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(published)
+    }
+
     /// Increment view count for a blog
     pub async fn increment_view_count(pool: &PgPool, id: i64) -> Result<(), ApiError> {
         sqlx::query(
@@ -0,0 +1,161 @@
+//! Comment repository - Data access layer for comment operations
+
+use crate::error::ApiError;
+use crate::models::comment::{Comment, CommentStatus, CommentTarget, CreateCommentRequest};
+use sqlx::PgPool;
+
+/// Comment repository for database operations
+pub struct CommentRepository;
+
+impl CommentRepository {
+    /// Find comment by ID
+    pub async fn find_by_id(pool: &PgPool, id: i64) -> Result<Option<Comment>, ApiError> {
+        let comment = sqlx::query_as::<_, Comment>(
+            r#"
+            SELECT id, blog_id, document_id, parent_id, author_name, author_email, author_url,
+                   content, status, ip_address, user_agent, created_at
+            FROM comments
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(comment)
+    }
+
+    /// Find all approved comments of a blog or document, oldest first
+    pub async fn find_approved(
+        pool: &PgPool,
+        target: CommentTarget,
+    ) -> Result<Vec<Comment>, ApiError> {
+        let comments = sqlx::query_as::<_, Comment>(
+            r#"
+            SELECT id, blog_id, document_id, parent_id, author_name, author_email, author_url,
+                   content, status, ip_address, user_agent, created_at
+            FROM comments
+            WHERE blog_id IS NOT DISTINCT FROM $1
+              AND document_id IS NOT DISTINCT FROM $2
+              AND status = 1
+            ORDER BY created_at ASC, id ASC
+            "#,
+        )
+        .bind(target.blog_id())
+        .bind(target.document_id())
+        .fetch_all(pool)
+        .await?;
+
+        Ok(comments)
+    }
+
+    /// Find comments with optional status filter and pagination, newest first
+    pub async fn find_with_filters(
+        pool: &PgPool,
+        page: i64,
+        page_size: i64,
+        status: Option<i16>,
+    ) -> Result<(Vec<Comment>, i64), ApiError> {
+        let offset = (page - 1) * page_size;
+
+        let total = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM comments
+            WHERE ($1::SMALLINT IS NULL OR status = $1)
+            "#,
+        )
+        .bind(status)
+        .fetch_one(pool)
+        .await?;
+
+        let comments = sqlx::query_as::<_, Comment>(
+            r#"
+            SELECT id, blog_id, document_id, parent_id, author_name, author_email, author_url,
+                   content, status, ip_address, user_agent, created_at
+            FROM comments
+            WHERE ($1::SMALLINT IS NULL OR status = $1)
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(status)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok((comments, total))
+    }
+
+    /// Create a new comment
+    pub async fn create(
+        pool: &PgPool,
+        target: CommentTarget,
+        req: &CreateCommentRequest,
+        status: CommentStatus,
+        ip_address: &str,
+        user_agent: Option<&str>,
+    ) -> Result<Comment, ApiError> {
+        let comment = sqlx::query_as::<_, Comment>(
+            r#"
+            INSERT INTO comments (blog_id, document_id, parent_id, author_name, author_email,
+                                  author_url, content, status, ip_address, user_agent)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id, blog_id, document_id, parent_id, author_name, author_email, author_url,
+                      content, status, ip_address, user_agent, created_at
+            "#,
+        )
+        .bind(target.blog_id())
+        .bind(target.document_id())
+        .bind(req.parent_id)
+        .bind(req.author_name.trim())
+        .bind(&req.author_email)
+        .bind(&req.author_url)
+        .bind(&req.content)
+        .bind(i16::from(status))
+        .bind(ip_address)
+        .bind(user_agent)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(comment)
+    }
+
+    /// Update comment status
+    pub async fn update_status(
+        pool: &PgPool,
+        id: i64,
+        status: CommentStatus,
+    ) -> Result<Option<Comment>, ApiError> {
+        let comment = sqlx::query_as::<_, Comment>(
+            r#"
+            UPDATE comments
+            SET status = $2
+            WHERE id = $1
+            RETURNING id, blog_id, document_id, parent_id, author_name, author_email, author_url,
+                      content, status, ip_address, user_agent, created_at
+            "#,
+        )
+        .bind(id)
+        .bind(i16::from(status))
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(comment)
+    }
+
+    /// Delete a comment (and its replies) by ID
+    pub async fn delete(pool: &PgPool, id: i64) -> Result<bool, ApiError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM comments
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,388 @@
+//! Data export repository - Table snapshots for versioned exports and the
+//! transaction that writes imports
+//!
+//! Rows are read as `row_to_json`, so every column is exported without a
+//! DTO per table, and written back with `json_populate_record`, limited to
+//! the columns the import provides and the table actually has.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use serde_json::Value as JsonValue;
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+
+use crate::error::ApiError;
+use crate::models::data_export::{ConflictPolicy, RowData};
+
+/// A table covered by exports
+#[derive(Debug)]
+pub struct ExportTable {
+    pub name: &'static str,
+    /// Conflict target when importing
+    pub key: &'static [&'static str],
+    /// Whether `id` is written on import; tables keyed by another column get fresh ids
+    pub import_id: bool,
+    /// Every row, in an order that can be imported as is
+    pub select_all: &'static str,
+    /// Rows changed at or after `$1`; tables whose rows change without a timestamp are always exported in full
+    pub select_since: Option<&'static str>,
+}
+
+/// Exported tables, parents before the rows referencing them
+pub const EXPORT_TABLES: &[ExportTable] = &[
+    ExportTable {
+        name: "categories",
+        key: &["id"],
+        import_id: true,
+        select_all: "SELECT row_to_json(t)::text FROM categories t ORDER BY t.id",
+        select_since: None,
+    },
+    ExportTable {
+        name: "tags",
+        key: &["id"],
+        import_id: true,
+        select_all: "SELECT row_to_json(t)::text FROM tags t ORDER BY t.id",
+        select_since: None,
+    },
+    ExportTable {
+        name: "files",
+        key: &["id"],
+        import_id: true,
+        select_all: "SELECT row_to_json(t)::text FROM files t ORDER BY t.id",
+        select_since: None,
+    },
+    ExportTable {
+        name: "file_variants",
+        key: &["id"],
+        import_id: true,
+        select_all: "SELECT row_to_json(t)::text FROM file_variants t ORDER BY t.id",
+        select_since: Some(
+            "SELECT row_to_json(t)::text FROM file_variants t WHERE t.created_at >= $1 ORDER BY t.id",
+        ),
+    },
+    ExportTable {
+        name: "blogs",
+        key: &["id"],
+        import_id: true,
+        select_all: "SELECT row_to_json(t)::text FROM blogs t ORDER BY t.id",
+        select_since: Some(
+            "SELECT row_to_json(t)::text FROM blogs t WHERE t.updated_at >= $1 ORDER BY t.id",
+        ),
+    },
+    ExportTable {
+        name: "blog_tags",
+        key: &["blog_id", "tag_id"],
+        import_id: false,
+        select_all: "SELECT row_to_json(t)::text FROM blog_tags t ORDER BY t.blog_id, t.tag_id",
+        select_since: Some(
+            "SELECT row_to_json(t)::text FROM blog_tags t
+             WHERE t.blog_id IN (SELECT id FROM blogs WHERE updated_at >= $1)
+             ORDER BY t.blog_id, t.tag_id",
+        ),
+    },
+    ExportTable {
+        name: "blog_revisions",
+        key: &["id"],
+        import_id: true,
+        select_all: "SELECT row_to_json(t)::text FROM blog_revisions t ORDER BY t.id",
+        select_since: Some(
+            "SELECT row_to_json(t)::text FROM blog_revisions t WHERE t.created_at >= $1 ORDER BY t.id",
+        ),
+    },
+    ExportTable {
+        name: "friend_links",
+        key: &["id"],
+        import_id: true,
+        select_all: "SELECT row_to_json(t)::text FROM friend_links t ORDER BY t.id",
+        select_since: None,
+    },
+    ExportTable {
+        name: "projects",
+        key: &["id"],
+        import_id: true,
+        select_all: "SELECT row_to_json(t)::text FROM projects t ORDER BY t.id",
+        select_since: None,
+    },
+    ExportTable {
+        name: "directories",
+        key: &["id"],
+        import_id: true,
+        select_all: r#"
+            WITH RECURSIVE tree AS (
+                SELECT id, 0 AS depth FROM directories WHERE parent_id IS NULL
+                UNION ALL
+                SELECT d.id, tree.depth + 1 FROM directories d JOIN tree ON d.parent_id = tree.id
+            )
+            SELECT row_to_json(t)::text FROM directories t
+            LEFT JOIN tree ON tree.id = t.id
+            ORDER BY tree.depth NULLS LAST, t.id
+        "#,
+        select_since: None,
+    },
+    ExportTable {
+        name: "documents",
+        key: &["id"],
+        import_id: true,
+        select_all: "SELECT row_to_json(t)::text FROM documents t ORDER BY t.id",
+        select_since: Some(
+            "SELECT row_to_json(t)::text FROM documents t WHERE t.updated_at >= $1 ORDER BY t.id",
+        ),
+    },
+    ExportTable {
+        name: "document_revisions",
+        key: &["id"],
+        import_id: true,
+        select_all: "SELECT row_to_json(t)::text FROM document_revisions t ORDER BY t.id",
+        select_since: Some(
+            "SELECT row_to_json(t)::text FROM document_revisions t WHERE t.created_at >= $1
+             ORDER BY t.id",
+        ),
+    },
+    ExportTable {
+        name: "comments",
+        key: &["id"],
+        import_id: true,
+        // Replies always come after the comment they answer
+        select_all: "SELECT row_to_json(t)::text FROM comments t ORDER BY t.id",
+        select_since: None,
+    },
+    ExportTable {
+        name: "texts",
+        key: &["id"],
+        import_id: true,
+        select_all: "SELECT row_to_json(t)::text FROM texts t ORDER BY t.id",
+        select_since: Some(
+            "SELECT row_to_json(t)::text FROM texts t WHERE t.updated_at >= $1 ORDER BY t.id",
+        ),
+    },
+    ExportTable {
+        name: "site_config",
+        key: &["config_key"],
+        import_id: false,
+        select_all: "SELECT row_to_json(t)::text FROM site_config t ORDER BY t.config_key",
+        select_since: Some(
+            "SELECT row_to_json(t)::text FROM site_config t WHERE t.updated_at >= $1
+             ORDER BY t.config_key",
+        ),
+    },
+];
+
+/// Look up an exported table by name
+pub fn export_table(name: &str) -> Option<&'static ExportTable> {
+    EXPORT_TABLES.iter().find(|table| table.name == name)
+}
+
+/// Data export repository for database operations
+pub struct DataExportRepository;
+
+impl DataExportRepository {
+    /// Start a read-only transaction so every table is read from the same snapshot
+    pub async fn snapshot(pool: &PgPool) -> Result<Transaction<'static, Postgres>, ApiError> {
+        let mut tx = pool.begin().await?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+            .execute(&mut *tx)
+            .await?;
+        Ok(tx)
+    }
+
+    /// Stream the rows of a table as JSON objects
+    pub fn rows<'c>(
+        conn: &'c mut PgConnection,
+        table: &'static ExportTable,
+        since: Option<DateTime<Utc>>,
+    ) -> BoxStream<'c, Result<String, sqlx::Error>> {
+        match (since, table.select_since) {
+            (Some(since), Some(sql)) => sqlx::query_scalar(sql).bind(since).fetch(conn),
+            _ => sqlx::query_scalar(table.select_all).fetch(conn),
+        }
+    }
+}
+
+/// What an imported row did to its table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowChange {
+    Inserted,
+    Updated,
+    Skipped,
+}
+
+/// Transaction an import is written in; nothing is kept unless `commit` is reached
+pub struct DataImportSession {
+    tx: Transaction<'static, Postgres>,
+    /// Writable columns of each table seen so far
+    columns: HashMap<&'static str, Vec<String>>,
+    /// Tables with rows written, for resetting their id sequences
+    written: Vec<&'static ExportTable>,
+}
+
+impl DataImportSession {
+    pub async fn begin(pool: &PgPool) -> Result<Self, ApiError> {
+        Ok(Self {
+            tx: pool.begin().await?,
+            columns: HashMap::new(),
+            written: Vec::new(),
+        })
+    }
+
+    /// Ids of the users in this database
+    pub async fn user_ids(&mut self) -> Result<HashSet<i64>, ApiError> {
+        let ids = sqlx::query_scalar::<_, i64>("SELECT id FROM users")
+            .fetch_all(&mut *self.tx)
+            .await?;
+        Ok(ids.into_iter().collect())
+    }
+
+    /// Keys of the settings this database treats as sensitive
+    pub async fn sensitive_config_keys(&mut self) -> Result<HashSet<String>, ApiError> {
+        let keys = sqlx::query_scalar::<_, String>(
+            "SELECT config_key FROM site_config WHERE is_sensitive = TRUE",
+        )
+        .fetch_all(&mut *self.tx)
+        .await?;
+        Ok(keys.into_iter().collect())
+    }
+
+    /// Write one row with the given conflict policy
+    pub async fn write(
+        &mut self,
+        table: &'static ExportTable,
+        row: &RowData,
+        policy: ConflictPolicy,
+    ) -> Result<RowChange, ApiError> {
+        let columns = self.columns(table).await?;
+        let provided: Vec<&str> = columns
+            .iter()
+            .map(String::as_str)
+            .filter(|column| row.contains_key(*column) && (table.import_id || *column != "id"))
+            .collect();
+        if let Some(missing) = table.key.iter().find(|key| !provided.contains(key)) {
+            return Err(ApiError::BadRequest(format!(
+                "{} row is missing its {} column",
+                table.name, missing
+            )));
+        }
+
+        // `id` is never overwritten: for tables keyed by another column it
+        // would take a fresh sequence value
+        let updated: Vec<&str> = match policy {
+            ConflictPolicy::Skip => Vec::new(),
+            ConflictPolicy::Upsert => provided.clone(),
+            ConflictPolicy::Replace => columns.iter().map(String::as_str).collect(),
+        }
+        .into_iter()
+        .filter(|column| *column != "id" && !table.key.contains(column))
+        .collect();
+
+        let list = provided
+            .iter()
+            .map(|column| quote(column))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let action = if updated.is_empty() {
+            "DO NOTHING".to_string()
+        } else {
+            format!(
+                "DO UPDATE SET {}",
+                updated
+                    .iter()
+                    .map(|column| format!("{0} = EXCLUDED.{0}", quote(column)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        };
+        let sql = format!(
+            "INSERT INTO {table} ({list}) SELECT {list} FROM json_populate_record(NULL::{table}, $1::json)
+             ON CONFLICT ({key}) {action} RETURNING (xmax = 0)",
+            table = table.name,
+            list = list,
+            key = table.key.join(", "),
+            action = action,
+        );
+
+        let inserted = sqlx::query_scalar::<_, bool>(&sql)
+            .bind(JsonValue::Object(row.clone()).to_string())
+            .fetch_optional(&mut *self.tx)
+            .await
+            .map_err(|e| {
+                ApiError::BadRequest(format!(
+                    "Failed to import {} row {}: {}",
+                    table.name,
+                    describe_key(table, row),
+                    e
+                ))
+            })?;
+
+        if !self
+            .written
+            .iter()
+            .any(|written| written.name == table.name)
+        {
+            self.written.push(table);
+        }
+
+        Ok(match inserted {
+            Some(true) => RowChange::Inserted,
+            Some(false) => RowChange::Updated,
+            None => RowChange::Skipped,
+        })
+    }
+
+    /// Move id sequences past the imported ids and commit
+    pub async fn commit(mut self) -> Result<(), ApiError> {
+        for table in self.written.iter().filter(|table| table.import_id) {
+            sqlx::query(&format!(
+                "SELECT setval(pg_get_serial_sequence($1, 'id'), (SELECT COALESCE(MAX(id), 1) FROM {}))",
+                table.name
+            ))
+            .bind(table.name)
+            .execute(&mut *self.tx)
+            .await?;
+        }
+
+        self.tx.commit().await?;
+        Ok(())
+    }
+
+    /// Columns of a table that can be written, generated ones excluded
+    async fn columns(&mut self, table: &'static ExportTable) -> Result<Vec<String>, ApiError> {
+        if let Some(columns) = self.columns.get(table.name) {
+            return Ok(columns.clone());
+        }
+
+        let columns = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT column_name::text FROM information_schema.columns
+            WHERE table_schema = current_schema() AND table_name = $1 AND is_generated = 'NEVER'
+            ORDER BY ordinal_position
+            "#,
+        )
+        .bind(table.name)
+        .fetch_all(&mut *self.tx)
+        .await?;
+
+        self.columns.insert(table.name, columns.clone());
+        Ok(columns)
+    }
+}
+
+/// Quote a column name read from the catalog
+fn quote(column: &str) -> String {
+    format!("\"{}\"", column.replace('"', "\"\""))
+}
+
+/// Key values of a row for error messages, e.g. `id=3`
+fn describe_key(table: &ExportTable, row: &RowData) -> String {
+    table
+        .key
+        .iter()
+        .map(|key| {
+            format!(
+                "{}={}",
+                key,
+                row.get(*key).map(JsonValue::to_string).unwrap_or_default()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -4,6 +4,8 @@ use crate::error::ApiError;
 use crate::models::document::{
     CreateDocumentRequest, Document, DocumentListItem, UpdateDocumentRequest,
 };
+use crate::models::revision::{RevisionActor, RevisionTarget};
+use crate::repositories::revision_repo::RevisionRepository;
 use sqlx::PgPool;
 
 /// Document repository for database operations
@@ -152,6 +154,7 @@ impl DocumentRepository {
         pool: &PgPool,
         id: i64,
         req: &UpdateDocumentRequest,
+        actor: &RevisionActor,
     ) -> Result<Option<Document>, ApiError> {
         // Validate directory_id if provided
         if let Some(directory_id) = req.directory_id {
@@ -170,6 +173,21 @@ impl DocumentRepository {
             }
         }
 
+        // Lock the document so the snapshots below bracket exactly this update
+        let mut tx = pool.begin().await?;
+        if !RevisionRepository::lock_entity(&mut tx, RevisionTarget::Document, id).await? {
+            return Ok(None);
+        }
+
+        // Capture any state the history doesn't have yet (e.g. the original version)
+        RevisionRepository::snapshot(
+            &mut tx,
+            RevisionTarget::Document,
+            id,
+            &RevisionActor::baseline(),
+        )
+        .await?;
+
         let document = sqlx::query_as::<_, Document>(
             r#"
             UPDATE documents
@@ -192,9 +210,12 @@ impl DocumentRepository {
         .bind(req.directory_id)
         .bind(req.sort_order)
         .bind(&req.references)
-        .fetch_optional(pool)
+        .fetch_optional(&mut *tx)
         .await?;
 
+        RevisionRepository::snapshot(&mut tx, RevisionTarget::Document, id, actor).await?;
+        tx.commit().await?;
+
         Ok(document)
     }
 
@@ -38,7 +38,8 @@ impl FileRepository {
                 sqlx::query_as::<_, File>(
                     r#"
                     SELECT id, filename, original_filename, file_type, file_size,
-                           url, thumbnail_url, width, height, bucket_name, object_key, created_at
+                           url, thumbnail_url, width, height, bucket_name, object_key,
+                           storage_backend, content_hash, ref_count, created_at
                     FROM files
                     WHERE file_type = $1
                     ORDER BY created_at DESC
@@ -55,7 +56,8 @@ impl FileRepository {
                 sqlx::query_as::<_, File>(
                     r#"
                     SELECT id, filename, original_filename, file_type, file_size,
-                           url, thumbnail_url, width, height, bucket_name, object_key, created_at
+                           url, thumbnail_url, width, height, bucket_name, object_key,
+                           storage_backend, content_hash, ref_count, created_at
                     FROM files
                     ORDER BY created_at DESC
                     LIMIT $1 OFFSET $2
@@ -76,7 +78,8 @@ impl FileRepository {
         let file = sqlx::query_as::<_, File>(
             r#"
             SELECT id, filename, original_filename, file_type, file_size,
-                   url, thumbnail_url, width, height, bucket_name, object_key, created_at
+                   url, thumbnail_url, width, height, bucket_name, object_key,
+                   storage_backend, content_hash, ref_count, created_at
             FROM files
             WHERE id = $1
             "#,
@@ -88,15 +91,32 @@ impl FileRepository {
         Ok(file)
     }
 
+    /// Check whether a file record points to the given object
+    pub async fn exists_by_object_key(pool: &PgPool, object_key: &str) -> Result<bool, ApiError> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM files WHERE object_key = $1)",
+        )
+        .bind(object_key)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(exists)
+    }
+
     /// Create a new file record
-    pub async fn create(pool: &PgPool, req: &CreateFileRequest) -> Result<File, ApiError> {
+    ///
+    /// Returns `None` when a file with the same content hash already exists.
+    pub async fn create(pool: &PgPool, req: &CreateFileRequest) -> Result<Option<File>, ApiError> {
         let file = sqlx::query_as::<_, File>(
             r#"
             INSERT INTO files (filename, original_filename, file_type, file_size,
-                              url, thumbnail_url, width, height, bucket_name, object_key)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                              url, thumbnail_url, width, height, bucket_name, object_key,
+                              storage_backend, content_hash)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ON CONFLICT (content_hash) WHERE content_hash IS NOT NULL DO NOTHING
             RETURNING id, filename, original_filename, file_type, file_size,
-                      url, thumbnail_url, width, height, bucket_name, object_key, created_at
+                      url, thumbnail_url, width, height, bucket_name, object_key,
+                      storage_backend, content_hash, ref_count, created_at
             "#,
         )
         .bind(&req.filename)
@@ -109,19 +129,128 @@ impl FileRepository {
         .bind(req.height)
         .bind(&req.bucket_name)
         .bind(&req.object_key)
-        .fetch_one(pool)
+        .bind(&req.storage_backend)
+        .bind(&req.content_hash)
+        .fetch_optional(pool)
         .await?;
 
         Ok(file)
     }
 
-    /// Delete a file by ID
-    pub async fn delete(pool: &PgPool, id: i64) -> Result<bool, ApiError> {
-        let result = sqlx::query("DELETE FROM files WHERE id = $1")
-            .bind(id)
+    /// Take another reference on the file with the given content hash
+    pub async fn acquire_by_hash(
+        pool: &PgPool,
+        content_hash: &str,
+    ) -> Result<Option<File>, ApiError> {
+        let file = sqlx::query_as::<_, File>(
+            r#"
+            UPDATE files
+            SET ref_count = ref_count + 1
+            WHERE content_hash = $1
+            RETURNING id, filename, original_filename, file_type, file_size,
+                      url, thumbnail_url, width, height, bucket_name, object_key,
+                      storage_backend, content_hash, ref_count, created_at
+            "#,
+        )
+        .bind(content_hash)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(file)
+    }
+
+    /// Find every file record, oldest first
+    pub async fn find_all_unpaged(pool: &PgPool) -> Result<Vec<File>, ApiError> {
+        let files = sqlx::query_as::<_, File>(
+            r#"
+            SELECT id, filename, original_filename, file_type, file_size,
+                   url, thumbnail_url, width, height, bucket_name, object_key,
+                   storage_backend, content_hash, ref_count, created_at
+            FROM files
+            ORDER BY id
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(files)
+    }
+
+    /// Every text column that may link to an uploaded file
+    ///
+    /// Covers blog and document content including revisions, logos, thumbnails,
+    /// avatars, unencrypted texts and non-sensitive site config values.
+    pub async fn find_referencing_texts(pool: &PgPool) -> Result<Vec<String>, ApiError> {
+        let texts = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT text FROM (
+                SELECT content AS text FROM blogs
+                UNION ALL SELECT html FROM blogs
+                UNION ALL SELECT thumbnail FROM blogs
+                UNION ALL SELECT content FROM blog_revisions
+                UNION ALL SELECT content FROM documents
+                UNION ALL SELECT content FROM document_revisions
+                UNION ALL SELECT logo FROM categories
+                UNION ALL SELECT intro FROM categories
+                UNION ALL SELECT logo FROM projects
+                UNION ALL SELECT description FROM projects
+                UNION ALL SELECT logo FROM friend_links
+                UNION ALL SELECT avatar FROM users
+                UNION ALL SELECT content FROM texts WHERE content_kdf IS NULL
+                UNION ALL SELECT intro FROM texts
+                UNION ALL SELECT config_value FROM site_config WHERE is_sensitive = false
+            ) AS sources
+            WHERE text IS NOT NULL AND text <> ''
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(texts)
+    }
+
+    /// Delete file records outright, whatever their reference count
+    pub async fn delete_many(pool: &PgPool, ids: &[i64]) -> Result<u64, ApiError> {
+        let result = sqlx::query("DELETE FROM files WHERE id = ANY($1)")
+            .bind(ids)
             .execute(pool)
             .await?;
 
-        Ok(result.rows_affected() > 0)
+        Ok(result.rows_affected())
+    }
+
+    /// Drop one reference on a file, deleting the record with the last one
+    ///
+    /// Returns the number of references left, or `None` if the file doesn't exist.
+    pub async fn release(pool: &PgPool, id: i64) -> Result<Option<i32>, ApiError> {
+        let mut tx = pool.begin().await?;
+
+        let ref_count =
+            sqlx::query_scalar::<_, i32>("SELECT ref_count FROM files WHERE id = $1 FOR UPDATE")
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+        let remaining = match ref_count {
+            None => return Ok(None),
+            Some(count) if count > 1 => {
+                sqlx::query("UPDATE files SET ref_count = ref_count - 1 WHERE id = $1")
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+                count - 1
+            }
+            Some(_) => {
+                sqlx::query("DELETE FROM files WHERE id = $1")
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+                0
+            }
+        };
+
+        tx.commit().await?;
+
+        Ok(Some(remaining))
     }
 }
@@ -0,0 +1,93 @@
+//! File variant repository - Data access layer for generated image variants
+
+use crate::error::ApiError;
+use crate::models::file::{CreateFileVariantRequest, FileVariant};
+use sqlx::PgPool;
+
+/// File variant repository for database operations
+pub struct FileVariantRepository;
+
+impl FileVariantRepository {
+    /// Find the variants of several files, ordered by file, kind and width
+    pub async fn find_by_file_ids(
+        pool: &PgPool,
+        file_ids: &[i64],
+    ) -> Result<Vec<FileVariant>, ApiError> {
+        let variants = sqlx::query_as::<_, FileVariant>(
+            r#"
+            SELECT id, file_id, kind, format, width, height, file_size, url, object_key, created_at
+            FROM file_variants
+            WHERE file_id = ANY($1)
+            ORDER BY file_id, kind DESC, width, format
+            "#,
+        )
+        .bind(file_ids)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(variants)
+    }
+
+    /// Find the variants of a file
+    pub async fn find_by_file_id(
+        pool: &PgPool,
+        file_id: i64,
+    ) -> Result<Vec<FileVariant>, ApiError> {
+        Self::find_by_file_ids(pool, &[file_id]).await
+    }
+
+    /// Find the variants of every file
+    pub async fn find_all(pool: &PgPool) -> Result<Vec<FileVariant>, ApiError> {
+        let variants = sqlx::query_as::<_, FileVariant>(
+            r#"
+            SELECT id, file_id, kind, format, width, height, file_size, url, object_key, created_at
+            FROM file_variants
+            ORDER BY file_id, id
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(variants)
+    }
+
+    /// Record the variants generated for a file
+    pub async fn create_many(
+        pool: &PgPool,
+        file_id: i64,
+        variants: &[CreateFileVariantRequest],
+    ) -> Result<Vec<FileVariant>, ApiError> {
+        if variants.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let kinds: Vec<&str> = variants.iter().map(|v| v.kind.as_str()).collect();
+        let formats: Vec<&str> = variants.iter().map(|v| v.format.as_str()).collect();
+        let widths: Vec<i32> = variants.iter().map(|v| v.width).collect();
+        let heights: Vec<i32> = variants.iter().map(|v| v.height).collect();
+        let sizes: Vec<i64> = variants.iter().map(|v| v.file_size).collect();
+        let urls: Vec<&str> = variants.iter().map(|v| v.url.as_str()).collect();
+        let keys: Vec<&str> = variants.iter().map(|v| v.object_key.as_str()).collect();
+
+        let created = sqlx::query_as::<_, FileVariant>(
+            r#"
+            INSERT INTO file_variants (file_id, kind, format, width, height, file_size, url, object_key)
+            SELECT $1, *
+            FROM UNNEST($2::VARCHAR[], $3::VARCHAR[], $4::INT[], $5::INT[], $6::BIGINT[], $7::TEXT[], $8::TEXT[])
+            RETURNING id, file_id, kind, format, width, height, file_size, url, object_key, created_at
+            "#,
+        )
+        .bind(file_id)
+        .bind(&kinds)
+        .bind(&formats)
+        .bind(&widths)
+        .bind(&heights)
+        .bind(&sizes)
+        .bind(&urls)
+        .bind(&keys)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(created)
+    }
+}
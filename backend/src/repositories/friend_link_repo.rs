@@ -2,6 +2,7 @@
 
 use crate::error::ApiError;
 use crate::models::friend_link::{CreateFriendLinkRequest, FriendLink, UpdateFriendLinkRequest};
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 
 /// Friend link repository for database operations
@@ -12,7 +13,8 @@ impl FriendLinkRepository {
     pub async fn find_all(pool: &PgPool) -> Result<Vec<FriendLink>, ApiError> {
         let links = sqlx::query_as::<_, FriendLink>(
             r#"
-            SELECT id, name, url, logo, intro, email, status, created_at
+            SELECT id, name, url, logo, intro, email, status, created_at,
+                   backlink_found, backlink_error, backlink_checked_at
             FROM friend_links
             ORDER BY created_at DESC
             "#,
@@ -27,7 +29,8 @@ impl FriendLinkRepository {
     pub async fn find_approved(pool: &PgPool) -> Result<Vec<FriendLink>, ApiError> {
         let links = sqlx::query_as::<_, FriendLink>(
             r#"
-            SELECT id, name, url, logo, intro, email, status, created_at
+            SELECT id, name, url, logo, intro, email, status, created_at,
+                   backlink_found, backlink_error, backlink_checked_at
             FROM friend_links
             WHERE status = 1
             ORDER BY created_at DESC
@@ -43,7 +46,8 @@ impl FriendLinkRepository {
     pub async fn find_by_id(pool: &PgPool, id: i64) -> Result<Option<FriendLink>, ApiError> {
         let link = sqlx::query_as::<_, FriendLink>(
             r#"
-            SELECT id, name, url, logo, intro, email, status, created_at
+            SELECT id, name, url, logo, intro, email, status, created_at,
+                   backlink_found, backlink_error, backlink_checked_at
             FROM friend_links
             WHERE id = $1
             "#,
@@ -65,7 +69,8 @@ impl FriendLinkRepository {
             r#"
             INSERT INTO friend_links (name, url, logo, intro, email, status)
             VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING id, name, url, logo, intro, email, status, created_at
+            RETURNING id, name, url, logo, intro, email, status, created_at,
+                      backlink_found, backlink_error, backlink_checked_at
             "#,
         )
         .bind(&req.name)
@@ -97,7 +102,8 @@ impl FriendLinkRepository {
                 email = COALESCE($6, email),
                 status = COALESCE($7, status)
             WHERE id = $1
-            RETURNING id, name, url, logo, intro, email, status, created_at
+            RETURNING id, name, url, logo, intro, email, status, created_at,
+                      backlink_found, backlink_error, backlink_checked_at
             "#,
         )
         .bind(id)
@@ -124,7 +130,8 @@ impl FriendLinkRepository {
             UPDATE friend_links
             SET status = $2
             WHERE id = $1
-            RETURNING id, name, url, logo, intro, email, status, created_at
+            RETURNING id, name, url, logo, intro, email, status, created_at,
+                      backlink_found, backlink_error, backlink_checked_at
             "#,
         )
         .bind(id)
@@ -135,6 +142,67 @@ impl FriendLinkRepository {
         Ok(link)
     }
 
+    /// Check whether a friend link with this URL already exists
+    pub async fn url_exists(pool: &PgPool, url: &str) -> Result<bool, ApiError> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            r#"SELECT EXISTS(SELECT 1 FROM friend_links WHERE LOWER(TRIM(TRAILING '/' FROM url)) = LOWER(TRIM(TRAILING '/' FROM $1)))"#,
+        )
+        .bind(url)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    /// Find pending friend links whose backlink hasn't been checked since `checked_before`
+    pub async fn find_pending_unchecked(
+        pool: &PgPool,
+        checked_before: DateTime<Utc>,
+    ) -> Result<Vec<FriendLink>, ApiError> {
+        let links = sqlx::query_as::<_, FriendLink>(
+            r#"
+            SELECT id, name, url, logo, intro, email, status, created_at,
+                   backlink_found, backlink_error, backlink_checked_at
+            FROM friend_links
+            WHERE status = 0
+              AND (backlink_checked_at IS NULL OR backlink_checked_at < $1)
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(checked_before)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(links)
+    }
+
+    /// Store the result of a backlink check
+    pub async fn record_backlink_check(
+        pool: &PgPool,
+        id: i64,
+        found: Option<bool>,
+        error: Option<&str>,
+    ) -> Result<Option<FriendLink>, ApiError> {
+        let link = sqlx::query_as::<_, FriendLink>(
+            r#"
+            UPDATE friend_links
+            SET backlink_found = $2,
+                backlink_error = $3,
+                backlink_checked_at = NOW()
+            WHERE id = $1
+            RETURNING id, name, url, logo, intro, email, status, created_at,
+                      backlink_found, backlink_error, backlink_checked_at
+            "#,
+        )
+        .bind(id)
+        .bind(found)
+        .bind(error)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(link)
+    }
+
     /// Delete a friend link by ID
     pub async fn delete(pool: &PgPool, id: i64) -> Result<bool, ApiError> {
         let result = sqlx::query(
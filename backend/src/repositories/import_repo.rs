@@ -0,0 +1,132 @@
+//! Import repository - Writes imported posts in one transaction
+
+use std::collections::HashMap;
+
+use sqlx::PgPool;
+
+use crate::error::ApiError;
+use crate::models::import::NewImportedBlog;
+
+/// Import repository for database operations
+pub struct ImportRepository;
+
+impl ImportRepository {
+    /// Slugs among `slugs` already used by a blog
+    pub async fn existing_slugs(pool: &PgPool, slugs: &[String]) -> Result<Vec<String>, ApiError> {
+        let existing =
+            sqlx::query_scalar::<_, String>("SELECT slug FROM blogs WHERE slug = ANY($1)")
+                .bind(slugs)
+                .fetch_all(pool)
+                .await?;
+
+        Ok(existing)
+    }
+
+    /// Create categories, tags and blogs; nothing is written if any insert fails
+    ///
+    /// Categories and tags are matched by name, case-insensitively, and only
+    /// created when missing. Returns the new blog ID for each of `blogs`, or
+    /// `None` where the slug was taken in the meantime.
+    pub async fn create_all(
+        pool: &PgPool,
+        blogs: &[NewImportedBlog],
+        author_id: Option<i64>,
+    ) -> Result<Vec<Option<i64>>, ApiError> {
+        let mut tx = pool.begin().await?;
+
+        let mut category_ids: HashMap<String, i64> =
+            sqlx::query_as::<_, (i64, String)>("SELECT id, name FROM categories ORDER BY id DESC")
+                .fetch_all(&mut *tx)
+                .await?
+                .into_iter()
+                .map(|(id, name)| (name.to_lowercase(), id))
+                .collect();
+        let mut tag_ids: HashMap<String, i64> =
+            sqlx::query_as::<_, (i64, String)>("SELECT id, name FROM tags")
+                .fetch_all(&mut *tx)
+                .await?
+                .into_iter()
+                .map(|(id, name)| (name.to_lowercase(), id))
+                .collect();
+
+        let mut blog_ids = Vec::with_capacity(blogs.len());
+        for blog in blogs {
+            let category_id = match &blog.category {
+                Some(name) => match category_ids.get(&name.to_lowercase()) {
+                    Some(id) => Some(*id),
+                    None => {
+                        let id = sqlx::query_scalar::<_, i64>(
+                            "INSERT INTO categories (name) VALUES ($1) RETURNING id",
+                        )
+                        .bind(name)
+                        .fetch_one(&mut *tx)
+                        .await?;
+                        category_ids.insert(name.to_lowercase(), id);
+                        Some(id)
+                    }
+                },
+                None => None,
+            };
+
+            let blog_id = sqlx::query_scalar::<_, i64>(
+                r#"
+                INSERT INTO blogs (title, slug, author, content, html, summary, thumbnail, category_id,
+                                   is_published, created_at, updated_at, author_id, "references")
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9,
+                        COALESCE($10, NOW()), COALESCE($11, $10, NOW()), $12, $13)
+                ON CONFLICT (slug) DO NOTHING
+                RETURNING id
+                "#,
+            )
+            .bind(&blog.title)
+            .bind(&blog.slug)
+            .bind(&blog.author)
+            .bind(&blog.content)
+            .bind(&blog.html)
+            .bind(&blog.summary)
+            .bind(&blog.thumbnail)
+            .bind(category_id)
+            .bind(blog.is_published)
+            .bind(blog.created_at)
+            .bind(blog.updated_at)
+            .bind(author_id)
+            .bind(&blog.references)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            if let Some(blog_id) = blog_id {
+                for name in &blog.tags {
+                    let tag_id = match tag_ids.get(&name.to_lowercase()) {
+                        Some(id) => *id,
+                        None => {
+                            let id = sqlx::query_scalar::<_, i64>(
+                                r#"
+                                INSERT INTO tags (name) VALUES ($1)
+                                ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name
+                                RETURNING id
+                                "#,
+                            )
+                            .bind(name)
+                            .fetch_one(&mut *tx)
+                            .await?;
+                            tag_ids.insert(name.to_lowercase(), id);
+                            id
+                        }
+                    };
+                    sqlx::query(
+                        "INSERT INTO blog_tags (blog_id, tag_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+                    )
+                    .bind(blog_id)
+                    .bind(tag_id)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+            }
+            blog_ids.push(blog_id);
+        }
+
+        tx.commit().await?;
+
+        Ok(blog_ids)
+    }
+}
@@ -0,0 +1,99 @@
+//! Link check repository - Data access layer for dead-link check results
+
+use crate::error::ApiError;
+use crate::models::link_check::{LinkCheck, LinkCheckOutcome, LinkSourceType};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+/// Link check repository for database operations
+pub struct LinkCheckRepository;
+
+impl LinkCheckRepository {
+    /// Current database time, used as the start of a run
+    ///
+    /// `record` stamps `last_checked_at` with the database clock, so the
+    /// cutoff for `delete_stale` must come from the same clock.
+    pub async fn now(pool: &PgPool) -> Result<DateTime<Utc>, ApiError> {
+        let now = sqlx::query_scalar::<_, DateTime<Utc>>("SELECT NOW()")
+            .fetch_one(pool)
+            .await?;
+
+        Ok(now)
+    }
+
+    /// Store the outcome of a check, counting consecutive failures
+    pub async fn record(
+        pool: &PgPool,
+        source_type: LinkSourceType,
+        source_id: i64,
+        url: &str,
+        outcome: &LinkCheckOutcome,
+    ) -> Result<(), ApiError> {
+        let ok = outcome.is_ok();
+        sqlx::query(
+            r#"
+            INSERT INTO link_checks (source_type, source_id, url, http_status, redirect_url, error,
+                                     consecutive_failures, last_checked_at, last_ok_at)
+            VALUES ($1, $2, $3, $4, $5, $6,
+                    CASE WHEN $7 THEN 0 ELSE 1 END, NOW(), CASE WHEN $7 THEN NOW() END)
+            ON CONFLICT (source_type, source_id, url) DO UPDATE SET
+                http_status = EXCLUDED.http_status,
+                redirect_url = EXCLUDED.redirect_url,
+                error = EXCLUDED.error,
+                consecutive_failures = CASE WHEN $7 THEN 0
+                                            ELSE link_checks.consecutive_failures + 1 END,
+                last_checked_at = NOW(),
+                last_ok_at = COALESCE(EXCLUDED.last_ok_at, link_checks.last_ok_at)
+            "#,
+        )
+        .bind(source_type.as_str())
+        .bind(source_id)
+        .bind(url)
+        .bind(outcome.http_status)
+        .bind(&outcome.redirect_url)
+        .bind(&outcome.error)
+        .bind(ok)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Remove results for links that weren't seen in a run started at `run_started`
+    /// (edited out of a blog, or the friend link was removed / unapproved)
+    pub async fn delete_stale(pool: &PgPool, run_started: DateTime<Utc>) -> Result<u64, ApiError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM link_checks
+            WHERE last_checked_at IS NULL OR last_checked_at < $1
+            "#,
+        )
+        .bind(run_started)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Find links that failed at least `min_failures` times in a row, worst first
+    pub async fn find_broken(pool: &PgPool, min_failures: i32) -> Result<Vec<LinkCheck>, ApiError> {
+        let links = sqlx::query_as::<_, LinkCheck>(
+            r#"
+            SELECT lc.id, lc.source_type, lc.source_id,
+                   COALESCE(b.title, f.name) AS source_title,
+                   lc.url, lc.http_status, lc.redirect_url, lc.error, lc.consecutive_failures,
+                   lc.last_checked_at, lc.last_ok_at
+            FROM link_checks lc
+            LEFT JOIN blogs b ON lc.source_type = 'blog' AND b.id = lc.source_id
+            LEFT JOIN friend_links f ON lc.source_type = 'friend_link' AND f.id = lc.source_id
+            WHERE lc.consecutive_failures >= $1
+            ORDER BY lc.consecutive_failures DESC, lc.last_checked_at DESC
+            "#,
+        )
+        .bind(min_failures)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(links)
+    }
+}
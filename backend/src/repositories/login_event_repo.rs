@@ -0,0 +1,80 @@
+//! Login event repository - Data access layer for failed login records
+
+use crate::error::ApiError;
+use crate::models::login_event::{LoginEvent, LoginEventQuery, LoginFailureReason};
+use crate::models::session::SessionClient;
+use sqlx::PgPool;
+
+/// Login event repository for database operations
+pub struct LoginEventRepository;
+
+impl LoginEventRepository {
+    /// Record one failed login
+    pub async fn record(
+        pool: &PgPool,
+        username: &str,
+        user_id: Option<i64>,
+        client: &SessionClient,
+        reason: LoginFailureReason,
+        locked_out: bool,
+    ) -> Result<(), ApiError> {
+        sqlx::query(
+            r#"
+            INSERT INTO login_events (username, user_id, ip, user_agent, reason, locked_out)
+            VALUES (LEFT($1, 255), $2, LEFT($3, 64), LEFT($4, 1000), $5, $6)
+            "#,
+        )
+        .bind(username)
+        .bind(user_id)
+        .bind(&client.ip)
+        .bind(&client.user_agent)
+        .bind(reason.as_str())
+        .bind(locked_out)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Find failed logins with optional username and IP filters, newest first
+    pub async fn find_with_filters(
+        pool: &PgPool,
+        query: &LoginEventQuery,
+    ) -> Result<(Vec<LoginEvent>, i64), ApiError> {
+        let page = query.page();
+        let page_size = query.page_size();
+        let offset = (page - 1) * page_size;
+
+        let events = sqlx::query_as::<_, LoginEvent>(
+            r#"
+            SELECT id, username, user_id, ip, user_agent, reason, locked_out, created_at
+            FROM login_events
+            WHERE ($1::VARCHAR IS NULL OR username = $1)
+              AND ($2::VARCHAR IS NULL OR ip = $2)
+            ORDER BY created_at DESC, id DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(&query.username)
+        .bind(&query.ip)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        let total = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*)
+            FROM login_events
+            WHERE ($1::VARCHAR IS NULL OR username = $1)
+              AND ($2::VARCHAR IS NULL OR ip = $2)
+            "#,
+        )
+        .bind(&query.username)
+        .bind(&query.ip)
+        .fetch_one(pool)
+        .await?;
+
+        Ok((events, total))
+    }
+}
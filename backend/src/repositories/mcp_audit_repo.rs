@@ -0,0 +1,86 @@
+//! MCP audit repository - Data access layer for the MCP tool call audit log
+
+use crate::error::ApiError;
+use crate::models::mcp::{McpAuditLog, McpAuditLogQuery, McpCallStatus, McpGrant};
+use serde_json::Value as JsonValue;
+use sqlx::PgPool;
+
+/// MCP audit repository for database operations
+pub struct McpAuditRepository;
+
+impl McpAuditRepository {
+    /// Record one tool call
+    pub async fn record(
+        pool: &PgPool,
+        grant: &McpGrant,
+        tool: &str,
+        arguments: Option<&JsonValue>,
+        status: McpCallStatus,
+        error: Option<&str>,
+        duration_ms: Option<i64>,
+    ) -> Result<(), ApiError> {
+        sqlx::query(
+            r#"
+            INSERT INTO mcp_audit_logs (token_id, token_name, tool, arguments, status, error, duration_ms)
+            VALUES ($1, $2, $3, $4, $5, LEFT($6, 1000), $7)
+            "#,
+        )
+        .bind(grant.token_id)
+        .bind(&grant.token_name)
+        .bind(tool)
+        .bind(arguments)
+        .bind(status.as_str())
+        .bind(error)
+        .bind(duration_ms)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Find audit entries with optional token, tool and status filters, newest first
+    pub async fn find_with_filters(
+        pool: &PgPool,
+        query: &McpAuditLogQuery,
+    ) -> Result<(Vec<McpAuditLog>, i64), ApiError> {
+        let page = query.page();
+        let page_size = query.page_size();
+        let offset = (page - 1) * page_size;
+
+        let logs = sqlx::query_as::<_, McpAuditLog>(
+            r#"
+            SELECT id, token_id, token_name, tool, arguments, status, error, duration_ms, created_at
+            FROM mcp_audit_logs
+            WHERE ($1::BIGINT IS NULL OR token_id = $1)
+              AND ($2::VARCHAR IS NULL OR tool = $2)
+              AND ($3::VARCHAR IS NULL OR status = $3)
+            ORDER BY created_at DESC, id DESC
+            LIMIT $4 OFFSET $5
+            "#,
+        )
+        .bind(query.token_id)
+        .bind(&query.tool)
+        .bind(&query.status)
+        .bind(page_size)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        let total = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*)
+            FROM mcp_audit_logs
+            WHERE ($1::BIGINT IS NULL OR token_id = $1)
+              AND ($2::VARCHAR IS NULL OR tool = $2)
+              AND ($3::VARCHAR IS NULL OR status = $3)
+            "#,
+        )
+        .bind(query.token_id)
+        .bind(&query.tool)
+        .bind(&query.status)
+        .fetch_one(pool)
+        .await?;
+
+        Ok((logs, total))
+    }
+}
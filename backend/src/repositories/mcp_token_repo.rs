@@ -0,0 +1,139 @@
+//! MCP token repository - Data access layer for MCP tokens
+
+use crate::error::ApiError;
+use crate::models::mcp::{McpScope, McpToken};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+/// MCP token repository for database operations
+pub struct McpTokenRepository;
+
+impl McpTokenRepository {
+    /// Find all tokens, newest first
+    pub async fn find_all(pool: &PgPool) -> Result<Vec<McpToken>, ApiError> {
+        let tokens = sqlx::query_as::<_, McpToken>(
+            r#"
+            SELECT id, name, token_hash, token_last_four, scopes,
+                   expires_at, last_used_at, created_at
+            FROM mcp_tokens
+            ORDER BY created_at DESC, id DESC
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(tokens)
+    }
+
+    /// Find token by name
+    pub async fn find_by_name(pool: &PgPool, name: &str) -> Result<Option<McpToken>, ApiError> {
+        let token = sqlx::query_as::<_, McpToken>(
+            r#"
+            SELECT id, name, token_hash, token_last_four, scopes,
+                   expires_at, last_used_at, created_at
+            FROM mcp_tokens
+            WHERE name = $1
+            "#,
+        )
+        .bind(name)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    /// Find the tokens a presented secret could belong to
+    ///
+    /// Matches on the plain prefix, plus the migrated legacy token which has none.
+    pub async fn find_candidates(pool: &PgPool, prefix: &str) -> Result<Vec<McpToken>, ApiError> {
+        let tokens = sqlx::query_as::<_, McpToken>(
+            r#"
+            SELECT id, name, token_hash, token_last_four, scopes,
+                   expires_at, last_used_at, created_at
+            FROM mcp_tokens
+            WHERE token_prefix = $1 OR token_prefix IS NULL
+            "#,
+        )
+        .bind(prefix)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(tokens)
+    }
+
+    /// Create a new token from an already hashed secret
+    pub async fn create(
+        pool: &PgPool,
+        name: &str,
+        token_prefix: &str,
+        token_hash: &str,
+        token_last_four: &str,
+        scopes: &[McpScope],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<McpToken, ApiError> {
+        let scopes: Vec<&str> = scopes.iter().map(McpScope::as_str).collect();
+
+        let token = sqlx::query_as::<_, McpToken>(
+            r#"
+            INSERT INTO mcp_tokens (name, token_prefix, token_hash, token_last_four, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, name, token_hash, token_last_four, scopes,
+                      expires_at, last_used_at, created_at
+            "#,
+        )
+        .bind(name)
+        .bind(token_prefix)
+        .bind(token_hash)
+        .bind(token_last_four)
+        .bind(&scopes)
+        .bind(expires_at)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    /// Delete a token by ID; its audit entries keep the token name
+    pub async fn delete(pool: &PgPool, id: i64) -> Result<bool, ApiError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM mcp_tokens
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record that a token was used, at most once a minute
+    pub async fn touch(pool: &PgPool, id: i64) -> Result<(), ApiError> {
+        sqlx::query(
+            r#"
+            UPDATE mcp_tokens
+            SET last_used_at = NOW()
+            WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < NOW() - INTERVAL '1 minute')
+            "#,
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Whether any token that has not expired exists
+    pub async fn any_active(pool: &PgPool) -> Result<bool, ApiError> {
+        let result = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT EXISTS(SELECT 1 FROM mcp_tokens WHERE expires_at IS NULL OR expires_at > NOW())
+            "#,
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(result)
+    }
+}
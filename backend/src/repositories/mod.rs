@@ -3,13 +3,25 @@
 pub mod archive_repo;
 pub mod blog_repo;
 pub mod category_repo;
+pub mod comment_repo;
+pub mod data_export_repo;
 pub mod directory_repo;
 pub mod document_repo;
 pub mod file_repo;
+pub mod file_variant_repo;
 pub mod friend_link_repo;
+pub mod import_repo;
+pub mod link_check_repo;
+pub mod login_event_repo;
+pub mod mcp_audit_repo;
+pub mod mcp_token_repo;
 pub mod project_repo;
+pub mod recovery_code_repo;
+pub mod revision_repo;
 pub mod search_repo;
 pub mod site_config_repo;
+pub mod site_export_repo;
+pub mod sitemap_repo;
 pub mod tag_repo;
 pub mod text_repo;
 pub mod user_repo;
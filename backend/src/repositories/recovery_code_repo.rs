@@ -0,0 +1,97 @@
+//! Recovery code repository - Data access layer for two-factor recovery codes
+
+use crate::error::ApiError;
+use crate::models::two_factor::RecoveryCode;
+use sqlx::PgPool;
+
+/// Recovery code repository for database operations
+pub struct RecoveryCodeRepository;
+
+impl RecoveryCodeRepository {
+    /// Replace all recovery codes of a user with new hashes
+    pub async fn replace_all(
+        pool: &PgPool,
+        user_id: i64,
+        code_hashes: &[String],
+    ) -> Result<(), ApiError> {
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("DELETE FROM user_recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_recovery_codes (user_id, code_hash)
+            SELECT $1, UNNEST($2::VARCHAR[])
+            "#,
+        )
+        .bind(user_id)
+        .bind(code_hashes)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Find the recovery codes a user has not used yet
+    pub async fn find_unused(pool: &PgPool, user_id: i64) -> Result<Vec<RecoveryCode>, ApiError> {
+        let codes = sqlx::query_as::<_, RecoveryCode>(
+            r#"
+            SELECT id, code_hash
+            FROM user_recovery_codes
+            WHERE user_id = $1 AND used_at IS NULL
+            ORDER BY id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(codes)
+    }
+
+    /// Mark a recovery code as used; returns false if it was already used
+    pub async fn mark_used(pool: &PgPool, id: i64) -> Result<bool, ApiError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_recovery_codes
+            SET used_at = NOW()
+            WHERE id = $1 AND used_at IS NULL
+            "#,
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Count the recovery codes a user has left
+    pub async fn count_unused(pool: &PgPool, user_id: i64) -> Result<i64, ApiError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM user_recovery_codes
+            WHERE user_id = $1 AND used_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Delete all recovery codes of a user
+    pub async fn delete_all(pool: &PgPool, user_id: i64) -> Result<(), ApiError> {
+        sqlx::query("DELETE FROM user_recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+//! Revision repository - Data access layer for blog and document revision history
+
+use crate::error::ApiError;
+use crate::models::revision::{Revision, RevisionActor, RevisionListItem, RevisionTarget};
+use sqlx::{PgConnection, PgPool};
+
+/// Table and column names for one revisioned content type
+struct RevisionTable {
+    /// Revision table
+    table: &'static str,
+    /// Foreign key column pointing at the content row
+    entity_column: &'static str,
+    /// Content table the snapshots are taken from
+    source_table: &'static str,
+    /// Column holding the title (document `name`)
+    title_column: &'static str,
+    /// Summary expression; documents have no summary
+    summary_column: &'static str,
+}
+
+impl RevisionTable {
+    fn of(target: RevisionTarget) -> Self {
+        match target {
+            RevisionTarget::Blog => Self {
+                table: "blog_revisions",
+                entity_column: "blog_id",
+                source_table: "blogs",
+                title_column: "title",
+                summary_column: "summary",
+            },
+            RevisionTarget::Document => Self {
+                table: "document_revisions",
+                entity_column: "document_id",
+                source_table: "documents",
+                title_column: "name",
+                summary_column: "NULL::TEXT",
+            },
+        }
+    }
+}
+
+/// Revision repository for database operations
+pub struct RevisionRepository;
+
+impl RevisionRepository {
+    /// Record the current state of a blog or document as a new revision
+    ///
+    /// Nothing is written when the state is identical to the latest revision,
+    /// so calling this for changes that don't touch title/content/summary/references
+    /// (e.g. publishing) doesn't grow the history. Returns the new revision id.
+    pub async fn snapshot(
+        conn: &mut PgConnection,
+        target: RevisionTarget,
+        entity_id: i64,
+        actor: &RevisionActor,
+    ) -> Result<Option<i64>, ApiError> {
+        let t = RevisionTable::of(target);
+        let sql = format!(
+            r#"
+            INSERT INTO {table} ({entity}, {title}, content, {summary_insert}"references", source, actor_id, actor_name)
+            SELECT s.id, s.{title}, s.content, {summary_select}s."references", $2, $3, $4
+            FROM {source} s
+            WHERE s.id = $1
+              AND NOT EXISTS (
+                  SELECT 1 FROM (
+                      SELECT {title} AS title, content, {summary} AS summary, "references"
+                      FROM {table}
+                      WHERE {entity} = $1
+                      ORDER BY id DESC
+                      LIMIT 1
+                  ) latest
+                  WHERE latest.title = s.{title}
+                    AND latest.content = s.content
+                    AND latest.summary IS NOT DISTINCT FROM {source_summary}
+                    AND latest."references" IS NOT DISTINCT FROM s."references"
+              )
+            RETURNING id
+            "#,
+            table = t.table,
+            entity = t.entity_column,
+            title = t.title_column,
+            source = t.source_table,
+            summary = t.summary_column,
+            summary_insert = match target {
+                RevisionTarget::Blog => "summary, ",
+                RevisionTarget::Document => "",
+            },
+            summary_select = match target {
+                RevisionTarget::Blog => "s.summary, ",
+                RevisionTarget::Document => "",
+            },
+            source_summary = match target {
+                RevisionTarget::Blog => "s.summary",
+                RevisionTarget::Document => "NULL::TEXT",
+            },
+        );
+
+        let id = sqlx::query_scalar::<_, i64>(&sql)
+            .bind(entity_id)
+            .bind(actor.source.as_str())
+            .bind(actor.actor_id)
+            .bind(&actor.actor_name)
+            .fetch_optional(conn)
+            .await?;
+
+        Ok(id)
+    }
+
+    /// Lock the content row of a blog or document for the rest of the transaction
+    ///
+    /// Taken before the baseline snapshot so no other write can land between
+    /// the snapshot, the update and the actor snapshot. Returns whether the row exists.
+    pub async fn lock_entity(
+        conn: &mut PgConnection,
+        target: RevisionTarget,
+        entity_id: i64,
+    ) -> Result<bool, ApiError> {
+        let sql = format!(
+            "SELECT id FROM {} WHERE id = $1 FOR UPDATE",
+            RevisionTable::of(target).source_table
+        );
+
+        let id = sqlx::query_scalar::<_, i64>(&sql)
+            .bind(entity_id)
+            .fetch_optional(conn)
+            .await?;
+
+        Ok(id.is_some())
+    }
+
+    /// List revisions of a blog or document, newest first
+    pub async fn find_by_entity(
+        pool: &PgPool,
+        target: RevisionTarget,
+        entity_id: i64,
+    ) -> Result<Vec<RevisionListItem>, ApiError> {
+        let t = RevisionTable::of(target);
+        let sql = format!(
+            r#"
+            SELECT id, {entity} AS entity_id, {title} AS title, LENGTH(content) AS content_length,
+                   source, actor_id, actor_name, created_at
+            FROM {table}
+            WHERE {entity} = $1
+            ORDER BY id DESC
+            "#,
+            table = t.table,
+            entity = t.entity_column,
+            title = t.title_column,
+        );
+
+        let revisions = sqlx::query_as::<_, RevisionListItem>(&sql)
+            .bind(entity_id)
+            .fetch_all(pool)
+            .await?;
+
+        Ok(revisions)
+    }
+
+    /// Find a single revision belonging to the given blog or document
+    pub async fn find_by_id(
+        pool: &PgPool,
+        target: RevisionTarget,
+        entity_id: i64,
+        revision_id: i64,
+    ) -> Result<Option<Revision>, ApiError> {
+        let t = RevisionTable::of(target);
+        let sql = format!(
+            r#"
+            SELECT id, {entity} AS entity_id, {title} AS title, content, {summary} AS summary,
+                   "references", source, actor_id, actor_name, created_at
+            FROM {table}
+            WHERE {entity} = $1 AND id = $2
+            "#,
+            table = t.table,
+            entity = t.entity_column,
+            title = t.title_column,
+            summary = t.summary_column,
+        );
+
+        let revision = sqlx::query_as::<_, Revision>(&sql)
+            .bind(entity_id)
+            .bind(revision_id)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(revision)
+    }
+
+    /// Put a blog or document back to the state recorded in a revision
+    ///
+    /// Every snapshotted column is written as is, so a revision without a
+    /// summary or references clears them rather than keeping the current
+    /// ones. The current state and the restored one are both recorded, so the
+    /// restore can be undone. Returns whether the row exists.
+    pub async fn restore(
+        pool: &PgPool,
+        target: RevisionTarget,
+        revision: &Revision,
+        html: Option<&str>,
+        actor: &RevisionActor,
+    ) -> Result<bool, ApiError> {
+        let entity_id = revision.entity_id;
+        let mut tx = pool.begin().await?;
+        if !Self::lock_entity(&mut tx, target, entity_id).await? {
+            return Ok(false);
+        }
+        Self::snapshot(&mut tx, target, entity_id, &RevisionActor::baseline()).await?;
+
+        let result = match target {
+            RevisionTarget::Blog => {
+                sqlx::query(
+                    r#"
+                    UPDATE blogs
+                    SET title = $2, content = $3, html = $4, summary = $5, "references" = $6,
+                        updated_at = NOW()
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(entity_id)
+                .bind(&revision.title)
+                .bind(&revision.content)
+                .bind(html)
+                .bind(&revision.summary)
+                .bind(&revision.references)
+                .execute(&mut *tx)
+                .await?
+            }
+            RevisionTarget::Document => {
+                sqlx::query(
+                    r#"
+                    UPDATE documents
+                    SET name = $2, content = $3, "references" = $4, updated_at = NOW()
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(entity_id)
+                .bind(&revision.title)
+                .bind(&revision.content)
+                .bind(&revision.references)
+                .execute(&mut *tx)
+                .await?
+            }
+        };
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        Self::snapshot(&mut tx, target, entity_id, actor).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+}
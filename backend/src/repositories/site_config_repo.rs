@@ -2,7 +2,10 @@ use sqlx::PgPool;
 use std::collections::HashMap;
 
 use crate::error::ApiError;
-use crate::models::site_config::{S3Config, SiteConfig, SiteConfigResponse};
+use crate::models::site_config::{
+    is_masked_secret, mask_secret, S3Config, SiteConfig, SiteConfigResponse, SECRET_MASK,
+};
+use crate::utils::crypto;
 
 pub struct SiteConfigRepo;
 
@@ -10,7 +13,8 @@ impl SiteConfigRepo {
     /// 获取所有配置
     pub async fn get_all(pool: &PgPool) -> Result<Vec<SiteConfig>, ApiError> {
         let configs = sqlx::query_as::<_, SiteConfig>(
-            "SELECT id, config_key, config_value, config_type, description, created_at, updated_at 
+            "SELECT id, config_key, config_value, config_type, description, is_sensitive,
+                    created_at, updated_at
              FROM site_config ORDER BY id",
         )
         .fetch_all(pool)
@@ -23,7 +27,8 @@ impl SiteConfigRepo {
     #[allow(dead_code)]
     pub async fn get_by_key(pool: &PgPool, key: &str) -> Result<Option<SiteConfig>, ApiError> {
         let config = sqlx::query_as::<_, SiteConfig>(
-            "SELECT id, config_key, config_value, config_type, description, created_at, updated_at 
+            "SELECT id, config_key, config_value, config_type, description, is_sensitive,
+                    created_at, updated_at
              FROM site_config WHERE config_key = $1",
         )
         .bind(key)
@@ -60,13 +65,126 @@ impl SiteConfigRepo {
     }
 
     /// 批量更新配置
-    pub async fn batch_update(pool: &PgPool, configs: &[(String, String)]) -> Result<(), ApiError> {
+    ///
+    /// 敏感配置只写：提交脱敏值时保持原值，新值在配置主密钥时加密存储
+    pub async fn batch_update(
+        pool: &PgPool,
+        configs: &[(String, String)],
+        master_key: Option<&str>,
+    ) -> Result<(), ApiError> {
+        let sensitive_keys = Self::get_sensitive_keys(pool).await?;
+
         for (key, value) in configs {
-            Self::update(pool, key, value).await?;
+            if !sensitive_keys.contains(key) {
+                Self::update(pool, key, value).await?;
+            } else if !is_masked_secret(value) {
+                Self::update(pool, key, &Self::seal_secret(master_key, key, value)?).await?;
+            }
         }
         Ok(())
     }
 
+    /// 获取所有配置，敏感配置脱敏显示（管理后台使用）
+    pub async fn get_all_redacted(
+        pool: &PgPool,
+        master_key: Option<&str>,
+    ) -> Result<Vec<SiteConfig>, ApiError> {
+        let mut configs = Self::get_all(pool).await?;
+
+        for config in configs.iter_mut().filter(|config| config.is_sensitive) {
+            config.config_value = config.config_value.as_deref().map(|value| {
+                match Self::reveal_secret(master_key, value) {
+                    Ok(plaintext) => mask_secret(&plaintext),
+                    Err(_) => SECRET_MASK.to_string(),
+                }
+            });
+        }
+
+        Ok(configs)
+    }
+
+    /// 获取敏感配置的明文，加密存储的值自动解密
+    pub async fn get_secret(
+        pool: &PgPool,
+        key: &str,
+        master_key: Option<&str>,
+    ) -> Result<Option<String>, ApiError> {
+        Self::get_value(pool, key)
+            .await?
+            .map(|value| Self::reveal_secret(master_key, &value))
+            .transpose()
+    }
+
+    /// 加密主密钥配置之前以明文保存的敏感配置
+    pub async fn seal_legacy_secrets(pool: &PgPool, master_key: &str) -> Result<usize, ApiError> {
+        let configs = Self::get_all(pool).await?;
+
+        let mut count = 0;
+        for config in configs.iter().filter(|config| config.is_sensitive) {
+            let Some(value) = config.config_value.as_deref() else {
+                continue;
+            };
+            if value.is_empty() || crypto::is_sealed_value(value) {
+                continue;
+            }
+            Self::update(
+                pool,
+                &config.config_key,
+                &crypto::seal_value(master_key, value)?,
+            )
+            .await?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    async fn get_sensitive_keys(pool: &PgPool) -> Result<Vec<String>, ApiError> {
+        let keys = sqlx::query_scalar::<_, String>(
+            "SELECT config_key FROM site_config WHERE is_sensitive = TRUE",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(keys)
+    }
+
+    /// 加密敏感配置值；未配置主密钥时原样保存
+    pub(crate) fn seal_secret(
+        master_key: Option<&str>,
+        key: &str,
+        value: &str,
+    ) -> Result<String, ApiError> {
+        if value.is_empty() {
+            return Ok(String::new());
+        }
+
+        match master_key {
+            Some(master_key) => crypto::seal_value(master_key, value),
+            None => {
+                tracing::warn!(
+                    "ENCRYPTION_MASTER_KEY is not set, storing {} unencrypted",
+                    key
+                );
+                Ok(value.to_string())
+            }
+        }
+    }
+
+    fn reveal_secret(master_key: Option<&str>, value: &str) -> Result<String, ApiError> {
+        if !crypto::is_sealed_value(value) {
+            return Ok(value.to_string());
+        }
+
+        let master_key = master_key.ok_or_else(|| {
+            ApiError::InternalError(
+                "ENCRYPTION_MASTER_KEY is not configured, encrypted settings are unavailable"
+                    .to_string(),
+            )
+        })?;
+        crypto::open_value(master_key, value)
+    }
+
     /// 获取配置为 HashMap
     pub async fn get_as_map(pool: &PgPool) -> Result<HashMap<String, String>, ApiError> {
         let configs: Vec<SiteConfig> = Self::get_all(pool).await?;
@@ -86,6 +204,7 @@ impl SiteConfigRepo {
             site_subtitle: map.get("site_subtitle").cloned().unwrap_or_default(),
             site_description: map.get("site_description").cloned().unwrap_or_default(),
             site_keywords: map.get("site_keywords").cloned().unwrap_or_default(),
+            site_url: map.get("site_url").cloned().unwrap_or_default(),
             blog_global_summary: map.get("blog_global_summary").cloned().unwrap_or_default(),
             owner_name: map.get("owner_name").cloned().unwrap_or_default(),
             owner_avatar: map.get("owner_avatar").cloned().unwrap_or_default(),
@@ -105,10 +224,18 @@ impl SiteConfigRepo {
         })
     }
 
-    /// 获取S3配置
-    #[allow(dead_code)]
-    pub async fn get_s3_config(pool: &PgPool) -> Result<S3Config, ApiError> {
+    /// 获取S3配置，密钥自动解密
+    pub async fn get_s3_config(
+        pool: &PgPool,
+        master_key: Option<&str>,
+    ) -> Result<S3Config, ApiError> {
         let map = Self::get_as_map(pool).await?;
+        let secret = |key: &str| -> Result<String, ApiError> {
+            map.get(key)
+                .map(|value| Self::reveal_secret(master_key, value))
+                .transpose()
+                .map(Option::unwrap_or_default)
+        };
 
         Ok(S3Config {
             endpoint: map.get("s3_endpoint").cloned().unwrap_or_default(),
@@ -117,8 +244,8 @@ impl SiteConfigRepo {
                 .cloned()
                 .unwrap_or_else(|| "us-east-1".to_string()),
             bucket: map.get("s3_bucket").cloned().unwrap_or_default(),
-            access_key: map.get("s3_access_key").cloned().unwrap_or_default(),
-            secret_key: map.get("s3_secret_key").cloned().unwrap_or_default(),
+            access_key: secret("s3_access_key")?,
+            secret_key: secret("s3_secret_key")?,
             public_url: map.get("s3_public_url").cloned().unwrap_or_default(),
         })
     }
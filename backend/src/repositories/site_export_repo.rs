@@ -0,0 +1,54 @@
+//! Site export repository - Reads the content written by a static-site export
+
+use crate::error::ApiError;
+use crate::models::document::Document;
+use crate::models::site_export::SiteExportBlog;
+use sqlx::PgPool;
+
+/// Site export repository for database operations
+pub struct SiteExportRepository;
+
+impl SiteExportRepository {
+    /// Every blog, published or not, with its category and tag names
+    pub async fn find_blogs(pool: &PgPool) -> Result<Vec<SiteExportBlog>, ApiError> {
+        let blogs = sqlx::query_as::<_, SiteExportBlog>(
+            r#"
+            SELECT b.id, b.title, b.slug, b.author, b.content, b.summary, b.thumbnail,
+                   c.name AS category,
+                   COALESCE(
+                       ARRAY(
+                           SELECT t.name FROM blog_tags bt
+                           JOIN tags t ON t.id = bt.tag_id
+                           WHERE bt.blog_id = b.id
+                           ORDER BY t.name
+                       ),
+                       '{}'
+                   ) AS tags,
+                   b.is_published, b.created_at, b.updated_at, b.publish_at, b."references"
+            FROM blogs b
+            LEFT JOIN categories c ON c.id = b.category_id
+            ORDER BY b.created_at ASC, b.id ASC
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(blogs)
+    }
+
+    /// Every document with its content
+    pub async fn find_documents(pool: &PgPool) -> Result<Vec<Document>, ApiError> {
+        let documents = sqlx::query_as::<_, Document>(
+            r#"
+            SELECT id, name, filename, content, directory_id, sort_order, created_at, updated_at,
+                   "references"
+            FROM documents
+            ORDER BY sort_order ASC, id ASC
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(documents)
+    }
+}
@@ -0,0 +1,94 @@
+//! Sitemap repository - Data access layer for sitemap generation
+
+use crate::error::ApiError;
+use crate::models::sitemap::{SitemapKind, SitemapUrl};
+use sqlx::PgPool;
+
+/// Sitemap repository for database operations
+pub struct SitemapRepository;
+
+impl SitemapRepository {
+    /// Count the URLs of a kind (only published blogs are counted)
+    pub async fn count(pool: &PgPool, kind: SitemapKind) -> Result<i64, ApiError> {
+        let sql = match kind {
+            SitemapKind::Blogs => "SELECT COUNT(*) FROM blogs WHERE is_published = true",
+            SitemapKind::Categories => "SELECT COUNT(*) FROM categories",
+            SitemapKind::Tags => "SELECT COUNT(*) FROM tags",
+            SitemapKind::Documents => "SELECT COUNT(*) FROM documents",
+        };
+
+        let total = sqlx::query_scalar::<_, i64>(sql).fetch_one(pool).await?;
+        Ok(total)
+    }
+
+    /// Find one page of URLs of a kind
+    ///
+    /// Blogs use their slug when present, falling back to the ID. Categories
+    /// and tags take the newest `updated_at` of their published blogs as
+    /// `lastmod`, since they have no modification time of their own.
+    pub async fn find_page(
+        pool: &PgPool,
+        kind: SitemapKind,
+        page: i64,
+        page_size: i64,
+    ) -> Result<Vec<SitemapUrl>, ApiError> {
+        let offset = (page - 1) * page_size;
+
+        let sql = match kind {
+            SitemapKind::Blogs => {
+                r#"
+                SELECT
+                    '/blog/' || COALESCE(NULLIF(slug, ''), id::TEXT) AS path,
+                    COALESCE(updated_at, created_at) AS lastmod
+                FROM blogs
+                WHERE is_published = true
+                ORDER BY id
+                LIMIT $1 OFFSET $2
+                "#
+            }
+            SitemapKind::Categories => {
+                r#"
+                SELECT
+                    '/category/' || c.id::TEXT AS path,
+                    MAX(COALESCE(b.updated_at, b.created_at)) AS lastmod
+                FROM categories c
+                LEFT JOIN blogs b ON b.category_id = c.id AND b.is_published = true
+                GROUP BY c.id
+                ORDER BY c.id
+                LIMIT $1 OFFSET $2
+                "#
+            }
+            SitemapKind::Tags => {
+                r#"
+                SELECT
+                    '/tag/' || t.id::TEXT AS path,
+                    MAX(COALESCE(b.updated_at, b.created_at)) AS lastmod
+                FROM tags t
+                LEFT JOIN blog_tags bt ON bt.tag_id = t.id
+                LEFT JOIN blogs b ON b.id = bt.blog_id AND b.is_published = true
+                GROUP BY t.id
+                ORDER BY t.id
+                LIMIT $1 OFFSET $2
+                "#
+            }
+            SitemapKind::Documents => {
+                r#"
+                SELECT
+                    '/docs/' || id::TEXT AS path,
+                    COALESCE(updated_at, created_at) AS lastmod
+                FROM documents
+                ORDER BY id
+                LIMIT $1 OFFSET $2
+                "#
+            }
+        };
+
+        let urls = sqlx::query_as::<_, SitemapUrl>(sql)
+            .bind(page_size)
+            .bind(offset)
+            .fetch_all(pool)
+            .await?;
+
+        Ok(urls)
+    }
+}
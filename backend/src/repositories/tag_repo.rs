@@ -313,6 +313,7 @@ impl TagRepository {
                 Option<String>,
                 i64,
                 Option<DateTime<Utc>>,
+                Option<DateTime<Utc>>,
                 Option<i64>,
                 Option<String>,
                 Option<String>,
@@ -330,6 +331,7 @@ impl TagRepository {
                 b.thumbnail,
                 b.view_count,
                 b.created_at,
+                b.publish_at,
                 c.id as category_id,
                 c.name as category_name,
                 c.intro as category_intro,
@@ -361,6 +363,7 @@ impl TagRepository {
                 thumbnail,
                 view_count,
                 created_at,
+                publish_at,
                 category_id,
                 category_name,
                 category_intro,
@@ -391,6 +394,7 @@ impl TagRepository {
                 view_count,
                 is_published: true, // This query only returns published blogs
                 created_at,
+                publish_at,
             });
         }
 
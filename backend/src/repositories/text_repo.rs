@@ -1,7 +1,9 @@
 //! Text repository - Data access layer for dictionary text operations
 
 use crate::error::ApiError;
-use crate::models::text::{CreateTextRequest, Text, UpdateTextRequest};
+use crate::models::text::{CreateTextRequest, Text, TextContent, UpdateTextRequest};
+use crate::repositories::user_repo::UserRepository;
+use crate::utils::crypto::SealedContent;
 use sqlx::PgPool;
 
 /// Text repository for database operations
@@ -12,7 +14,8 @@ impl TextRepository {
     pub async fn find_all(pool: &PgPool) -> Result<Vec<Text>, ApiError> {
         let texts = sqlx::query_as::<_, Text>(
             r#"
-            SELECT id, name, intro, content, is_encrypted, view_password, created_at, updated_at
+            SELECT id, name, intro, content, is_encrypted, view_password, content_kdf,
+                   content_nonce, created_at, updated_at
             FROM texts
             ORDER BY created_at DESC
             "#,
@@ -27,7 +30,8 @@ impl TextRepository {
     pub async fn find_by_id(pool: &PgPool, id: i64) -> Result<Option<Text>, ApiError> {
         let text = sqlx::query_as::<_, Text>(
             r#"
-            SELECT id, name, intro, content, is_encrypted, view_password, created_at, updated_at
+            SELECT id, name, intro, content, is_encrypted, view_password, content_kdf,
+                   content_nonce, created_at, updated_at
             FROM texts
             WHERE id = $1
             "#,
@@ -40,24 +44,37 @@ impl TextRepository {
     }
 
     /// Create a new text
-    pub async fn create(pool: &PgPool, req: &CreateTextRequest) -> Result<Text, ApiError> {
-        let is_encrypted = req.is_encrypted.unwrap_or(false);
-
-        // If encrypted but no password provided, set is_encrypted to false
-        let actual_encrypted = is_encrypted && req.view_password.is_some();
+    ///
+    /// The text is marked encrypted exactly when `content` is sealed.
+    pub async fn create(
+        pool: &PgPool,
+        req: &CreateTextRequest,
+        content: &TextContent,
+    ) -> Result<Text, ApiError> {
+        let password_hash = req
+            .view_password
+            .as_deref()
+            .map(UserRepository::hash_password)
+            .transpose()?;
+        let (content, content_kdf, content_nonce) = content.columns();
+        let is_encrypted = content_kdf.is_some();
 
         let text = sqlx::query_as::<_, Text>(
             r#"
-            INSERT INTO texts (name, intro, content, is_encrypted, view_password)
-            VALUES ($1, $2, $3, $4, $5)
-            RETURNING id, name, intro, content, is_encrypted, view_password, created_at, updated_at
+            INSERT INTO texts (name, intro, content, is_encrypted, view_password, content_kdf,
+                               content_nonce)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, name, intro, content, is_encrypted, view_password, content_kdf,
+                      content_nonce, created_at, updated_at
             "#,
         )
         .bind(&req.name)
         .bind(&req.intro)
-        .bind(&req.content)
-        .bind(actual_encrypted)
-        .bind(&req.view_password)
+        .bind(content)
+        .bind(is_encrypted)
+        .bind(&password_hash)
+        .bind(content_kdf)
+        .bind(content_nonce)
         .fetch_one(pool)
         .await?;
 
@@ -65,26 +82,21 @@ impl TextRepository {
     }
 
     /// Update an existing text
+    ///
+    /// `content` replaces the stored content; the text is marked encrypted exactly when it is sealed.
     pub async fn update(
         pool: &PgPool,
         id: i64,
         req: &UpdateTextRequest,
+        content: &TextContent,
     ) -> Result<Option<Text>, ApiError> {
-        // First get the existing text to handle is_encrypted logic
-        let existing = Self::find_by_id(pool, id).await?;
-        if existing.is_none() {
-            return Ok(None);
-        }
-        let existing = existing.unwrap();
-
-        // Determine the new is_encrypted value
-        let new_is_encrypted = req
-            .is_encrypted
-            .unwrap_or(existing.is_encrypted.unwrap_or(false));
-        let new_password = req.view_password.clone().or(existing.view_password.clone());
-
-        // If encrypted but no password, set to false
-        let actual_encrypted = new_is_encrypted && new_password.is_some();
+        let password_hash = req
+            .view_password
+            .as_deref()
+            .map(UserRepository::hash_password)
+            .transpose()?;
+        let (content, content_kdf, content_nonce) = content.columns();
+        let is_encrypted = content_kdf.is_some();
 
         let text = sqlx::query_as::<_, Text>(
             r#"
@@ -92,25 +104,108 @@ impl TextRepository {
             SET 
                 name = COALESCE($2, name),
                 intro = COALESCE($3, intro),
-                content = COALESCE($4, content),
+                content = $4,
                 is_encrypted = $5,
-                view_password = $6
+                view_password = COALESCE($6, view_password),
+                content_kdf = $7,
+                content_nonce = $8
             WHERE id = $1
-            RETURNING id, name, intro, content, is_encrypted, view_password, created_at, updated_at
+            RETURNING id, name, intro, content, is_encrypted, view_password, content_kdf,
+                      content_nonce, created_at, updated_at
             "#,
         )
         .bind(id)
         .bind(&req.name)
         .bind(&req.intro)
-        .bind(&req.content)
-        .bind(actual_encrypted)
-        .bind(&new_password)
+        .bind(content)
+        .bind(is_encrypted)
+        .bind(&password_hash)
+        .bind(content_kdf)
+        .bind(content_nonce)
         .fetch_optional(pool)
         .await?;
 
         Ok(text)
     }
 
+    /// Find encrypted texts whose content is still stored in plaintext
+    pub async fn find_unsealed_encrypted(pool: &PgPool) -> Result<Vec<Text>, ApiError> {
+        let texts = sqlx::query_as::<_, Text>(
+            r#"
+            SELECT id, name, intro, content, is_encrypted, view_password, content_kdf,
+                   content_nonce, created_at, updated_at
+            FROM texts
+            WHERE is_encrypted = true AND content_kdf IS NULL
+            ORDER BY id
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(texts)
+    }
+
+    /// Find texts whose content is sealed
+    pub async fn find_sealed(pool: &PgPool) -> Result<Vec<Text>, ApiError> {
+        let texts = sqlx::query_as::<_, Text>(
+            r#"
+            SELECT id, name, intro, content, is_encrypted, view_password, content_kdf,
+                   content_nonce, created_at, updated_at
+            FROM texts
+            WHERE content_kdf IS NOT NULL
+            ORDER BY id
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(texts)
+    }
+
+    /// Replace plaintext content with its sealed form
+    pub async fn seal_content(
+        pool: &PgPool,
+        id: i64,
+        sealed: &SealedContent,
+    ) -> Result<(), ApiError> {
+        sqlx::query(
+            r#"
+            UPDATE texts
+            SET content = $2, content_kdf = $3, content_nonce = $4
+            WHERE id = $1 AND content_kdf IS NULL
+            "#,
+        )
+        .bind(id)
+        .bind(&sealed.ciphertext)
+        .bind(&sealed.kdf)
+        .bind(&sealed.nonce)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Replace the stored view password with an Argon2 hash
+    pub async fn update_password_hash(
+        pool: &PgPool,
+        id: i64,
+        password_hash: &str,
+    ) -> Result<(), ApiError> {
+        sqlx::query(
+            r#"
+            UPDATE texts
+            SET view_password = $2
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(password_hash)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
     /// Delete a text by ID
     pub async fn delete(pool: &PgPool, id: i64) -> Result<bool, ApiError> {
         let result = sqlx::query(
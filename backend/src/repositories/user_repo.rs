@@ -1,7 +1,7 @@
 //! User repository - Data access layer for user operations
 
 use crate::error::ApiError;
-use crate::models::user::{CreateUserRequest, User};
+use crate::models::user::{CreateUserRequest, UpdateUserRequest, User, UserRole};
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
@@ -16,7 +16,8 @@ impl UserRepository {
     pub async fn find_by_id(pool: &PgPool, id: i64) -> Result<Option<User>, ApiError> {
         let user = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, username, password_hash, email, nickname, avatar, created_at, updated_at
+            SELECT id, username, password_hash, email, nickname, avatar, role, totp_secret, totp_enabled, totp_last_step,
+                   created_at, updated_at
             FROM users
             WHERE id = $1
             "#,
@@ -32,7 +33,8 @@ impl UserRepository {
     pub async fn find_by_username(pool: &PgPool, username: &str) -> Result<Option<User>, ApiError> {
         let user = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, username, password_hash, email, nickname, avatar, created_at, updated_at
+            SELECT id, username, password_hash, email, nickname, avatar, role, totp_secret, totp_enabled, totp_last_step,
+                   created_at, updated_at
             FROM users
             WHERE username = $1
             "#,
@@ -44,15 +46,36 @@ impl UserRepository {
         Ok(user)
     }
 
+    /// Find all users, oldest first
+    pub async fn find_all(pool: &PgPool) -> Result<Vec<User>, ApiError> {
+        let users = sqlx::query_as::<_, User>(
+            r#"
+            SELECT id, username, password_hash, email, nickname, avatar, role, totp_secret, totp_enabled, totp_last_step,
+                   created_at, updated_at
+            FROM users
+            ORDER BY id
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(users)
+    }
+
     /// Create a new user with hashed password
-    pub async fn create(pool: &PgPool, req: &CreateUserRequest) -> Result<User, ApiError> {
+    pub async fn create(
+        pool: &PgPool,
+        req: &CreateUserRequest,
+        role: UserRole,
+    ) -> Result<User, ApiError> {
         let password_hash = Self::hash_password(&req.password)?;
 
         let user = sqlx::query_as::<_, User>(
             r#"
-            INSERT INTO users (username, password_hash, email, nickname, avatar)
-            VALUES ($1, $2, $3, $4, $5)
-            RETURNING id, username, password_hash, email, nickname, avatar, created_at, updated_at
+            INSERT INTO users (username, password_hash, email, nickname, avatar, role)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, username, password_hash, email, nickname, avatar, role, totp_secret, totp_enabled, totp_last_step,
+                   created_at, updated_at
             "#,
         )
         .bind(&req.username)
@@ -60,12 +83,80 @@ impl UserRepository {
         .bind(&req.email)
         .bind(&req.nickname)
         .bind(&req.avatar)
+        .bind(role.as_str())
         .fetch_one(pool)
         .await?;
 
         Ok(user)
     }
 
+    /// Update a user's profile, role and optionally password
+    pub async fn update(
+        pool: &PgPool,
+        id: i64,
+        req: &UpdateUserRequest,
+    ) -> Result<Option<User>, ApiError> {
+        let password_hash = req
+            .password
+            .as_deref()
+            .map(Self::hash_password)
+            .transpose()?;
+
+        let user = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users
+            SET
+                email = COALESCE($2, email),
+                nickname = COALESCE($3, nickname),
+                avatar = COALESCE($4, avatar),
+                role = COALESCE($5, role),
+                password_hash = COALESCE($6, password_hash),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, username, password_hash, email, nickname, avatar, role, totp_secret, totp_enabled, totp_last_step,
+                   created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(&req.email)
+        .bind(&req.nickname)
+        .bind(&req.avatar)
+        .bind(req.role.map(|role| role.as_str()))
+        .bind(&password_hash)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    /// Delete a user by ID; their blogs stay, with `author_id` cleared
+    pub async fn delete(pool: &PgPool, id: i64) -> Result<bool, ApiError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM users
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Count users with the admin role
+    pub async fn count_admins(pool: &PgPool) -> Result<i64, ApiError> {
+        let count = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM users WHERE role = 'admin'
+            "#,
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
     /// Verify password against stored hash
     pub fn verify_password(password: &str, password_hash: &str) -> Result<bool, ApiError> {
         let parsed_hash = PasswordHash::new(password_hash).map_err(|e| {
@@ -117,6 +208,112 @@ impl UserRepository {
         Ok(())
     }
 
+    /// Store a new TOTP secret awaiting confirmation
+    ///
+    /// Does nothing once two-factor authentication is enabled.
+    pub async fn set_pending_totp_secret(
+        pool: &PgPool,
+        user_id: i64,
+        secret: &str,
+    ) -> Result<bool, ApiError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE users
+            SET totp_secret = $2, totp_last_step = NULL, updated_at = NOW()
+            WHERE id = $1 AND totp_enabled = FALSE
+            "#,
+        )
+        .bind(user_id)
+        .bind(secret)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Stored TOTP secrets of all users that have one, as (id, secret)
+    pub async fn find_totp_secrets(pool: &PgPool) -> Result<Vec<(i64, String)>, ApiError> {
+        let users = sqlx::query_as::<_, (i64, String)>(
+            "SELECT id, totp_secret FROM users WHERE totp_secret IS NOT NULL",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(users)
+    }
+
+    /// Replace a TOTP secret with its encrypted form, unless it changed meanwhile
+    pub async fn replace_totp_secret(
+        pool: &PgPool,
+        user_id: i64,
+        current: &str,
+        sealed: &str,
+    ) -> Result<(), ApiError> {
+        sqlx::query("UPDATE users SET totp_secret = $3 WHERE id = $1 AND totp_secret = $2")
+            .bind(user_id)
+            .bind(current)
+            .bind(sealed)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Enable two-factor authentication with the pending secret
+    pub async fn enable_totp(pool: &PgPool, user_id: i64, step: i64) -> Result<(), ApiError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET totp_enabled = TRUE, totp_last_step = $2, updated_at = NOW()
+            WHERE id = $1 AND totp_secret IS NOT NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(step)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Turn off two-factor authentication and forget the secret
+    pub async fn disable_totp(pool: &PgPool, user_id: i64) -> Result<(), ApiError> {
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET totp_enabled = FALSE, totp_secret = NULL, totp_last_step = NULL,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record a used TOTP step; returns false if it or a later step was already used
+    pub async fn advance_totp_step(
+        pool: &PgPool,
+        user_id: i64,
+        step: i64,
+    ) -> Result<bool, ApiError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE users
+            SET totp_last_step = $2
+            WHERE id = $1 AND (totp_last_step IS NULL OR totp_last_step < $2)
+            "#,
+        )
+        .bind(user_id)
+        .bind(step)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     /// Check if username already exists
     pub async fn username_exists(pool: &PgPool, username: &str) -> Result<bool, ApiError> {
         let result = sqlx::query_scalar::<_, bool>(
@@ -16,6 +16,11 @@ pub fn admin_routes() -> Router<AppState> {
         .route("/ai/status", get(ai_status))
         .route("/ai/polish", post(polish_text))
         .route("/ai/summarize", post(summarize_text))
+}
+
+/// Batch AI routes that rewrite stored blogs (require the editor role)
+pub fn editor_routes() -> Router<AppState> {
+    Router::new()
         .route("/ai/batch-preview", post(batch_preview))
         .route("/ai/batch-confirm", post(batch_confirm))
         .route("/ai/batch-summarize-all", post(batch_summarize_all))
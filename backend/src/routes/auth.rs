@@ -12,7 +12,9 @@ use crate::AppState;
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/auth/login", post(auth::login))
+        .route("/auth/login/2fa", post(auth::login_two_factor))
         .route("/auth/refresh", post(auth::refresh_token))
+        .route("/auth/logout", post(auth::logout))
         .route("/auth/check-admin", get(auth::check_admin_exists))
         .route("/auth/setup", post(auth::setup_admin))
 }
@@ -24,8 +24,12 @@ pub fn admin_routes() -> Router<AppState> {
         .route("/blogs/{id}", get(blog::admin_get_blog))
         .route("/blogs/{id}", put(blog::update_blog))
         .route("/blogs/{id}", delete(blog::delete_blog))
-        .route(
-            "/blogs/convert-markdown",
-            post(blog::batch_convert_markdown),
-        )
+}
+
+/// Create blog maintenance routes (requires the editor role)
+pub fn editor_routes() -> Router<AppState> {
+    Router::new().route(
+        "/blogs/convert-markdown",
+        post(blog::batch_convert_markdown),
+    )
 }
@@ -0,0 +1,32 @@
+//! Comment routes
+
+use axum::{
+    routing::{delete, get, post, put},
+    Router,
+};
+
+use crate::handlers::comment;
+use crate::AppState;
+
+/// Create public comment routes
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/blogs/{id}/comments", get(comment::list_blog_comments))
+        .route("/blogs/{id}/comments", post(comment::create_blog_comment))
+        .route(
+            "/documents/{id}/comments",
+            get(comment::list_document_comments),
+        )
+        .route(
+            "/documents/{id}/comments",
+            post(comment::create_document_comment),
+        )
+}
+
+/// Create admin comment routes (requires authentication)
+pub fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/comments", get(comment::list_comments))
+        .route("/comments/{id}/status", put(comment::update_comment_status))
+        .route("/comments/{id}", delete(comment::delete_comment))
+}
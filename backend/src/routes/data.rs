@@ -11,6 +11,9 @@ use crate::AppState;
 pub fn admin_routes() -> Router<AppState> {
     Router::new()
         .route("/data/export", get(data::export_data))
+        .route("/data/export/ndjson", get(data::export_ndjson))
+        .route("/data/export/site", get(data::export_site))
         .route("/data/import", post(data::import_data))
+        .route("/data/import/{source}", post(data::import_posts))
         .route("/data/import-sql", post(data::import_sql))
 }
@@ -0,0 +1,16 @@
+//! Feed routes
+
+use axum::{routing::get, Router};
+
+use crate::handlers::feed;
+use crate::AppState;
+
+/// Create public feed routes
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/feed.xml", get(feed::rss_feed))
+        .route("/atom.xml", get(feed::atom_feed))
+        .route("/feed.json", get(feed::json_feed))
+        .route("/categories/{id}/feed.xml", get(feed::category_rss_feed))
+        .route("/tags/{id}/feed.xml", get(feed::tag_rss_feed))
+}
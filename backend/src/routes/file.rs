@@ -5,13 +5,26 @@ use axum::{
     Router,
 };
 
-use crate::handlers::file::{delete_file, list_files, upload_file};
+use crate::handlers::file::{
+    abort_direct_upload, complete_direct_upload, delete_file, list_files, presign_upload,
+    purge_orphaned_files, scan_orphaned_files, upload_file,
+};
 use crate::AppState;
 
 /// Admin routes for file management (requires authentication)
 pub fn admin_routes() -> Router<AppState> {
     Router::new()
         .route("/files/upload", post(upload_file))
+        .route("/files/presign", post(presign_upload))
+        .route("/files/presign/complete", post(complete_direct_upload))
+        .route("/files/presign/abort", post(abort_direct_upload))
         .route("/files", get(list_files))
+}
+
+/// Routes for removing files (requires the editor role)
+pub fn editor_routes() -> Router<AppState> {
+    Router::new()
         .route("/files/{id}", delete(delete_file))
+        .route("/files/orphans", get(scan_orphaned_files))
+        .route("/files/orphans/purge", post(purge_orphaned_files))
 }
@@ -10,7 +10,9 @@ use crate::AppState;
 
 /// Create public friend link routes
 pub fn routes() -> Router<AppState> {
-    Router::new().route("/friend-links", get(friend_link::list_friend_links))
+    Router::new()
+        .route("/friend-links", get(friend_link::list_friend_links))
+        .route("/friend-links/apply", post(friend_link::apply_friend_link))
 }
 
 /// Create admin friend link routes (requires authentication)
@@ -20,6 +22,14 @@ pub fn admin_routes() -> Router<AppState> {
         .route("/friend-links", post(friend_link::create_friend_link))
         .route("/friend-links/{id}", get(friend_link::get_friend_link))
         .route("/friend-links/{id}", put(friend_link::update_friend_link))
+        .route(
+            "/friend-links/{id}/status",
+            put(friend_link::update_friend_link_status),
+        )
+        .route(
+            "/friend-links/{id}/check-backlink",
+            post(friend_link::check_friend_link_backlink),
+        )
         .route(
             "/friend-links/{id}",
             delete(friend_link::delete_friend_link),
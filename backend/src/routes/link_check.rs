@@ -0,0 +1,16 @@
+//! Dead-link check routes
+
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::handlers::link_check;
+use crate::AppState;
+
+/// Create admin link check routes (requires authentication)
+pub fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/link-checks/broken", get(link_check::list_broken_links))
+        .route("/link-checks/run", post(link_check::run_link_check))
+}
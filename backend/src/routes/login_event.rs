@@ -0,0 +1,11 @@
+//! Login event routes
+
+use axum::{routing::get, Router};
+
+use crate::handlers::login_event;
+use crate::AppState;
+
+/// Create admin login event routes (requires the admin role)
+pub fn admin_routes() -> Router<AppState> {
+    Router::new().route("/login-events", get(login_event::list_login_events))
+}
@@ -1,5 +1,5 @@
 use axum::{
-    routing::{get, post, put},
+    routing::{delete, get, post, put},
     Router,
 };
 
@@ -11,4 +11,8 @@ pub fn admin_routes() -> Router<AppState> {
         .route("/mcp/settings", get(mcp::get_mcp_settings))
         .route("/mcp/settings", put(mcp::update_mcp_settings))
         .route("/mcp/token/rotate", post(mcp::rotate_mcp_token))
+        .route("/mcp/tokens", get(mcp::list_mcp_tokens))
+        .route("/mcp/tokens", post(mcp::create_mcp_token))
+        .route("/mcp/tokens/{id}", delete(mcp::delete_mcp_token))
+        .route("/mcp/audit-logs", get(mcp::list_mcp_audit_logs))
 }
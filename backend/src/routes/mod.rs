@@ -1,8 +1,15 @@
 //! Route definitions for the API
 
-use axum::{middleware, Router};
+use axum::{
+    http::{header, HeaderValue},
+    middleware,
+    response::Response,
+    Router,
+};
+use tower_http::services::ServeDir;
 
-use crate::middleware::auth::auth_middleware;
+use crate::middleware::auth::{auth_middleware, require_role};
+use crate::models::user::UserRole;
 use crate::AppState;
 
 pub mod ai;
@@ -10,19 +17,31 @@ pub mod archive;
 pub mod auth;
 pub mod blog;
 pub mod category;
+pub mod comment;
 pub mod data;
 pub mod directory;
 pub mod document;
+pub mod feed;
 pub mod file;
 pub mod friend_link;
 pub mod health;
+pub mod link_check;
+pub mod login_event;
 pub mod mcp;
 pub mod project;
+pub mod revision;
 pub mod search;
+pub mod session;
 pub mod site_config;
+pub mod sitemap;
 pub mod stats;
 pub mod tag;
 pub mod text;
+pub mod two_factor;
+pub mod user;
+
+/// Prefix under which the public API routes are nested
+pub const API_PREFIX: &str = "/api/v1";
 
 /// Create all API routes with state
 pub fn create_routes() -> Router<AppState> {
@@ -39,10 +58,16 @@ pub fn create_routes() -> Router<AppState> {
         .merge(tag::routes())
         // Archive routes (public)
         .merge(archive::routes())
+        // Feed routes (public)
+        .merge(feed::routes())
+        // Sitemap and robots.txt routes (public)
+        .merge(sitemap::routes())
         // Directory routes (public)
         .merge(directory::routes())
         // Document routes (public)
         .merge(document::routes())
+        // Comment routes (public)
+        .merge(comment::routes())
         // Friend link routes (public)
         .merge(friend_link::routes())
         // Project routes (public)
@@ -57,10 +82,29 @@ pub fn create_routes() -> Router<AppState> {
 
 /// Create admin routes with authentication middleware
 /// This function takes AppState to properly set up the middleware
+///
+/// Routes are grouped by the minimum role they require; blog ownership
+/// for authors is checked in the blog handlers.
 pub fn create_admin_routes(state: AppState) -> Router<AppState> {
-    Router::new()
+    // Routes open to every role (authors write their own blogs)
+    let author_routes = Router::new()
         // Blog admin routes
         .merge(blog::admin_routes())
+        // Blog revision history routes
+        .merge(revision::admin_routes())
+        // File upload routes
+        .merge(file::admin_routes())
+        // Stats admin routes
+        .merge(stats::admin_routes())
+        // AI writing assistant routes
+        .merge(ai::admin_routes())
+        // Own two-factor authentication routes
+        .merge(two_factor::admin_routes());
+
+    // Routes that publish or manage shared content
+    let editor_routes = Router::new()
+        // Blog maintenance routes
+        .merge(blog::editor_routes())
         // Category admin routes
         .merge(category::admin_routes())
         // Tag admin routes
@@ -69,24 +113,68 @@ pub fn create_admin_routes(state: AppState) -> Router<AppState> {
         .merge(directory::admin_routes())
         // Document admin routes
         .merge(document::admin_routes())
-        // File admin routes
-        .merge(file::admin_routes())
+        // Document revision history routes
+        .merge(revision::editor_routes())
+        // Comment moderation routes
+        .merge(comment::admin_routes())
+        // File deletion routes
+        .merge(file::editor_routes())
         // Friend link admin routes
         .merge(friend_link::admin_routes())
+        // Dead-link check routes
+        .merge(link_check::admin_routes())
         // Project admin routes
         .merge(project::admin_routes())
         // Text admin routes
         .merge(text::admin_routes())
-        // Stats admin routes
-        .merge(stats::admin_routes())
+        // Batch AI routes
+        .merge(ai::editor_routes());
+
+    // Routes that manage the site itself
+    let admin_routes = Router::new()
+        // User management routes
+        .merge(user::admin_routes())
+        // Login session routes
+        .merge(session::admin_routes())
+        // Failed login routes
+        .merge(login_event::admin_routes())
         // Data import/export routes
         .merge(data::admin_routes())
         // Site config admin routes
         .merge(site_config::admin_routes())
-        // AI admin routes
-        .merge(ai::admin_routes())
         // MCP admin routes
-        .merge(mcp::admin_routes())
-        // Other admin routes will be added here as features are implemented
+        .merge(mcp::admin_routes());
+
+    Router::new()
+        .merge(author_routes)
+        .merge(with_role(editor_routes, UserRole::Editor))
+        .merge(with_role(admin_routes, UserRole::Admin))
         .layer(middleware::from_fn_with_state(state, auth_middleware))
 }
+
+/// Restrict a group of admin routes to users with at least `role`
+fn with_role(routes: Router<AppState>, role: UserRole) -> Router<AppState> {
+    routes.route_layer(middleware::from_fn_with_state(role, require_role))
+}
+
+/// Serve the directory of the local storage backend
+///
+/// Responses are sandboxed so uploaded HTML or SVG can't run scripts on the API origin.
+pub fn create_local_files_routes(dir: &str) -> Router<AppState> {
+    Router::new()
+        .fallback_service(ServeDir::new(dir))
+        .layer(middleware::map_response(sandbox_local_file))
+}
+
+async fn sandbox_local_file(mut response: Response) -> Response {
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_SECURITY_POLICY,
+        HeaderValue::from_static("sandbox"),
+    );
+    headers.insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+    response
+}
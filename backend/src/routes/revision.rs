@@ -0,0 +1,48 @@
+//! Revision history routes
+
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::handlers::revision;
+use crate::AppState;
+
+/// Create admin blog revision routes (requires authentication)
+pub fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/blogs/{id}/revisions", get(revision::list_blog_revisions))
+        .route(
+            "/blogs/{id}/revisions/diff",
+            get(revision::diff_blog_revisions),
+        )
+        .route(
+            "/blogs/{id}/revisions/{revision_id}",
+            get(revision::get_blog_revision),
+        )
+        .route(
+            "/blogs/{id}/revisions/{revision_id}/restore",
+            post(revision::restore_blog_revision),
+        )
+}
+
+/// Create document revision routes (requires the editor role)
+pub fn editor_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/documents/{id}/revisions",
+            get(revision::list_document_revisions),
+        )
+        .route(
+            "/documents/{id}/revisions/diff",
+            get(revision::diff_document_revisions),
+        )
+        .route(
+            "/documents/{id}/revisions/{revision_id}",
+            get(revision::get_document_revision),
+        )
+        .route(
+            "/documents/{id}/revisions/{revision_id}/restore",
+            post(revision::restore_document_revision),
+        )
+}
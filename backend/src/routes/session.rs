@@ -0,0 +1,16 @@
+//! Login session routes
+
+use axum::{
+    routing::{delete, get},
+    Router,
+};
+
+use crate::handlers::session;
+use crate::AppState;
+
+/// Create admin session routes (requires the admin role)
+pub fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/sessions", get(session::list_sessions))
+        .route("/sessions/{session_id}", delete(session::revoke_session))
+}
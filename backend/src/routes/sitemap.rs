@@ -0,0 +1,20 @@
+//! Sitemap routes
+
+use axum::{routing::get, Router};
+
+use crate::handlers::sitemap;
+use crate::AppState;
+
+/// Path of the sitemap index
+pub const SITEMAP_INDEX_PATH: &str = "/sitemap.xml";
+
+/// Path of a sub-sitemap page; `page` is the page number with an `.xml` suffix
+pub const SITEMAP_PAGE_PATH: &str = "/sitemaps/{kind}/{page}";
+
+/// Create public sitemap and robots.txt routes
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(SITEMAP_INDEX_PATH, get(sitemap::get_sitemap_index))
+        .route(SITEMAP_PAGE_PATH, get(sitemap::get_sitemap_page))
+        .route("/robots.txt", get(sitemap::get_robots))
+}
@@ -0,0 +1,22 @@
+//! Two-factor authentication routes
+
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::handlers::two_factor;
+use crate::AppState;
+
+/// Create two-factor routes for the signed-in user (any role)
+pub fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/auth/2fa", get(two_factor::get_status))
+        .route("/auth/2fa/setup", post(two_factor::setup))
+        .route("/auth/2fa/confirm", post(two_factor::confirm))
+        .route("/auth/2fa/disable", post(two_factor::disable))
+        .route(
+            "/auth/2fa/recovery-codes",
+            post(two_factor::regenerate_recovery_codes),
+        )
+}
@@ -0,0 +1,19 @@
+//! User management routes
+
+use axum::{
+    routing::{delete, get, post, put},
+    Router,
+};
+
+use crate::handlers::user;
+use crate::AppState;
+
+/// Create admin user routes (requires the admin role)
+pub fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/users", get(user::list_users))
+        .route("/users", post(user::create_user))
+        .route("/users/{id}", get(user::get_user))
+        .route("/users/{id}", put(user::update_user))
+        .route("/users/{id}", delete(user::delete_user))
+}
@@ -20,8 +20,52 @@ pub struct Claims {
     pub iat: i64,
     /// Token type: "access" or "refresh"
     pub token_type: String,
+    /// Login session the token belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+    /// Unique ID of a refresh token, rotated on every refresh
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
 }
 
+/// Claims of a token that unlocks one protected text
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TextUnlockClaims {
+    /// Text ID
+    pub sub: i64,
+    /// Expiration time (Unix timestamp)
+    pub exp: i64,
+    /// Issued at time (Unix timestamp)
+    pub iat: i64,
+    /// Always "text_unlock"
+    pub token_type: String,
+    /// Fingerprint of the view password the token was issued for
+    pub pwd: String,
+}
+
+/// Lifetime of a text unlock token in seconds (30 minutes)
+pub const TEXT_UNLOCK_TOKEN_EXPIRE_SECONDS: i64 = 30 * 60;
+
+const TEXT_UNLOCK_TOKEN_TYPE: &str = "text_unlock";
+
+/// Claims of a challenge token proving the password step of a login succeeded
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TwoFactorClaims {
+    /// Subject (user_id)
+    pub sub: i64,
+    /// Expiration time (Unix timestamp)
+    pub exp: i64,
+    /// Issued at time (Unix timestamp)
+    pub iat: i64,
+    /// Always "2fa_challenge"
+    pub token_type: String,
+}
+
+/// Lifetime of a two-factor challenge token in seconds (5 minutes)
+pub const TWO_FACTOR_TOKEN_EXPIRE_SECONDS: i64 = 5 * 60;
+
+const TWO_FACTOR_TOKEN_TYPE: &str = "2fa_challenge";
+
 /// Token type enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TokenType {
@@ -58,18 +102,29 @@ impl AuthService {
         }
     }
 
-    /// Generate access token for a user
-    pub fn generate_access_token(&self, user: &User) -> Result<String, ApiError> {
-        self.generate_token(user, TokenType::Access)
+    /// Generate access token for a user's session
+    pub fn generate_access_token(&self, user: &User, session_id: &str) -> Result<String, ApiError> {
+        self.generate_token(user, TokenType::Access, session_id, None)
     }
 
-    /// Generate refresh token for a user
-    pub fn generate_refresh_token(&self, user: &User) -> Result<String, ApiError> {
-        self.generate_token(user, TokenType::Refresh)
+    /// Generate refresh token `jti` for a user's session
+    pub fn generate_refresh_token(
+        &self,
+        user: &User,
+        session_id: &str,
+        jti: &str,
+    ) -> Result<String, ApiError> {
+        self.generate_token(user, TokenType::Refresh, session_id, Some(jti))
     }
 
     /// Generate a token of specified type
-    fn generate_token(&self, user: &User, token_type: TokenType) -> Result<String, ApiError> {
+    fn generate_token(
+        &self,
+        user: &User,
+        token_type: TokenType,
+        session_id: &str,
+        jti: Option<&str>,
+    ) -> Result<String, ApiError> {
         let now = Utc::now();
         let exp = match token_type {
             TokenType::Access => now + Duration::hours(self.jwt_config.access_token_expire_hours),
@@ -82,6 +137,8 @@ impl AuthService {
             exp: exp.timestamp(),
             iat: now.timestamp(),
             token_type: token_type.as_str().to_string(),
+            sid: Some(session_id.to_string()),
+            jti: jti.map(ToOwned::to_owned),
         };
 
         encode(&Header::default(), &claims, &self.encoding_key).map_err(|e| {
@@ -134,10 +191,15 @@ impl AuthService {
         Ok(token_data.claims)
     }
 
-    /// Create login response with tokens
-    pub fn create_login_response(&self, user: User) -> Result<LoginResponse, ApiError> {
-        let access_token = self.generate_access_token(&user)?;
-        let refresh_token = self.generate_refresh_token(&user)?;
+    /// Create login response with tokens for a new session
+    pub fn create_login_response(
+        &self,
+        user: User,
+        session_id: &str,
+        jti: &str,
+    ) -> Result<LoginResponse, ApiError> {
+        let access_token = self.generate_access_token(&user, session_id)?;
+        let refresh_token = self.generate_refresh_token(&user, session_id, jti)?;
 
         Ok(LoginResponse {
             access_token,
@@ -148,19 +210,179 @@ impl AuthService {
         })
     }
 
-    /// Create refresh token response
-    pub fn create_refresh_response(&self, user: &User) -> Result<RefreshTokenResponse, ApiError> {
-        let access_token = self.generate_access_token(user)?;
+    /// Create refresh token response with the rotated refresh token `jti`
+    pub fn create_refresh_response(
+        &self,
+        user: &User,
+        session_id: &str,
+        jti: &str,
+    ) -> Result<RefreshTokenResponse, ApiError> {
+        let access_token = self.generate_access_token(user, session_id)?;
+        let refresh_token = self.generate_refresh_token(user, session_id, jti)?;
 
         Ok(RefreshTokenResponse {
             access_token,
+            refresh_token,
             token_type: "Bearer".to_string(),
             expires_in: self.jwt_config.access_token_expire_hours * 3600,
         })
     }
 
+    /// Generate a short-lived token proving the password of a text was verified
+    ///
+    /// `password_fingerprint` identifies the current view password, see `Text::password_fingerprint`.
+    pub fn generate_text_unlock_token(
+        &self,
+        text_id: i64,
+        password_fingerprint: &str,
+    ) -> Result<String, ApiError> {
+        let now = Utc::now();
+        let claims = TextUnlockClaims {
+            sub: text_id,
+            exp: (now + Duration::seconds(TEXT_UNLOCK_TOKEN_EXPIRE_SECONDS)).timestamp(),
+            iat: now.timestamp(),
+            token_type: TEXT_UNLOCK_TOKEN_TYPE.to_string(),
+            pwd: password_fingerprint.to_string(),
+        };
+
+        encode(&Header::default(), &claims, &self.encoding_key).map_err(|e| {
+            tracing::error!("Failed to generate text unlock token: {}", e);
+            ApiError::InternalError("Failed to generate token".to_string())
+        })
+    }
+
+    /// Check that a token unlocks the given text with its current view password
+    pub fn verify_text_unlock_token(
+        &self,
+        token: &str,
+        text_id: i64,
+        password_fingerprint: &str,
+    ) -> bool {
+        decode::<TextUnlockClaims>(token, &self.decoding_key, &Validation::default()).is_ok_and(
+            |data| {
+                data.claims.token_type == TEXT_UNLOCK_TOKEN_TYPE
+                    && data.claims.sub == text_id
+                    && data.claims.pwd == password_fingerprint
+            },
+        )
+    }
+
+    /// Generate a challenge token for a user who still has to pass the second factor
+    pub fn generate_two_factor_token(&self, user_id: i64) -> Result<String, ApiError> {
+        let now = Utc::now();
+        let claims = TwoFactorClaims {
+            sub: user_id,
+            exp: (now + Duration::seconds(TWO_FACTOR_TOKEN_EXPIRE_SECONDS)).timestamp(),
+            iat: now.timestamp(),
+            token_type: TWO_FACTOR_TOKEN_TYPE.to_string(),
+        };
+
+        encode(&Header::default(), &claims, &self.encoding_key).map_err(|e| {
+            tracing::error!("Failed to generate two-factor challenge token: {}", e);
+            ApiError::InternalError("Failed to generate token".to_string())
+        })
+    }
+
+    /// Verify a challenge token, returning the user ID it was issued to
+    pub fn verify_two_factor_token(&self, token: &str) -> Result<i64, ApiError> {
+        decode::<TwoFactorClaims>(token, &self.decoding_key, &Validation::default())
+            .ok()
+            .filter(|data| data.claims.token_type == TWO_FACTOR_TOKEN_TYPE)
+            .map(|data| data.claims.sub)
+            .ok_or_else(|| {
+                ApiError::Unauthorized(
+                    "Login challenge expired or invalid, please log in again".to_string(),
+                )
+            })
+    }
+
     /// Get access token expiration in seconds
     pub fn get_access_token_expire_seconds(&self) -> i64 {
         self.jwt_config.access_token_expire_hours * 3600
     }
+
+    /// How long a session lives without being refreshed
+    pub fn session_ttl(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.jwt_config.refresh_token_expire_days as u64 * 86400)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(secret: &str) -> AuthService {
+        AuthService::new(JwtConfig {
+            secret: secret.to_string(),
+            access_token_expire_hours: 2,
+            refresh_token_expire_days: 7,
+        })
+    }
+
+    #[test]
+    fn text_unlock_token_is_bound_to_text_password_and_secret() {
+        let auth = service("secret");
+        let token = auth.generate_text_unlock_token(7, "pwd-a").unwrap();
+
+        assert!(auth.verify_text_unlock_token(&token, 7, "pwd-a"));
+        assert!(!auth.verify_text_unlock_token(&token, 8, "pwd-a"));
+        assert!(!auth.verify_text_unlock_token(&token, 7, "pwd-b"));
+        assert!(!service("other").verify_text_unlock_token(&token, 7, "pwd-a"));
+        assert!(!auth.verify_text_unlock_token("garbage", 7, "pwd-a"));
+    }
+
+    fn user() -> User {
+        User {
+            id: 7,
+            username: "admin".to_string(),
+            password_hash: String::new(),
+            role: "admin".to_string(),
+            totp_secret: None,
+            totp_enabled: false,
+            totp_last_step: None,
+            email: None,
+            nickname: None,
+            avatar: None,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn access_token_does_not_unlock_texts() {
+        let auth = service("secret");
+        let token = auth.generate_access_token(&user(), "session").unwrap();
+
+        assert!(!auth.verify_text_unlock_token(&token, 7, ""));
+    }
+
+    #[test]
+    fn two_factor_token_is_not_an_access_token() {
+        let auth = service("secret");
+        let token = auth.generate_two_factor_token(7).unwrap();
+
+        assert_eq!(auth.verify_two_factor_token(&token).unwrap(), 7);
+        assert!(auth.verify_access_token(&token).is_err());
+        assert!(service("other").verify_two_factor_token(&token).is_err());
+
+        let access = auth.generate_access_token(&user(), "session").unwrap();
+        assert!(auth.verify_two_factor_token(&access).is_err());
+    }
+
+    #[test]
+    fn tokens_carry_session_and_refresh_id() {
+        let auth = service("secret");
+        let response = auth
+            .create_refresh_response(&user(), "session", "jti-1")
+            .unwrap();
+
+        let access = auth.verify_access_token(&response.access_token).unwrap();
+        assert_eq!(access.sid.as_deref(), Some("session"));
+        assert_eq!(access.jti, None);
+
+        let refresh = auth.verify_refresh_token(&response.refresh_token).unwrap();
+        assert_eq!(refresh.sid.as_deref(), Some("session"));
+        assert_eq!(refresh.jti.as_deref(), Some("jti-1"));
+        assert!(auth.verify_refresh_token(&response.access_token).is_err());
+    }
 }
@@ -1,12 +1,21 @@
 //! Blog service - Business logic for blog operations
 
 use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
 
 use crate::error::ApiError;
+use crate::middleware::auth::AuthUser;
+use crate::models::blog::Blog;
+use crate::models::user::UserRole;
 use crate::repositories::blog_repo::BlogRepository;
 use crate::services::cache_service::{cache_keys, cache_ttl, CacheService};
 use sqlx::PgPool;
 
+/// How often the scheduler checks for blogs whose `publish_at` has passed
+const PUBLISH_SCHEDULER_INTERVAL: Duration = Duration::from_secs(60);
+
 /// Blog service for business logic
 pub struct BlogService;
 
@@ -74,14 +83,175 @@ impl BlogService {
         // Invalidate archive cache
         cache.delete(&cache_keys::archive_list()).await?;
 
+        // Invalidate sitemaps
+        cache.delete_pattern("sitemap:*").await?;
+
         // Invalidate category blogs caches
         cache.delete_pattern("category:*:blogs:*").await?;
 
         // Invalidate tag blogs caches
         cache.delete_pattern("tag:*:blogs:*").await?;
 
+        // Invalidate rendered feeds
+        cache.delete_pattern("feed:*").await?;
+
         tracing::debug!("Invalidated blog caches for blog {}", blog_id);
 
         Ok(())
     }
+
+    /// Publish all blogs whose scheduled `publish_at` has passed
+    ///
+    /// Returns the number of blogs that were published.
+    pub async fn publish_due_blogs(
+        pool: &PgPool,
+        cache: &Arc<CacheService>,
+    ) -> Result<usize, ApiError> {
+        let published = BlogRepository::publish_due(pool).await?;
+
+        for (id, slug) in &published {
+            tracing::info!("Published scheduled blog (id: {})", id);
+            if let Err(e) = Self::invalidate_blog_cache(cache, *id, slug.as_deref()).await {
+                tracing::warn!("Failed to invalidate blog cache: {}", e);
+            }
+        }
+
+        Ok(published.len())
+    }
+
+    /// Check that a user may edit a blog
+    ///
+    /// Editors and admins may edit any blog, authors only the blogs they own.
+    pub fn ensure_can_edit(user: &AuthUser, blog: &Blog) -> Result<(), ApiError> {
+        if user.role.allows(UserRole::Editor) || blog.author_id == Some(user.user_id) {
+            Ok(())
+        } else {
+            Err(ApiError::Forbidden(
+                "Authors can only modify their own blogs".to_string(),
+            ))
+        }
+    }
+
+    /// Check that a user may delete a blog
+    ///
+    /// Authors may only delete their own blogs while they are unpublished.
+    pub fn ensure_can_delete(user: &AuthUser, blog: &Blog) -> Result<(), ApiError> {
+        Self::ensure_can_edit(user, blog)?;
+        if blog.is_published && !user.role.allows(UserRole::Editor) {
+            return Err(ApiError::Forbidden(
+                "Published blogs can only be deleted by an editor".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check that a user may apply a publish state change
+    ///
+    /// `current` is the blog's present publish state (false for new blogs).
+    /// Publishing, unpublishing and scheduling require the editor role.
+    pub fn ensure_can_publish(
+        user: &AuthUser,
+        current: bool,
+        is_published: Option<bool>,
+        publish_at: Option<DateTime<Utc>>,
+    ) -> Result<(), ApiError> {
+        let changes_state = is_published.is_some_and(|published| published != current);
+        if (changes_state || publish_at.is_some()) && !user.role.allows(UserRole::Editor) {
+            return Err(ApiError::Forbidden(
+                "Publishing blogs requires the editor role".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Run the scheduled publishing loop forever
+    ///
+    /// Intended to be spawned once as a background tokio task at startup.
+    pub async fn run_publish_scheduler(pool: PgPool, cache: Arc<CacheService>) {
+        let mut interval = tokio::time::interval(PUBLISH_SCHEDULER_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = Self::publish_due_blogs(&pool, &cache).await {
+                tracing::error!("Scheduled publishing failed: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(user_id: i64, role: UserRole) -> AuthUser {
+        AuthUser {
+            user_id,
+            username: format!("user{}", user_id),
+            role,
+            session_id: String::new(),
+        }
+    }
+
+    fn blog(author_id: Option<i64>, is_published: bool) -> Blog {
+        Blog {
+            id: 1,
+            title: "Title".to_string(),
+            slug: None,
+            author: None,
+            content: "content".to_string(),
+            html: None,
+            summary: None,
+            thumbnail: None,
+            category_id: None,
+            view_count: 0,
+            is_published,
+            created_at: None,
+            updated_at: None,
+            references: None,
+            publish_at: None,
+            author_id,
+        }
+    }
+
+    #[test]
+    fn authors_edit_only_their_own_blogs() {
+        let author = user(2, UserRole::Author);
+
+        assert!(BlogService::ensure_can_edit(&author, &blog(Some(2), true)).is_ok());
+        assert!(BlogService::ensure_can_edit(&author, &blog(Some(3), false)).is_err());
+        assert!(BlogService::ensure_can_edit(&author, &blog(None, false)).is_err());
+        assert!(
+            BlogService::ensure_can_edit(&user(4, UserRole::Editor), &blog(Some(3), true)).is_ok()
+        );
+    }
+
+    #[test]
+    fn authors_delete_only_unpublished_own_blogs() {
+        let author = user(2, UserRole::Author);
+
+        assert!(BlogService::ensure_can_delete(&author, &blog(Some(2), false)).is_ok());
+        assert!(BlogService::ensure_can_delete(&author, &blog(Some(2), true)).is_err());
+        assert!(
+            BlogService::ensure_can_delete(&user(4, UserRole::Admin), &blog(Some(2), true)).is_ok()
+        );
+    }
+
+    #[test]
+    fn only_editors_change_publish_state() {
+        let author = user(2, UserRole::Author);
+        let editor = user(3, UserRole::Editor);
+
+        assert!(BlogService::ensure_can_publish(&author, false, None, None).is_ok());
+        assert!(BlogService::ensure_can_publish(&author, false, Some(false), None).is_ok());
+        assert!(BlogService::ensure_can_publish(&author, true, Some(true), None).is_ok());
+        assert!(BlogService::ensure_can_publish(&author, false, Some(true), None).is_err());
+        assert!(BlogService::ensure_can_publish(&author, true, Some(false), None).is_err());
+        assert!(BlogService::ensure_can_publish(&author, false, None, Some(Utc::now())).is_err());
+        assert!(
+            BlogService::ensure_can_publish(&editor, false, Some(true), Some(Utc::now())).is_ok()
+        );
+    }
 }
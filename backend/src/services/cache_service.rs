@@ -43,6 +43,25 @@ impl CacheService {
         }
     }
 
+    /// Get a value from cache together with its stored form, for `compare_and_set`
+    pub async fn get_with_raw<T: DeserializeOwned>(
+        &self,
+        key: &str,
+    ) -> Result<Option<(T, String)>, ApiError> {
+        let mut conn = self.conn.clone();
+        let value: Option<String> = conn.get(key).await?;
+
+        match value {
+            Some(v) => {
+                let parsed = serde_json::from_str(&v).map_err(|e| {
+                    ApiError::CacheError(format!("Failed to deserialize cache value: {}", e))
+                })?;
+                Ok(Some((parsed, v)))
+            }
+            None => Ok(None),
+        }
+    }
+
     /// Set a value in cache with TTL
     pub async fn set<T: Serialize>(
         &self,
@@ -59,6 +78,32 @@ impl CacheService {
         Ok(())
     }
 
+    /// Replace a value only if it is still stored as `expected` (from `get_with_raw`)
+    ///
+    /// Returns whether the value was replaced. The check and the write happen
+    /// atomically in a Lua script.
+    pub async fn compare_and_set<T: Serialize>(
+        &self,
+        key: &str,
+        expected: &str,
+        value: &T,
+        ttl: Duration,
+    ) -> Result<bool, ApiError> {
+        let mut conn = self.conn.clone();
+        let serialized = serde_json::to_string(value)
+            .map_err(|e| ApiError::CacheError(format!("Failed to serialize cache value: {}", e)))?;
+
+        let replaced: i64 = redis::Script::new(COMPARE_AND_SET_SCRIPT)
+            .key(key)
+            .arg(expected)
+            .arg(serialized)
+            .arg(ttl.as_secs())
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok(replaced == 1)
+    }
+
     /// Delete a key from cache
     pub async fn delete(&self, key: &str) -> Result<(), ApiError> {
         let mut conn = self.conn.clone();
@@ -66,6 +111,13 @@ impl CacheService {
         Ok(())
     }
 
+    /// Find keys matching a pattern
+    pub async fn keys(&self, pattern: &str) -> Result<Vec<String>, ApiError> {
+        let mut conn = self.conn.clone();
+        let keys: Vec<String> = conn.keys(pattern).await?;
+        Ok(keys)
+    }
+
     /// Delete keys matching a pattern
     pub async fn delete_pattern(&self, pattern: &str) -> Result<(), ApiError> {
         let mut conn = self.conn.clone();
@@ -101,6 +153,86 @@ impl CacheService {
         Ok(())
     }
 
+    /// Increment a fixed-window counter, starting the window on the first hit
+    ///
+    /// Returns the number of hits in the current window.
+    pub async fn incr_window(&self, key: &str, window: Duration) -> Result<i64, ApiError> {
+        let count = self.incr(key).await?;
+        if count == 1 {
+            self.expire(key, window).await?;
+        }
+        Ok(count)
+    }
+
+    /// Record a hit in a sliding-window log and return the hits within the window
+    ///
+    /// Hits are kept in a sorted set scored by time in milliseconds; entries older
+    /// than `window` are dropped on every call.
+    pub async fn hit_sliding_window(
+        &self,
+        key: &str,
+        now_ms: i64,
+        window: Duration,
+    ) -> Result<i64, ApiError> {
+        let mut conn = self.conn.clone();
+        let window_ms = window.as_millis() as i64;
+        let member = format!("{}-{}", now_ms, uuid::Uuid::new_v4().simple());
+
+        let (count,): (i64,) = redis::pipe()
+            .atomic()
+            .zrembyscore(key, "-inf", now_ms - window_ms)
+            .ignore()
+            .zadd(key, member, now_ms)
+            .ignore()
+            .zcard(key)
+            .pexpire(key, window_ms)
+            .ignore()
+            .query_async(&mut conn)
+            .await?;
+        Ok(count)
+    }
+
+    /// Count the hits of a sliding-window log within the window, without adding one
+    pub async fn count_sliding_window(
+        &self,
+        key: &str,
+        now_ms: i64,
+        window: Duration,
+    ) -> Result<i64, ApiError> {
+        let mut conn = self.conn.clone();
+        let count: i64 = conn
+            .zcount(key, now_ms - window.as_millis() as i64, "+inf")
+            .await?;
+        Ok(count)
+    }
+
+    /// Take one token from a token bucket, refilling it for the time since the last take
+    ///
+    /// Returns whether a token was available and how many tokens are left.
+    /// The bucket is updated atomically by a Lua script.
+    pub async fn take_token(
+        &self,
+        key: &str,
+        capacity: f64,
+        refill_per_second: f64,
+        now_ms: i64,
+    ) -> Result<(bool, f64), ApiError> {
+        let mut conn = self.conn.clone();
+        // Expire the bucket once it would be full again anyway
+        let ttl_ms = ((capacity / refill_per_second) * 1000.0).ceil() as i64 + 1000;
+
+        let (allowed, tokens): (i64, String) = redis::Script::new(TOKEN_BUCKET_SCRIPT)
+            .key(key)
+            .arg(capacity)
+            .arg(refill_per_second / 1000.0)
+            .arg(now_ms)
+            .arg(ttl_ms)
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok((allowed == 1, tokens.parse().unwrap_or(0.0)))
+    }
+
     /// Ping Redis to check connection health
     pub async fn ping(&self) -> Result<(), ApiError> {
         let mut conn = self.conn.clone();
@@ -112,6 +244,34 @@ impl CacheService {
     }
 }
 
+/// Overwrite a string key only if it still holds the expected value
+const COMPARE_AND_SET_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    redis.call('SET', KEYS[1], ARGV[2], 'EX', ARGV[3])
+    return 1
+end
+return 0
+"#;
+
+/// Refill a token bucket stored as a hash and take one token if available
+const TOKEN_BUCKET_SCRIPT: &str = r#"
+local capacity = tonumber(ARGV[1])
+local rate_per_ms = tonumber(ARGV[2])
+local now = tonumber(ARGV[3])
+local bucket = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+local tokens = tonumber(bucket[1]) or capacity
+local ts = tonumber(bucket[2]) or now
+tokens = math.min(capacity, tokens + math.max(0, now - ts) * rate_per_ms)
+local allowed = 0
+if tokens >= 1 then
+    tokens = tokens - 1
+    allowed = 1
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'ts', tostring(now))
+redis.call('PEXPIRE', KEYS[1], ARGV[4])
+return {allowed, tostring(tokens)}
+"#;
+
 /// Cache key builders for consistent key naming
 pub mod cache_keys {
     /// Blog list cache key
@@ -154,6 +314,21 @@ pub mod cache_keys {
         "archive:list".to_string()
     }
 
+    /// Sitemap index cache key
+    pub fn sitemap_index() -> String {
+        "sitemap:index".to_string()
+    }
+
+    /// Sub-sitemap page cache key
+    pub fn sitemap_page(kind: &str, page: i64) -> String {
+        format!("sitemap:{}:{}", kind, page)
+    }
+
+    /// robots.txt cache key
+    pub fn robots_txt() -> String {
+        "sitemap:robots".to_string()
+    }
+
     /// Directory tree cache key
     pub fn directory_tree() -> String {
         "directory:tree".to_string()
@@ -174,9 +349,19 @@ pub mod cache_keys {
         "project:list".to_string()
     }
 
-    /// User session cache key
-    pub fn user_session(user_id: i64) -> String {
-        format!("user:session:{}", user_id)
+    /// Login session key
+    pub fn user_session(user_id: i64, session_id: &str) -> String {
+        format!("user:session:{}:{}", user_id, session_id)
+    }
+
+    /// Pattern matching all sessions of a user
+    pub fn user_sessions_pattern(user_id: i64) -> String {
+        format!("user:session:{}:*", user_id)
+    }
+
+    /// Pattern matching one session without knowing its user (or all sessions with "*")
+    pub fn session_pattern(session_id: &str) -> String {
+        format!("user:session:*:{}", session_id)
     }
 
     /// View count rate limit key
@@ -184,11 +369,66 @@ pub mod cache_keys {
         format!("view:rate:{}:{}", blog_id, ip)
     }
 
+    /// Comment submission rate limit key
+    pub fn comment_rate_limit(ip: &str) -> String {
+        format!("comment:rate:{}", ip)
+    }
+
+    /// Friend link application rate limit key
+    pub fn friend_link_apply_rate_limit(ip: &str) -> String {
+        format!("friend_link:apply:rate:{}", ip)
+    }
+
+    /// Failed text password attempts per IP
+    pub fn text_verify_ip_rate_limit(ip: &str) -> String {
+        format!("text:verify:rate:ip:{}", ip)
+    }
+
+    /// Failed text password attempts per MCP token
+    pub fn text_verify_mcp_token_rate_limit(token_id: i64) -> String {
+        format!("text:verify:rate:mcp:{}", token_id)
+    }
+
+    /// Failed text password attempts per text, across all requesters
+    pub fn text_verify_rate_limit(text_id: i64) -> String {
+        format!("text:verify:rate:text:{}", text_id)
+    }
+
+    /// Sliding-window log of failed logins from an IP
+    pub fn login_failures_ip(ip: &str) -> String {
+        format!("login:fail:ip:{}", ip)
+    }
+
+    /// Sliding-window log of failed logins for a username
+    pub fn login_failures_user(username: &str) -> String {
+        format!("login:fail:user:{}", username)
+    }
+
+    /// Backoff or lockout currently applied to a username
+    pub fn login_block(username: &str) -> String {
+        format!("login:block:{}", username)
+    }
+
+    /// Failed second-factor attempts per user
+    pub fn two_factor_rate_limit(user_id: i64) -> String {
+        format!("auth:2fa:rate:{}", user_id)
+    }
+
+    /// Token bucket of a client for one route group
+    pub fn rate_limit_bucket(group: &str, client: &str) -> String {
+        format!("ratelimit:{}:{}", group, client)
+    }
+
     /// Site config cache key
     pub fn site_config() -> String {
         "site:config".to_string()
     }
 
+    /// Rendered feed cache key (format: rss/atom/json, scope: all, category:{id}, tag:{id})
+    pub fn feed(format: &str, scope: &str) -> String {
+        format!("feed:{}:{}", format, scope)
+    }
+
     /// MCP runtime config cache key
     pub fn mcp_runtime_config() -> String {
         "mcp:runtime-config".to_string()
@@ -208,18 +448,33 @@ pub mod cache_ttl {
     /// Category/Tag list TTL: 1 hour
     pub const CATEGORY_TAG_LIST: Duration = Duration::from_secs(60 * 60);
 
+    /// Sitemap and robots.txt TTL: 1 hour
+    pub const SITEMAP: Duration = Duration::from_secs(60 * 60);
+
     /// Directory tree TTL: 1 hour
     pub const DIRECTORY_TREE: Duration = Duration::from_secs(60 * 60);
 
-    /// User session TTL: 24 hours
-    pub const USER_SESSION: Duration = Duration::from_secs(24 * 60 * 60);
-
     /// View rate limit TTL: 1 hour
     pub const VIEW_RATE_LIMIT: Duration = Duration::from_secs(60 * 60);
 
+    /// Comment submission rate limit window: 10 minutes
+    pub const COMMENT_RATE_LIMIT: Duration = Duration::from_secs(10 * 60);
+
+    /// Friend link application rate limit window: 1 hour
+    pub const FRIEND_LINK_APPLY_RATE_LIMIT: Duration = Duration::from_secs(60 * 60);
+
+    /// Text password attempt limit window: 15 minutes
+    pub const TEXT_VERIFY_RATE_LIMIT: Duration = Duration::from_secs(15 * 60);
+
+    /// Second-factor attempt limit window: 15 minutes
+    pub const TWO_FACTOR_RATE_LIMIT: Duration = Duration::from_secs(15 * 60);
+
     /// Site config TTL: 10 minutes
     pub const SITE_CONFIG: Duration = Duration::from_secs(10 * 60);
 
+    /// Feed TTL: 1 hour
+    pub const FEED: Duration = Duration::from_secs(60 * 60);
+
     /// MCP runtime config TTL: 30 seconds
     pub const MCP_RUNTIME_CONFIG: Duration = Duration::from_secs(30);
 }
@@ -0,0 +1,248 @@
+//! Comment service - Validation, spam resistance and threading for reader comments
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::comment::{
+    Comment, CommentNode, CommentStatus, CommentTarget, CreateCommentRequest, PublicComment,
+};
+use crate::repositories::blog_repo::BlogRepository;
+use crate::repositories::comment_repo::CommentRepository;
+use crate::repositories::document_repo::DocumentRepository;
+use crate::services::cache_service::{cache_keys, cache_ttl, CacheService};
+use sqlx::PgPool;
+
+/// Maximum comments one IP may submit per rate limit window
+const COMMENT_RATE_LIMIT: i64 = 5;
+
+/// Maximum comment length in characters
+const MAX_CONTENT_LENGTH: usize = 5000;
+
+/// Maximum author name length in characters
+const MAX_AUTHOR_NAME_LENGTH: usize = 100;
+
+/// Comment service for business logic
+pub struct CommentService;
+
+impl CommentService {
+    /// Submit a public comment; new comments wait in the moderation queue
+    ///
+    /// Returns `None` when the honeypot field was filled in. The caller answers
+    /// such requests exactly like a real submission so bots get no signal.
+    pub async fn submit(
+        pool: &PgPool,
+        cache: &Arc<CacheService>,
+        target: CommentTarget,
+        client_ip: &str,
+        user_agent: Option<&str>,
+        req: &CreateCommentRequest,
+    ) -> Result<Option<Comment>, ApiError> {
+        if req.website.as_deref().is_some_and(|v| !v.trim().is_empty()) {
+            tracing::info!("Dropped honeypot comment from IP {}", client_ip);
+            return Ok(None);
+        }
+
+        Self::validate(req)?;
+        Self::check_rate_limit(cache, client_ip).await?;
+        Self::ensure_target_visible(pool, target).await?;
+
+        if let Some(parent_id) = req.parent_id {
+            let parent = CommentRepository::find_by_id(pool, parent_id).await?;
+            let valid = parent.is_some_and(|p| {
+                p.target() == Some(target) && p.status == i16::from(CommentStatus::Approved)
+            });
+            if !valid {
+                return Err(ApiError::ValidationError(format!(
+                    "Parent comment {} not found",
+                    parent_id
+                )));
+            }
+        }
+
+        let user_agent = user_agent.map(|ua| ua.chars().take(500).collect::<String>());
+        let comment = CommentRepository::create(
+            pool,
+            target,
+            req,
+            CommentStatus::Pending,
+            client_ip,
+            user_agent.as_deref(),
+        )
+        .await?;
+
+        tracing::info!(
+            "New comment {} from {} awaiting moderation",
+            comment.id,
+            comment.author_name
+        );
+
+        Ok(Some(comment))
+    }
+
+    /// Get the approved comments of a blog or document as a reply tree
+    pub async fn list_thread(
+        pool: &PgPool,
+        target: CommentTarget,
+    ) -> Result<Vec<CommentNode>, ApiError> {
+        Self::ensure_target_visible(pool, target).await?;
+
+        let comments = CommentRepository::find_approved(pool, target).await?;
+        Ok(build_thread(
+            comments.into_iter().map(PublicComment::from).collect(),
+        ))
+    }
+
+    fn validate(req: &CreateCommentRequest) -> Result<(), ApiError> {
+        let name = req.author_name.trim();
+        if name.is_empty() {
+            return Err(ApiError::ValidationError("Name is required".to_string()));
+        }
+        if name.chars().count() > MAX_AUTHOR_NAME_LENGTH {
+            return Err(ApiError::ValidationError(format!(
+                "Name must be at most {} characters",
+                MAX_AUTHOR_NAME_LENGTH
+            )));
+        }
+
+        if req.content.trim().is_empty() {
+            return Err(ApiError::ValidationError(
+                "Comment content is required".to_string(),
+            ));
+        }
+        if req.content.chars().count() > MAX_CONTENT_LENGTH {
+            return Err(ApiError::ValidationError(format!(
+                "Comment must be at most {} characters",
+                MAX_CONTENT_LENGTH
+            )));
+        }
+
+        if let Some(email) = req.author_email.as_deref() {
+            if email.len() > 255 || !email.contains('@') {
+                return Err(ApiError::ValidationError(
+                    "Invalid email address".to_string(),
+                ));
+            }
+        }
+
+        if let Some(url) = req.author_url.as_deref() {
+            if url.len() > 500 || !(url.starts_with("http://") || url.starts_with("https://")) {
+                return Err(ApiError::ValidationError(
+                    "Website must be an http(s) URL".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Allow at most `COMMENT_RATE_LIMIT` submissions per IP per window
+    async fn check_rate_limit(cache: &Arc<CacheService>, client_ip: &str) -> Result<(), ApiError> {
+        let rate_key = cache_keys::comment_rate_limit(client_ip);
+
+        let count = cache
+            .incr_window(&rate_key, cache_ttl::COMMENT_RATE_LIMIT)
+            .await?;
+
+        if count > COMMENT_RATE_LIMIT {
+            tracing::debug!("Comment rate limited for IP {}", client_ip);
+            return Err(ApiError::TooManyRequests(
+                "Too many comments, please try again later".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Comments are only readable / writable on publicly visible content
+    async fn ensure_target_visible(pool: &PgPool, target: CommentTarget) -> Result<(), ApiError> {
+        match target {
+            CommentTarget::Blog(id) => {
+                let visible = BlogRepository::find_detail_by_id(pool, id)
+                    .await?
+                    .is_some_and(|blog| blog.is_publicly_visible());
+                if !visible {
+                    return Err(ApiError::NotFound(format!("Blog with id {} not found", id)));
+                }
+            }
+            CommentTarget::Document(id) => {
+                DocumentRepository::find_by_id(pool, id)
+                    .await?
+                    .ok_or_else(|| {
+                        ApiError::NotFound(format!("Document with id {} not found", id))
+                    })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Nest comments under their parents, keeping the input order at every level
+///
+/// Replies whose parent isn't in the list (still pending, or marked spam) are hidden.
+fn build_thread(comments: Vec<PublicComment>) -> Vec<CommentNode> {
+    let mut children: HashMap<Option<i64>, Vec<PublicComment>> = HashMap::new();
+    for comment in comments {
+        children.entry(comment.parent_id).or_default().push(comment);
+    }
+
+    fn attach(
+        parent_id: Option<i64>,
+        children: &mut HashMap<Option<i64>, Vec<PublicComment>>,
+    ) -> Vec<CommentNode> {
+        children
+            .remove(&parent_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|comment| {
+                let replies = attach(Some(comment.id), children);
+                CommentNode { comment, replies }
+            })
+            .collect()
+    }
+
+    attach(None, &mut children)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(id: i64, parent_id: Option<i64>) -> PublicComment {
+        PublicComment {
+            id,
+            parent_id,
+            author_name: format!("user{}", id),
+            author_url: None,
+            content: format!("comment {}", id),
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn build_thread_nests_replies_in_order() {
+        let thread = build_thread(vec![
+            comment(1, None),
+            comment(2, Some(1)),
+            comment(3, None),
+            comment(4, Some(2)),
+            comment(5, Some(1)),
+        ]);
+
+        assert_eq!(thread.len(), 2);
+        assert_eq!(thread[0].comment.id, 1);
+        assert_eq!(thread[1].comment.id, 3);
+        let replies: Vec<i64> = thread[0].replies.iter().map(|r| r.comment.id).collect();
+        assert_eq!(replies, vec![2, 5]);
+        assert_eq!(thread[0].replies[0].replies[0].comment.id, 4);
+    }
+
+    #[test]
+    fn build_thread_hides_replies_to_unapproved_parents() {
+        let thread = build_thread(vec![comment(1, None), comment(7, Some(6))]);
+
+        assert_eq!(thread.len(), 1);
+        assert!(thread[0].replies.is_empty());
+    }
+}
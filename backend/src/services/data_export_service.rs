@@ -0,0 +1,660 @@
+//! Data export service - Versioned NDJSON backups and transactional imports
+//!
+//! An export is read from a single snapshot and streamed row by row, so it
+//! never sits in memory as a whole. Imports accept that format as well as
+//! the legacy JSON backup; either way every row is written in one
+//! transaction, and the first failing row rolls the whole import back.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use axum::body::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt, TryStreamExt};
+use serde_json::Value as JsonValue;
+use sqlx::PgPool;
+use tokio::sync::mpsc;
+
+use crate::error::ApiError;
+use crate::models::data_export::{
+    ConflictPolicy, DataImportReport, ExportHeader, ExportRecord, RowData, TableChanges,
+    EXPORT_FORMAT, EXPORT_SCHEMA_VERSION,
+};
+use crate::repositories::data_export_repo::{
+    export_table, DataExportRepository, DataImportSession, RowChange, EXPORT_TABLES,
+};
+use crate::repositories::site_config_repo::SiteConfigRepo;
+use crate::repositories::user_repo::UserRepository;
+use crate::utils::crypto;
+use crate::utils::migration::latest_migration;
+use argon2::password_hash::PasswordHash;
+
+/// Lines buffered between the database reader and a slow client
+const EXPORT_BUFFER_LINES: usize = 64;
+
+/// Data export service for business logic
+pub struct DataExportService;
+
+impl DataExportService {
+    /// Stream an NDJSON export; with `since`, tables that track modification
+    /// time only contain rows changed from then on
+    ///
+    /// The snapshot is opened before returning so connection errors still
+    /// become an error response; failures after that abort the stream.
+    pub async fn export(
+        pool: &PgPool,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<impl Stream<Item = Result<String, ApiError>> + Send + 'static, ApiError> {
+        let mut tx = DataExportRepository::snapshot(pool).await?;
+        let (sender, receiver) = mpsc::channel(EXPORT_BUFFER_LINES);
+
+        tokio::spawn(async move {
+            let header = ExportRecord::Header(ExportHeader {
+                format: EXPORT_FORMAT.to_string(),
+                schema_version: EXPORT_SCHEMA_VERSION,
+                migration: latest_migration().to_string(),
+                exported_at: Utc::now(),
+                since,
+                tables: EXPORT_TABLES
+                    .iter()
+                    .map(|table| table.name.to_string())
+                    .collect(),
+            });
+            if sender.send(record_line(&header)).await.is_err() {
+                return;
+            }
+
+            let mut counts = BTreeMap::new();
+            for table in EXPORT_TABLES {
+                let mut rows = DataExportRepository::rows(&mut tx, table, since);
+                let mut count = 0u64;
+                loop {
+                    let line = match rows.try_next().await {
+                        Ok(Some(row)) => row_line(table.name, &row),
+                        Ok(None) => break,
+                        Err(e) => Err(e.into()),
+                    };
+                    let failed = line.is_err();
+                    if sender.send(line).await.is_err() || failed {
+                        return;
+                    }
+                    count += 1;
+                }
+                counts.insert(table.name.to_string(), count);
+            }
+
+            let total: u64 = counts.values().sum();
+            let _ = sender
+                .send(record_line(&ExportRecord::End { rows: counts }))
+                .await;
+            tracing::info!("Exported {} rows (since {:?})", total, since);
+        });
+
+        Ok(futures::stream::unfold(
+            receiver,
+            |mut receiver| async move { receiver.recv().await.map(|line| (line, receiver)) },
+        ))
+    }
+
+    /// Import an NDJSON export read from `body`
+    pub async fn import_ndjson<S, E>(
+        pool: &PgPool,
+        master_key: Option<&str>,
+        mut body: S,
+        policy: ConflictPolicy,
+    ) -> Result<DataImportReport, ApiError>
+    where
+        S: Stream<Item = Result<Bytes, E>> + Unpin,
+        E: std::fmt::Display,
+    {
+        let mut importer = Importer::begin(pool, master_key, policy).await?;
+        let mut lines = LineSplitter::default();
+        let mut reader = ExportReader::default();
+
+        while let Some(chunk) = body.next().await {
+            let chunk =
+                chunk.map_err(|e| ApiError::BadRequest(format!("Failed to read import: {}", e)))?;
+            for line in lines.push(&chunk) {
+                if let Some((table, row)) = reader.read(&line)? {
+                    importer.row(&table, row).await?;
+                }
+            }
+        }
+        if let Some(line) = lines.finish() {
+            if let Some((table, row)) = reader.read(&line)? {
+                importer.row(&table, row).await?;
+            }
+        }
+
+        let header = reader.finish()?;
+        importer
+            .finish(Some(header.schema_version), header.since)
+            .await
+    }
+
+    /// Import rows converted from a legacy JSON backup
+    pub async fn import_rows(
+        pool: &PgPool,
+        master_key: Option<&str>,
+        rows: Vec<(&'static str, RowData)>,
+        policy: ConflictPolicy,
+    ) -> Result<DataImportReport, ApiError> {
+        let mut importer = Importer::begin(pool, master_key, policy).await?;
+
+        let (directories, others): (Vec<_>, Vec<_>) = rows
+            .into_iter()
+            .partition(|(table, _)| *table == "directories");
+        let directories = parents_first(directories.into_iter().map(|(_, row)| row).collect());
+
+        // Rows are written in export order, so references resolve whatever
+        // order the backup listed its tables in
+        let mut by_table: HashMap<&str, Vec<RowData>> = HashMap::new();
+        for (table, row) in others {
+            by_table.entry(table).or_default().push(row);
+        }
+        by_table.insert("directories", directories);
+        for table in EXPORT_TABLES {
+            for row in by_table.remove(table.name).unwrap_or_default() {
+                importer.row(table.name, row).await?;
+            }
+        }
+
+        importer.finish(None, None).await
+    }
+}
+
+/// Serialize a record as one NDJSON line
+fn record_line(record: &ExportRecord) -> Result<String, ApiError> {
+    serde_json::to_string(record)
+        .map(|line| line + "\n")
+        .map_err(|e| ApiError::InternalError(format!("Failed to serialize export: {}", e)))
+}
+
+/// Row line from the JSON produced by the database, without re-serializing
+/// every row; texts and settings are parsed so legacy plaintext passwords and
+/// unencrypted secrets never leave it
+fn row_line(table: &str, row: &str) -> Result<String, ApiError> {
+    if table != "texts" && table != "site_config" {
+        return Ok(format!(
+            "{{\"type\":\"row\",\"table\":\"{}\",\"data\":{}}}\n",
+            table, row
+        ));
+    }
+
+    let mut data: RowData = serde_json::from_str(row)
+        .map_err(|e| ApiError::InternalError(format!("Failed to read {} row: {}", table, e)))?;
+    if table == "texts" {
+        hash_view_password(&mut data)?;
+    } else {
+        omit_plaintext_secret(&mut data);
+    }
+    record_line(&ExportRecord::Row {
+        table: table.to_string(),
+        data,
+    })
+}
+
+/// Replace a plaintext `view_password` with its hash
+fn hash_view_password(row: &mut RowData) -> Result<(), ApiError> {
+    if let Some(JsonValue::String(password)) = row.get("view_password") {
+        if PasswordHash::new(password).is_err() {
+            let hash = UserRepository::hash_password(password)?;
+            row.insert("view_password".to_string(), JsonValue::String(hash));
+        }
+    }
+    Ok(())
+}
+
+/// Drop the value of a sensitive setting unless it is sealed
+fn omit_plaintext_secret(row: &mut RowData) {
+    let is_sensitive = row.get("is_sensitive").and_then(JsonValue::as_bool) == Some(true);
+    let is_plaintext = row
+        .get("config_value")
+        .and_then(JsonValue::as_str)
+        .is_some_and(|value| !value.is_empty() && !crypto::is_sealed_value(value));
+    if is_sensitive && is_plaintext {
+        row.remove("config_value");
+    }
+}
+
+/// Import state shared by both formats
+struct Importer {
+    session: DataImportSession,
+    master_key: Option<String>,
+    policy: ConflictPolicy,
+    tables: Vec<TableChanges>,
+    /// Unknown tables whose rows were skipped
+    unknown: BTreeSet<String>,
+    /// Users of this database, loaded with the first blog
+    user_ids: Option<HashSet<i64>>,
+    /// Sensitive settings of this database, loaded with the first setting
+    sensitive_keys: Option<HashSet<String>>,
+    cleared_authors: u64,
+}
+
+impl Importer {
+    async fn begin(
+        pool: &PgPool,
+        master_key: Option<&str>,
+        policy: ConflictPolicy,
+    ) -> Result<Self, ApiError> {
+        Ok(Self {
+            session: DataImportSession::begin(pool).await?,
+            master_key: master_key.map(ToOwned::to_owned),
+            policy,
+            tables: Vec::new(),
+            unknown: BTreeSet::new(),
+            user_ids: None,
+            sensitive_keys: None,
+            cleared_authors: 0,
+        })
+    }
+
+    async fn row(&mut self, table: &str, mut row: RowData) -> Result<(), ApiError> {
+        let Some(spec) = export_table(table) else {
+            self.unknown.insert(table.to_string());
+            return Ok(());
+        };
+
+        match spec.name {
+            "blogs" => self.clear_missing_author(&mut row).await?,
+            "site_config" => self.seal_secret(&mut row).await?,
+            "texts" => {
+                hash_view_password(&mut row)?;
+                if let Some(JsonValue::String(kdf)) = row.get("content_kdf") {
+                    crypto::validate_kdf(kdf)?;
+                }
+            }
+            _ => {}
+        }
+
+        let change = self.session.write(spec, &row, self.policy).await?;
+        let index = match self.tables.iter().position(|t| t.table == spec.name) {
+            Some(index) => index,
+            None => {
+                self.tables.push(TableChanges {
+                    table: spec.name.to_string(),
+                    ..Default::default()
+                });
+                self.tables.len() - 1
+            }
+        };
+        let changes = &mut self.tables[index];
+        match change {
+            RowChange::Inserted => changes.inserted += 1,
+            RowChange::Updated => changes.updated += 1,
+            RowChange::Skipped => changes.skipped += 1,
+        }
+        Ok(())
+    }
+
+    /// Blog authors are users of the exporting instance; unknown ones are cleared
+    async fn clear_missing_author(&mut self, row: &mut RowData) -> Result<(), ApiError> {
+        let Some(author_id) = row.get("author_id").and_then(JsonValue::as_i64) else {
+            return Ok(());
+        };
+        if self.user_ids.is_none() {
+            self.user_ids = Some(self.session.user_ids().await?);
+        }
+        if !self
+            .user_ids
+            .as_ref()
+            .is_some_and(|ids| ids.contains(&author_id))
+        {
+            row.insert("author_id".to_string(), JsonValue::Null);
+            self.cleared_authors += 1;
+        }
+        Ok(())
+    }
+
+    /// Sensitive settings are stored sealed, whether the export or this
+    /// database marks them sensitive
+    async fn seal_secret(&mut self, row: &mut RowData) -> Result<(), ApiError> {
+        let Some(value) = row.get("config_value").and_then(JsonValue::as_str) else {
+            return Ok(());
+        };
+        if crypto::is_sealed_value(value) {
+            return Ok(());
+        }
+        let key = row
+            .get("config_key")
+            .and_then(JsonValue::as_str)
+            .unwrap_or_default();
+
+        if self.sensitive_keys.is_none() {
+            self.sensitive_keys = Some(self.session.sensitive_config_keys().await?);
+        }
+        let is_sensitive = row.get("is_sensitive").and_then(JsonValue::as_bool) == Some(true)
+            || self
+                .sensitive_keys
+                .as_ref()
+                .is_some_and(|keys| keys.contains(key));
+        if is_sensitive {
+            let sealed = SiteConfigRepo::seal_secret(self.master_key.as_deref(), key, value)?;
+            row.insert("config_value".to_string(), JsonValue::String(sealed));
+        }
+        Ok(())
+    }
+
+    async fn finish(
+        self,
+        schema_version: Option<u32>,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<DataImportReport, ApiError> {
+        self.session.commit().await?;
+
+        let mut warnings: Vec<String> = self
+            .unknown
+            .iter()
+            .map(|table| format!("Skipped rows of unknown table {}", table))
+            .collect();
+        if self.cleared_authors > 0 {
+            warnings.push(format!(
+                "{} blog(s) had an author that doesn't exist here and were imported without one",
+                self.cleared_authors
+            ));
+        }
+
+        let report = DataImportReport {
+            schema_version,
+            policy: self.policy,
+            since,
+            tables: self.tables,
+            warnings,
+        };
+        tracing::info!("Imported data: {:?}", report);
+        Ok(report)
+    }
+}
+
+/// Splits a byte stream into lines
+#[derive(Default)]
+struct LineSplitter {
+    pending: Vec<u8>,
+}
+
+impl LineSplitter {
+    /// Complete lines ending in `chunk`
+    fn push(&mut self, chunk: &[u8]) -> Vec<Vec<u8>> {
+        self.pending.extend_from_slice(chunk);
+        let Some(last) = self.pending.iter().rposition(|byte| *byte == b'\n') else {
+            return Vec::new();
+        };
+        let rest = self.pending.split_off(last + 1);
+        let complete = std::mem::replace(&mut self.pending, rest);
+        complete
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(<[u8]>::to_vec)
+            .collect()
+    }
+
+    /// Last line when the stream doesn't end with a newline
+    fn finish(self) -> Option<Vec<u8>> {
+        Some(self.pending).filter(|line| !line.is_empty())
+    }
+}
+
+/// Checks the records of an export as they are read
+#[derive(Default)]
+struct ExportReader {
+    header: Option<ExportHeader>,
+    rows: BTreeMap<String, u64>,
+    ended: bool,
+    line: usize,
+}
+
+impl ExportReader {
+    /// Read one line, returning the row it holds
+    fn read(&mut self, line: &[u8]) -> Result<Option<(String, RowData)>, ApiError> {
+        self.line += 1;
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if line.iter().all(u8::is_ascii_whitespace) {
+            return Ok(None);
+        }
+        if self.ended {
+            return Err(self.error("data after the end record"));
+        }
+
+        let record: ExportRecord = serde_json::from_slice(line)
+            .map_err(|e| self.error(&format!("invalid record: {}", e)))?;
+        match (record, &self.header) {
+            (ExportRecord::Header(header), None) => {
+                if header.format != EXPORT_FORMAT {
+                    return Err(self.error(&format!("unknown format '{}'", header.format)));
+                }
+                if header.schema_version > EXPORT_SCHEMA_VERSION {
+                    return Err(ApiError::BadRequest(format!(
+                        "Export schema version {} is newer than the supported version {}",
+                        header.schema_version, EXPORT_SCHEMA_VERSION
+                    )));
+                }
+                self.header = Some(header);
+                Ok(None)
+            }
+            (_, None) => Err(self.error("the export must start with a header")),
+            (ExportRecord::Header(_), Some(_)) => Err(self.error("repeated header")),
+            (ExportRecord::Row { table, data }, Some(_)) => {
+                *self.rows.entry(table.clone()).or_default() += 1;
+                Ok(Some((table, data)))
+            }
+            (ExportRecord::End { mut rows }, Some(_)) => {
+                rows.retain(|_, count| *count > 0);
+                if rows != self.rows {
+                    return Err(self.error("row counts don't match the end record"));
+                }
+                self.ended = true;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Header of a complete export
+    fn finish(self) -> Result<ExportHeader, ApiError> {
+        match self.header {
+            Some(header) if self.ended => Ok(header),
+            Some(_) => Err(ApiError::BadRequest(
+                "Export is incomplete: no end record".to_string(),
+            )),
+            None => Err(ApiError::BadRequest("Export is empty".to_string())),
+        }
+    }
+
+    fn error(&self, message: &str) -> ApiError {
+        ApiError::BadRequest(format!("Invalid export at line {}: {}", self.line, message))
+    }
+}
+
+/// Order directory rows so parents come before their children; rows in a
+/// parent cycle or under a missing parent keep their order at the end
+fn parents_first(rows: Vec<RowData>) -> Vec<RowData> {
+    let id_of = |row: &RowData, key: &str| row.get(key).and_then(JsonValue::as_i64);
+    let ids: HashSet<i64> = rows.iter().filter_map(|row| id_of(row, "id")).collect();
+
+    let mut placed: HashSet<i64> = HashSet::new();
+    let mut ordered = Vec::with_capacity(rows.len());
+    let mut remaining = rows;
+    loop {
+        let (ready, waiting): (Vec<_>, Vec<_>) = remaining.into_iter().partition(|row| {
+            id_of(row, "parent_id")
+                .is_none_or(|parent| placed.contains(&parent) || !ids.contains(&parent))
+        });
+        if ready.is_empty() {
+            ordered.extend(waiting);
+            return ordered;
+        }
+        placed.extend(ready.iter().filter_map(|row| id_of(row, "id")));
+        ordered.extend(ready);
+        remaining = waiting;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(records: &[JsonValue]) -> Vec<Vec<u8>> {
+        records
+            .iter()
+            .map(|record| record.to_string().into_bytes())
+            .collect()
+    }
+
+    fn header() -> JsonValue {
+        serde_json::json!({
+            "type": "header", "format": EXPORT_FORMAT, "schema_version": 1,
+            "migration": "001_init", "exported_at": "2026-01-01T00:00:00Z",
+            "since": null, "tables": ["tags"]
+        })
+    }
+
+    #[test]
+    fn lines_are_split_across_chunks() {
+        let mut splitter = LineSplitter::default();
+        assert!(splitter.push(b"{\"a\":").is_empty());
+        assert_eq!(
+            splitter.push(b"1}\n\n{\"b\":2}\n{\"c\""),
+            vec![b"{\"a\":1}".to_vec(), b"{\"b\":2}".to_vec()]
+        );
+        assert_eq!(splitter.push(b":3}"), Vec::<Vec<u8>>::new());
+        assert_eq!(splitter.finish(), Some(b"{\"c\":3}".to_vec()));
+    }
+
+    #[test]
+    fn row_lines_are_export_records() {
+        let line = row_line("tags", r#"{"id":1,"name":"Rust"}"#).unwrap();
+        assert!(line.ends_with('\n'));
+
+        match serde_json::from_str::<ExportRecord>(&line).unwrap() {
+            ExportRecord::Row { table, data } => {
+                assert_eq!(table, "tags");
+                assert_eq!(data.get("name"), Some(&JsonValue::from("Rust")));
+            }
+            other => panic!("unexpected record {:?}", other),
+        }
+    }
+
+    #[test]
+    fn plaintext_view_passwords_are_hashed_on_export() {
+        let line = row_line("texts", r#"{"id":1,"view_password":"secret"}"#).unwrap();
+        let ExportRecord::Row { data, .. } = serde_json::from_str(&line).unwrap() else {
+            panic!("expected a row");
+        };
+        let hash = data
+            .get("view_password")
+            .and_then(JsonValue::as_str)
+            .unwrap();
+        assert!(PasswordHash::new(hash).is_ok());
+    }
+
+    #[test]
+    fn only_sealed_secrets_are_exported() {
+        let data = |line: String| match serde_json::from_str(&line).unwrap() {
+            ExportRecord::Row { data, .. } => data,
+            other => panic!("unexpected record {:?}", other),
+        };
+
+        let plaintext = data(
+            row_line(
+                "site_config",
+                r#"{"config_key":"ai_api_key","config_value":"sk-secret","is_sensitive":true}"#,
+            )
+            .unwrap(),
+        );
+        assert!(!plaintext.contains_key("config_value"));
+
+        let sealed = crypto::seal_value("test-master-key", "sk-secret").unwrap();
+        let row = serde_json::json!({
+            "config_key": "ai_api_key", "config_value": sealed, "is_sensitive": true
+        });
+        let exported = data(row_line("site_config", &row.to_string()).unwrap());
+        assert_eq!(exported.get("config_value"), Some(&JsonValue::from(sealed)));
+
+        let public = data(
+            row_line(
+                "site_config",
+                r#"{"config_key":"site_name","config_value":"Blog","is_sensitive":false}"#,
+            )
+            .unwrap(),
+        );
+        assert_eq!(public.get("config_value"), Some(&JsonValue::from("Blog")));
+    }
+
+    #[test]
+    fn complete_exports_are_read() {
+        let mut reader = ExportReader::default();
+        let records = lines(&[
+            header(),
+            serde_json::json!({ "type": "row", "table": "tags", "data": { "id": 1, "name": "Rust" } }),
+            serde_json::json!({ "type": "end", "rows": { "tags": 1, "blogs": 0 } }),
+        ]);
+
+        assert!(reader.read(&records[0]).unwrap().is_none());
+        let (table, data) = reader.read(&records[1]).unwrap().unwrap();
+        assert_eq!(table, "tags");
+        assert_eq!(data.get("id"), Some(&JsonValue::from(1)));
+        assert!(reader.read(&records[2]).unwrap().is_none());
+        assert!(reader.read(b"\r").unwrap().is_none());
+        assert_eq!(reader.finish().unwrap().schema_version, 1);
+    }
+
+    #[test]
+    fn truncated_or_mismatched_exports_are_rejected() {
+        let row = serde_json::json!({ "type": "row", "table": "tags", "data": { "id": 1 } });
+
+        let mut reader = ExportReader::default();
+        assert!(reader.read(&row.to_string().into_bytes()).is_err());
+
+        let mut reader = ExportReader::default();
+        for line in lines(&[header(), row.clone()]) {
+            reader.read(&line).unwrap();
+        }
+        assert!(reader.finish().is_err());
+
+        let mut reader = ExportReader::default();
+        let records = lines(&[
+            header(),
+            row,
+            serde_json::json!({ "type": "end", "rows": { "tags": 2 } }),
+        ]);
+        reader.read(&records[0]).unwrap();
+        reader.read(&records[1]).unwrap();
+        assert!(reader.read(&records[2]).is_err());
+    }
+
+    #[test]
+    fn newer_schema_versions_are_rejected() {
+        let mut newer = header();
+        newer["schema_version"] = JsonValue::from(EXPORT_SCHEMA_VERSION + 1);
+        let mut reader = ExportReader::default();
+        assert!(reader.read(newer.to_string().as_bytes()).is_err());
+
+        let mut other = header();
+        other["format"] = JsonValue::from("something-else");
+        let mut reader = ExportReader::default();
+        assert!(reader.read(other.to_string().as_bytes()).is_err());
+    }
+
+    #[test]
+    fn directories_are_ordered_parents_first() {
+        let directory = |id: i64, parent: Option<i64>| {
+            serde_json::json!({ "id": id, "parent_id": parent })
+                .as_object()
+                .cloned()
+                .unwrap()
+        };
+        let rows = vec![
+            directory(3, Some(2)),
+            directory(2, Some(1)),
+            directory(4, Some(99)),
+            directory(1, None),
+            directory(5, Some(6)),
+            directory(6, Some(5)),
+        ];
+
+        let ids: Vec<i64> = parents_first(rows)
+            .iter()
+            .filter_map(|row| row.get("id").and_then(JsonValue::as_i64))
+            .collect();
+        assert_eq!(ids, vec![4, 1, 2, 3, 5, 6]);
+    }
+}
@@ -0,0 +1,294 @@
+//! Direct upload service - Presigned uploads straight to S3
+//!
+//! Large files such as videos and archives don't go through the API: the
+//! client gets presigned URLs, uploads to the bucket itself and then asks the
+//! API to record the file. Files above `MULTIPART_THRESHOLD` use a multipart
+//! upload so each request stays small. Direct uploads skip image processing
+//! and content deduplication, since the API never sees the bytes.
+
+use std::time::Duration;
+
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::models::file::{
+    AbortUploadRequest, CompleteUploadRequest, CreateFileRequest, FileResponse,
+    PresignUploadRequest, PresignedPart, PresignedUpload,
+};
+use crate::repositories::file_repo::FileRepository;
+use crate::repositories::site_config_repo::SiteConfigRepo;
+use crate::services::s3_service::S3Service;
+use crate::services::storage::{
+    new_object_key, Storage, StorageBackend, StorageService, UPLOADS_PREFIX,
+};
+
+/// How long presigned URLs stay valid
+const PRESIGN_EXPIRY: Duration = Duration::from_secs(60 * 60);
+
+/// Files larger than this are uploaded in parts
+const MULTIPART_THRESHOLD: i64 = 64 * 1024 * 1024;
+
+/// Smallest part size handed out; S3 requires at least 5 MiB
+const MIN_PART_SIZE: i64 = 16 * 1024 * 1024;
+
+/// Most parts S3 accepts for one upload
+const MAX_PARTS: i64 = 10_000;
+
+const DEFAULT_MAX_SIZE_MB: i64 = 4096;
+
+/// Direct upload service for business logic
+pub struct DirectUploadService;
+
+impl DirectUploadService {
+    /// Issue presigned URLs for uploading a file of the given size
+    pub async fn presign(
+        pool: &PgPool,
+        config: &Config,
+        req: &PresignUploadRequest,
+    ) -> Result<PresignedUpload, ApiError> {
+        if req.filename.trim().is_empty() {
+            return Err(ApiError::BadRequest("Filename is required".to_string()));
+        }
+        let max_size = Self::max_size(pool).await?;
+        if req.size <= 0 || req.size > max_size {
+            return Err(ApiError::BadRequest(format!(
+                "File size must be between 1 byte and {} MB",
+                max_size / (1024 * 1024)
+            )));
+        }
+
+        let s3 = StorageService::s3(pool, config).await?;
+        let object_key = new_object_key(req.filename.trim());
+        let content_type = req
+            .content_type
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .unwrap_or("application/octet-stream")
+            .to_string();
+        let expires_at = Utc::now() + PRESIGN_EXPIRY;
+
+        let mut upload = PresignedUpload {
+            object_key: object_key.clone(),
+            content_type: content_type.clone(),
+            url: None,
+            upload_id: None,
+            part_size: None,
+            parts: Vec::new(),
+            expires_at,
+        };
+
+        match plan_parts(req.size) {
+            None => {
+                upload.url = Some(
+                    s3.presign_put(&object_key, &content_type, PRESIGN_EXPIRY)
+                        .await?,
+                );
+            }
+            Some((part_size, part_count)) => {
+                let upload_id = s3
+                    .create_multipart_upload(&object_key, &content_type)
+                    .await?;
+                for part_number in 1..=part_count {
+                    let url = s3
+                        .presign_upload_part(&object_key, &upload_id, part_number, PRESIGN_EXPIRY)
+                        .await?;
+                    upload.parts.push(PresignedPart { part_number, url });
+                }
+                upload.upload_id = Some(upload_id);
+                upload.part_size = Some(part_size);
+            }
+        }
+
+        tracing::info!(
+            "Direct upload presigned: {} ({} bytes, {} parts)",
+            object_key,
+            req.size,
+            upload.parts.len().max(1)
+        );
+
+        Ok(upload)
+    }
+
+    /// Verify an uploaded object and record it as a file
+    pub async fn complete(
+        pool: &PgPool,
+        config: &Config,
+        req: &CompleteUploadRequest,
+    ) -> Result<FileResponse, ApiError> {
+        validate_object_key(&req.object_key)?;
+        let s3 = StorageService::s3(pool, config).await?;
+
+        if let Some(upload_id) = &req.upload_id {
+            if req.parts.is_empty() {
+                return Err(ApiError::BadRequest(
+                    "Parts are required to complete a multipart upload".to_string(),
+                ));
+            }
+            let mut parts: Vec<(i32, String)> = req
+                .parts
+                .iter()
+                .map(|part| (part.part_number, part.etag.clone()))
+                .collect();
+            parts.sort_by_key(|(part_number, _)| *part_number);
+            s3.complete_multipart_upload(&req.object_key, upload_id, &parts)
+                .await?;
+        }
+
+        let object = s3.head_object(&req.object_key).await?.ok_or_else(|| {
+            ApiError::BadRequest(format!("Uploaded object {} not found", req.object_key))
+        })?;
+
+        let max_size = Self::max_size(pool).await?;
+        if object.size > max_size {
+            Self::discard(&s3, &req.object_key).await;
+            return Err(ApiError::BadRequest(format!(
+                "File exceeds the {} MB direct upload limit",
+                max_size / (1024 * 1024)
+            )));
+        }
+        if FileRepository::exists_by_object_key(pool, &req.object_key).await? {
+            return Err(ApiError::BadRequest(format!(
+                "Upload {} was already completed",
+                req.object_key
+            )));
+        }
+
+        let content_type = object
+            .content_type
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+        let filename = req
+            .object_key
+            .trim_start_matches(UPLOADS_PREFIX)
+            .to_string();
+
+        let create_req = CreateFileRequest {
+            filename: filename.clone(),
+            original_filename: Some(
+                req.original_filename
+                    .clone()
+                    .filter(|name| !name.trim().is_empty())
+                    .unwrap_or(filename),
+            ),
+            file_type: content_type.split('/').next().map(|s| s.to_string()),
+            file_size: Some(object.size),
+            url: s3.object_url(&req.object_key),
+            thumbnail_url: None,
+            width: None,
+            height: None,
+            bucket_name: s3.bucket_name().map(ToOwned::to_owned),
+            object_key: Some(req.object_key.clone()),
+            storage_backend: StorageBackend::S3.as_str().to_string(),
+            content_hash: None,
+        };
+        let file = FileRepository::create(pool, &create_req)
+            .await?
+            .ok_or_else(|| ApiError::InternalError("Failed to record upload".to_string()))?;
+
+        tracing::info!(
+            "Direct upload completed: {} (id: {}, size: {} bytes)",
+            req.object_key,
+            file.id,
+            object.size
+        );
+
+        Ok(FileResponse::from(file))
+    }
+
+    /// Abandon a multipart upload, freeing the parts stored so far
+    pub async fn abort(
+        pool: &PgPool,
+        config: &Config,
+        req: &AbortUploadRequest,
+    ) -> Result<(), ApiError> {
+        validate_object_key(&req.object_key)?;
+        let s3 = StorageService::s3(pool, config).await?;
+        s3.abort_multipart_upload(&req.object_key, &req.upload_id)
+            .await
+    }
+
+    async fn max_size(pool: &PgPool) -> Result<i64, ApiError> {
+        let max_size_mb = SiteConfigRepo::get_value(pool, "direct_upload_max_size_mb")
+            .await?
+            .and_then(|value| value.trim().parse::<i64>().ok())
+            .filter(|mb| *mb > 0)
+            .unwrap_or(DEFAULT_MAX_SIZE_MB);
+
+        Ok(max_size_mb * 1024 * 1024)
+    }
+
+    async fn discard(s3: &S3Service, object_key: &str) {
+        if let Err(e) = s3.delete_file(object_key).await {
+            tracing::warn!("Failed to delete rejected upload {}: {}", object_key, e);
+        }
+    }
+}
+
+/// Part size and count for a multipart upload, `None` for a single PUT
+fn plan_parts(size: i64) -> Option<(i64, i32)> {
+    if size <= MULTIPART_THRESHOLD {
+        return None;
+    }
+
+    let part_size = MIN_PART_SIZE.max((size + MAX_PARTS - 1) / MAX_PARTS);
+    let part_count = (size + part_size - 1) / part_size;
+
+    Some((part_size, part_count as i32))
+}
+
+/// Only keys issued by `presign` may be completed: `uploads/<uuid>[.<ext>]`
+fn validate_object_key(object_key: &str) -> Result<(), ApiError> {
+    let is_issued = object_key
+        .strip_prefix(UPLOADS_PREFIX)
+        .filter(|name| !name.contains('/'))
+        .map(|name| name.split_once('.').map_or(name, |(stem, _)| stem))
+        .is_some_and(|stem| Uuid::parse_str(stem).is_ok());
+
+    if is_issued {
+        Ok(())
+    } else {
+        Err(ApiError::BadRequest(format!(
+            "Invalid upload object key: {}",
+            object_key
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: i64 = 1024 * 1024;
+
+    #[test]
+    fn small_files_use_a_single_put() {
+        assert_eq!(plan_parts(1), None);
+        assert_eq!(plan_parts(MULTIPART_THRESHOLD), None);
+    }
+
+    #[test]
+    fn large_files_are_split_into_parts() {
+        assert_eq!(plan_parts(100 * MIB), Some((16 * MIB, 7)));
+        assert_eq!(plan_parts(160 * MIB), Some((16 * MIB, 10)));
+
+        // Huge files grow the part size to stay within the part limit
+        let (part_size, part_count) = plan_parts(500 * 1024 * MIB).unwrap();
+        assert!(part_size > MIN_PART_SIZE);
+        assert!(part_count as i64 <= MAX_PARTS);
+        assert!(part_size * part_count as i64 >= 500 * 1024 * MIB);
+    }
+
+    #[test]
+    fn only_issued_keys_can_be_completed() {
+        let key = new_object_key("video.mp4");
+        assert!(validate_object_key(&key).is_ok());
+        assert!(validate_object_key(&new_object_key("README")).is_ok());
+
+        assert!(validate_object_key("uploads/not-a-uuid.mp4").is_err());
+        assert!(validate_object_key("other/3f2a1c1e-7c1b-4a1e-9a5e-2b1f0f0e6d1a.mp4").is_err());
+        assert!(validate_object_key(&format!("{}/thumbnail.jpg", key)).is_err());
+    }
+}
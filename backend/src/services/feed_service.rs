@@ -0,0 +1,198 @@
+//! Feed service - Builds RSS/Atom/JSON feeds for published blogs
+
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::blog::BlogListItem;
+use crate::repositories::blog_repo::BlogRepository;
+use crate::repositories::category_repo::CategoryRepository;
+use crate::repositories::site_config_repo::SiteConfigRepo;
+use crate::repositories::tag_repo::TagRepository;
+use crate::services::cache_service::{cache_keys, cache_ttl, CacheService};
+use crate::utils::feed::{render_atom, render_json_feed, render_rss, FeedChannel, FeedItem};
+use sqlx::PgPool;
+
+/// Number of most recent blogs included in a feed
+const FEED_SIZE: i64 = 20;
+
+/// Output format of a feed
+#[derive(Debug, Clone, Copy)]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    Json,
+}
+
+impl FeedFormat {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "rss",
+            FeedFormat::Atom => "atom",
+            FeedFormat::Json => "json",
+        }
+    }
+
+    /// Content-Type header value for this format
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "application/rss+xml; charset=utf-8",
+            FeedFormat::Atom => "application/atom+xml; charset=utf-8",
+            FeedFormat::Json => "application/feed+json; charset=utf-8",
+        }
+    }
+}
+
+/// Which blogs a feed covers
+#[derive(Debug, Clone, Copy)]
+pub enum FeedScope {
+    All,
+    Category(i64),
+    Tag(i64),
+}
+
+impl FeedScope {
+    fn cache_scope(&self) -> String {
+        match self {
+            FeedScope::All => "all".to_string(),
+            FeedScope::Category(id) => format!("category:{}", id),
+            FeedScope::Tag(id) => format!("tag:{}", id),
+        }
+    }
+}
+
+/// Feed service for business logic
+pub struct FeedService;
+
+impl FeedService {
+    /// Get a rendered feed document, served from cache when possible
+    ///
+    /// The cache is cleared by `BlogService::invalidate_blog_cache`, so feeds
+    /// refresh whenever a blog is created, updated, published or unpublished.
+    pub async fn get_feed(
+        pool: &PgPool,
+        cache: &Arc<CacheService>,
+        format: FeedFormat,
+        scope: FeedScope,
+    ) -> Result<String, ApiError> {
+        let cache_key = cache_keys::feed(format.as_str(), &scope.cache_scope());
+
+        if let Ok(Some(cached)) = cache.get::<String>(&cache_key).await {
+            tracing::debug!("Cache hit for feed {}", cache_key);
+            return Ok(cached);
+        }
+
+        let (channel, items) = Self::build_feed(pool, scope).await?;
+        let body = match format {
+            FeedFormat::Rss => render_rss(&channel, &items),
+            FeedFormat::Atom => render_atom(&channel, &items),
+            FeedFormat::Json => render_json_feed(&channel, &items).to_string(),
+        };
+
+        if let Err(e) = cache.set(&cache_key, &body, cache_ttl::FEED).await {
+            tracing::warn!("Failed to cache feed: {}", e);
+        }
+
+        Ok(body)
+    }
+
+    /// Load channel metadata and the latest published blogs for a scope
+    async fn build_feed(
+        pool: &PgPool,
+        scope: FeedScope,
+    ) -> Result<(FeedChannel, Vec<FeedItem>), ApiError> {
+        let config = SiteConfigRepo::get_public_config(pool).await?;
+        let site_url = config.site_url.trim_end_matches('/').to_string();
+
+        let (title, link, blogs) = match scope {
+            FeedScope::All => {
+                let (blogs, _) = BlogRepository::find_all_published(pool, 1, FEED_SIZE).await?;
+                (config.site_title.clone(), site_url.clone(), blogs)
+            }
+            FeedScope::Category(id) => {
+                let category =
+                    CategoryRepository::find_by_id(pool, id)
+                        .await?
+                        .ok_or_else(|| {
+                            ApiError::NotFound(format!("Category with id {} not found", id))
+                        })?;
+                let (blogs, _) = BlogRepository::find_by_category(pool, id, 1, FEED_SIZE).await?;
+                (
+                    format!("{} - {}", config.site_title, category.name),
+                    format!("{}/category/{}", site_url, id),
+                    blogs,
+                )
+            }
+            FeedScope::Tag(id) => {
+                let tag = TagRepository::find_by_id(pool, id)
+                    .await?
+                    .ok_or_else(|| ApiError::NotFound(format!("Tag with id {} not found", id)))?;
+                let (blogs, _) = BlogRepository::find_with_filters(
+                    pool,
+                    1,
+                    FEED_SIZE,
+                    None,
+                    Some(id),
+                    Some(true),
+                )
+                .await?;
+                (
+                    format!("{} - {}", config.site_title, tag.name),
+                    format!("{}/tag/{}", site_url, id),
+                    blogs,
+                )
+            }
+        };
+
+        let mut items = Vec::with_capacity(blogs.len());
+        for blog in blogs {
+            items.push(Self::build_item(pool, &site_url, blog).await?);
+        }
+
+        let updated = items
+            .iter()
+            .filter_map(|item| item.updated.or(item.published))
+            .max();
+
+        let channel = FeedChannel {
+            title,
+            description: config.site_description,
+            author: config.owner_name,
+            link,
+            updated,
+        };
+
+        Ok((channel, items))
+    }
+
+    /// Build a feed item, loading the summary and rendered HTML body
+    async fn build_item(
+        pool: &PgPool,
+        site_url: &str,
+        blog: BlogListItem,
+    ) -> Result<FeedItem, ApiError> {
+        let full = BlogRepository::find_by_id(pool, blog.id).await?;
+        let (summary, html, updated) = match full {
+            Some(b) => (b.summary, b.html, b.updated_at),
+            None => (None, None, None),
+        };
+
+        let path = blog
+            .slug
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| blog.id.to_string());
+
+        let mut categories: Vec<String> = blog.category.map(|c| c.name).into_iter().collect();
+        categories.extend(blog.tags.into_iter().map(|t| t.name));
+
+        Ok(FeedItem {
+            title: blog.title,
+            link: format!("{}/blog/{}", site_url, path),
+            author: blog.author,
+            summary: summary.filter(|s| !s.trim().is_empty()).or(blog.excerpt),
+            content_html: html,
+            categories,
+            published: blog.created_at,
+            updated,
+        })
+    }
+}
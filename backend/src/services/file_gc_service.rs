@@ -0,0 +1,500 @@
+//! File garbage collection - Find and purge uploads nothing uses any more
+//!
+//! Content refers to uploads only by URL, so nothing links a `files` record to
+//! the blogs or projects using it. The scan extracts upload object keys from
+//! every content column and cross-references them with the file records and
+//! the storage listing of each backend. Files and objects younger than the
+//! requested minimum age are skipped: they may belong to content being written.
+//!
+//! Sealed protected texts are decrypted with the master key. When one can't be
+//! read, files nothing else refers to are reported as undecidable instead of
+//! unreferenced, so a purge never deletes what such a text might use.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::models::file::{File, OrphanPurgeSummary, OrphanReport, OrphanedFile, UntrackedObject};
+use crate::models::text::Text;
+use crate::repositories::file_repo::FileRepository;
+use crate::repositories::file_variant_repo::FileVariantRepository;
+use crate::repositories::text_repo::TextRepository;
+use crate::services::storage::{
+    Storage, StorageBackend, StorageService, StoredObject, UPLOADS_PREFIX,
+};
+use crate::utils::crypto;
+
+const BACKENDS: [StorageBackend; 2] = [StorageBackend::S3, StorageBackend::Local];
+
+/// Everything gathered for one scan
+struct Scan {
+    report: OrphanReport,
+    variant_keys: HashMap<i64, Vec<String>>,
+    storages: HashMap<&'static str, Arc<dyn Storage>>,
+    object_sizes: HashMap<(&'static str, String), i64>,
+}
+
+/// File garbage collection service for business logic
+pub struct FileGcService;
+
+impl FileGcService {
+    /// Report orphans without changing anything
+    pub async fn scan(
+        pool: &PgPool,
+        config: &Config,
+        min_age_hours: i64,
+    ) -> Result<OrphanReport, ApiError> {
+        Ok(Self::run_scan(pool, config, min_age_hours).await?.report)
+    }
+
+    /// Scan again and remove every orphan found
+    ///
+    /// Unreferenced files and records without an object lose their record and
+    /// objects; untracked objects are deleted from storage. Files on backends
+    /// that can't be reached are left alone.
+    pub async fn purge(
+        pool: &PgPool,
+        config: &Config,
+        min_age_hours: i64,
+    ) -> Result<(OrphanReport, OrphanPurgeSummary), ApiError> {
+        let scan = Self::run_scan(pool, config, min_age_hours).await?;
+        let mut summary = OrphanPurgeSummary::default();
+
+        let mut ids = Vec::new();
+        let mut objects: Vec<(&'static str, String)> = Vec::new();
+        for file in scan
+            .report
+            .unreferenced_files
+            .iter()
+            .chain(&scan.report.missing_objects)
+        {
+            let Some(backend) = StorageBackend::parse(&file.storage_backend) else {
+                continue;
+            };
+            if !scan.storages.contains_key(backend.as_str()) {
+                continue;
+            }
+
+            ids.push(file.id);
+            for key in file
+                .object_key
+                .iter()
+                .chain(scan.variant_keys.get(&file.id).into_iter().flatten())
+            {
+                objects.push((backend.as_str(), key.clone()));
+            }
+        }
+        for object in &scan.report.untracked_objects {
+            if let Some(backend) = StorageBackend::parse(&object.storage_backend) {
+                objects.push((backend.as_str(), object.object_key.clone()));
+            }
+        }
+
+        // Records go first so nothing hands out URLs of objects being removed
+        if !ids.is_empty() {
+            summary.deleted_files = FileRepository::delete_many(pool, &ids).await? as usize;
+        }
+
+        for (backend, object_key) in objects {
+            // Objects missing from the listing are already gone
+            let Some(size) = scan.object_sizes.get(&(backend, object_key.clone())) else {
+                continue;
+            };
+            match scan.storages[backend].delete(&object_key).await {
+                Ok(()) => {
+                    summary.deleted_objects += 1;
+                    summary.freed_bytes += size;
+                }
+                Err(e) => tracing::warn!("Failed to delete orphaned object {}: {}", object_key, e),
+            }
+        }
+
+        tracing::info!(
+            "Orphaned files purged: {} records, {} objects, {} bytes freed",
+            summary.deleted_files,
+            summary.deleted_objects,
+            summary.freed_bytes
+        );
+
+        Ok((scan.report, summary))
+    }
+
+    async fn run_scan(
+        pool: &PgPool,
+        config: &Config,
+        min_age_hours: i64,
+    ) -> Result<Scan, ApiError> {
+        let cutoff = Utc::now() - Duration::hours(min_age_hours);
+
+        let files = FileRepository::find_all_unpaged(pool).await?;
+        let mut variant_keys: HashMap<i64, Vec<String>> = HashMap::new();
+        for variant in FileVariantRepository::find_all(pool).await? {
+            variant_keys
+                .entry(variant.file_id)
+                .or_default()
+                .push(variant.object_key);
+        }
+        let mut texts = FileRepository::find_referencing_texts(pool).await?;
+        let unreadable_texts = open_sealed_texts(
+            config.encryption.master_key.as_deref(),
+            &TextRepository::find_sealed(pool).await?,
+            &mut texts,
+        );
+        if unreadable_texts > 0 {
+            tracing::warn!(
+                "Orphan scan can't decrypt {} protected texts; unreferenced files are left undecided",
+                unreadable_texts
+            );
+        }
+        let referenced: HashSet<String> = texts
+            .iter()
+            .flat_map(|text| extract_upload_keys(text))
+            .map(ToOwned::to_owned)
+            .collect();
+
+        let mut storages: HashMap<&'static str, Arc<dyn Storage>> = HashMap::new();
+        let mut listings: HashMap<&'static str, Vec<StoredObject>> = HashMap::new();
+        let mut unavailable_backends = Vec::new();
+        for backend in BACKENDS {
+            let in_use = files
+                .iter()
+                .any(|file| file.storage_backend == backend.as_str());
+            let listed = match StorageService::open(pool, config, backend).await {
+                Ok(storage) => storage
+                    .list(UPLOADS_PREFIX)
+                    .await
+                    .map(|objects| (storage, objects)),
+                Err(e) => Err(e),
+            };
+
+            match listed {
+                Ok((storage, objects)) => {
+                    storages.insert(backend.as_str(), storage);
+                    listings.insert(backend.as_str(), objects);
+                }
+                // An unconfigured backend nothing was ever stored on is not worth reporting
+                Err(e) if in_use => {
+                    tracing::warn!("Orphan scan can't list {} storage: {}", backend.as_str(), e);
+                    unavailable_backends.push(backend.as_str().to_string());
+                }
+                Err(_) => {}
+            }
+        }
+
+        let mut report = classify(
+            &files,
+            &variant_keys,
+            &referenced,
+            unreadable_texts == 0,
+            &listings,
+            cutoff,
+        );
+        report.unreadable_texts = unreadable_texts;
+        report.unavailable_backends = unavailable_backends;
+
+        let object_sizes = listings
+            .into_iter()
+            .flat_map(|(backend, objects)| {
+                objects
+                    .into_iter()
+                    .map(move |object| ((backend, object.object_key), object.size))
+            })
+            .collect();
+
+        tracing::info!(
+            "Orphan scan: {} unreferenced files, {} undecidable files, {} records without object, {} untracked objects",
+            report.unreferenced_files.len(),
+            report.undecidable_files.len(),
+            report.missing_objects.len(),
+            report.untracked_objects.len()
+        );
+
+        Ok(Scan {
+            report,
+            variant_keys,
+            storages,
+            object_sizes,
+        })
+    }
+}
+
+/// Cross-reference file records, referenced keys and storage listings
+///
+/// `listings` holds the objects of each backend that could be listed; records
+/// on other backends are only checked for references. Unless
+/// `references_complete`, some content couldn't be read and files without a
+/// reference are undecidable.
+fn classify(
+    files: &[File],
+    variant_keys: &HashMap<i64, Vec<String>>,
+    referenced: &HashSet<String>,
+    references_complete: bool,
+    listings: &HashMap<&'static str, Vec<StoredObject>>,
+    cutoff: DateTime<Utc>,
+) -> OrphanReport {
+    let listed: HashMap<&str, HashSet<&str>> = listings
+        .iter()
+        .map(|(backend, objects)| {
+            let keys = objects.iter().map(|o| o.object_key.as_str()).collect();
+            (*backend, keys)
+        })
+        .collect();
+
+    let mut report = OrphanReport::default();
+    let mut tracked: HashSet<(&str, &str)> = HashSet::new();
+
+    for file in files {
+        let keys: Vec<&str> = file
+            .object_key
+            .iter()
+            .chain(variant_keys.get(&file.id).into_iter().flatten())
+            .map(String::as_str)
+            .collect();
+        for key in &keys {
+            tracked.insert((file.storage_backend.as_str(), key));
+        }
+
+        // Files stored elsewhere or by hand aren't ours to judge
+        let Some(object_key) = file
+            .object_key
+            .as_deref()
+            .filter(|key| key.starts_with(UPLOADS_PREFIX))
+        else {
+            continue;
+        };
+        if file.created_at.is_some_and(|created| created > cutoff) {
+            continue;
+        }
+
+        let is_missing = listed
+            .get(file.storage_backend.as_str())
+            .is_some_and(|objects| !objects.contains(object_key));
+        if is_missing {
+            report.missing_objects.push(OrphanedFile::from(file));
+        } else if !keys.iter().any(|key| referenced.contains(*key)) {
+            if references_complete {
+                report.unreferenced_files.push(OrphanedFile::from(file));
+            } else {
+                report.undecidable_files.push(OrphanedFile::from(file));
+            }
+        }
+    }
+
+    for (backend, objects) in listings {
+        for object in objects {
+            let is_old = object
+                .last_modified
+                .is_none_or(|modified| modified <= cutoff);
+            if is_old && !tracked.contains(&(*backend, object.object_key.as_str())) {
+                report.untracked_objects.push(UntrackedObject {
+                    storage_backend: backend.to_string(),
+                    object_key: object.object_key.clone(),
+                    size: object.size,
+                    last_modified: object.last_modified,
+                });
+            }
+        }
+    }
+    report
+        .untracked_objects
+        .sort_by(|a, b| a.object_key.cmp(&b.object_key));
+
+    report
+}
+
+/// Decrypt sealed texts into `contents`, returning how many couldn't be read
+fn open_sealed_texts(
+    master_key: Option<&str>,
+    sealed: &[Text],
+    contents: &mut Vec<String>,
+) -> usize {
+    let mut unreadable = 0;
+    for text in sealed {
+        let opened = match (master_key, text.sealed_content()) {
+            (Some(master_key), Some(sealed)) => crypto::open(master_key, &sealed).ok(),
+            _ => None,
+        };
+        match opened {
+            Some(content) => contents.push(content),
+            None => unreadable += 1,
+        }
+    }
+    unreadable
+}
+
+/// Upload object keys mentioned in a text, e.g. in URLs or Markdown images
+pub(crate) fn extract_upload_keys(text: &str) -> impl Iterator<Item = &str> {
+    text.match_indices(UPLOADS_PREFIX).map(move |(start, _)| {
+        let len = text[start..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '/')))
+            .unwrap_or(text.len() - start);
+        text[start..start + len].trim_end_matches(['.', '/'])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(id: i64, object_key: &str, backend: &str, age_hours: i64) -> File {
+        File {
+            id,
+            filename: format!("{}.png", id),
+            original_filename: None,
+            file_type: Some("image".to_string()),
+            file_size: Some(10),
+            url: format!("https://cdn.example.com/{}", object_key),
+            thumbnail_url: None,
+            width: None,
+            height: None,
+            bucket_name: None,
+            object_key: Some(object_key.to_string()),
+            storage_backend: backend.to_string(),
+            content_hash: None,
+            ref_count: 1,
+            created_at: Some(Utc::now() - Duration::hours(age_hours)),
+        }
+    }
+
+    fn object(object_key: &str, age_hours: i64) -> StoredObject {
+        StoredObject {
+            object_key: object_key.to_string(),
+            size: 10,
+            last_modified: Some(Utc::now() - Duration::hours(age_hours)),
+        }
+    }
+
+    #[test]
+    fn keys_are_extracted_from_markdown_and_urls() {
+        let text = "![a](https://cdn.example.com/uploads/a.png) and \
+                    <img src=\"/files/uploads/b/thumbnail.jpg\">, see uploads/c.webp.";
+        let keys: Vec<&str> = extract_upload_keys(text).collect();
+
+        assert_eq!(
+            keys,
+            vec!["uploads/a.png", "uploads/b/thumbnail.jpg", "uploads/c.webp"]
+        );
+        assert_eq!(extract_upload_keys("no uploads here").count(), 0);
+    }
+
+    #[test]
+    fn classify_reports_orphans_in_both_directions() {
+        let files = vec![
+            file(1, "uploads/used.png", "s3", 48),
+            file(2, "uploads/variant-used.png", "s3", 48),
+            file(3, "uploads/unused.png", "s3", 48),
+            file(4, "uploads/gone.png", "s3", 48),
+            file(5, "uploads/fresh.png", "s3", 1),
+        ];
+        let variant_keys =
+            HashMap::from([(2, vec!["uploads/variant-used/thumbnail.jpg".to_string()])]);
+        let referenced: HashSet<String> =
+            ["uploads/used.png", "uploads/variant-used/thumbnail.jpg"]
+                .into_iter()
+                .map(ToOwned::to_owned)
+                .collect();
+        let listings = HashMap::from([(
+            "s3",
+            vec![
+                object("uploads/used.png", 48),
+                object("uploads/variant-used.png", 48),
+                object("uploads/variant-used/thumbnail.jpg", 48),
+                object("uploads/unused.png", 48),
+                object("uploads/fresh.png", 1),
+                object("uploads/stray.png", 48),
+                object("uploads/stray-new.png", 1),
+            ],
+        )]);
+
+        let report = classify(
+            &files,
+            &variant_keys,
+            &referenced,
+            true,
+            &listings,
+            Utc::now() - Duration::hours(24),
+        );
+
+        let ids = |files: &[OrphanedFile]| files.iter().map(|f| f.id).collect::<Vec<_>>();
+        assert_eq!(ids(&report.unreferenced_files), vec![3]);
+        assert_eq!(ids(&report.missing_objects), vec![4]);
+        assert_eq!(report.untracked_objects.len(), 1);
+        assert_eq!(report.untracked_objects[0].object_key, "uploads/stray.png");
+    }
+
+    fn sealed_text(master_key: &str, content: &str) -> Text {
+        let sealed = crypto::seal(master_key, content).unwrap();
+        Text {
+            id: 1,
+            name: "secret".to_string(),
+            intro: None,
+            content: sealed.ciphertext,
+            is_encrypted: Some(true),
+            view_password: None,
+            content_kdf: Some(sealed.kdf),
+            content_nonce: Some(sealed.nonce),
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn classify_keeps_files_used_by_protected_texts() {
+        let master_key = "test-master-key";
+        let sealed = vec![sealed_text(master_key, "![](/files/uploads/secret.png)")];
+        let files = vec![file(1, "uploads/secret.png", "s3", 48)];
+        let listings = HashMap::from([("s3", vec![object("uploads/secret.png", 48)])]);
+        let classify_with = |master_key: Option<&str>| {
+            let mut texts = Vec::new();
+            let unreadable = open_sealed_texts(master_key, &sealed, &mut texts);
+            let referenced: HashSet<String> = texts
+                .iter()
+                .flat_map(|text| extract_upload_keys(text))
+                .map(ToOwned::to_owned)
+                .collect();
+            classify(
+                &files,
+                &HashMap::new(),
+                &referenced,
+                unreadable == 0,
+                &listings,
+                Utc::now() - Duration::hours(24),
+            )
+        };
+
+        let report = classify_with(Some(master_key));
+        assert!(report.unreferenced_files.is_empty());
+        assert!(report.undecidable_files.is_empty());
+
+        // Without the key the reference can't be seen, but the file must not be purged
+        let report = classify_with(None);
+        assert!(report.unreferenced_files.is_empty());
+        assert_eq!(report.undecidable_files.len(), 1);
+        assert_eq!(report.undecidable_files[0].id, 1);
+    }
+
+    #[test]
+    fn unlisted_backends_are_only_checked_for_references() {
+        let files = vec![
+            file(1, "uploads/a.png", "local", 48),
+            file(2, "legacy/b.png", "s3", 48),
+        ];
+
+        let report = classify(
+            &files,
+            &HashMap::new(),
+            &HashSet::new(),
+            true,
+            &HashMap::new(),
+            Utc::now(),
+        );
+
+        assert!(report.missing_objects.is_empty());
+        assert_eq!(report.unreferenced_files.len(), 1);
+        assert_eq!(report.unreferenced_files[0].id, 1);
+    }
+}
@@ -0,0 +1,302 @@
+//! File service - Uploads with image processing
+//!
+//! Images get their dimensions recorded, metadata stripped and a thumbnail plus
+//! responsive variants rendered next to the original in storage. Settings come
+//! from the `image_*` site config keys.
+//!
+//! Uploads are deduplicated by the SHA-256 of their content: uploading bytes
+//! that are already stored takes another reference on the existing file.
+
+use std::collections::HashMap;
+
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+use crate::error::ApiError;
+use crate::models::file::{
+    CreateFileRequest, CreateFileVariantRequest, File, FileResponse, FileVariant,
+};
+use crate::repositories::file_repo::FileRepository;
+use crate::repositories::file_variant_repo::FileVariantRepository;
+use crate::repositories::site_config_repo::SiteConfigRepo;
+use crate::services::storage::{new_object_key, Storage};
+use crate::utils::imaging::{
+    self, ImageOptions, ImageVariant, ProcessedImage, VariantFormat, VariantKind,
+};
+
+/// File service for business logic
+pub struct FileService;
+
+impl FileService {
+    /// Store an uploaded file, processing it first if it is an image
+    ///
+    /// Returns the existing file instead if the same content was uploaded before.
+    pub async fn upload(
+        pool: &PgPool,
+        storage: &dyn Storage,
+        original_filename: &str,
+        content_type: &str,
+        data: Vec<u8>,
+    ) -> Result<FileResponse, ApiError> {
+        let content_hash = content_hash(&data);
+        if let Some(file) = FileRepository::acquire_by_hash(pool, &content_hash).await? {
+            tracing::info!(
+                "Upload of {} matches file {} ({} references)",
+                original_filename,
+                file.id,
+                file.ref_count
+            );
+            return Self::to_response(pool, file).await;
+        }
+
+        let options = ImageOptions::from_map(&SiteConfigRepo::get_as_map(pool).await?);
+
+        // Decoding and resizing are CPU bound, keep them off the async workers
+        let (data, processed) = tokio::task::spawn_blocking(move || {
+            let processed = imaging::process(&data, &options);
+            (data, processed)
+        })
+        .await
+        .map_err(|e| ApiError::InternalError(format!("Image processing failed: {}", e)))?;
+        let mut processed = processed?;
+
+        let data = processed
+            .as_mut()
+            .and_then(|image| image.original.take())
+            .unwrap_or(data);
+        let content_type = processed
+            .as_ref()
+            .map(|image| image.content_type)
+            .unwrap_or(content_type);
+        let file_size = data.len() as i64;
+
+        let upload_result = storage
+            .put(&new_object_key(original_filename), data, content_type)
+            .await?;
+        let variants = match &processed {
+            Some(image) => {
+                match Self::upload_variants(storage, &upload_result.object_key, image).await {
+                    Ok(variants) => variants,
+                    Err(e) => {
+                        Self::delete_objects(
+                            storage,
+                            std::slice::from_ref(&upload_result.object_key),
+                        )
+                        .await;
+                        return Err(e);
+                    }
+                }
+            }
+            None => Vec::new(),
+        };
+
+        // Thumbnail in the widely supported format, WebP copies are listed as variants
+        let thumbnail_url = variants
+            .iter()
+            .find(|v| {
+                v.kind == VariantKind::Thumbnail.as_str()
+                    && v.format != VariantFormat::WebP.as_str()
+            })
+            .map(|v| v.url.clone());
+
+        // Extract file type from content type (e.g., "image/png" -> "image")
+        let file_type = content_type.split('/').next().map(|s| s.to_string());
+
+        // Generate filename from object key
+        let filename = upload_result
+            .object_key
+            .split('/')
+            .next_back()
+            .unwrap_or(&upload_result.object_key)
+            .to_string();
+
+        let create_req = CreateFileRequest {
+            filename,
+            original_filename: Some(original_filename.to_string()),
+            file_type,
+            file_size: Some(file_size),
+            url: upload_result.url,
+            thumbnail_url,
+            width: processed.as_ref().map(|image| image.width as i32),
+            height: processed.as_ref().map(|image| image.height as i32),
+            bucket_name: storage.bucket_name().map(ToOwned::to_owned),
+            object_key: Some(upload_result.object_key),
+            storage_backend: storage.backend().as_str().to_string(),
+            content_hash: Some(content_hash.clone()),
+        };
+
+        let Some(file) = FileRepository::create(pool, &create_req).await? else {
+            // The same content was uploaded concurrently; keep that copy
+            let object_keys: Vec<String> = create_req
+                .object_key
+                .into_iter()
+                .chain(variants.into_iter().map(|v| v.object_key))
+                .collect();
+            Self::delete_objects(storage, &object_keys).await;
+
+            let file = FileRepository::acquire_by_hash(pool, &content_hash)
+                .await?
+                .ok_or_else(|| {
+                    ApiError::FileUploadError(
+                        "File was deleted during upload, please retry".to_string(),
+                    )
+                })?;
+            return Self::to_response(pool, file).await;
+        };
+        let variants = FileVariantRepository::create_many(pool, file.id, &variants).await?;
+
+        tracing::info!(
+            "File uploaded: {} (id: {}, size: {} bytes, {} variants)",
+            original_filename,
+            file.id,
+            file_size,
+            variants.len()
+        );
+
+        Ok(FileResponse::from(file).with_variants(variants))
+    }
+
+    /// Build the response for a single file, with its variants
+    async fn to_response(pool: &PgPool, file: File) -> Result<FileResponse, ApiError> {
+        let variants = FileVariantRepository::find_by_file_id(pool, file.id).await?;
+        Ok(FileResponse::from(file).with_variants(variants))
+    }
+
+    /// Build responses for a page of files, with their variants
+    pub async fn to_responses(
+        pool: &PgPool,
+        files: Vec<File>,
+    ) -> Result<Vec<FileResponse>, ApiError> {
+        let ids: Vec<i64> = files.iter().map(|file| file.id).collect();
+        let mut variants_by_file: HashMap<i64, Vec<FileVariant>> = HashMap::new();
+        for variant in FileVariantRepository::find_by_file_ids(pool, &ids).await? {
+            variants_by_file
+                .entry(variant.file_id)
+                .or_default()
+                .push(variant);
+        }
+
+        Ok(files
+            .into_iter()
+            .map(|file| {
+                let variants = variants_by_file.remove(&file.id).unwrap_or_default();
+                FileResponse::from(file).with_variants(variants)
+            })
+            .collect())
+    }
+
+    /// Remove objects from storage, logging instead of failing on errors
+    pub async fn delete_objects(storage: &dyn Storage, object_keys: &[String]) {
+        for object_key in object_keys {
+            if let Err(e) = storage.delete(object_key).await {
+                tracing::warn!("Failed to delete file from storage: {}", e);
+            }
+        }
+    }
+
+    /// Upload the rendered variants; removes the ones already stored if one fails
+    async fn upload_variants(
+        storage: &dyn Storage,
+        object_key: &str,
+        image: &ProcessedImage,
+    ) -> Result<Vec<CreateFileVariantRequest>, ApiError> {
+        let mut uploaded: Vec<CreateFileVariantRequest> = Vec::with_capacity(image.variants.len());
+
+        for variant in &image.variants {
+            let key = variant_object_key(object_key, variant);
+            let result = storage
+                .put(&key, variant.data.clone(), variant.format.content_type())
+                .await;
+
+            match result {
+                Ok(result) => uploaded.push(CreateFileVariantRequest {
+                    kind: variant.kind.as_str().to_string(),
+                    format: variant.format.as_str().to_string(),
+                    width: variant.width as i32,
+                    height: variant.height as i32,
+                    file_size: variant.data.len() as i64,
+                    url: result.url,
+                    object_key: result.object_key,
+                }),
+                Err(e) => {
+                    let keys: Vec<String> = uploaded.into_iter().map(|v| v.object_key).collect();
+                    Self::delete_objects(storage, &keys).await;
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(uploaded)
+    }
+}
+
+/// Hex encoded SHA-256 of uploaded content
+fn content_hash(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Key of a variant, in a folder named after the original:
+/// `uploads/<uuid>.jpg` -> `uploads/<uuid>/thumbnail.jpg`, `uploads/<uuid>/w640.webp`
+fn variant_object_key(object_key: &str, variant: &ImageVariant) -> String {
+    let file_name_start = object_key.rfind('/').map(|i| i + 1).unwrap_or(0);
+    let stem = match object_key[file_name_start..].rfind('.') {
+        Some(dot) => &object_key[..file_name_start + dot],
+        None => object_key,
+    };
+
+    let name = match variant.kind {
+        VariantKind::Thumbnail => "thumbnail".to_string(),
+        VariantKind::Responsive => format!("w{}", variant.width),
+    };
+
+    format!("{}/{}.{}", stem, name, variant.format.extension())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variant(kind: VariantKind, format: VariantFormat, width: u32) -> ImageVariant {
+        ImageVariant {
+            kind,
+            format,
+            width,
+            height: width / 2,
+            data: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn content_hash_is_hex_sha256() {
+        assert_eq!(
+            content_hash(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_ne!(content_hash(b"abc"), content_hash(b"abd"));
+    }
+
+    #[test]
+    fn variants_are_stored_next_to_original() {
+        assert_eq!(
+            variant_object_key(
+                "uploads/abc.jpeg",
+                &variant(VariantKind::Thumbnail, VariantFormat::Jpeg, 320)
+            ),
+            "uploads/abc/thumbnail.jpg"
+        );
+        assert_eq!(
+            variant_object_key(
+                "uploads/abc.png",
+                &variant(VariantKind::Responsive, VariantFormat::WebP, 640)
+            ),
+            "uploads/abc/w640.webp"
+        );
+        assert_eq!(
+            variant_object_key(
+                "uploads/abc",
+                &variant(VariantKind::Responsive, VariantFormat::Png, 1280)
+            ),
+            "uploads/abc/w1280.png"
+        );
+    }
+}
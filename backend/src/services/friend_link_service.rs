@@ -0,0 +1,238 @@
+//! Friend link service - Public applications and backlink verification
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::Url;
+use sqlx::PgPool;
+
+use crate::error::ApiError;
+use crate::models::friend_link::{ApplyFriendLinkRequest, CreateFriendLinkRequest, FriendLink};
+use crate::repositories::friend_link_repo::FriendLinkRepository;
+use crate::repositories::site_config_repo::SiteConfigRepo;
+use crate::services::cache_service::{cache_keys, cache_ttl, CacheService};
+use crate::services::page_fetcher::PageFetcher;
+use crate::utils::links::links_to_site;
+
+/// Maximum applications one IP may submit per rate limit window
+const APPLY_RATE_LIMIT: i64 = 3;
+
+/// How often the background checker looks for pending applications
+pub const BACKLINK_CHECKER_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Pending applications are re-checked once their last check is this old
+const BACKLINK_RECHECK_AFTER: chrono::Duration = chrono::Duration::hours(6);
+
+/// Friend link service for business logic
+pub struct FriendLinkService;
+
+impl FriendLinkService {
+    /// Create a pending friend link from a public application and check its backlink in the background
+    pub async fn apply(
+        pool: &PgPool,
+        cache: &Arc<CacheService>,
+        fetcher: Arc<dyn PageFetcher>,
+        client_ip: &str,
+        req: &ApplyFriendLinkRequest,
+    ) -> Result<FriendLink, ApiError> {
+        let name = req.name.trim();
+        if name.is_empty() || name.chars().count() > 100 {
+            return Err(ApiError::ValidationError(
+                "Site name is required and must be at most 100 characters".to_string(),
+            ));
+        }
+        let url = Self::parse_site_url(&req.url)?;
+        if let Some(logo) = req.logo.as_deref().filter(|l| !l.is_empty()) {
+            Self::parse_site_url(logo)?;
+        }
+
+        let rate_key = cache_keys::friend_link_apply_rate_limit(client_ip);
+        let count = cache
+            .incr_window(&rate_key, cache_ttl::FRIEND_LINK_APPLY_RATE_LIMIT)
+            .await?;
+        if count > APPLY_RATE_LIMIT {
+            return Err(ApiError::TooManyRequests(
+                "Too many applications, please try again later".to_string(),
+            ));
+        }
+
+        if FriendLinkRepository::url_exists(pool, url.as_str()).await? {
+            return Err(ApiError::ValidationError(
+                "This site has already applied".to_string(),
+            ));
+        }
+
+        let link = FriendLinkRepository::create(
+            pool,
+            &CreateFriendLinkRequest {
+                name: name.to_string(),
+                url: url.to_string(),
+                logo: req.logo.clone().filter(|l| !l.is_empty()),
+                intro: req.intro.clone(),
+                email: req.email.clone(),
+                status: Some(0),
+            },
+        )
+        .await?;
+
+        tracing::info!(
+            "New friend link application: {} ({}) from IP {}",
+            link.name,
+            link.url,
+            client_ip
+        );
+
+        let pool = pool.clone();
+        let pending = link.clone();
+        tokio::spawn(async move {
+            if let Err(e) = Self::check_backlink(&pool, fetcher.as_ref(), &pending).await {
+                tracing::warn!(
+                    "Backlink check for friend link {} failed: {}",
+                    pending.id,
+                    e
+                );
+            }
+        });
+
+        Ok(link)
+    }
+
+    /// Fetch the friend's page and record whether it links back to `site_url`
+    pub async fn check_backlink(
+        pool: &PgPool,
+        fetcher: &dyn PageFetcher,
+        link: &FriendLink,
+    ) -> Result<Option<FriendLink>, ApiError> {
+        let site_url = SiteConfigRepo::get_value(pool, "site_url")
+            .await?
+            .and_then(|url| Url::parse(url.trim()).ok());
+
+        let (found, error) = match site_url {
+            Some(site_url) => Self::detect_backlink(fetcher, &link.url, &site_url).await,
+            None => (None, Some("site_url is not configured".to_string())),
+        };
+
+        tracing::info!(
+            "Backlink check for friend link {}: found={:?} error={:?}",
+            link.id,
+            found,
+            error
+        );
+
+        FriendLinkRepository::record_backlink_check(pool, link.id, found, error.as_deref()).await
+    }
+
+    /// Re-check every pending application whose last check is stale
+    pub async fn check_pending_backlinks(
+        pool: &PgPool,
+        fetcher: &dyn PageFetcher,
+    ) -> Result<usize, ApiError> {
+        let due = chrono::Utc::now() - BACKLINK_RECHECK_AFTER;
+        let links = FriendLinkRepository::find_pending_unchecked(pool, due).await?;
+
+        for link in &links {
+            Self::check_backlink(pool, fetcher, link).await?;
+        }
+
+        Ok(links.len())
+    }
+
+    /// Background task: periodically check pending applications
+    pub async fn run_backlink_checker(pool: PgPool, fetcher: Arc<dyn PageFetcher>) {
+        let mut interval = tokio::time::interval(BACKLINK_CHECKER_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = Self::check_pending_backlinks(&pool, fetcher.as_ref()).await {
+                tracing::warn!("Friend link backlink check failed: {}", e);
+            }
+        }
+    }
+
+    /// Returns `(found, error)`; `found` is `None` when the page couldn't be checked
+    async fn detect_backlink(
+        fetcher: &dyn PageFetcher,
+        page_url: &str,
+        site_url: &Url,
+    ) -> (Option<bool>, Option<String>) {
+        let Ok(page) = Url::parse(page_url) else {
+            return (None, Some("Invalid friend link URL".to_string()));
+        };
+
+        match fetcher.fetch(page.as_str()).await {
+            Ok(fetched) if fetched.status >= 400 => {
+                (None, Some(format!("HTTP status {}", fetched.status)))
+            }
+            Ok(fetched) => (Some(links_to_site(&fetched.body, &page, site_url)), None),
+            Err(e) => (None, Some(e.chars().take(500).collect())),
+        }
+    }
+
+    fn parse_site_url(value: &str) -> Result<Url, ApiError> {
+        let url = Url::parse(value.trim())
+            .map_err(|_| ApiError::ValidationError(format!("Invalid URL: {}", value)))?;
+        if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+            return Err(ApiError::ValidationError(format!(
+                "URL must be http(s): {}",
+                value
+            )));
+        }
+        Ok(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::page_fetcher::FetchedPage;
+    use async_trait::async_trait;
+
+    struct MockFetcher(Result<FetchedPage, String>);
+
+    #[async_trait]
+    impl PageFetcher for MockFetcher {
+        async fn fetch(&self, _url: &str) -> Result<FetchedPage, String> {
+            self.0.clone()
+        }
+    }
+
+    fn page(status: u16, body: &str) -> MockFetcher {
+        MockFetcher(Ok(FetchedPage {
+            status,
+            final_url: "https://friend.dev/links".to_string(),
+            body: body.to_string(),
+        }))
+    }
+
+    fn site() -> Url {
+        Url::parse("https://example.com").unwrap()
+    }
+
+    #[tokio::test]
+    async fn detects_backlink_on_friend_page() {
+        let fetcher = page(200, r#"<a href="https://www.example.com/">Example</a>"#);
+        let result =
+            FriendLinkService::detect_backlink(&fetcher, "https://friend.dev/links", &site()).await;
+        assert_eq!(result, (Some(true), None));
+    }
+
+    #[tokio::test]
+    async fn reports_missing_backlink() {
+        let fetcher = page(200, r#"<a href="https://someone-else.com/">Other</a>"#);
+        let result =
+            FriendLinkService::detect_backlink(&fetcher, "https://friend.dev/links", &site()).await;
+        assert_eq!(result, (Some(false), None));
+    }
+
+    #[tokio::test]
+    async fn fetch_failures_are_recorded_as_unknown() {
+        let fetcher = page(404, "");
+        let result =
+            FriendLinkService::detect_backlink(&fetcher, "https://friend.dev/links", &site()).await;
+        assert_eq!(result, (None, Some("HTTP status 404".to_string())));
+
+        let fetcher = MockFetcher(Err("Request failed: timeout".to_string()));
+        let result =
+            FriendLinkService::detect_backlink(&fetcher, "https://friend.dev/links", &site()).await;
+        assert_eq!(result, (None, Some("Request failed: timeout".to_string())));
+    }
+}
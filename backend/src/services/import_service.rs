@@ -0,0 +1,423 @@
+//! Import service - Brings posts over from other blog engines
+//!
+//! Exports are parsed by `utils::importers`, then planned against what is
+//! already stored: categories and tags are matched by name ignoring case,
+//! and posts whose slug is taken (or repeated within the export) are skipped
+//! rather than overwriting anything. A dry run stops after planning, so the
+//! report shows every conflict before a single row is written.
+
+use std::collections::{HashMap, HashSet};
+
+use sqlx::PgPool;
+
+use crate::error::ApiError;
+use crate::models::import::{
+    ImportAction, ImportReport, ImportSource, ImportedPostResult, NewImportedBlog, ParsedImport,
+};
+use crate::repositories::category_repo::CategoryRepository;
+use crate::repositories::import_repo::ImportRepository;
+use crate::repositories::tag_repo::TagRepository;
+use crate::repositories::user_repo::UserRepository;
+use crate::utils::importers::{ghost, markdown, wordpress};
+use crate::utils::markdown::render_markdown;
+
+/// Column limits of the rows an import writes
+const MAX_TITLE_LEN: usize = 255;
+const MAX_SLUG_LEN: usize = 255;
+const MAX_AUTHOR_LEN: usize = 100;
+const MAX_THUMBNAIL_LEN: usize = 500;
+const MAX_CATEGORY_LEN: usize = 100;
+const MAX_TAG_LEN: usize = 50;
+
+/// Import service for business logic
+pub struct ImportService;
+
+impl ImportService {
+    /// Parse an uploaded export
+    pub fn parse(source: ImportSource, data: &[u8]) -> Result<ParsedImport, ApiError> {
+        match source {
+            ImportSource::Markdown => markdown::parse_archive(data),
+            ImportSource::WordPress | ImportSource::Ghost => {
+                let text = std::str::from_utf8(data).map_err(|_| {
+                    ApiError::BadRequest("Export file is not valid UTF-8".to_string())
+                })?;
+                match source {
+                    ImportSource::WordPress => wordpress::parse(text),
+                    _ => ghost::parse(text),
+                }
+            }
+        }
+    }
+
+    /// Import an export, or only report what would be imported when `dry_run` is set
+    ///
+    /// Created blogs belong to `user_id`; posts without an author get that
+    /// user's display name as their byline.
+    pub async fn import(
+        pool: &PgPool,
+        source: ImportSource,
+        data: &[u8],
+        dry_run: bool,
+        user_id: i64,
+    ) -> Result<ImportReport, ApiError> {
+        let parsed = Self::parse(source, data)?;
+
+        let slugs: Vec<String> = parsed
+            .posts
+            .iter()
+            .filter_map(|post| post.slug.clone())
+            .collect();
+        let existing_slugs: HashSet<String> = ImportRepository::existing_slugs(pool, &slugs)
+            .await?
+            .into_iter()
+            .collect();
+        let categories: Vec<String> = CategoryRepository::find_all(pool)
+            .await?
+            .into_iter()
+            .map(|category| category.name)
+            .collect();
+        let tags: Vec<String> = TagRepository::find_all(pool)
+            .await?
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect();
+        let byline = UserRepository::find_by_id(pool, user_id)
+            .await?
+            .map(|user| user.display_name());
+
+        let mut plan = plan(
+            parsed,
+            &existing_slugs,
+            &categories,
+            &tags,
+            byline.as_deref(),
+        );
+        let mut report = ImportReport {
+            source,
+            dry_run,
+            posts: Vec::new(),
+            new_categories: plan.new_categories,
+            new_tags: plan.new_tags,
+            created: 0,
+            skipped: 0,
+            warnings: plan.warnings,
+        };
+
+        if !dry_run && !plan.blogs.is_empty() {
+            let blogs: Vec<NewImportedBlog> =
+                plan.blogs.iter().map(|(_, blog)| blog.clone()).collect();
+            let blog_ids = ImportRepository::create_all(pool, &blogs, Some(user_id)).await?;
+            for ((index, _), blog_id) in plan.blogs.iter().zip(blog_ids) {
+                let result = &mut plan.posts[*index];
+                match blog_id {
+                    Some(id) => result.blog_id = Some(id),
+                    None => {
+                        result.action = ImportAction::Skip;
+                        result.conflict =
+                            Some("Slug was taken by another blog during the import".to_string());
+                    }
+                }
+            }
+        }
+
+        report.skipped = plan
+            .posts
+            .iter()
+            .filter(|post| post.action == ImportAction::Skip)
+            .count();
+        report.created = plan.posts.len() - report.skipped;
+        report.posts = plan.posts;
+
+        tracing::info!(
+            "Import from {} ({}): {} to create, {} skipped",
+            source.as_str(),
+            if dry_run { "dry run" } else { "applied" },
+            report.created,
+            report.skipped
+        );
+
+        Ok(report)
+    }
+}
+
+/// Outcome of planning an import against the stored content
+#[derive(Debug)]
+struct ImportPlan {
+    /// One result per parsed post
+    posts: Vec<ImportedPostResult>,
+    /// Blogs to create, with the index of their result in `posts`
+    blogs: Vec<(usize, NewImportedBlog)>,
+    new_categories: Vec<String>,
+    new_tags: Vec<String>,
+    warnings: Vec<String>,
+}
+
+/// Names matched ignoring case, keeping the stored spelling
+struct NameSet {
+    known: HashMap<String, String>,
+    created: Vec<String>,
+}
+
+impl NameSet {
+    fn new(existing: &[String]) -> Self {
+        let mut known = HashMap::new();
+        for name in existing {
+            known
+                .entry(name.to_lowercase())
+                .or_insert_with(|| name.clone());
+        }
+        Self {
+            known,
+            created: Vec::new(),
+        }
+    }
+
+    /// Stored spelling of `name`, recording it as new when it isn't stored yet
+    fn resolve(&mut self, name: &str) -> String {
+        let key = name.to_lowercase();
+        if let Some(existing) = self.known.get(&key) {
+            return existing.clone();
+        }
+        self.known.insert(key, name.to_string());
+        self.created.push(name.to_string());
+        name.to_string()
+    }
+}
+
+fn plan(
+    parsed: ParsedImport,
+    existing_slugs: &HashSet<String>,
+    categories: &[String],
+    tags: &[String],
+    byline: Option<&str>,
+) -> ImportPlan {
+    let mut categories = NameSet::new(categories);
+    let mut tags = NameSet::new(tags);
+    let mut warnings = parsed.warnings;
+    let mut seen_slugs = HashSet::new();
+    let mut posts = Vec::new();
+    let mut blogs = Vec::new();
+
+    for post in parsed.posts {
+        let title = post.title.trim().to_string();
+        let conflict = match &post.slug {
+            _ if title.is_empty() => Some("Post has no title".to_string()),
+            _ if title.chars().count() > MAX_TITLE_LEN => {
+                Some(format!("Title is longer than {} characters", MAX_TITLE_LEN))
+            }
+            None => Some("No slug given and none can be derived from the title".to_string()),
+            Some(slug) if slug.chars().count() > MAX_SLUG_LEN => {
+                Some(format!("Slug is longer than {} characters", MAX_SLUG_LEN))
+            }
+            Some(slug) if existing_slugs.contains(slug) => {
+                Some(format!("A blog with slug '{}' already exists", slug))
+            }
+            Some(slug) if !seen_slugs.insert(slug.clone()) => Some(format!(
+                "Slug '{}' appears more than once in the export",
+                slug
+            )),
+            Some(_) => None,
+        };
+
+        let mut result = ImportedPostResult {
+            title: title.clone(),
+            slug: post.slug.clone(),
+            category: None,
+            tags: Vec::new(),
+            is_published: post.is_published,
+            created_at: post.created_at,
+            action: ImportAction::Skip,
+            conflict,
+            blog_id: None,
+        };
+        let Some(slug) = post.slug.filter(|_| result.conflict.is_none()) else {
+            result.category = post.category;
+            result.tags = post.tags;
+            posts.push(result);
+            continue;
+        };
+
+        // Categories and tags are only created for posts that are imported
+        let category = post.category.and_then(|name| {
+            if name.chars().count() > MAX_CATEGORY_LEN {
+                warnings.push(format!(
+                    "Category '{}' of '{}' is longer than {} characters and was dropped",
+                    name, title, MAX_CATEGORY_LEN
+                ));
+                return None;
+            }
+            Some(categories.resolve(&name))
+        });
+        let mut post_tags: Vec<String> = Vec::new();
+        for name in post.tags {
+            if name.chars().count() > MAX_TAG_LEN {
+                warnings.push(format!(
+                    "Tag '{}' of '{}' is longer than {} characters and was dropped",
+                    name, title, MAX_TAG_LEN
+                ));
+                continue;
+            }
+            let name = tags.resolve(&name);
+            if !post_tags.contains(&name) {
+                post_tags.push(name);
+            }
+        }
+        let thumbnail = post.thumbnail.filter(|url| {
+            let fits = url.len() <= MAX_THUMBNAIL_LEN;
+            if !fits {
+                warnings.push(format!(
+                    "Thumbnail of '{}' is longer than {} characters and was dropped",
+                    title, MAX_THUMBNAIL_LEN
+                ));
+            }
+            fits
+        });
+        let author = post
+            .author
+            .or_else(|| byline.map(ToOwned::to_owned))
+            .map(|author| author.chars().take(MAX_AUTHOR_LEN).collect::<String>());
+
+        result.action = ImportAction::Create;
+        result.category = category.clone();
+        result.tags = post_tags.clone();
+        blogs.push((
+            posts.len(),
+            NewImportedBlog {
+                title,
+                slug,
+                author,
+                html: render_markdown(&post.content),
+                content: post.content,
+                summary: post.summary,
+                thumbnail,
+                category,
+                tags: post_tags,
+                is_published: post.is_published,
+                created_at: post.created_at,
+                updated_at: post.updated_at,
+                references: post.references,
+            },
+        ));
+        posts.push(result);
+    }
+
+    ImportPlan {
+        posts,
+        blogs,
+        new_categories: categories.created,
+        new_tags: tags.created,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::import::ImportedPost;
+
+    fn post(
+        title: &str,
+        slug: Option<&str>,
+        category: Option<&str>,
+        tags: &[&str],
+    ) -> ImportedPost {
+        ImportedPost {
+            title: title.to_string(),
+            slug: slug.map(ToOwned::to_owned),
+            content: "Body".to_string(),
+            category: category.map(ToOwned::to_owned),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn names(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn taxonomy_is_matched_ignoring_case() {
+        let parsed = ParsedImport {
+            posts: vec![
+                post("One", Some("one"), Some("rust"), &["Web", "async", "ASYNC"]),
+                post("Two", Some("two"), Some("Databases"), &["async"]),
+            ],
+            warnings: Vec::new(),
+        };
+
+        let plan = plan(
+            parsed,
+            &HashSet::new(),
+            &names(&["Rust"]),
+            &names(&["web"]),
+            Some("Admin"),
+        );
+
+        assert_eq!(plan.blogs.len(), 2);
+        assert_eq!(plan.new_categories, names(&["Databases"]));
+        assert_eq!(plan.new_tags, names(&["async"]));
+        assert_eq!(plan.posts[0].category.as_deref(), Some("Rust"));
+        assert_eq!(plan.posts[0].tags, names(&["web", "async"]));
+        assert_eq!(plan.blogs[0].1.author.as_deref(), Some("Admin"));
+        assert_eq!(plan.blogs[0].1.html.trim(), "<p>Body</p>");
+    }
+
+    #[test]
+    fn slug_conflicts_are_skipped() {
+        let parsed = ParsedImport {
+            posts: vec![
+                post("Taken", Some("taken"), Some("New"), &["new-tag"]),
+                post("First", Some("dup"), None, &[]),
+                post("Second", Some("dup"), None, &[]),
+                post("你好", None, None, &[]),
+                post("  ", Some("blank"), None, &[]),
+            ],
+            warnings: vec!["from parser".to_string()],
+        };
+        let existing: HashSet<String> = ["taken".to_string()].into_iter().collect();
+
+        let plan = plan(parsed, &existing, &[], &[], None);
+
+        let actions: Vec<ImportAction> = plan.posts.iter().map(|post| post.action).collect();
+        assert_eq!(
+            actions,
+            vec![
+                ImportAction::Skip,
+                ImportAction::Create,
+                ImportAction::Skip,
+                ImportAction::Skip,
+                ImportAction::Skip,
+            ]
+        );
+        assert!(plan.posts[0]
+            .conflict
+            .as_deref()
+            .unwrap()
+            .contains("already exists"));
+        assert!(plan.posts[2]
+            .conflict
+            .as_deref()
+            .unwrap()
+            .contains("more than once"));
+        assert_eq!(plan.blogs.len(), 1);
+        assert_eq!(plan.blogs[0].0, 1);
+        // Taxonomy of skipped posts isn't created
+        assert!(plan.new_categories.is_empty());
+        assert!(plan.new_tags.is_empty());
+        assert_eq!(plan.warnings, names(&["from parser"]));
+    }
+
+    #[test]
+    fn oversized_values_are_dropped_with_a_warning() {
+        let long_tag = "t".repeat(MAX_TAG_LEN + 1);
+        let parsed = ParsedImport {
+            posts: vec![post("Post", Some("post"), None, &[&long_tag, "ok"])],
+            warnings: Vec::new(),
+        };
+
+        let plan = plan(parsed, &HashSet::new(), &[], &[], None);
+
+        assert_eq!(plan.blogs[0].1.tags, names(&["ok"]));
+        assert_eq!(plan.warnings.len(), 1);
+    }
+}
@@ -0,0 +1,180 @@
+//! Link check service - Periodic dead-link detection for friend links and blog outbound links
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::stream::{self, StreamExt};
+use reqwest::Url;
+use sqlx::PgPool;
+
+use crate::error::ApiError;
+use crate::models::link_check::{LinkCheckOutcome, LinkCheckSummary, LinkSourceType};
+use crate::repositories::blog_repo::BlogRepository;
+use crate::repositories::friend_link_repo::FriendLinkRepository;
+use crate::repositories::link_check_repo::LinkCheckRepository;
+use crate::services::page_fetcher::PageFetcher;
+use crate::utils::markdown::extract_link_urls;
+
+/// How often the dead-link checker runs
+pub const LINK_CHECKER_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Number of URLs requested concurrently
+const CHECK_CONCURRENCY: usize = 5;
+
+/// Link check service for business logic
+pub struct LinkCheckService;
+
+impl LinkCheckService {
+    /// Check every approved friend link and every outbound link in blog content once
+    pub async fn run_once(
+        pool: &PgPool,
+        fetcher: &dyn PageFetcher,
+    ) -> Result<LinkCheckSummary, ApiError> {
+        let started = LinkCheckRepository::now(pool).await?;
+
+        let mut targets: Vec<(LinkSourceType, i64, String)> = Vec::new();
+        for link in FriendLinkRepository::find_approved(pool).await? {
+            targets.push((LinkSourceType::FriendLink, link.id, link.url));
+        }
+        for (blog_id, content) in BlogRepository::find_all_contents(pool).await? {
+            for url in extract_link_urls(&content) {
+                targets.push((LinkSourceType::Blog, blog_id, url));
+            }
+        }
+
+        // Request each distinct URL once, even if several blogs link to it
+        let mut urls: Vec<String> = targets.iter().map(|(_, _, url)| url.clone()).collect();
+        urls.sort_unstable();
+        urls.dedup();
+
+        let outcomes: HashMap<String, LinkCheckOutcome> = stream::iter(urls)
+            .map(|url| async move {
+                let outcome = Self::check_url(fetcher, &url).await;
+                (url, outcome)
+            })
+            .buffer_unordered(CHECK_CONCURRENCY)
+            .collect()
+            .await;
+
+        let mut broken = 0;
+        for (source_type, source_id, url) in &targets {
+            let outcome = &outcomes[url];
+            if !outcome.is_ok() {
+                broken += 1;
+            }
+            LinkCheckRepository::record(pool, *source_type, *source_id, url, outcome).await?;
+        }
+
+        let removed = LinkCheckRepository::delete_stale(pool, started).await?;
+
+        let summary = LinkCheckSummary {
+            checked_urls: outcomes.len(),
+            links: targets.len(),
+            broken,
+        };
+        tracing::info!(
+            "Link check finished: {} URLs, {} links, {} broken, {} stale results removed",
+            summary.checked_urls,
+            summary.links,
+            summary.broken,
+            removed
+        );
+
+        Ok(summary)
+    }
+
+    /// Background task: run the checker every `LINK_CHECKER_INTERVAL`
+    ///
+    /// The first run happens one interval after startup, so restarts don't re-check everything.
+    pub async fn run_link_checker(pool: PgPool, fetcher: Arc<dyn PageFetcher>) {
+        let start = tokio::time::Instant::now() + LINK_CHECKER_INTERVAL;
+        let mut interval = tokio::time::interval_at(start, LINK_CHECKER_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = Self::run_once(&pool, fetcher.as_ref()).await {
+                tracing::warn!("Dead-link check failed: {}", e);
+            }
+        }
+    }
+
+    /// Request a URL and describe the result
+    pub async fn check_url(fetcher: &dyn PageFetcher, url: &str) -> LinkCheckOutcome {
+        match fetcher.probe(url).await {
+            Ok(page) => {
+                let requested = Url::parse(url).map(String::from).unwrap_or_default();
+                LinkCheckOutcome {
+                    http_status: Some(i32::from(page.status)),
+                    redirect_url: (page.final_url != requested).then_some(page.final_url),
+                    error: None,
+                }
+            }
+            Err(e) => LinkCheckOutcome {
+                http_status: None,
+                redirect_url: None,
+                error: Some(e.chars().take(500).collect()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::page_fetcher::HttpPageFetcher;
+    use axum::{response::Redirect, routing::get, Router};
+    use reqwest::StatusCode;
+
+    /// Serve a few fixed routes on an ephemeral local port
+    async fn stub_server() -> String {
+        let app = Router::new()
+            .route("/ok", get(|| async { "fine" }))
+            .route("/moved", get(|| async { Redirect::permanent("/ok") }))
+            .route("/gone", get(|| async { StatusCode::NOT_FOUND }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn check_url_records_status_and_redirect_target() {
+        let base = stub_server().await;
+        let fetcher = HttpPageFetcher::allowing_private_networks().unwrap();
+
+        let ok = LinkCheckService::check_url(&fetcher, &format!("{}/ok", base)).await;
+        assert_eq!(ok.http_status, Some(200));
+        assert_eq!(ok.redirect_url, None);
+        assert!(ok.is_ok());
+
+        let moved = LinkCheckService::check_url(&fetcher, &format!("{}/moved", base)).await;
+        assert_eq!(moved.http_status, Some(200));
+        assert_eq!(moved.redirect_url, Some(format!("{}/ok", base)));
+        assert!(moved.is_ok());
+
+        let gone = LinkCheckService::check_url(&fetcher, &format!("{}/gone", base)).await;
+        assert_eq!(gone.http_status, Some(404));
+        assert!(!gone.is_ok());
+    }
+
+    #[tokio::test]
+    async fn check_url_reports_connection_errors() {
+        let fetcher = HttpPageFetcher::allowing_private_networks().unwrap();
+        // Port 9 (discard) is closed on any sane test host
+        let outcome = LinkCheckService::check_url(&fetcher, "http://127.0.0.1:9/").await;
+
+        assert_eq!(outcome.http_status, None);
+        assert!(outcome.error.is_some());
+        assert!(!outcome.is_ok());
+    }
+
+    #[tokio::test]
+    async fn default_fetcher_refuses_loopback() {
+        let base = stub_server().await;
+        let outcome =
+            LinkCheckService::check_url(&HttpPageFetcher::new().unwrap(), &format!("{}/ok", base))
+                .await;
+
+        assert!(outcome.error.is_some_and(|e| e.contains("private address")));
+    }
+}
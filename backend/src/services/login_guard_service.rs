@@ -0,0 +1,332 @@
+//! Login guard service - Brute-force protection for the login endpoints
+//!
+//! Failed logins are counted in sliding windows per IP and per username. Each
+//! failure for a username doubles the wait before the next attempt, and too many
+//! failures lock the username for a while. Thresholds come from the `login_*`
+//! site config keys; every check takes the current time so tests can fix the clock.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::error::ApiError;
+use crate::models::login_event::LoginFailureReason;
+use crate::models::session::SessionClient;
+use crate::repositories::login_event_repo::LoginEventRepository;
+use crate::repositories::site_config_repo::SiteConfigRepo;
+use crate::services::cache_service::{cache_keys, CacheService};
+
+/// Thresholds read from site config
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoginLimits {
+    /// Sliding window failures are counted in
+    pub window: Duration,
+    pub max_failures_per_ip: i64,
+    /// Failures after which the username is locked
+    pub max_failures_per_user: i64,
+    pub lockout: Duration,
+    /// Wait after the first failure, doubled on every further one
+    pub backoff_base_seconds: i64,
+}
+
+impl Default for LoginLimits {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(15 * 60),
+            max_failures_per_ip: 20,
+            max_failures_per_user: 5,
+            lockout: Duration::from_secs(15 * 60),
+            backoff_base_seconds: 1,
+        }
+    }
+}
+
+impl LoginLimits {
+    /// Load limits from the `login_*` site config keys
+    pub async fn load(pool: &PgPool) -> Result<Self, ApiError> {
+        Ok(Self::from_map(&SiteConfigRepo::get_as_map(pool).await?))
+    }
+
+    /// Build limits from config values, using defaults for missing or invalid ones
+    pub fn from_map(map: &HashMap<String, String>) -> Self {
+        let defaults = Self::default();
+        let positive = |key: &str| {
+            map.get(key)
+                .and_then(|value| value.trim().parse::<i64>().ok())
+                .filter(|value| *value > 0)
+        };
+        let minutes = |value: i64| Duration::from_secs(value as u64 * 60);
+
+        Self {
+            window: positive("login_window_minutes")
+                .map(minutes)
+                .unwrap_or(defaults.window),
+            max_failures_per_ip: positive("login_max_failures_per_ip")
+                .unwrap_or(defaults.max_failures_per_ip),
+            max_failures_per_user: positive("login_max_failures_per_user")
+                .unwrap_or(defaults.max_failures_per_user),
+            lockout: positive("login_lockout_minutes")
+                .map(minutes)
+                .unwrap_or(defaults.lockout),
+            backoff_base_seconds: positive("login_backoff_base_seconds")
+                .unwrap_or(defaults.backoff_base_seconds),
+        }
+    }
+
+    /// Wait imposed after the given number of failures, never longer than the lockout
+    pub fn backoff_seconds(&self, failures: i64) -> i64 {
+        if failures < 1 {
+            return 0;
+        }
+        let doublings = (failures - 1).min(30) as u32;
+        self.backoff_base_seconds
+            .saturating_mul(1 << doublings)
+            .min(self.lockout.as_secs() as i64)
+    }
+}
+
+/// Backoff or lockout applied to a username, stored until it ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoginBlock {
+    /// Unix time the block ends
+    pub until: i64,
+    /// Lockout after too many failures rather than a backoff
+    pub locked: bool,
+}
+
+impl LoginBlock {
+    /// Error to return while the block is active, or `None` once it ended
+    pub fn error_at(&self, now: DateTime<Utc>) -> Option<ApiError> {
+        let remaining = self.until - now.timestamp();
+        if remaining <= 0 {
+            return None;
+        }
+
+        Some(ApiError::TooManyRequests(if self.locked {
+            format!(
+                "Account temporarily locked after too many failed logins, try again in {} minutes",
+                (remaining + 59) / 60
+            )
+        } else {
+            format!("Too many failed logins, try again in {} seconds", remaining)
+        }))
+    }
+}
+
+/// Who is trying to log in, and from where
+#[derive(Debug, Clone, Copy)]
+pub struct LoginAttempt<'a> {
+    pub username: &'a str,
+    pub client: &'a SessionClient,
+}
+
+/// Login guard service for business logic
+pub struct LoginGuardService;
+
+impl LoginGuardService {
+    /// Refuse the attempt while the username is blocked or the IP has failed too often
+    pub async fn check(
+        cache: &Arc<CacheService>,
+        limits: &LoginLimits,
+        attempt: &LoginAttempt<'_>,
+        now: DateTime<Utc>,
+    ) -> Result<(), ApiError> {
+        let username = normalize_username(attempt.username);
+        if let Some(block) = cache
+            .get::<LoginBlock>(&cache_keys::login_block(&username))
+            .await?
+        {
+            if let Some(error) = block.error_at(now) {
+                tracing::debug!("Login for '{}' blocked", username);
+                return Err(error);
+            }
+        }
+
+        if let Some(ip) = attempt.client.ip.as_deref() {
+            let failures = cache
+                .count_sliding_window(
+                    &cache_keys::login_failures_ip(ip),
+                    now.timestamp_millis(),
+                    limits.window,
+                )
+                .await?;
+            if failures >= limits.max_failures_per_ip {
+                tracing::debug!("Logins from IP {} rate limited", ip);
+                return Err(ApiError::TooManyRequests(
+                    "Too many failed logins from this address, please try again later".to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Count a failed attempt, apply backoff or lockout and record it
+    pub async fn record_failure(
+        pool: &PgPool,
+        cache: &Arc<CacheService>,
+        limits: &LoginLimits,
+        attempt: &LoginAttempt<'_>,
+        user_id: Option<i64>,
+        reason: LoginFailureReason,
+        now: DateTime<Utc>,
+    ) -> Result<(), ApiError> {
+        let client = attempt.client;
+        let normalized = normalize_username(attempt.username);
+        let now_ms = now.timestamp_millis();
+
+        if let Some(ip) = client.ip.as_deref() {
+            cache
+                .hit_sliding_window(&cache_keys::login_failures_ip(ip), now_ms, limits.window)
+                .await?;
+        }
+        let failures = cache
+            .hit_sliding_window(
+                &cache_keys::login_failures_user(&normalized),
+                now_ms,
+                limits.window,
+            )
+            .await?;
+
+        let locked = failures >= limits.max_failures_per_user;
+        let block = if locked {
+            LoginBlock {
+                until: now.timestamp() + limits.lockout.as_secs() as i64,
+                locked: true,
+            }
+        } else {
+            LoginBlock {
+                until: now.timestamp() + limits.backoff_seconds(failures),
+                locked: false,
+            }
+        };
+        let ttl = (block.until - now.timestamp()).max(1) as u64;
+        cache
+            .set(
+                &cache_keys::login_block(&normalized),
+                &block,
+                Duration::from_secs(ttl),
+            )
+            .await?;
+
+        if locked {
+            tracing::warn!(
+                "Login for '{}' locked after {} failures (last from {})",
+                normalized,
+                failures,
+                client.ip.as_deref().unwrap_or("unknown")
+            );
+        }
+
+        LoginEventRepository::record(pool, attempt.username, user_id, client, reason, locked).await
+    }
+
+    /// Forget the failures of a username after it logged in
+    pub async fn record_success(cache: &Arc<CacheService>, username: &str) -> Result<(), ApiError> {
+        let username = normalize_username(username);
+        cache
+            .delete(&cache_keys::login_failures_user(&username))
+            .await?;
+        cache.delete(&cache_keys::login_block(&username)).await
+    }
+}
+
+/// Usernames are throttled case-insensitively so case variants share a counter
+fn normalize_username(username: &str) -> String {
+    username.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn map(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn limits_fall_back_to_defaults() {
+        assert_eq!(
+            LoginLimits::from_map(&HashMap::new()),
+            LoginLimits::default()
+        );
+        assert_eq!(
+            LoginLimits::from_map(&map(&[
+                ("login_max_failures_per_user", "0"),
+                ("login_window_minutes", "abc"),
+            ])),
+            LoginLimits::default()
+        );
+    }
+
+    #[test]
+    fn limits_read_site_config() {
+        let limits = LoginLimits::from_map(&map(&[
+            ("login_window_minutes", "10"),
+            ("login_max_failures_per_ip", "50"),
+            ("login_max_failures_per_user", " 3 "),
+            ("login_lockout_minutes", "60"),
+            ("login_backoff_base_seconds", "2"),
+        ]));
+
+        assert_eq!(limits.window, Duration::from_secs(600));
+        assert_eq!(limits.max_failures_per_ip, 50);
+        assert_eq!(limits.max_failures_per_user, 3);
+        assert_eq!(limits.lockout, Duration::from_secs(3600));
+        assert_eq!(limits.backoff_base_seconds, 2);
+    }
+
+    #[test]
+    fn backoff_doubles_and_is_capped_by_lockout() {
+        let limits = LoginLimits {
+            backoff_base_seconds: 2,
+            lockout: Duration::from_secs(60),
+            ..LoginLimits::default()
+        };
+
+        assert_eq!(limits.backoff_seconds(0), 0);
+        assert_eq!(limits.backoff_seconds(1), 2);
+        assert_eq!(limits.backoff_seconds(2), 4);
+        assert_eq!(limits.backoff_seconds(4), 16);
+        assert_eq!(limits.backoff_seconds(6), 60);
+        assert_eq!(limits.backoff_seconds(1000), 60);
+    }
+
+    #[test]
+    fn block_expires_at_fixed_time() {
+        let now = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let backoff = LoginBlock {
+            until: now.timestamp() + 8,
+            locked: false,
+        };
+        let lockout = LoginBlock {
+            until: now.timestamp() + 61,
+            locked: true,
+        };
+
+        assert!(matches!(
+            backoff.error_at(now),
+            Some(ApiError::TooManyRequests(message)) if message.contains("8 seconds")
+        ));
+        assert!(matches!(
+            lockout.error_at(now),
+            Some(ApiError::TooManyRequests(message)) if message.contains("2 minutes")
+        ));
+        assert!(backoff
+            .error_at(now + chrono::Duration::seconds(8))
+            .is_none());
+    }
+
+    #[test]
+    fn usernames_are_throttled_case_insensitively() {
+        assert_eq!(normalize_username(" Admin "), "admin");
+    }
+}
@@ -4,4 +4,21 @@ pub mod ai_service;
 pub mod auth_service;
 pub mod blog_service;
 pub mod cache_service;
+pub mod comment_service;
+pub mod data_export_service;
+pub mod direct_upload_service;
+pub mod feed_service;
+pub mod file_gc_service;
+pub mod file_service;
+pub mod friend_link_service;
+pub mod import_service;
+pub mod link_check_service;
+pub mod login_guard_service;
+pub mod page_fetcher;
 pub mod s3_service;
+pub mod session_service;
+pub mod site_export_service;
+pub mod sitemap_service;
+pub mod storage;
+pub mod text_service;
+pub mod two_factor_service;
@@ -0,0 +1,310 @@
+//! Page fetcher - HTTP access to third-party pages behind a mockable trait
+//!
+//! Used by background checks that load URLs submitted by visitors, so the
+//! default implementation refuses to connect to loopback or private networks.
+//! Host names are filtered by the DNS resolver, i.e. at connect time for the
+//! first request and every redirect alike, so a name that resolves (or is
+//! rebound) to a private address never gets a connection.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::{redirect, Client, Url};
+
+/// Request timeout for a single page fetch
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum number of body bytes read from a page
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Maximum number of redirects followed
+const MAX_REDIRECTS: usize = 5;
+
+/// A fetched page: final status code, URL after redirects and (possibly truncated) body
+#[derive(Debug, Clone)]
+pub struct FetchedPage {
+    pub status: u16,
+    pub final_url: String,
+    pub body: String,
+}
+
+/// Fetches a page by URL
+///
+/// Errors are human-readable strings, stored as-is on the checked record.
+#[async_trait]
+pub trait PageFetcher: Send + Sync {
+    async fn fetch(&self, url: &str) -> Result<FetchedPage, String>;
+
+    /// Like `fetch`, for callers that only need the status and final URL
+    async fn probe(&self, url: &str) -> Result<FetchedPage, String> {
+        self.fetch(url).await
+    }
+}
+
+/// `PageFetcher` backed by reqwest
+pub struct HttpPageFetcher {
+    client: Client,
+    allow_private: bool,
+}
+
+impl HttpPageFetcher {
+    pub fn new() -> Result<Self, reqwest::Error> {
+        Self::build(false)
+    }
+
+    /// Fetcher that may connect to loopback and private networks (for local stub servers in tests)
+    #[cfg(test)]
+    pub fn allowing_private_networks() -> Result<Self, reqwest::Error> {
+        Self::build(true)
+    }
+
+    fn build(allow_private: bool) -> Result<Self, reqwest::Error> {
+        let mut builder = Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .redirect(redirect::Policy::custom(move |attempt| {
+                if attempt.previous().len() >= MAX_REDIRECTS {
+                    attempt.error("too many redirects")
+                } else if !allow_private && is_private_literal_host(attempt.url()) {
+                    attempt.error("redirect to a private address")
+                } else {
+                    attempt.follow()
+                }
+            }))
+            .user_agent(concat!("blog-backend/", env!("CARGO_PKG_VERSION")));
+        if !allow_private {
+            // A proxy would resolve the target itself, bypassing the resolver
+            builder = builder
+                .no_proxy()
+                .dns_resolver(Arc::new(PublicOnlyResolver));
+        }
+        // Failing here rather than falling back to a default client, which
+        // would silently drop the private network filtering
+        let client = builder.build()?;
+
+        Ok(Self {
+            client,
+            allow_private,
+        })
+    }
+
+    async fn send(&self, url: &str) -> Result<reqwest::Response, String> {
+        let parsed = Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(format!("Unsupported URL scheme: {}", parsed.scheme()));
+        }
+        if !self.allow_private && is_private_literal_host(&parsed) {
+            return Err("URL points to a private address".to_string());
+        }
+
+        self.client
+            .get(parsed)
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))
+    }
+}
+
+#[async_trait]
+impl PageFetcher for HttpPageFetcher {
+    async fn fetch(&self, url: &str) -> Result<FetchedPage, String> {
+        let mut response = self.send(url).await?;
+        let status = response.status().as_u16();
+        let final_url = response.url().to_string();
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("Failed to read body: {}", e))?
+        {
+            let remaining = MAX_BODY_BYTES - body.len();
+            body.extend_from_slice(&chunk[..chunk.len().min(remaining)]);
+            if body.len() >= MAX_BODY_BYTES {
+                break;
+            }
+        }
+
+        Ok(FetchedPage {
+            status,
+            final_url,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        })
+    }
+
+    async fn probe(&self, url: &str) -> Result<FetchedPage, String> {
+        let response = self.send(url).await?;
+
+        Ok(FetchedPage {
+            status: response.status().as_u16(),
+            final_url: response.url().to_string(),
+            body: String::new(),
+        })
+    }
+}
+
+/// Resolver that only hands out public addresses
+struct PublicOnlyResolver;
+
+impl Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} resolves to a private address", host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// IP literal hosts are connected to without a DNS lookup, so the resolver
+/// never sees them; they are checked here instead
+fn is_private_literal_host(url: &Url) -> bool {
+    let Some(host) = url.host_str() else {
+        return true;
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+
+    host.parse::<IpAddr>().is_ok_and(|ip| !is_public_ip(ip))
+}
+
+/// Whether an address is publicly routable
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_private()
+                // 0.0.0.0/8 "this network"
+                || ip.octets()[0] == 0
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                // 100.64.0.0/10 carrier-grade NAT
+                || (ip.octets()[0] == 100 && (ip.octets()[1] & 0xc0) == 64))
+        }
+        IpAddr::V6(ip) => {
+            // IPv4-mapped ::ffff:a.b.c.d and IPv4-compatible ::a.b.c.d
+            if let Some(v4) = ip.to_ipv4() {
+                return is_public_ip(IpAddr::V4(v4));
+            }
+            let segments = ip.segments();
+            // NAT64 64:ff9b::/96 and 6to4 2002::/16 reach the IPv4 address they embed
+            if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+                return is_public_ip(IpAddr::V4(embedded_ipv4(segments[6], segments[7])));
+            }
+            if segments[0] == 0x2002 {
+                return is_public_ip(IpAddr::V4(embedded_ipv4(segments[1], segments[2])));
+            }
+            let first = segments[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                // fc00::/7 unique local
+                || (first & 0xfe00) == 0xfc00
+                // fe80::/10 link local
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// IPv4 address stored in two IPv6 segments
+fn embedded_ipv4(high: u16, low: u16) -> Ipv4Addr {
+    Ipv4Addr::from((u32::from(high) << 16) | u32::from(low))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn private_and_loopback_addresses_are_not_public() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "0.1.2.3",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::127.0.0.1",
+            "::10.0.0.1",
+            "64:ff9b::127.0.0.1",
+            "64:ff9b::a9fe:a9fe",
+            "2002:7f00:1::",
+            "2002:c0a8:101::1",
+        ] {
+            assert!(
+                !is_public_ip(ip.parse().unwrap()),
+                "{} should be blocked",
+                ip
+            );
+        }
+    }
+
+    #[test]
+    fn public_addresses_are_allowed() {
+        for ip in [
+            "1.1.1.1",
+            "8.8.8.8",
+            "2606:4700:4700::1111",
+            "64:ff9b::8.8.8.8",
+            "2002:808:808::1",
+        ] {
+            assert!(
+                is_public_ip(ip.parse().unwrap()),
+                "{} should be allowed",
+                ip
+            );
+        }
+    }
+
+    #[test]
+    fn private_literals_are_blocked() {
+        assert!(is_private_literal_host(
+            &Url::parse("http://127.0.0.1/admin").unwrap()
+        ));
+        assert!(is_private_literal_host(
+            &Url::parse("http://[::1]:8080/").unwrap()
+        ));
+        assert!(!is_private_literal_host(
+            &Url::parse("https://example.com/").unwrap()
+        ));
+    }
+
+    #[tokio::test]
+    async fn names_resolving_to_private_addresses_are_not_resolved() {
+        let name: Name = "localhost".parse().unwrap();
+        assert!(PublicOnlyResolver.resolve(name).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn host_names_of_private_addresses_are_refused() {
+        let app = axum::Router::new().route("/", axum::routing::get(|| async { "hi" }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        let url = format!("http://localhost:{}/", port);
+
+        let allowed = HttpPageFetcher::allowing_private_networks()
+            .unwrap()
+            .fetch(&url)
+            .await
+            .unwrap();
+        assert_eq!(allowed.body, "hi");
+        assert!(HttpPageFetcher::new().unwrap().fetch(&url).await.is_err());
+    }
+}
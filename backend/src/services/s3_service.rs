@@ -1,15 +1,29 @@
 //! S3 storage service
 //!
 //! Provides file upload and delete operations for S3-compatible storage.
+//! Implements the `Storage` trait as the `s3` backend, and presigns requests
+//! so browsers can upload large files directly to the bucket.
 
+use std::time::Duration;
+
+use async_trait::async_trait;
 use aws_config::BehaviorVersion;
 use aws_sdk_s3::config::{Credentials, Region};
+use aws_sdk_s3::presigning::PresigningConfig;
 use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart};
 use aws_sdk_s3::Client;
-use uuid::Uuid;
 
 use crate::config::S3Config;
 use crate::error::ApiError;
+use crate::services::storage::{Storage, StorageBackend, StoredObject, UploadResult};
+
+/// Metadata of a stored object, from a HEAD request
+#[derive(Debug, Clone)]
+pub struct ObjectInfo {
+    pub size: i64,
+    pub content_type: Option<String>,
+}
 
 /// S3 service for file operations
 #[derive(Clone)]
@@ -20,14 +34,6 @@ pub struct S3Service {
     public_url: String,
 }
 
-/// Result of a file upload operation
-#[derive(Debug, Clone)]
-pub struct UploadResult {
-    pub object_key: String,
-    pub url: String,
-    pub bucket: String,
-}
-
 impl S3Service {
     /// Create a new S3 service instance
     pub async fn new(config: &S3Config) -> Result<Self, ApiError> {
@@ -57,40 +63,20 @@ impl S3Service {
         })
     }
 
-    /// Upload a file to S3
-    ///
-    /// # Arguments
-    /// * `data` - File content as bytes
-    /// * `original_filename` - Original filename for extension extraction
-    /// * `content_type` - MIME type of the file
-    ///
-    /// # Returns
-    /// Upload result containing the object key and URL
-    pub async fn upload_file(
+    /// Upload data under the given object key
+    pub async fn upload_object(
         &self,
+        object_key: &str,
         data: Vec<u8>,
-        original_filename: &str,
         content_type: &str,
     ) -> Result<UploadResult, ApiError> {
-        // Generate unique object key with original extension
-        let extension = std::path::Path::new(original_filename)
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("");
-
-        let object_key = if extension.is_empty() {
-            format!("uploads/{}", Uuid::new_v4())
-        } else {
-            format!("uploads/{}.{}", Uuid::new_v4(), extension)
-        };
-
         // Upload to S3
         let body = ByteStream::from(data);
 
         self.client
             .put_object()
             .bucket(&self.bucket)
-            .key(&object_key)
+            .key(object_key)
             .body(body)
             .content_type(content_type)
             .send()
@@ -100,8 +86,18 @@ impl S3Service {
                 ApiError::FileUploadError(format!("Failed to upload file: {}", e))
             })?;
 
+        tracing::info!("File uploaded successfully: {}", object_key);
+
+        Ok(UploadResult {
+            object_key: object_key.to_string(),
+            url: self.object_url(object_key),
+        })
+    }
+
+    /// Public URL of an object
+    pub fn object_url(&self, object_key: &str) -> String {
         // Prefer configured public URL for browser access. Fall back to endpoint-style URLs.
-        let url = if self.public_url.trim().is_empty() {
+        if self.public_url.trim().is_empty() {
             format!(
                 "{}/{}/{}",
                 self.endpoint.trim_end_matches('/'),
@@ -110,17 +106,138 @@ impl S3Service {
             )
         } else {
             format!("{}/{}", self.public_url.trim_end_matches('/'), object_key)
-        };
+        }
+    }
 
-        tracing::info!("File uploaded successfully: {}", object_key);
+    /// Presigned URL for uploading an object with a single PUT
+    pub async fn presign_put(
+        &self,
+        object_key: &str,
+        content_type: &str,
+        expires_in: Duration,
+    ) -> Result<String, ApiError> {
+        let request = self
+            .client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(object_key)
+            .content_type(content_type)
+            .presigned(Self::presigning_config(expires_in)?)
+            .await
+            .map_err(|e| ApiError::FileUploadError(format!("Failed to presign upload: {}", e)))?;
 
-        Ok(UploadResult {
-            object_key,
-            url,
-            bucket: self.bucket.clone(),
+        Ok(request.uri().to_string())
+    }
+
+    /// Start a multipart upload, returning its upload ID
+    pub async fn create_multipart_upload(
+        &self,
+        object_key: &str,
+        content_type: &str,
+    ) -> Result<String, ApiError> {
+        let output = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.bucket)
+            .key(object_key)
+            .content_type(content_type)
+            .send()
+            .await
+            .map_err(|e| {
+                tracing::error!("S3 create multipart upload error: {:?}", e);
+                ApiError::FileUploadError(format!("Failed to start multipart upload: {}", e))
+            })?;
+
+        output.upload_id().map(ToOwned::to_owned).ok_or_else(|| {
+            ApiError::FileUploadError("Storage returned no multipart upload ID".to_string())
         })
     }
 
+    /// Presigned URL for uploading one part of a multipart upload
+    pub async fn presign_upload_part(
+        &self,
+        object_key: &str,
+        upload_id: &str,
+        part_number: i32,
+        expires_in: Duration,
+    ) -> Result<String, ApiError> {
+        let request = self
+            .client
+            .upload_part()
+            .bucket(&self.bucket)
+            .key(object_key)
+            .upload_id(upload_id)
+            .part_number(part_number)
+            .presigned(Self::presigning_config(expires_in)?)
+            .await
+            .map_err(|e| ApiError::FileUploadError(format!("Failed to presign upload: {}", e)))?;
+
+        Ok(request.uri().to_string())
+    }
+
+    /// Assemble the uploaded parts, given as part numbers and ETags
+    pub async fn complete_multipart_upload(
+        &self,
+        object_key: &str,
+        upload_id: &str,
+        parts: &[(i32, String)],
+    ) -> Result<(), ApiError> {
+        let parts = parts
+            .iter()
+            .map(|(part_number, e_tag)| {
+                CompletedPart::builder()
+                    .part_number(*part_number)
+                    .e_tag(e_tag)
+                    .build()
+            })
+            .collect();
+
+        self.client
+            .complete_multipart_upload()
+            .bucket(&self.bucket)
+            .key(object_key)
+            .upload_id(upload_id)
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(parts))
+                    .build(),
+            )
+            .send()
+            .await
+            .map_err(|e| {
+                tracing::error!("S3 complete multipart upload error: {:?}", e);
+                ApiError::FileUploadError(format!("Failed to complete multipart upload: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Abort a multipart upload, discarding the parts uploaded so far
+    pub async fn abort_multipart_upload(
+        &self,
+        object_key: &str,
+        upload_id: &str,
+    ) -> Result<(), ApiError> {
+        self.client
+            .abort_multipart_upload()
+            .bucket(&self.bucket)
+            .key(object_key)
+            .upload_id(upload_id)
+            .send()
+            .await
+            .map_err(|e| {
+                tracing::error!("S3 abort multipart upload error: {:?}", e);
+                ApiError::FileUploadError(format!("Failed to abort multipart upload: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    fn presigning_config(expires_in: Duration) -> Result<PresigningConfig, ApiError> {
+        PresigningConfig::expires_in(expires_in)
+            .map_err(|e| ApiError::InternalError(format!("Invalid presigning config: {}", e)))
+    }
+
     /// Delete a file from S3
     ///
     /// # Arguments
@@ -142,8 +259,88 @@ impl S3Service {
         Ok(())
     }
 
+    /// List every object whose key starts with `prefix`, following pagination
+    pub async fn list_objects(&self, prefix: &str) -> Result<Vec<StoredObject>, ApiError> {
+        let mut objects = Vec::new();
+        let mut continuation_token: Option<String> = None;
+
+        loop {
+            let output = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.bucket)
+                .prefix(prefix)
+                .set_continuation_token(continuation_token.take())
+                .send()
+                .await
+                .map_err(|e| {
+                    tracing::error!("S3 list error: {:?}", e);
+                    ApiError::InternalError(format!("Failed to list files: {}", e))
+                })?;
+
+            for object in output.contents() {
+                let Some(key) = object.key() else {
+                    continue;
+                };
+                objects.push(StoredObject {
+                    object_key: key.to_string(),
+                    size: object.size().unwrap_or(0),
+                    last_modified: object.last_modified().and_then(|time| {
+                        chrono::DateTime::from_timestamp(time.secs(), time.subsec_nanos())
+                    }),
+                });
+            }
+
+            match output.next_continuation_token() {
+                Some(token) if output.is_truncated().unwrap_or(false) => {
+                    continuation_token = Some(token.to_string());
+                }
+                _ => break,
+            }
+        }
+
+        Ok(objects)
+    }
+
+    /// Download an object, `None` if it doesn't exist
+    pub async fn get_object(&self, object_key: &str) -> Result<Option<Vec<u8>>, ApiError> {
+        let output = match self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(object_key)
+            .send()
+            .await
+        {
+            Ok(output) => output,
+            Err(e) => {
+                let service_error = e.into_service_error();
+                if service_error.is_no_such_key() {
+                    return Ok(None);
+                }
+                return Err(ApiError::InternalError(format!(
+                    "Failed to read file: {}",
+                    service_error
+                )));
+            }
+        };
+
+        let data = output
+            .body
+            .collect()
+            .await
+            .map_err(|e| ApiError::InternalError(format!("Failed to read file: {}", e)))?;
+
+        Ok(Some(data.into_bytes().to_vec()))
+    }
+
     /// Check if a file exists in S3
     pub async fn file_exists(&self, object_key: &str) -> Result<bool, ApiError> {
+        Ok(self.head_object(object_key).await?.is_some())
+    }
+
+    /// Size and content type of an object, `None` if it doesn't exist
+    pub async fn head_object(&self, object_key: &str) -> Result<Option<ObjectInfo>, ApiError> {
         match self
             .client
             .head_object()
@@ -152,12 +349,15 @@ impl S3Service {
             .send()
             .await
         {
-            Ok(_) => Ok(true),
+            Ok(output) => Ok(Some(ObjectInfo {
+                size: output.content_length().unwrap_or(0),
+                content_type: output.content_type().map(ToOwned::to_owned),
+            })),
             Err(e) => {
                 // Check if it's a "not found" error
                 let service_error = e.into_service_error();
                 if service_error.is_not_found() {
-                    Ok(false)
+                    Ok(None)
                 } else {
                     Err(ApiError::FileUploadError(format!(
                         "Failed to check file existence: {}",
@@ -167,9 +367,36 @@ impl S3Service {
             }
         }
     }
+}
+
+#[async_trait]
+impl Storage for S3Service {
+    fn backend(&self) -> StorageBackend {
+        StorageBackend::S3
+    }
+
+    fn bucket_name(&self) -> Option<&str> {
+        Some(&self.bucket)
+    }
+
+    async fn put(
+        &self,
+        object_key: &str,
+        data: Vec<u8>,
+        content_type: &str,
+    ) -> Result<UploadResult, ApiError> {
+        self.upload_object(object_key, data, content_type).await
+    }
+
+    async fn get(&self, object_key: &str) -> Result<Option<Vec<u8>>, ApiError> {
+        self.get_object(object_key).await
+    }
+
+    async fn delete(&self, object_key: &str) -> Result<(), ApiError> {
+        self.delete_file(object_key).await
+    }
 
-    /// Get the bucket name
-    pub fn bucket(&self) -> &str {
-        &self.bucket
+    async fn list(&self, prefix: &str) -> Result<Vec<StoredObject>, ApiError> {
+        self.list_objects(prefix).await
     }
 }
@@ -0,0 +1,184 @@
+//! Session service - Server-side login sessions with refresh token rotation
+//!
+//! Every login starts a session in Redis. Each `/auth/refresh` rotates the
+//! session's refresh token; presenting a token that was already rotated means it
+//! leaked, so the whole session is revoked.
+
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::models::session::{SessionClient, UserSession};
+use crate::models::user::{LoginResponse, RefreshTokenResponse, User};
+use crate::repositories::user_repo::UserRepository;
+use crate::services::auth_service::AuthService;
+use crate::services::cache_service::{cache_keys, CacheService};
+
+/// Session service for business logic
+pub struct SessionService;
+
+impl SessionService {
+    /// Start a session for a user who just authenticated
+    pub async fn login(
+        cache: &CacheService,
+        auth: &AuthService,
+        user: User,
+        client: SessionClient,
+    ) -> Result<LoginResponse, ApiError> {
+        let session = UserSession {
+            session_id: new_id(),
+            user_id: user.id,
+            username: user.username.clone(),
+            refresh_jti: new_id(),
+            ip: client.ip,
+            user_agent: client.user_agent,
+            created_at: Utc::now(),
+            last_refreshed_at: None,
+        };
+        Self::save(cache, auth, &session).await?;
+
+        auth.create_login_response(user, &session.session_id, &session.refresh_jti)
+    }
+
+    /// Exchange a refresh token for new access and refresh tokens
+    pub async fn refresh(
+        pool: &PgPool,
+        cache: &CacheService,
+        auth: &AuthService,
+        refresh_token: &str,
+    ) -> Result<RefreshTokenResponse, ApiError> {
+        let claims = auth.verify_refresh_token(refresh_token)?;
+        let (Some(session_id), Some(jti)) = (claims.sid, claims.jti) else {
+            return Err(ApiError::Unauthorized(
+                "Session expired, please log in again".to_string(),
+            ));
+        };
+
+        let key = cache_keys::user_session(claims.sub, &session_id);
+        let (mut session, stored) = cache
+            .get_with_raw::<UserSession>(&key)
+            .await?
+            .ok_or_else(|| ApiError::Unauthorized("Session has been revoked".to_string()))?;
+
+        if session.refresh_jti != jti {
+            return Err(Self::revoke_reused(cache, &key, &session).await);
+        }
+
+        // Make sure the user still exists
+        let user = UserRepository::find_by_id(pool, claims.sub)
+            .await?
+            .ok_or_else(|| ApiError::Unauthorized("User not found".to_string()))?;
+
+        // Rotate only if the session is unchanged since it was read; otherwise a
+        // concurrent refresh with the same token already rotated it
+        session.refresh_jti = new_id();
+        session.last_refreshed_at = Some(Utc::now());
+        if !cache
+            .compare_and_set(&key, &stored, &session, auth.session_ttl())
+            .await?
+        {
+            return Err(Self::revoke_reused(cache, &key, &session).await);
+        }
+
+        auth.create_refresh_response(&user, &session.session_id, &session.refresh_jti)
+    }
+
+    /// End the session a refresh token belongs to
+    pub async fn logout(
+        cache: &CacheService,
+        auth: &AuthService,
+        refresh_token: &str,
+    ) -> Result<(), ApiError> {
+        let claims = auth.verify_refresh_token(refresh_token)?;
+        if let Some(session_id) = claims.sid {
+            cache
+                .delete(&cache_keys::user_session(claims.sub, &session_id))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether a session is still active
+    pub async fn is_active(
+        cache: &CacheService,
+        user_id: i64,
+        session_id: &str,
+    ) -> Result<bool, ApiError> {
+        cache
+            .exists(&cache_keys::user_session(user_id, session_id))
+            .await
+    }
+
+    /// All active sessions, most recently created first
+    pub async fn list(cache: &CacheService) -> Result<Vec<UserSession>, ApiError> {
+        let keys = cache.keys(&cache_keys::session_pattern("*")).await?;
+
+        let mut sessions = Vec::with_capacity(keys.len());
+        for key in keys {
+            // A session may expire between KEYS and GET
+            if let Some(session) = cache.get::<UserSession>(&key).await? {
+                sessions.push(session);
+            }
+        }
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.created_at));
+
+        Ok(sessions)
+    }
+
+    /// Revoke one session; returns false if it does not exist
+    pub async fn revoke(cache: &CacheService, session_id: &str) -> Result<bool, ApiError> {
+        // Session IDs are hex; anything else could widen the key pattern
+        if session_id.is_empty() || !session_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Ok(false);
+        }
+
+        let keys = cache.keys(&cache_keys::session_pattern(session_id)).await?;
+        for key in &keys {
+            cache.delete(key).await?;
+        }
+
+        Ok(!keys.is_empty())
+    }
+
+    /// Revoke every session of a user (after deletion or a password change)
+    pub async fn revoke_user(cache: &CacheService, user_id: i64) -> Result<(), ApiError> {
+        cache
+            .delete_pattern(&cache_keys::user_sessions_pattern(user_id))
+            .await
+    }
+
+    /// Store a session until its latest refresh token would expire
+    /// Revoke a session whose refresh token was presented after it had been rotated
+    async fn revoke_reused(cache: &CacheService, key: &str, session: &UserSession) -> ApiError {
+        if let Err(e) = cache.delete(key).await {
+            return e;
+        }
+        tracing::warn!(
+            "Reuse of a rotated refresh token for user '{}', revoked session {}",
+            session.username,
+            session.session_id
+        );
+
+        ApiError::Unauthorized("Refresh token reuse detected, session revoked".to_string())
+    }
+
+    async fn save(
+        cache: &CacheService,
+        auth: &AuthService,
+        session: &UserSession,
+    ) -> Result<(), ApiError> {
+        cache
+            .set(
+                &cache_keys::user_session(session.user_id, &session.session_id),
+                session,
+                auth.session_ttl(),
+            )
+            .await
+    }
+}
+
+fn new_id() -> String {
+    Uuid::new_v4().simple().to_string()
+}
@@ -0,0 +1,386 @@
+//! Site export service - Zip of the blog as a static-site source tree
+//!
+//! Unlike the JSON backup, the archive is plain Markdown with front matter
+//! (see `utils::site_export` for the layout), so Hugo or Hexo can build it
+//! and the Markdown importer can read it back. With assets enabled, uploads
+//! referenced from content are copied into the archive and their links
+//! rewritten. The archive is built in memory, so uploads above a size cap,
+//! or beyond a budget for all assets, are left out like unreadable ones: they
+//! keep their original URL and are listed in `skipped-assets.txt`.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{Cursor, Write};
+use std::sync::Arc;
+
+use serde_json::Value as JsonValue;
+use sqlx::PgPool;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::repositories::directory_repo::DirectoryRepository;
+use crate::repositories::file_repo::FileRepository;
+use crate::repositories::file_variant_repo::FileVariantRepository;
+use crate::repositories::site_export_repo::SiteExportRepository;
+use crate::services::file_gc_service::extract_upload_keys;
+use crate::services::storage::{Storage, StorageBackend, StorageService};
+use crate::utils::site_export::{
+    asset_path, blog_markdown, blog_paths, directory_index, directory_paths, document_markdown,
+    document_paths, rewrite_links, SKIPPED_ASSETS_FILE,
+};
+
+/// Largest single upload copied into an archive
+const MAX_ASSET_BYTES: i64 = 20 * 1024 * 1024;
+
+/// Total size of the uploads copied into one archive
+const MAX_TOTAL_ASSET_BYTES: i64 = 200 * 1024 * 1024;
+
+/// Site export service for business logic
+pub struct SiteExportService;
+
+impl SiteExportService {
+    /// Build the zip archive, copying referenced uploads into it when `include_assets` is set
+    pub async fn export(
+        pool: &PgPool,
+        config: &Config,
+        include_assets: bool,
+    ) -> Result<Vec<u8>, ApiError> {
+        let mut blogs = SiteExportRepository::find_blogs(pool).await?;
+        let directories = DirectoryRepository::find_all(pool).await?;
+        let mut documents = SiteExportRepository::find_documents(pool).await?;
+
+        let mut archive = Archive::new();
+
+        if include_assets {
+            let mut texts: Vec<String> = Vec::new();
+            for blog in &blogs {
+                texts.push(blog.content.clone());
+                texts.extend(blog.thumbnail.clone());
+                texts.extend(blog.references.as_ref().map(JsonValue::to_string));
+            }
+            for document in &documents {
+                texts.push(document.content.clone());
+                texts.extend(document.references.as_ref().map(JsonValue::to_string));
+            }
+            let referenced: HashSet<&str> = texts
+                .iter()
+                .flat_map(|text| extract_upload_keys(text))
+                .collect();
+
+            let (links, skipped) =
+                Self::copy_assets(pool, config, &referenced, &mut archive).await?;
+            if !skipped.is_empty() {
+                tracing::warn!("Site export: skipped {} assets", skipped.len());
+                archive.add_text(SKIPPED_ASSETS_FILE, &skipped_assets_report(&skipped))?;
+            }
+            if !links.is_empty() {
+                for blog in &mut blogs {
+                    blog.content = rewrite_links(&blog.content, &links);
+                    blog.thumbnail = blog
+                        .thumbnail
+                        .as_deref()
+                        .map(|thumbnail| rewrite_links(thumbnail, &links));
+                    blog.references = blog
+                        .references
+                        .take()
+                        .map(|references| rewrite_json(references, &links));
+                }
+                for document in &mut documents {
+                    document.content = rewrite_links(&document.content, &links);
+                    document.references = document
+                        .references
+                        .take()
+                        .map(|references| rewrite_json(references, &links));
+                }
+            }
+        }
+
+        for (blog, path) in blogs.iter().zip(blog_paths(&blogs)) {
+            archive.add_text(&path, &blog_markdown(blog))?;
+        }
+        let directory_paths = directory_paths(&directories);
+        for directory in &directories {
+            if let Some(path) = directory_paths.get(&directory.id) {
+                archive.add_text(&format!("{}/_index.md", path), &directory_index(directory))?;
+            }
+        }
+        for (document, path) in documents
+            .iter()
+            .zip(document_paths(&documents, &directory_paths))
+        {
+            archive.add_text(&path, &document_markdown(document))?;
+        }
+
+        let data = archive.finish()?;
+
+        tracing::info!(
+            "Site export: {} blogs, {} directories, {} documents ({} bytes)",
+            blogs.len(),
+            directories.len(),
+            documents.len(),
+            data.len()
+        );
+
+        Ok(data)
+    }
+
+    /// Copy referenced uploads and their variants into `assets/`
+    ///
+    /// Returns `(upload URL, asset link)` pairs for rewriting content, and the
+    /// uploads that were left out.
+    async fn copy_assets(
+        pool: &PgPool,
+        config: &Config,
+        referenced: &HashSet<&str>,
+        archive: &mut Archive,
+    ) -> Result<(Vec<(String, String)>, Vec<SkippedAsset>), ApiError> {
+        if referenced.is_empty() {
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        // Object key -> (URL, backend, size) of every upload and variant
+        let files = FileRepository::find_all_unpaged(pool).await?;
+        let backends: HashMap<i64, String> = files
+            .iter()
+            .map(|file| (file.id, file.storage_backend.clone()))
+            .collect();
+        let mut objects: HashMap<String, (String, String, Option<i64>)> = HashMap::new();
+        for file in files {
+            if let Some(object_key) = file.object_key {
+                objects.insert(object_key, (file.url, file.storage_backend, file.file_size));
+            }
+        }
+        for variant in FileVariantRepository::find_all(pool).await? {
+            if let Some(backend) = backends.get(&variant.file_id) {
+                objects.insert(
+                    variant.object_key,
+                    (variant.url, backend.clone(), Some(variant.file_size)),
+                );
+            }
+        }
+
+        let mut storages: HashMap<String, Option<Arc<dyn Storage>>> = HashMap::new();
+        let mut links = Vec::new();
+        let mut skipped = Vec::new();
+        let mut total_bytes = 0;
+        let mut keys: Vec<&&str> = referenced.iter().collect();
+        keys.sort();
+        for object_key in keys {
+            let Some((url, backend, size)) = objects.get(*object_key) else {
+                continue;
+            };
+            let skip = |reason: String| SkippedAsset {
+                url: url.clone(),
+                reason,
+            };
+
+            // Checked before downloading when the size is on record, and again after
+            if let Some(reason) = size.and_then(|size| size_skip_reason(size, total_bytes)) {
+                skipped.push(skip(reason));
+                continue;
+            }
+
+            if !storages.contains_key(backend) {
+                let storage = match StorageBackend::parse(backend) {
+                    Some(parsed) => match StorageService::open(pool, config, parsed).await {
+                        Ok(storage) => Some(storage),
+                        Err(e) => {
+                            tracing::warn!("Site export can't open {} storage: {}", backend, e);
+                            None
+                        }
+                    },
+                    None => None,
+                };
+                storages.insert(backend.clone(), storage);
+            }
+            let Some(storage) = storages.get(backend).and_then(Option::as_ref) else {
+                skipped.push(skip(format!("{} storage is unavailable", backend)));
+                continue;
+            };
+
+            match storage.get(object_key).await {
+                Ok(Some(data)) => {
+                    if let Some(reason) = size_skip_reason(data.len() as i64, total_bytes) {
+                        skipped.push(skip(reason));
+                        continue;
+                    }
+                    total_bytes += data.len() as i64;
+                    let path = asset_path(object_key);
+                    archive.add_binary(&path, &data)?;
+                    links.push((url.clone(), format!("/{}", path)));
+                }
+                Ok(None) => {
+                    tracing::warn!("Site export: upload {} is missing", object_key);
+                    skipped.push(skip("missing from storage".to_string()));
+                }
+                Err(e) => {
+                    tracing::warn!("Site export can't read {}: {}", object_key, e);
+                    skipped.push(skip(format!("unreadable: {}", e)));
+                }
+            }
+        }
+
+        Ok((links, skipped))
+    }
+}
+
+/// A referenced upload left out of the archive
+struct SkippedAsset {
+    url: String,
+    reason: String,
+}
+
+/// Why an asset of `size` bytes can't be added after `total_bytes` of assets, if it can't
+fn size_skip_reason(size: i64, total_bytes: i64) -> Option<String> {
+    if size > MAX_ASSET_BYTES {
+        Some(format!(
+            "{} bytes is over the {} bytes limit per asset",
+            size, MAX_ASSET_BYTES
+        ))
+    } else if total_bytes + size > MAX_TOTAL_ASSET_BYTES {
+        Some(format!(
+            "the archive would exceed {} bytes of assets",
+            MAX_TOTAL_ASSET_BYTES
+        ))
+    } else {
+        None
+    }
+}
+
+/// Contents of `skipped-assets.txt`: one `URL<TAB>reason` line per asset
+fn skipped_assets_report(skipped: &[SkippedAsset]) -> String {
+    let mut report = String::from(
+        "# Uploads not copied into this archive; links to them keep the original URL\n",
+    );
+    for asset in skipped {
+        report.push_str(&format!("{}\t{}\n", asset.url, asset.reason));
+    }
+    report
+}
+
+/// Rewrite links inside a JSON value such as blog references
+fn rewrite_json(value: JsonValue, links: &[(String, String)]) -> JsonValue {
+    match value {
+        JsonValue::String(text) => JsonValue::String(rewrite_links(&text, links)),
+        JsonValue::Array(items) => JsonValue::Array(
+            items
+                .into_iter()
+                .map(|item| rewrite_json(item, links))
+                .collect(),
+        ),
+        JsonValue::Object(map) => JsonValue::Object(
+            map.into_iter()
+                .map(|(key, value)| (key, rewrite_json(value, links)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// Zip archive built in memory
+struct Archive {
+    zip: ZipWriter<Cursor<Vec<u8>>>,
+    /// Paths already written, so a collision can't produce an invalid archive
+    paths: HashSet<String>,
+}
+
+impl Archive {
+    fn new() -> Self {
+        Self {
+            zip: ZipWriter::new(Cursor::new(Vec::new())),
+            paths: HashSet::new(),
+        }
+    }
+
+    fn add_text(&mut self, path: &str, text: &str) -> Result<(), ApiError> {
+        self.add(path, text.as_bytes(), CompressionMethod::Deflated)
+    }
+
+    /// Uploads are mostly compressed media already, so they are stored as is
+    fn add_binary(&mut self, path: &str, data: &[u8]) -> Result<(), ApiError> {
+        self.add(path, data, CompressionMethod::Stored)
+    }
+
+    fn add(&mut self, path: &str, data: &[u8], method: CompressionMethod) -> Result<(), ApiError> {
+        if !self.paths.insert(path.to_string()) {
+            tracing::warn!("Site export: skipped duplicate path {}", path);
+            return Ok(());
+        }
+
+        let options = SimpleFileOptions::default()
+            .compression_method(method)
+            .large_file(data.len() as u64 >= u32::MAX as u64);
+        self.zip
+            .start_file(path, options)
+            .map_err(|e| ApiError::InternalError(format!("Failed to build archive: {}", e)))?;
+        self.zip
+            .write_all(data)
+            .map_err(|e| ApiError::InternalError(format!("Failed to build archive: {}", e)))
+    }
+
+    fn finish(self) -> Result<Vec<u8>, ApiError> {
+        self.zip
+            .finish()
+            .map(Cursor::into_inner)
+            .map_err(|e| ApiError::InternalError(format!("Failed to build archive: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn references_are_rewritten_in_place() {
+        let links = vec![(
+            "https://cdn.example.com/uploads/a.png".to_string(),
+            "/assets/a.png".to_string(),
+        )];
+        let references = serde_json::json!({
+            "ref-1": { "id": "ref-1", "content": "![](https://cdn.example.com/uploads/a.png)" }
+        });
+
+        assert_eq!(
+            rewrite_json(references, &links),
+            serde_json::json!({ "ref-1": { "id": "ref-1", "content": "![](/assets/a.png)" } })
+        );
+    }
+
+    #[test]
+    fn oversized_assets_and_assets_over_the_budget_are_skipped() {
+        assert_eq!(size_skip_reason(1024, 0), None);
+        assert!(size_skip_reason(MAX_ASSET_BYTES + 1, 0).is_some());
+        assert_eq!(
+            size_skip_reason(MAX_ASSET_BYTES, MAX_TOTAL_ASSET_BYTES - MAX_ASSET_BYTES),
+            None
+        );
+        assert!(size_skip_reason(2, MAX_TOTAL_ASSET_BYTES - 1).is_some());
+    }
+
+    #[test]
+    fn skipped_assets_are_listed_with_their_reason() {
+        let report = skipped_assets_report(&[SkippedAsset {
+            url: "https://cdn.example.com/uploads/big.mp4".to_string(),
+            reason: "too big".to_string(),
+        }]);
+
+        assert!(report.starts_with('#'));
+        assert!(report.ends_with("https://cdn.example.com/uploads/big.mp4\ttoo big\n"));
+    }
+
+    #[test]
+    fn archives_keep_the_first_of_duplicate_paths() {
+        let mut archive = Archive::new();
+        archive.add_text("posts/a.md", "first").unwrap();
+        archive.add_text("posts/a.md", "second").unwrap();
+        archive.add_binary("assets/a.png", b"png").unwrap();
+        let data = archive.finish().unwrap();
+
+        let mut zip = zip::ZipArchive::new(Cursor::new(data)).unwrap();
+        assert_eq!(zip.len(), 2);
+        let mut text = String::new();
+        std::io::Read::read_to_string(&mut zip.by_name("posts/a.md").unwrap(), &mut text).unwrap();
+        assert_eq!(text, "first");
+    }
+}
@@ -0,0 +1,241 @@
+//! Sitemap service - Builds the sitemap index, sub-sitemaps and robots.txt
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::sitemap::SitemapKind;
+use crate::repositories::site_config_repo::SiteConfigRepo;
+use crate::repositories::sitemap_repo::SitemapRepository;
+use crate::routes::sitemap::SITEMAP_INDEX_PATH;
+use crate::routes::API_PREFIX;
+use crate::services::cache_service::{cache_keys, cache_ttl, CacheService};
+use crate::utils::sitemap::{render_robots, render_sitemap_index, render_urlset, SitemapEntry};
+use sqlx::PgPool;
+
+/// Maximum number of URLs per sub-sitemap
+const SITEMAP_PAGE_SIZE: i64 = 5000;
+
+/// Sitemap service for business logic
+pub struct SitemapService;
+
+impl SitemapService {
+    /// Get the sitemap index listing every non-empty sub-sitemap page
+    pub async fn get_index(pool: &PgPool, cache: &Arc<CacheService>) -> Result<String, ApiError> {
+        Self::cached(cache, &cache_keys::sitemap_index(), async {
+            let urls = SiteUrls::load(pool).await?;
+
+            let mut entries = Vec::new();
+            for kind in SitemapKind::ALL {
+                let total = SitemapRepository::count(pool, kind).await?;
+                for page in 1..=Self::page_count(total) {
+                    entries.push(SitemapEntry {
+                        loc: urls.page_url(kind, page),
+                        lastmod: None,
+                    });
+                }
+            }
+
+            Ok(render_sitemap_index(&entries))
+        })
+        .await
+    }
+
+    /// Get one page of a sub-sitemap
+    pub async fn get_page(
+        pool: &PgPool,
+        cache: &Arc<CacheService>,
+        kind: SitemapKind,
+        page: i64,
+    ) -> Result<String, ApiError> {
+        let cache_key = cache_keys::sitemap_page(kind.as_str(), page);
+        Self::cached(cache, &cache_key, async {
+            let total = SitemapRepository::count(pool, kind).await?;
+            if page < 1 || page > Self::page_count(total).max(1) {
+                return Err(ApiError::NotFound(format!(
+                    "Sitemap page {} of {} not found",
+                    page,
+                    kind.as_str()
+                )));
+            }
+
+            let urls = SiteUrls::load(pool).await?;
+            let entries: Vec<SitemapEntry> =
+                SitemapRepository::find_page(pool, kind, page, SITEMAP_PAGE_SIZE)
+                    .await?
+                    .into_iter()
+                    .map(|url| SitemapEntry {
+                        loc: urls.absolute(&url.path),
+                        lastmod: url.lastmod,
+                    })
+                    .collect();
+
+            Ok(render_urlset(&entries))
+        })
+        .await
+    }
+
+    /// Get robots.txt built from the `robots_*` site config keys
+    pub async fn get_robots(pool: &PgPool, cache: &Arc<CacheService>) -> Result<String, ApiError> {
+        Self::cached(cache, &cache_keys::robots_txt(), async {
+            let map = SiteConfigRepo::get_as_map(pool).await?;
+            let urls = SiteUrls::from_map(&map);
+            let sitemap_url = urls.index_url();
+
+            Ok(render_robots(
+                map.get("robots_disallow").map(String::as_str).unwrap_or(""),
+                map.get("robots_extra").map(String::as_str).unwrap_or(""),
+                sitemap_url.as_deref(),
+            ))
+        })
+        .await
+    }
+
+    fn page_count(total: i64) -> i64 {
+        (total + SITEMAP_PAGE_SIZE - 1) / SITEMAP_PAGE_SIZE
+    }
+
+    /// Serve a rendered document from cache, building and caching it on a miss
+    async fn cached<F>(cache: &Arc<CacheService>, key: &str, build: F) -> Result<String, ApiError>
+    where
+        F: std::future::Future<Output = Result<String, ApiError>>,
+    {
+        if let Ok(Some(cached)) = cache.get::<String>(key).await {
+            tracing::debug!("Cache hit for {}", key);
+            return Ok(cached);
+        }
+
+        let body = build.await?;
+
+        if let Err(e) = cache.set(key, &body, cache_ttl::SITEMAP).await {
+            tracing::warn!("Failed to cache {}: {}", key, e);
+        }
+
+        Ok(body)
+    }
+}
+
+/// Public base URLs used to build absolute sitemap links
+struct SiteUrls {
+    site: String,
+    sitemap_base: String,
+}
+
+impl SiteUrls {
+    async fn load(pool: &PgPool) -> Result<Self, ApiError> {
+        Ok(Self::from_map(&SiteConfigRepo::get_as_map(pool).await?))
+    }
+
+    fn from_map(map: &HashMap<String, String>) -> Self {
+        let site = map
+            .get("site_url")
+            .map(|url| url.trim().trim_end_matches('/').to_string())
+            .unwrap_or_default();
+        let sitemap_base = map
+            .get("sitemap_base_url")
+            .map(|url| url.trim().trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| {
+                // The sitemap routes are only mounted under the API prefix
+                if site.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}{}", site, API_PREFIX)
+                }
+            });
+
+        Self { site, sitemap_base }
+    }
+
+    /// Absolute URL of the sitemap index, if a base URL is known
+    fn index_url(&self) -> Option<String> {
+        if self.sitemap_base.is_empty() {
+            None
+        } else {
+            Some(format!("{}{}", self.sitemap_base, SITEMAP_INDEX_PATH))
+        }
+    }
+
+    /// Absolute URL of one sub-sitemap page
+    fn page_url(&self, kind: SitemapKind, page: i64) -> String {
+        format!(
+            "{}/sitemaps/{}/{}.xml",
+            self.sitemap_base,
+            kind.as_str(),
+            page
+        )
+    }
+
+    /// Join a site-relative path onto the site URL, percent-encoding it
+    fn absolute(&self, path: &str) -> String {
+        let url = format!("{}{}", self.site, path);
+        reqwest::Url::parse(&url).map(String::from).unwrap_or(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::sitemap::SITEMAP_PAGE_PATH;
+    use axum::routing::get;
+    use axum::Router;
+
+    fn urls(site: &str, sitemap_base: &str) -> SiteUrls {
+        SiteUrls::from_map(&HashMap::from([
+            ("site_url".to_string(), site.to_string()),
+            ("sitemap_base_url".to_string(), sitemap_base.to_string()),
+        ]))
+    }
+
+    #[test]
+    fn sitemap_base_defaults_to_api_prefix_under_site_url() {
+        let urls = urls("https://example.com/", "");
+        assert_eq!(
+            urls.index_url().as_deref(),
+            Some("https://example.com/api/v1/sitemap.xml")
+        );
+        assert_eq!(
+            urls.page_url(SitemapKind::Blogs, 2),
+            "https://example.com/api/v1/sitemaps/blogs/2.xml"
+        );
+    }
+
+    #[test]
+    fn explicit_sitemap_base_is_used_as_is() {
+        let urls = urls("https://example.com", "https://cdn.example.com/");
+        assert_eq!(
+            urls.index_url().as_deref(),
+            Some("https://cdn.example.com/sitemap.xml")
+        );
+    }
+
+    #[test]
+    fn no_sitemap_url_without_site_url() {
+        assert_eq!(urls("", "").index_url(), None);
+    }
+
+    #[tokio::test]
+    async fn emitted_locs_resolve_to_mounted_routes() {
+        let app = Router::new().nest(
+            API_PREFIX,
+            Router::new()
+                .route(SITEMAP_INDEX_PATH, get(|| async { "index" }))
+                .route(SITEMAP_PAGE_PATH, get(|| async { "page" })),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let site = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let urls = urls(&site, "");
+        let mut locs = vec![urls.index_url().unwrap()];
+        locs.extend(
+            SitemapKind::ALL
+                .into_iter()
+                .map(|kind| urls.page_url(kind, 1)),
+        );
+        for loc in locs {
+            let status = reqwest::get(&loc).await.unwrap().status();
+            assert!(status.is_success(), "{} is not routed ({})", loc, status);
+        }
+    }
+}
@@ -0,0 +1,391 @@
+//! Storage backends for uploaded files
+//!
+//! Files live either in S3-compatible object storage or in a local directory
+//! served by the backend under `/files`. The `storage_backend` site config key
+//! selects where new uploads go; every file row records the backend holding it
+//! so it can be deleted after the setting changed.
+
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::repositories::site_config_repo::SiteConfigRepo;
+use crate::services::s3_service::S3Service;
+
+/// Route the local storage directory is served under
+pub const LOCAL_FILES_ROUTE: &str = "/files";
+
+/// Prefix of every object key created for uploads
+pub const UPLOADS_PREFIX: &str = "uploads/";
+
+/// Where a file's objects are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageBackend {
+    S3,
+    Local,
+}
+
+impl StorageBackend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StorageBackend::S3 => "s3",
+            StorageBackend::Local => "local",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "s3" => Some(StorageBackend::S3),
+            "local" => Some(StorageBackend::Local),
+            _ => None,
+        }
+    }
+}
+
+/// Result of storing an object
+#[derive(Debug, Clone)]
+pub struct UploadResult {
+    pub object_key: String,
+    /// Public URL of the object
+    pub url: String,
+}
+
+/// Object found when listing a backend
+#[derive(Debug, Clone)]
+pub struct StoredObject {
+    pub object_key: String,
+    pub size: i64,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+/// Object storage used for uploads
+#[async_trait]
+pub trait Storage: Send + Sync {
+    fn backend(&self) -> StorageBackend;
+
+    /// Bucket holding the objects, for backends that have one
+    fn bucket_name(&self) -> Option<&str>;
+
+    /// Store data under the given object key, replacing any existing object
+    async fn put(
+        &self,
+        object_key: &str,
+        data: Vec<u8>,
+        content_type: &str,
+    ) -> Result<UploadResult, ApiError>;
+
+    /// Read an object, `None` if it doesn't exist
+    async fn get(&self, object_key: &str) -> Result<Option<Vec<u8>>, ApiError>;
+
+    /// Remove an object
+    async fn delete(&self, object_key: &str) -> Result<(), ApiError>;
+
+    /// List every object whose key starts with `prefix`
+    async fn list(&self, prefix: &str) -> Result<Vec<StoredObject>, ApiError>;
+}
+
+/// Storage in a local directory
+pub struct LocalStorage {
+    root: PathBuf,
+    public_url: String,
+}
+
+impl LocalStorage {
+    /// `public_url` is the URL prefix objects are reachable under; empty means `/files`
+    pub fn new(root: impl Into<PathBuf>, public_url: &str) -> Self {
+        let public_url = public_url.trim().trim_end_matches('/');
+        Self {
+            root: root.into(),
+            public_url: if public_url.is_empty() {
+                LOCAL_FILES_ROUTE.to_string()
+            } else {
+                public_url.to_string()
+            },
+        }
+    }
+
+    /// Path of an object, refusing keys that would leave the storage directory
+    fn path(&self, object_key: &str) -> Result<PathBuf, ApiError> {
+        let key = Path::new(object_key);
+        let is_safe = !object_key.is_empty()
+            && key
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+        if !is_safe {
+            return Err(ApiError::BadRequest(format!(
+                "Invalid object key: {}",
+                object_key
+            )));
+        }
+
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    fn backend(&self) -> StorageBackend {
+        StorageBackend::Local
+    }
+
+    fn bucket_name(&self) -> Option<&str> {
+        None
+    }
+
+    async fn put(
+        &self,
+        object_key: &str,
+        data: Vec<u8>,
+        _content_type: &str,
+    ) -> Result<UploadResult, ApiError> {
+        let path = self.path(object_key)?;
+        let write_error =
+            |e: std::io::Error| ApiError::FileUploadError(format!("Failed to store file: {}", e));
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(write_error)?;
+        }
+        tokio::fs::write(&path, data).await.map_err(write_error)?;
+
+        tracing::info!("File stored locally: {}", object_key);
+
+        Ok(UploadResult {
+            object_key: object_key.to_string(),
+            url: format!("{}/{}", self.public_url, object_key),
+        })
+    }
+
+    async fn get(&self, object_key: &str) -> Result<Option<Vec<u8>>, ApiError> {
+        match tokio::fs::read(self.path(object_key)?).await {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(ApiError::InternalError(format!(
+                "Failed to read file: {}",
+                e
+            ))),
+        }
+    }
+
+    async fn delete(&self, object_key: &str) -> Result<(), ApiError> {
+        match tokio::fs::remove_file(self.path(object_key)?).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(ApiError::FileUploadError(format!(
+                    "Failed to delete file: {}",
+                    e
+                )))
+            }
+        }
+
+        tracing::info!("File deleted locally: {}", object_key);
+
+        Ok(())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<StoredObject>, ApiError> {
+        let list_error =
+            |e: std::io::Error| ApiError::InternalError(format!("Failed to list files: {}", e));
+
+        let mut objects = Vec::new();
+        let mut pending = vec![self.root.clone()];
+        while let Some(dir) = pending.pop() {
+            let mut entries = match tokio::fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(list_error(e)),
+            };
+
+            while let Some(entry) = entries.next_entry().await.map_err(list_error)? {
+                let metadata = entry.metadata().await.map_err(list_error)?;
+                if metadata.is_dir() {
+                    pending.push(entry.path());
+                    continue;
+                }
+
+                // Keys always use `/`, whatever the platform separator is
+                let Ok(relative) = entry.path().strip_prefix(&self.root).map(Path::to_path_buf)
+                else {
+                    continue;
+                };
+                let object_key = relative
+                    .components()
+                    .filter_map(|component| component.as_os_str().to_str())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                if !object_key.starts_with(prefix) {
+                    continue;
+                }
+
+                objects.push(StoredObject {
+                    object_key,
+                    size: metadata.len() as i64,
+                    last_modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+                });
+            }
+        }
+
+        Ok(objects)
+    }
+}
+
+/// Storage service for picking a backend
+pub struct StorageService;
+
+impl StorageService {
+    /// Backend new uploads go to, from the `storage_backend` site config key
+    pub async fn active(pool: &PgPool, config: &Config) -> Result<Arc<dyn Storage>, ApiError> {
+        let backend = SiteConfigRepo::get_value(pool, "storage_backend")
+            .await?
+            .and_then(|value| StorageBackend::parse(&value))
+            .unwrap_or(StorageBackend::S3);
+
+        Self::open(pool, config, backend).await
+    }
+
+    /// S3 storage from the site config, for features only S3 offers
+    pub async fn s3(pool: &PgPool, config: &Config) -> Result<S3Service, ApiError> {
+        let master_key = config.encryption.master_key.as_deref();
+        let db_s3_config = SiteConfigRepo::get_s3_config(pool, master_key).await?;
+        if db_s3_config.endpoint.trim().is_empty() || db_s3_config.bucket.trim().is_empty() {
+            return Err(ApiError::FileUploadError(
+                "S3 storage is not configured".to_string(),
+            ));
+        }
+
+        let s3_config = crate::config::S3Config {
+            endpoint: db_s3_config.endpoint,
+            region: db_s3_config.region,
+            bucket: db_s3_config.bucket,
+            access_key: db_s3_config.access_key,
+            secret_key: db_s3_config.secret_key,
+            public_url: db_s3_config.public_url,
+        };
+        S3Service::new(&s3_config)
+            .await
+            .map_err(|e| ApiError::FileUploadError(format!("Failed to initialize S3: {}", e)))
+    }
+
+    /// Open a specific backend, e.g. the one recorded on an existing file
+    pub async fn open(
+        pool: &PgPool,
+        config: &Config,
+        backend: StorageBackend,
+    ) -> Result<Arc<dyn Storage>, ApiError> {
+        match backend {
+            StorageBackend::S3 => Ok(Arc::new(Self::s3(pool, config).await?)),
+            StorageBackend::Local => {
+                let public_url = SiteConfigRepo::get_value(pool, "local_storage_public_url")
+                    .await?
+                    .unwrap_or_default();
+
+                Ok(Arc::new(LocalStorage::new(
+                    &config.storage.local_dir,
+                    &public_url,
+                )))
+            }
+        }
+    }
+}
+
+/// Unique object key for a new upload, keeping the original extension
+pub fn new_object_key(original_filename: &str) -> String {
+    let extension = Path::new(original_filename)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .unwrap_or("");
+
+    if extension.is_empty() {
+        format!("{}{}", UPLOADS_PREFIX, Uuid::new_v4())
+    } else {
+        format!("{}{}.{}", UPLOADS_PREFIX, Uuid::new_v4(), extension)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_storage() -> (LocalStorage, PathBuf) {
+        let root = std::env::temp_dir().join(format!("blog-storage-{}", Uuid::new_v4()));
+        (LocalStorage::new(&root, ""), root)
+    }
+
+    #[test]
+    fn backend_round_trips() {
+        for backend in [StorageBackend::S3, StorageBackend::Local] {
+            assert_eq!(StorageBackend::parse(backend.as_str()), Some(backend));
+        }
+        assert_eq!(StorageBackend::parse("ftp"), None);
+    }
+
+    #[test]
+    fn object_keys_keep_safe_extensions() {
+        assert!(new_object_key("photo.JPG").starts_with("uploads/"));
+        assert!(new_object_key("photo.JPG").ends_with(".JPG"));
+        assert!(!new_object_key("notes").contains('.'));
+        assert!(!new_object_key("x.j/../pg").contains(".."));
+    }
+
+    #[test]
+    fn local_keys_cannot_escape_root() {
+        let (storage, _) = temp_storage();
+
+        assert!(storage.path("uploads/a.png").is_ok());
+        assert!(storage.path("../etc/passwd").is_err());
+        assert!(storage.path("/etc/passwd").is_err());
+        assert!(storage.path("uploads/../../x").is_err());
+        assert!(storage.path("").is_err());
+    }
+
+    #[tokio::test]
+    async fn local_storage_writes_and_deletes() {
+        let (storage, root) = temp_storage();
+
+        let result = storage
+            .put("uploads/abc/thumbnail.jpg", b"data".to_vec(), "image/jpeg")
+            .await
+            .unwrap();
+        assert_eq!(result.url, "/files/uploads/abc/thumbnail.jpg");
+        assert_eq!(
+            std::fs::read(root.join("uploads/abc/thumbnail.jpg")).unwrap(),
+            b"data"
+        );
+
+        let listed = storage.list(UPLOADS_PREFIX).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].object_key, "uploads/abc/thumbnail.jpg");
+        assert_eq!(listed[0].size, 4);
+        assert!(storage.list("other/").await.unwrap().is_empty());
+        assert_eq!(
+            storage.get("uploads/abc/thumbnail.jpg").await.unwrap(),
+            Some(b"data".to_vec())
+        );
+
+        storage.delete("uploads/abc/thumbnail.jpg").await.unwrap();
+        assert_eq!(
+            storage.get("uploads/abc/thumbnail.jpg").await.unwrap(),
+            None
+        );
+        assert!(!root.join("uploads/abc/thumbnail.jpg").exists());
+        // Deleting a missing object is not an error
+        storage.delete("uploads/abc/thumbnail.jpg").await.unwrap();
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn local_public_url_is_configurable() {
+        let storage = LocalStorage::new("/tmp", "https://cdn.example.com/files/");
+        assert_eq!(storage.public_url, "https://cdn.example.com/files");
+    }
+}
@@ -0,0 +1,219 @@
+//! Text service - Content encryption and password verification for protected texts
+
+use std::sync::Arc;
+
+use crate::error::ApiError;
+use crate::models::text::{CreateTextRequest, Text, TextContent, UpdateTextRequest};
+use crate::repositories::text_repo::TextRepository;
+use crate::repositories::user_repo::UserRepository;
+use crate::services::cache_service::{cache_keys, cache_ttl, CacheService};
+use crate::utils::crypto;
+use sqlx::PgPool;
+
+/// Maximum failed password attempts per IP (or MCP token) per window
+const VERIFY_REQUESTER_LIMIT: i64 = 10;
+
+/// Maximum failed password attempts per text per window, across all requesters
+const VERIFY_TEXT_LIMIT: i64 = 50;
+
+/// Who submits a view password; failed attempts are limited per requester
+#[derive(Debug, Clone, Copy)]
+pub enum PasswordRequester<'a> {
+    /// A reader, by client IP
+    Ip(&'a str),
+    /// An MCP tool call, by token id
+    McpToken(i64),
+}
+
+impl PasswordRequester<'_> {
+    fn rate_limit_key(&self) -> String {
+        match self {
+            PasswordRequester::Ip(ip) => cache_keys::text_verify_ip_rate_limit(ip),
+            PasswordRequester::McpToken(token_id) => {
+                cache_keys::text_verify_mcp_token_rate_limit(*token_id)
+            }
+        }
+    }
+}
+
+/// Text service for business logic
+pub struct TextService;
+
+impl TextService {
+    /// Create a text, encrypting its content when it is password protected
+    pub async fn create(
+        pool: &PgPool,
+        master_key: Option<&str>,
+        req: &CreateTextRequest,
+    ) -> Result<Text, ApiError> {
+        // Encrypted texts without a password are stored as plain texts
+        let encrypt = req.is_encrypted.unwrap_or(false) && req.view_password.is_some();
+        let content = Self::prepare_content(master_key, req.content.clone(), encrypt)?;
+
+        TextRepository::create(pool, req, &content).await
+    }
+
+    /// Update a text, re-encrypting its content whenever it stays or becomes protected
+    pub async fn update(
+        pool: &PgPool,
+        master_key: Option<&str>,
+        id: i64,
+        req: &UpdateTextRequest,
+    ) -> Result<Option<Text>, ApiError> {
+        let Some(existing) = TextRepository::find_by_id(pool, id).await? else {
+            return Ok(None);
+        };
+
+        let encrypt = req
+            .is_encrypted
+            .unwrap_or(existing.is_encrypted.unwrap_or(false))
+            && (req.view_password.is_some() || existing.view_password.is_some());
+        let plaintext = match &req.content {
+            Some(content) => content.clone(),
+            None => Self::decrypt(master_key, existing)?.content,
+        };
+        let content = Self::prepare_content(master_key, plaintext, encrypt)?;
+
+        TextRepository::update(pool, id, req, &content).await
+    }
+
+    /// Replace sealed content with its plaintext
+    ///
+    /// Only call this once the reader proved they may see the text, or for the admin.
+    pub fn decrypt(master_key: Option<&str>, mut text: Text) -> Result<Text, ApiError> {
+        if let Some(sealed) = text.sealed_content() {
+            text.content = crypto::open(Self::require_key(master_key)?, &sealed)?;
+            text.content_kdf = None;
+            text.content_nonce = None;
+        }
+
+        Ok(text)
+    }
+
+    /// Encrypt protected texts that were stored before content encryption existed
+    pub async fn seal_legacy_texts(pool: &PgPool, master_key: &str) -> Result<usize, ApiError> {
+        let texts = TextRepository::find_unsealed_encrypted(pool).await?;
+        for text in &texts {
+            let sealed = crypto::seal(master_key, &text.content)?;
+            TextRepository::seal_content(pool, text.id, &sealed).await?;
+        }
+
+        Ok(texts.len())
+    }
+
+    /// Unlock a text for a reader: check the view password of a protected
+    /// text against the attempt limits, then decrypt it
+    ///
+    /// This and the admin endpoints are the only ways to get at sealed content.
+    pub async fn verify_text_password(
+        pool: &PgPool,
+        cache: &Arc<CacheService>,
+        master_key: Option<&str>,
+        mut text: Text,
+        password: &str,
+        requester: PasswordRequester<'_>,
+    ) -> Result<Text, ApiError> {
+        if text.is_encrypted.unwrap_or(false) {
+            Self::verify(pool, cache, &mut text, password, requester).await?;
+        }
+
+        Self::decrypt(master_key, text)
+    }
+
+    /// Verify a view password; only failed attempts count towards the
+    /// per-requester and per-text limits
+    async fn verify(
+        pool: &PgPool,
+        cache: &Arc<CacheService>,
+        text: &mut Text,
+        password: &str,
+        requester: PasswordRequester<'_>,
+    ) -> Result<(), ApiError> {
+        let requester_key = requester.rate_limit_key();
+        let text_key = cache_keys::text_verify_rate_limit(text.id);
+
+        let requester_failures = cache.get::<i64>(&requester_key).await?.unwrap_or(0);
+        let text_failures = cache.get::<i64>(&text_key).await?.unwrap_or(0);
+        if requester_failures >= VERIFY_REQUESTER_LIMIT || text_failures >= VERIFY_TEXT_LIMIT {
+            tracing::debug!(
+                "Text {} password attempts rate limited for {:?}",
+                text.id,
+                requester
+            );
+            return Err(ApiError::TooManyRequests(
+                "Too many password attempts, please try again later".to_string(),
+            ));
+        }
+
+        if !Self::check_password(pool, text, password).await? {
+            cache
+                .incr_window(&requester_key, cache_ttl::TEXT_VERIFY_RATE_LIMIT)
+                .await?;
+            cache
+                .incr_window(&text_key, cache_ttl::TEXT_VERIFY_RATE_LIMIT)
+                .await?;
+            return Err(ApiError::Unauthorized("Invalid password".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Check a password, replacing a legacy plaintext password with its hash once it matches
+    async fn check_password(
+        pool: &PgPool,
+        text: &mut Text,
+        password: &str,
+    ) -> Result<bool, ApiError> {
+        if !text.verify_password(password) {
+            return Ok(false);
+        }
+
+        if text.has_legacy_password() {
+            let password_hash = UserRepository::hash_password(password)?;
+            TextRepository::update_password_hash(pool, text.id, &password_hash).await?;
+            text.view_password = Some(password_hash);
+            tracing::info!("Migrated view password of text {} to argon2", text.id);
+        }
+
+        Ok(true)
+    }
+
+    fn prepare_content(
+        master_key: Option<&str>,
+        plaintext: String,
+        encrypt: bool,
+    ) -> Result<TextContent, ApiError> {
+        if encrypt {
+            let sealed = crypto::seal(Self::require_key(master_key)?, &plaintext)?;
+            Ok(TextContent::Sealed(sealed))
+        } else {
+            Ok(TextContent::Plain(plaintext))
+        }
+    }
+
+    fn require_key(master_key: Option<&str>) -> Result<&str, ApiError> {
+        master_key.ok_or_else(|| {
+            ApiError::InternalError(
+                "ENCRYPTION_MASTER_KEY is not configured, protected texts are unavailable"
+                    .to_string(),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requesters_are_limited_separately() {
+        assert_eq!(
+            PasswordRequester::Ip("203.0.113.7").rate_limit_key(),
+            "text:verify:rate:ip:203.0.113.7"
+        );
+        assert_eq!(
+            PasswordRequester::McpToken(7).rate_limit_key(),
+            "text:verify:rate:mcp:7"
+        );
+    }
+}
@@ -0,0 +1,443 @@
+//! Two-factor service - TOTP enrollment, recovery codes and second-step verification
+//!
+//! Enrollment stores a pending secret that only takes effect once the user
+//! confirms a code from their authenticator app. Secrets are encrypted with the
+//! master key when one is configured. Every check takes the current time as an
+//! argument so tests can drive it with a fixed clock.
+
+use std::sync::Arc;
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::error::ApiError;
+use crate::models::two_factor::{TwoFactorSetupResponse, TwoFactorStatus};
+use crate::models::user::User;
+use crate::repositories::recovery_code_repo::RecoveryCodeRepository;
+use crate::repositories::user_repo::UserRepository;
+use crate::services::cache_service::{cache_keys, cache_ttl, CacheService};
+use crate::utils::{crypto, totp};
+
+/// Maximum failed second-factor attempts per user per window
+const VERIFY_LIMIT: i64 = 5;
+
+/// Recovery codes issued per user
+const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Characters per recovery code half, e.g. `k7m2p-x9q4t`
+const RECOVERY_CODE_HALF_LEN: usize = 5;
+
+/// Recovery code alphabet without look-alike characters (0/o, 1/l/i)
+const RECOVERY_CODE_ALPHABET: &[u8] = b"23456789abcdefghjkmnpqrstuvwxyz";
+
+/// Two-factor service for business logic
+pub struct TwoFactorService;
+
+impl TwoFactorService {
+    /// Two-factor status of a user
+    pub async fn status(pool: &PgPool, user: &User) -> Result<TwoFactorStatus, ApiError> {
+        let recovery_codes_remaining = if user.totp_enabled {
+            RecoveryCodeRepository::count_unused(pool, user.id).await?
+        } else {
+            0
+        };
+
+        Ok(TwoFactorStatus {
+            enabled: user.totp_enabled,
+            pending: !user.totp_enabled && user.totp_secret.is_some(),
+            recovery_codes_remaining,
+        })
+    }
+
+    /// Start enrollment with a new secret; replaces any unconfirmed one
+    pub async fn setup(
+        pool: &PgPool,
+        master_key: Option<&str>,
+        user: &User,
+        issuer: &str,
+    ) -> Result<TwoFactorSetupResponse, ApiError> {
+        let secret = totp::generate_secret();
+        let stored = seal_secret(master_key, &secret)?;
+        if !UserRepository::set_pending_totp_secret(pool, user.id, &stored).await? {
+            return Err(ApiError::BadRequest(
+                "Two-factor authentication is already enabled".to_string(),
+            ));
+        }
+
+        Ok(TwoFactorSetupResponse {
+            provisioning_uri: totp::provisioning_uri(issuer, &user.username, &secret),
+            secret,
+        })
+    }
+
+    /// Finish enrollment with a code from the authenticator app
+    ///
+    /// Returns the recovery codes, which are only ever shown here.
+    pub async fn confirm(
+        pool: &PgPool,
+        master_key: Option<&str>,
+        user: &User,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<String>, ApiError> {
+        if user.totp_enabled {
+            return Err(ApiError::BadRequest(
+                "Two-factor authentication is already enabled".to_string(),
+            ));
+        }
+        if user.totp_secret.is_none() {
+            return Err(ApiError::BadRequest(
+                "Start two-factor setup first".to_string(),
+            ));
+        }
+
+        let step = match_totp(user, master_key, code, now.timestamp())?
+            .ok_or_else(|| ApiError::ValidationError("Invalid verification code".to_string()))?;
+
+        UserRepository::enable_totp(pool, user.id, step).await?;
+        let codes = Self::issue_recovery_codes(pool, user.id).await?;
+
+        tracing::info!("User '{}' enabled two-factor authentication", user.username);
+
+        Ok(codes)
+    }
+
+    /// Check the second factor of a user with two-factor authentication enabled
+    ///
+    /// Accepts an authenticator code or an unused recovery code. Only failed
+    /// attempts count towards the per-user limit.
+    pub async fn verify(
+        pool: &PgPool,
+        cache: &Arc<CacheService>,
+        master_key: Option<&str>,
+        user: &User,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(), ApiError> {
+        let key = cache_keys::two_factor_rate_limit(user.id);
+        let failures = cache.get::<i64>(&key).await?.unwrap_or(0);
+        if failures >= VERIFY_LIMIT {
+            tracing::debug!("Two-factor attempts rate limited for user {}", user.id);
+            return Err(ApiError::TooManyRequests(
+                "Too many verification attempts, please try again later".to_string(),
+            ));
+        }
+
+        if !Self::check_code(pool, master_key, user, code, now).await? {
+            cache
+                .incr_window(&key, cache_ttl::TWO_FACTOR_RATE_LIMIT)
+                .await?;
+            return Err(ApiError::Unauthorized(
+                "Invalid verification code".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Turn off two-factor authentication after checking password and second factor
+    pub async fn disable(
+        pool: &PgPool,
+        cache: &Arc<CacheService>,
+        master_key: Option<&str>,
+        user: &User,
+        password: &str,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(), ApiError> {
+        Self::require_enabled(user)?;
+        if !UserRepository::verify_password(password, &user.password_hash)? {
+            return Err(ApiError::Unauthorized("Invalid password".to_string()));
+        }
+        Self::verify(pool, cache, master_key, user, code, now).await?;
+
+        UserRepository::disable_totp(pool, user.id).await?;
+        RecoveryCodeRepository::delete_all(pool, user.id).await?;
+
+        tracing::info!(
+            "User '{}' disabled two-factor authentication",
+            user.username
+        );
+
+        Ok(())
+    }
+
+    /// Replace all recovery codes after checking the second factor
+    pub async fn regenerate_recovery_codes(
+        pool: &PgPool,
+        cache: &Arc<CacheService>,
+        master_key: Option<&str>,
+        user: &User,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<String>, ApiError> {
+        Self::require_enabled(user)?;
+        Self::verify(pool, cache, master_key, user, code, now).await?;
+
+        Self::issue_recovery_codes(pool, user.id).await
+    }
+
+    /// Encrypt TOTP secrets stored before the master key was configured
+    pub async fn seal_legacy_secrets(pool: &PgPool, master_key: &str) -> Result<usize, ApiError> {
+        let mut count = 0;
+        for (user_id, secret) in UserRepository::find_totp_secrets(pool).await? {
+            if crypto::is_sealed_value(&secret) {
+                continue;
+            }
+            let sealed = crypto::seal_value(master_key, &secret)?;
+            UserRepository::replace_totp_secret(pool, user_id, &secret, &sealed).await?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    async fn check_code(
+        pool: &PgPool,
+        master_key: Option<&str>,
+        user: &User,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> Result<bool, ApiError> {
+        if let Some(step) = match_totp(user, master_key, code, now.timestamp())? {
+            // Guarded update, so two requests can't both spend the same code
+            return UserRepository::advance_totp_step(pool, user.id, step).await;
+        }
+
+        let Some(code) = normalize_recovery_code(code) else {
+            return Ok(false);
+        };
+        for recovery_code in RecoveryCodeRepository::find_unused(pool, user.id).await? {
+            if UserRepository::verify_password(&code, &recovery_code.code_hash)? {
+                let used = RecoveryCodeRepository::mark_used(pool, recovery_code.id).await?;
+                if used {
+                    tracing::info!("User '{}' used a recovery code", user.username);
+                }
+                return Ok(used);
+            }
+        }
+
+        Ok(false)
+    }
+
+    async fn issue_recovery_codes(pool: &PgPool, user_id: i64) -> Result<Vec<String>, ApiError> {
+        let codes = generate_recovery_codes();
+        let hashes = codes
+            .iter()
+            .map(|code| {
+                let normalized = normalize_recovery_code(code).unwrap_or_default();
+                UserRepository::hash_password(&normalized)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        RecoveryCodeRepository::replace_all(pool, user_id, &hashes).await?;
+
+        Ok(codes)
+    }
+
+    fn require_enabled(user: &User) -> Result<(), ApiError> {
+        if !user.totp_enabled {
+            return Err(ApiError::BadRequest(
+                "Two-factor authentication is not enabled".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Match an authenticator code against the user's secret at `unix_time`
+///
+/// Steps at or before the last accepted one are refused so a code works once.
+fn match_totp(
+    user: &User,
+    master_key: Option<&str>,
+    code: &str,
+    unix_time: i64,
+) -> Result<Option<i64>, ApiError> {
+    let Some(stored) = user.totp_secret.as_deref() else {
+        return Ok(None);
+    };
+    let secret = open_secret(master_key, stored)?;
+
+    Ok(totp::verify(&secret, code, unix_time)
+        .filter(|step| user.totp_last_step.is_none_or(|last| *step > last)))
+}
+
+/// Encrypt a TOTP secret for storage; kept as is without a master key
+fn seal_secret(master_key: Option<&str>, secret: &str) -> Result<String, ApiError> {
+    match master_key {
+        Some(master_key) => crypto::seal_value(master_key, secret),
+        None => {
+            tracing::warn!("ENCRYPTION_MASTER_KEY is not set, storing TOTP secret unencrypted");
+            Ok(secret.to_string())
+        }
+    }
+}
+
+/// Decrypt a stored TOTP secret; secrets stored without a master key are plaintext
+fn open_secret(master_key: Option<&str>, stored: &str) -> Result<String, ApiError> {
+    if !crypto::is_sealed_value(stored) {
+        return Ok(stored.to_string());
+    }
+
+    let master_key = master_key.ok_or_else(|| {
+        ApiError::InternalError(
+            "ENCRYPTION_MASTER_KEY is not configured, two-factor secrets are unavailable"
+                .to_string(),
+        )
+    })?;
+    crypto::open_value(master_key, stored)
+}
+
+fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| format!("{}-{}", random_chunk(), random_chunk()))
+        .collect()
+}
+
+fn random_chunk() -> String {
+    (0..RECOVERY_CODE_HALF_LEN)
+        .map(|_| {
+            let index = OsRng.next_u32() as usize % RECOVERY_CODE_ALPHABET.len();
+            RECOVERY_CODE_ALPHABET[index] as char
+        })
+        .collect()
+}
+
+/// Canonical form of a typed recovery code: lowercase, without dashes or spaces
+fn normalize_recovery_code(code: &str) -> Option<String> {
+    let normalized: String = code
+        .chars()
+        .filter(|c| *c != '-' && !c.is_whitespace())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+
+    (normalized.len() == RECOVERY_CODE_HALF_LEN * 2
+        && normalized
+            .bytes()
+            .all(|b| RECOVERY_CODE_ALPHABET.contains(&b)))
+    .then_some(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+    const NOW: i64 = 1_700_000_000;
+
+    fn user(secret: Option<&str>, last_step: Option<i64>) -> User {
+        User {
+            id: 1,
+            username: "admin".to_string(),
+            password_hash: String::new(),
+            email: None,
+            nickname: None,
+            avatar: None,
+            role: "admin".to_string(),
+            totp_secret: secret.map(ToOwned::to_owned),
+            totp_enabled: secret.is_some(),
+            totp_last_step: last_step,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn totp_code_matches_at_fixed_time() {
+        let code = totp::code_at(SECRET, NOW).unwrap();
+
+        assert_eq!(
+            match_totp(&user(Some(SECRET), None), None, &code, NOW).unwrap(),
+            Some(NOW / totp::STEP_SECONDS)
+        );
+        assert_eq!(
+            match_totp(&user(Some(SECRET), None), None, &code, NOW + 600).unwrap(),
+            None
+        );
+        assert_eq!(
+            match_totp(&user(None, None), None, &code, NOW).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn totp_code_cannot_be_replayed() {
+        let step = NOW / totp::STEP_SECONDS;
+        let code = totp::code_at(SECRET, NOW).unwrap();
+
+        assert_eq!(
+            match_totp(&user(Some(SECRET), Some(step)), None, &code, NOW).unwrap(),
+            None
+        );
+        assert_eq!(
+            match_totp(&user(Some(SECRET), Some(step - 1)), None, &code, NOW).unwrap(),
+            Some(step)
+        );
+
+        // The next step's code is still accepted after this one was used
+        let next = totp::code_at(SECRET, NOW + totp::STEP_SECONDS).unwrap();
+        assert_eq!(
+            match_totp(
+                &user(Some(SECRET), Some(step)),
+                None,
+                &next,
+                NOW + totp::STEP_SECONDS
+            )
+            .unwrap(),
+            Some(step + 1)
+        );
+    }
+
+    #[test]
+    fn sealed_totp_secret_is_opened_with_the_master_key() {
+        let master_key = "test-master-key";
+        let sealed = seal_secret(Some(master_key), SECRET).unwrap();
+        assert!(crypto::is_sealed_value(&sealed));
+        assert!(!sealed.contains(SECRET));
+
+        let user = user(Some(&sealed), None);
+        let code = totp::code_at(SECRET, NOW).unwrap();
+        assert_eq!(
+            match_totp(&user, Some(master_key), &code, NOW).unwrap(),
+            Some(NOW / totp::STEP_SECONDS)
+        );
+        assert!(match_totp(&user, None, &code, NOW).is_err());
+        assert!(match_totp(&user, Some("other-key"), &code, NOW).is_err());
+    }
+
+    #[test]
+    fn recovery_codes_are_unique_and_normalizable() {
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+
+        let mut unique = codes.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), codes.len());
+
+        for code in &codes {
+            assert_eq!(code.len(), RECOVERY_CODE_HALF_LEN * 2 + 1);
+            assert_eq!(
+                normalize_recovery_code(code).unwrap(),
+                code.replace('-', "")
+            );
+        }
+    }
+
+    #[test]
+    fn normalize_recovery_code_accepts_typing_variants() {
+        assert_eq!(
+            normalize_recovery_code(" K7M2P-X9Q4T ").as_deref(),
+            Some("k7m2px9q4t")
+        );
+        assert_eq!(
+            normalize_recovery_code("k7m2p x9q4t").as_deref(),
+            Some("k7m2px9q4t")
+        );
+        assert_eq!(normalize_recovery_code("123456"), None);
+        assert_eq!(normalize_recovery_code("k7m2p-x9q40"), None);
+    }
+}
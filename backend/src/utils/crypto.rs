@@ -0,0 +1,204 @@
+//! At-rest content encryption
+//!
+//! Content is sealed with XChaCha20-Poly1305 under a key derived from the server
+//! master key by HKDF-SHA256 with a random per-record salt. The master key is
+//! high-entropy, so a fast KDF is enough and every read stays cheap. The KDF
+//! and salt are stored next to the ciphertext, so a record can be exported and
+//! restored without re-encrypting.
+
+use argon2::password_hash::{rand_core::OsRng, SaltString};
+use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine as _};
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit},
+    XChaCha20Poly1305, XNonce,
+};
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+use crate::error::ApiError;
+
+/// Encrypted content with everything needed to decrypt it besides the master key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SealedContent {
+    /// Base64 ciphertext (including the authentication tag)
+    pub ciphertext: String,
+    /// KDF and salt, e.g. `$hkdf-sha256$<salt>`
+    pub kdf: String,
+    /// Base64 XChaCha20-Poly1305 nonce
+    pub nonce: String,
+}
+
+/// KDF identifier of keys derived with HKDF-SHA256
+const HKDF_PREFIX: &str = "$hkdf-sha256$";
+
+/// HKDF context binding derived keys to content encryption
+const HKDF_INFO: &[u8] = b"blog-backend content key v1";
+
+/// Encrypt content with a freshly salted key
+pub fn seal(master_key: &str, plaintext: &str) -> Result<SealedContent, ApiError> {
+    let salt = SaltString::generate(&mut OsRng);
+    let kdf = format!("{}{}", HKDF_PREFIX, salt.as_str());
+
+    let cipher = cipher_for(master_key, &kdf)?;
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| ApiError::InternalError("Failed to encrypt content".to_string()))?;
+
+    Ok(SealedContent {
+        ciphertext: BASE64_STANDARD.encode(ciphertext),
+        kdf,
+        nonce: BASE64_STANDARD.encode(nonce),
+    })
+}
+
+/// Decrypt content sealed by `seal`
+pub fn open(master_key: &str, sealed: &SealedContent) -> Result<String, ApiError> {
+    let cipher = cipher_for(master_key, &sealed.kdf)?;
+    let nonce: [u8; 24] = BASE64_STANDARD
+        .decode(&sealed.nonce)
+        .ok()
+        .and_then(|nonce| nonce.try_into().ok())
+        .ok_or_else(|| ApiError::InternalError("Invalid content nonce".to_string()))?;
+    let ciphertext = BASE64_STANDARD
+        .decode(&sealed.ciphertext)
+        .map_err(|_| ApiError::InternalError("Invalid content ciphertext".to_string()))?;
+
+    let plaintext = cipher
+        .decrypt(&XNonce::from(nonce), ciphertext.as_slice())
+        .map_err(|_| {
+            tracing::error!("Failed to decrypt content: wrong master key or corrupted data");
+            ApiError::InternalError("Failed to decrypt content".to_string())
+        })?;
+
+    String::from_utf8(plaintext)
+        .map_err(|_| ApiError::InternalError("Decrypted content is not UTF-8".to_string()))
+}
+
+/// Prefix of single-string values produced by `seal_value`
+const SEALED_VALUE_PREFIX: &str = "enc:v1:";
+
+/// Encrypt a short value, such as a credential, into one storable string
+///
+/// The format is `enc:v1:<nonce>:<ciphertext>:<kdf>`.
+pub fn seal_value(master_key: &str, plaintext: &str) -> Result<String, ApiError> {
+    let sealed = seal(master_key, plaintext)?;
+    Ok(format!(
+        "{}{}:{}:{}",
+        SEALED_VALUE_PREFIX, sealed.nonce, sealed.ciphertext, sealed.kdf
+    ))
+}
+
+/// Whether a stored value was produced by `seal_value`
+pub fn is_sealed_value(value: &str) -> bool {
+    value.starts_with(SEALED_VALUE_PREFIX)
+}
+
+/// Decrypt a value produced by `seal_value`
+pub fn open_value(master_key: &str, value: &str) -> Result<String, ApiError> {
+    let mut parts = value
+        .strip_prefix(SEALED_VALUE_PREFIX)
+        .ok_or_else(|| ApiError::InternalError("Value is not encrypted".to_string()))?
+        .splitn(3, ':');
+    let (Some(nonce), Some(ciphertext), Some(kdf)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(ApiError::InternalError(
+            "Invalid encrypted value".to_string(),
+        ));
+    };
+
+    open(
+        master_key,
+        &SealedContent {
+            ciphertext: ciphertext.to_string(),
+            kdf: kdf.to_string(),
+            nonce: nonce.to_string(),
+        },
+    )
+}
+
+/// Check that a KDF string can be used to derive a key
+pub fn validate_kdf(kdf: &str) -> Result<(), ApiError> {
+    parse_kdf(kdf).map(|_| ())
+}
+
+/// Salt of a `$hkdf-sha256$<salt>` KDF string
+fn parse_kdf(kdf: &str) -> Result<SaltString, ApiError> {
+    kdf.strip_prefix(HKDF_PREFIX)
+        .and_then(|salt| SaltString::from_b64(salt).ok())
+        .ok_or_else(|| ApiError::ValidationError(format!("Unsupported content KDF: {}", kdf)))
+}
+
+fn cipher_for(master_key: &str, kdf: &str) -> Result<XChaCha20Poly1305, ApiError> {
+    let salt = parse_kdf(kdf)?;
+
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(salt.as_str().as_bytes()), master_key.as_bytes())
+        .expand(HKDF_INFO, &mut key)
+        .map_err(|e| {
+            tracing::error!("Failed to derive content key: {}", e);
+            ApiError::InternalError("Failed to derive content key".to_string())
+        })?;
+
+    Ok(XChaCha20Poly1305::new(&key.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seal_and_open_round_trip() {
+        let sealed = seal("master", "秘密内容").unwrap();
+
+        assert!(sealed.kdf.starts_with("$hkdf-sha256$"));
+        assert_ne!(sealed.ciphertext, "秘密内容");
+        assert_eq!(open("master", &sealed).unwrap(), "秘密内容");
+    }
+
+    #[test]
+    fn each_seal_uses_fresh_salt_and_nonce() {
+        let a = seal("master", "same").unwrap();
+        let b = seal("master", "same").unwrap();
+
+        assert_ne!(a.kdf, b.kdf);
+        assert_ne!(a.nonce, b.nonce);
+        assert_ne!(a.ciphertext, b.ciphertext);
+    }
+
+    #[test]
+    fn open_rejects_wrong_key_and_tampering() {
+        let sealed = seal("master", "secret").unwrap();
+        assert!(open("other", &sealed).is_err());
+
+        let mut bytes = BASE64_STANDARD.decode(&sealed.ciphertext).unwrap();
+        bytes[0] ^= 1;
+        let tampered = SealedContent {
+            ciphertext: BASE64_STANDARD.encode(bytes),
+            ..sealed
+        };
+        assert!(open("master", &tampered).is_err());
+    }
+
+    #[test]
+    fn sealed_values_round_trip_as_one_string() {
+        let value = seal_value("master", "AKIA-secret").unwrap();
+
+        assert!(is_sealed_value(&value));
+        assert!(!value.contains("AKIA-secret"));
+        assert_eq!(open_value("master", &value).unwrap(), "AKIA-secret");
+        assert!(open_value("other", &value).is_err());
+        assert!(!is_sealed_value("AKIA-secret"));
+        assert!(open_value("master", "AKIA-secret").is_err());
+        assert!(open_value("master", "enc:v1:broken").is_err());
+    }
+
+    #[test]
+    fn validate_kdf_requires_salted_hkdf() {
+        let sealed = seal("master", "secret").unwrap();
+        assert!(validate_kdf(&sealed.kdf).is_ok());
+        assert!(validate_kdf("$argon2id$v=19$m=19456,t=2,p=1$c2FsdHNhbHQ").is_err());
+        assert!(validate_kdf("$hkdf-sha256$").is_err());
+        assert!(validate_kdf("plain").is_err());
+    }
+}
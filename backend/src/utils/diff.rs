@@ -0,0 +1,76 @@
+//! Line diff utilities
+
+use similar::{ChangeTag, TextDiff};
+
+/// Number of unchanged lines shown around each hunk
+const CONTEXT_LINES: usize = 3;
+
+/// Result of a line-by-line comparison
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineDiff {
+    pub additions: usize,
+    pub deletions: usize,
+    /// Unified diff text, empty when both sides are identical
+    pub unified: String,
+}
+
+/// Compare two texts line by line and render a unified diff
+pub fn line_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> LineDiff {
+    let diff = TextDiff::from_lines(old, new);
+
+    let mut additions = 0;
+    let mut deletions = 0;
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => additions += 1,
+            ChangeTag::Delete => deletions += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+
+    let unified = if additions == 0 && deletions == 0 {
+        String::new()
+    } else {
+        diff.unified_diff()
+            .context_radius(CONTEXT_LINES)
+            .header(old_label, new_label)
+            .to_string()
+    };
+
+    LineDiff {
+        additions,
+        deletions,
+        unified,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_texts_have_no_diff() {
+        let diff = line_diff("a\nb\n", "a\nb\n", "r1", "r2");
+
+        assert_eq!(diff.additions, 0);
+        assert_eq!(diff.deletions, 0);
+        assert!(diff.unified.is_empty());
+    }
+
+    #[test]
+    fn changed_line_counts_as_one_deletion_and_one_addition() {
+        let diff = line_diff(
+            "# Title\nold line\nend\n",
+            "# Title\nnew line\nend\n",
+            "r1",
+            "r2",
+        );
+
+        assert_eq!(diff.additions, 1);
+        assert_eq!(diff.deletions, 1);
+        assert!(diff.unified.starts_with("--- r1\n+++ r2\n"));
+        assert!(diff.unified.contains("-old line\n"));
+        assert!(diff.unified.contains("+new line\n"));
+        assert!(diff.unified.contains(" end\n"));
+    }
+}
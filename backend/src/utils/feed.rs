@@ -0,0 +1,277 @@
+//! Feed rendering utilities
+//!
+//! Renders RSS 2.0, Atom 1.0 and JSON Feed 1.1 documents from blog data.
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+/// Channel-level metadata shared by every feed format
+#[derive(Debug, Clone)]
+pub struct FeedChannel {
+    pub title: String,
+    pub description: String,
+    pub author: String,
+    pub link: String,
+    pub updated: Option<DateTime<Utc>>,
+}
+
+/// A single feed entry
+#[derive(Debug, Clone)]
+pub struct FeedItem {
+    pub title: String,
+    pub link: String,
+    pub author: Option<String>,
+    pub summary: Option<String>,
+    pub content_html: Option<String>,
+    pub categories: Vec<String>,
+    pub published: Option<DateTime<Utc>>,
+    pub updated: Option<DateTime<Utc>>,
+}
+
+/// Render an RSS 2.0 document
+pub fn render_rss(channel: &FeedChannel, items: &[FeedItem]) -> String {
+    let mut xml = String::new();
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push('\n');
+    xml.push_str(
+        r#"<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/" xmlns:dc="http://purl.org/dc/elements/1.1/">"#,
+    );
+    xml.push_str("<channel>");
+    push_element(&mut xml, "title", &channel.title);
+    push_element(&mut xml, "link", &channel.link);
+    push_element(&mut xml, "description", &channel.description);
+    if let Some(updated) = channel.updated {
+        push_element(&mut xml, "lastBuildDate", &updated.to_rfc2822());
+    }
+    push_element(&mut xml, "generator", "blog-backend");
+
+    for item in items {
+        xml.push_str("<item>");
+        push_element(&mut xml, "title", &item.title);
+        push_element(&mut xml, "link", &item.link);
+        xml.push_str(&format!(
+            r#"<guid isPermaLink="true">{}</guid>"#,
+            xml_escape(&item.link)
+        ));
+        if let Some(author) = item.author.as_deref().or(Some(&channel.author)) {
+            if !author.is_empty() {
+                push_element(&mut xml, "dc:creator", author);
+            }
+        }
+        if let Some(published) = item.published {
+            push_element(&mut xml, "pubDate", &published.to_rfc2822());
+        }
+        for category in &item.categories {
+            push_element(&mut xml, "category", category);
+        }
+        if let Some(summary) = &item.summary {
+            push_element(&mut xml, "description", summary);
+        }
+        if let Some(html) = &item.content_html {
+            xml.push_str("<content:encoded>");
+            xml.push_str(&cdata(html));
+            xml.push_str("</content:encoded>");
+        }
+        xml.push_str("</item>");
+    }
+
+    xml.push_str("</channel></rss>");
+    xml
+}
+
+/// Render an Atom 1.0 document
+pub fn render_atom(channel: &FeedChannel, items: &[FeedItem]) -> String {
+    let updated = channel.updated.unwrap_or_else(Utc::now);
+
+    let mut xml = String::new();
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push('\n');
+    xml.push_str(r#"<feed xmlns="http://www.w3.org/2005/Atom">"#);
+    push_element(&mut xml, "id", &channel.link);
+    push_element(&mut xml, "title", &channel.title);
+    push_element(&mut xml, "subtitle", &channel.description);
+    push_element(&mut xml, "updated", &updated.to_rfc3339());
+    xml.push_str(&format!(
+        r#"<link rel="alternate" href="{}"/>"#,
+        xml_escape(&channel.link)
+    ));
+    if !channel.author.is_empty() {
+        xml.push_str("<author>");
+        push_element(&mut xml, "name", &channel.author);
+        xml.push_str("</author>");
+    }
+    push_element(&mut xml, "generator", "blog-backend");
+
+    for item in items {
+        let entry_updated = item.updated.or(item.published).unwrap_or(updated);
+
+        xml.push_str("<entry>");
+        push_element(&mut xml, "id", &item.link);
+        push_element(&mut xml, "title", &item.title);
+        xml.push_str(&format!(
+            r#"<link rel="alternate" href="{}"/>"#,
+            xml_escape(&item.link)
+        ));
+        push_element(&mut xml, "updated", &entry_updated.to_rfc3339());
+        if let Some(published) = item.published {
+            push_element(&mut xml, "published", &published.to_rfc3339());
+        }
+        if let Some(author) = item.author.as_deref().filter(|a| !a.is_empty()) {
+            xml.push_str("<author>");
+            push_element(&mut xml, "name", author);
+            xml.push_str("</author>");
+        }
+        for category in &item.categories {
+            xml.push_str(&format!(r#"<category term="{}"/>"#, xml_escape(category)));
+        }
+        if let Some(summary) = &item.summary {
+            push_element(&mut xml, "summary", summary);
+        }
+        if let Some(html) = &item.content_html {
+            xml.push_str(r#"<content type="html">"#);
+            xml.push_str(&xml_escape(html));
+            xml.push_str("</content>");
+        }
+        xml.push_str("</entry>");
+    }
+
+    xml.push_str("</feed>");
+    xml
+}
+
+/// Render a JSON Feed 1.1 document
+pub fn render_json_feed(channel: &FeedChannel, items: &[FeedItem]) -> serde_json::Value {
+    let items: Vec<serde_json::Value> = items
+        .iter()
+        .map(|item| {
+            let mut entry = json!({
+                "id": item.link,
+                "url": item.link,
+                "title": item.title,
+                "tags": item.categories,
+            });
+            if let Some(summary) = &item.summary {
+                entry["summary"] = json!(summary);
+            }
+            // JSON Feed requires at least one of content_html / content_text
+            entry["content_html"] = json!(item
+                .content_html
+                .as_deref()
+                .or(item.summary.as_deref())
+                .unwrap_or_default());
+            if let Some(published) = item.published {
+                entry["date_published"] = json!(published.to_rfc3339());
+            }
+            if let Some(updated) = item.updated {
+                entry["date_modified"] = json!(updated.to_rfc3339());
+            }
+            if let Some(author) = item.author.as_deref().filter(|a| !a.is_empty()) {
+                entry["authors"] = json!([{ "name": author }]);
+            }
+            entry
+        })
+        .collect();
+
+    let mut feed = json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": channel.title,
+        "home_page_url": channel.link,
+        "description": channel.description,
+        "items": items,
+    });
+    if !channel.author.is_empty() {
+        feed["authors"] = json!([{ "name": channel.author }]);
+    }
+    feed
+}
+
+/// Append `<tag>escaped text</tag>`
+fn push_element(xml: &mut String, tag: &str, text: &str) {
+    xml.push('<');
+    xml.push_str(tag);
+    xml.push('>');
+    xml.push_str(&xml_escape(text));
+    xml.push_str("</");
+    xml.push_str(tag);
+    xml.push('>');
+}
+
+/// Escape XML special characters
+pub fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Wrap text in a CDATA section, splitting any embedded `]]>` terminator
+fn cdata(s: &str) -> String {
+    format!("<![CDATA[{}]]>", s.replace("]]>", "]]]]><![CDATA[>"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn sample_channel() -> FeedChannel {
+        FeedChannel {
+            title: "Tom & Jerry's Blog".to_string(),
+            description: "A <tech> blog".to_string(),
+            author: "Tom".to_string(),
+            link: "https://example.com".to_string(),
+            updated: Some(Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap()),
+        }
+    }
+
+    fn sample_item() -> FeedItem {
+        FeedItem {
+            title: "Hello <World>".to_string(),
+            link: "https://example.com/blog/hello".to_string(),
+            author: None,
+            summary: Some("Summary & more".to_string()),
+            content_html: Some("<p>Body ]]> end</p>".to_string()),
+            categories: vec!["Rust".to_string()],
+            published: Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()),
+            updated: None,
+        }
+    }
+
+    #[test]
+    fn rss_escapes_text_and_wraps_html_in_cdata() {
+        let xml = render_rss(&sample_channel(), &[sample_item()]);
+
+        assert!(xml.contains("<title>Tom &amp; Jerry&apos;s Blog</title>"));
+        assert!(xml.contains("<title>Hello &lt;World&gt;</title>"));
+        assert!(xml.contains("<description>Summary &amp; more</description>"));
+        assert!(xml.contains("<![CDATA[<p>Body ]]]]><![CDATA[> end</p>]]>"));
+        assert!(xml.contains("<dc:creator>Tom</dc:creator>"));
+        assert!(xml.contains("<pubDate>Mon, 1 Jan 2024 00:00:00 +0000</pubDate>"));
+    }
+
+    #[test]
+    fn atom_entry_falls_back_to_published_for_updated() {
+        let xml = render_atom(&sample_channel(), &[sample_item()]);
+
+        assert!(xml.contains("<updated>2024-01-02T03:04:05+00:00</updated>"));
+        assert!(xml.contains(
+            "<entry><id>https://example.com/blog/hello</id><title>Hello &lt;World&gt;</title>"
+        ));
+        assert!(xml.contains("<updated>2024-01-01T00:00:00+00:00</updated>"));
+        assert!(xml.contains(r#"<category term="Rust"/>"#));
+        assert!(xml.contains(r#"<content type="html">&lt;p&gt;Body"#));
+    }
+
+    #[test]
+    fn json_feed_always_has_content() {
+        let mut item = sample_item();
+        item.content_html = None;
+        let feed = render_json_feed(&sample_channel(), &[item]);
+
+        assert_eq!(feed["version"], "https://jsonfeed.org/version/1.1");
+        assert_eq!(feed["items"][0]["content_html"], "Summary & more");
+        assert_eq!(feed["items"][0]["tags"][0], "Rust");
+        assert_eq!(feed["authors"][0]["name"], "Tom");
+    }
+}
@@ -0,0 +1,565 @@
+//! Image processing for uploads
+//!
+//! Decodes uploaded raster images to read their dimensions, renders a thumbnail
+//! and responsive-width variants, and strips EXIF/XMP metadata (GPS position,
+//! camera details) from the stored original. Formats the decoder doesn't know,
+//! like SVG, are passed through untouched.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits};
+
+use crate::error::ApiError;
+
+/// Largest width or height accepted for decoding
+const MAX_DIMENSION: u32 = 16_384;
+
+/// Memory the decoder may allocate for one image
+const MAX_DECODE_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Quality of generated JPEG variants
+const JPEG_QUALITY: u8 = 82;
+
+/// Quality used when an original has to be re-encoded
+const ORIGINAL_JPEG_QUALITY: u8 = 92;
+
+/// Processing settings read from site config
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageOptions {
+    /// Bounding box of the thumbnail, in pixels
+    pub thumbnail_size: u32,
+    /// Widths of the responsive variants; widths above the original are skipped
+    pub variant_widths: Vec<u32>,
+    /// Also render every variant as WebP
+    pub webp: bool,
+    /// Remove EXIF/XMP metadata from the stored original
+    pub strip_metadata: bool,
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        Self {
+            thumbnail_size: 320,
+            variant_widths: vec![640, 1280, 1920],
+            webp: true,
+            strip_metadata: true,
+        }
+    }
+}
+
+impl ImageOptions {
+    /// Build options from the `image_*` site config keys, using defaults for missing or invalid ones
+    pub fn from_map(map: &HashMap<String, String>) -> Self {
+        let defaults = Self::default();
+        let flag = |key: &str, default: bool| {
+            map.get(key)
+                .map(|value| value.trim() == "true")
+                .unwrap_or(default)
+        };
+
+        let thumbnail_size = map
+            .get("image_thumbnail_size")
+            .and_then(|value| value.trim().parse::<u32>().ok())
+            .filter(|size| (1..=MAX_DIMENSION).contains(size))
+            .unwrap_or(defaults.thumbnail_size);
+
+        let variant_widths = match map.get("image_variant_widths") {
+            Some(value) => {
+                let mut widths: Vec<u32> = value
+                    .split(',')
+                    .filter_map(|width| width.trim().parse::<u32>().ok())
+                    .filter(|width| (1..=MAX_DIMENSION).contains(width))
+                    .collect();
+                widths.sort_unstable();
+                widths.dedup();
+                widths
+            }
+            None => defaults.variant_widths,
+        };
+
+        Self {
+            thumbnail_size,
+            variant_widths,
+            webp: flag("image_webp_enabled", defaults.webp),
+            strip_metadata: flag("image_strip_metadata", defaults.strip_metadata),
+        }
+    }
+}
+
+/// Encoding of a generated variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariantFormat {
+    Jpeg,
+    Png,
+    WebP,
+}
+
+impl VariantFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VariantFormat::Jpeg => "jpeg",
+            VariantFormat::Png => "png",
+            VariantFormat::WebP => "webp",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            VariantFormat::Jpeg => "jpg",
+            VariantFormat::Png => "png",
+            VariantFormat::WebP => "webp",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            VariantFormat::Jpeg => "image/jpeg",
+            VariantFormat::Png => "image/png",
+            VariantFormat::WebP => "image/webp",
+        }
+    }
+}
+
+/// Purpose of a generated variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariantKind {
+    Thumbnail,
+    Responsive,
+}
+
+impl VariantKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VariantKind::Thumbnail => "thumbnail",
+            VariantKind::Responsive => "responsive",
+        }
+    }
+}
+
+/// Encoded variant ready for upload
+#[derive(Debug, Clone)]
+pub struct ImageVariant {
+    pub kind: VariantKind,
+    pub format: VariantFormat,
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+/// Result of processing an uploaded image
+#[derive(Debug, Clone)]
+pub struct ProcessedImage {
+    /// MIME type detected from the file content
+    pub content_type: &'static str,
+    /// Dimensions as displayed, after applying the EXIF orientation
+    pub width: u32,
+    pub height: u32,
+    /// Replacement for the uploaded bytes when metadata had to be removed
+    pub original: Option<Vec<u8>>,
+    pub variants: Vec<ImageVariant>,
+}
+
+/// Process an uploaded file; returns `None` when it isn't a supported raster image
+pub fn process(data: &[u8], options: &ImageOptions) -> Result<Option<ProcessedImage>, ApiError> {
+    let mut reader = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .map_err(|e| ApiError::FileUploadError(format!("Failed to read image: {}", e)))?;
+    let format = match reader.format() {
+        Some(
+            format @ (ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::Gif | ImageFormat::WebP),
+        ) => format,
+        _ => return Ok(None),
+    };
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODE_BYTES);
+    reader.limits(limits);
+
+    let invalid = |e: image::ImageError| ApiError::FileUploadError(format!("Invalid image: {}", e));
+    let mut decoder = reader.into_decoder().map_err(invalid)?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut image = DynamicImage::from_decoder(decoder).map_err(invalid)?;
+    image.apply_orientation(orientation);
+
+    let original = if !options.strip_metadata {
+        None
+    } else if orientation != Orientation::NoTransforms {
+        // Dropping the orientation tag would show the photo sideways, so bake it in
+        Some(encode_original(&image, format)?)
+    } else {
+        match strip_metadata(data, format) {
+            Some(stripped) if stripped.len() == data.len() => None,
+            Some(stripped) => Some(stripped),
+            None => Some(encode_original(&image, format)?),
+        }
+    };
+
+    Ok(Some(ProcessedImage {
+        content_type: format.to_mime_type(),
+        width: image.width(),
+        height: image.height(),
+        original,
+        variants: render_variants(&image, options)?,
+    }))
+}
+
+fn render_variants(
+    image: &DynamicImage,
+    options: &ImageOptions,
+) -> Result<Vec<ImageVariant>, ApiError> {
+    let fallback = if image.color().has_alpha() {
+        VariantFormat::Png
+    } else {
+        VariantFormat::Jpeg
+    };
+    let mut formats = vec![fallback];
+    if options.webp {
+        formats.push(VariantFormat::WebP);
+    }
+
+    let size = options.thumbnail_size;
+    let thumbnail = if image.width() > size || image.height() > size {
+        image.thumbnail(size, size)
+    } else {
+        image.clone()
+    };
+    let mut resized = vec![(VariantKind::Thumbnail, thumbnail)];
+    for &width in options
+        .variant_widths
+        .iter()
+        .filter(|width| **width < image.width())
+    {
+        let height = ((image.height() as u64 * width as u64) / image.width() as u64).max(1) as u32;
+        resized.push((
+            VariantKind::Responsive,
+            image.resize_exact(width, height, FilterType::Lanczos3),
+        ));
+    }
+
+    let mut variants = Vec::with_capacity(resized.len() * formats.len());
+    for (kind, image) in &resized {
+        for &format in &formats {
+            variants.push(ImageVariant {
+                kind: *kind,
+                format,
+                width: image.width(),
+                height: image.height(),
+                data: encode(image, format, JPEG_QUALITY)?,
+            });
+        }
+    }
+
+    Ok(variants)
+}
+
+fn encode(image: &DynamicImage, format: VariantFormat, quality: u8) -> Result<Vec<u8>, ApiError> {
+    let mut buffer = Vec::new();
+    let result = match format {
+        VariantFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut buffer, quality)),
+        VariantFormat::Png => image.write_with_encoder(PngEncoder::new(&mut buffer)),
+        VariantFormat::WebP if image.color().has_alpha() => {
+            DynamicImage::ImageRgba8(image.to_rgba8())
+                .write_with_encoder(WebPEncoder::new_lossless(&mut buffer))
+        }
+        VariantFormat::WebP => DynamicImage::ImageRgb8(image.to_rgb8())
+            .write_with_encoder(WebPEncoder::new_lossless(&mut buffer)),
+    };
+    result.map_err(|e| ApiError::InternalError(format!("Failed to encode image: {}", e)))?;
+
+    Ok(buffer)
+}
+
+/// Re-encode the original in its own format; the encoders write no metadata
+fn encode_original(image: &DynamicImage, format: ImageFormat) -> Result<Vec<u8>, ApiError> {
+    match format {
+        ImageFormat::Jpeg => encode(image, VariantFormat::Jpeg, ORIGINAL_JPEG_QUALITY),
+        ImageFormat::WebP => encode(image, VariantFormat::WebP, ORIGINAL_JPEG_QUALITY),
+        _ => encode(image, VariantFormat::Png, ORIGINAL_JPEG_QUALITY),
+    }
+}
+
+/// Remove metadata blocks without re-encoding; `None` when the file can't be parsed
+fn strip_metadata(data: &[u8], format: ImageFormat) -> Option<Vec<u8>> {
+    match format {
+        ImageFormat::Jpeg => strip_jpeg(data),
+        ImageFormat::Png => strip_png(data),
+        ImageFormat::WebP => strip_webp(data),
+        // GIF has no EXIF
+        _ => Some(data.to_vec()),
+    }
+}
+
+/// Drop APP1 (EXIF, XMP), the other application segments and comments;
+/// keep JFIF (APP0), ICC profiles (APP2) and the Adobe color transform (APP14)
+fn strip_jpeg(data: &[u8]) -> Option<Vec<u8>> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+
+    let mut output = Vec::with_capacity(data.len());
+    output.extend_from_slice(&data[..2]);
+    let mut pos = 2;
+    loop {
+        if data.get(pos) != Some(&0xFF) {
+            return None;
+        }
+        let marker = *data.get(pos + 1)?;
+        // Start of scan: the rest is entropy-coded image data
+        if marker == 0xDA {
+            output.extend_from_slice(&data[pos..]);
+            return Some(output);
+        }
+
+        let length = u16::from_be_bytes([*data.get(pos + 2)?, *data.get(pos + 3)?]) as usize;
+        let end = pos + 2 + length;
+        if length < 2 || end > data.len() {
+            return None;
+        }
+
+        let is_metadata = matches!(marker, 0xE1 | 0xE3..=0xED | 0xEF | 0xFE);
+        if !is_metadata {
+            output.extend_from_slice(&data[pos..end]);
+        }
+        pos = end;
+    }
+}
+
+/// Drop `eXIf`, text and timestamp chunks
+fn strip_png(data: &[u8]) -> Option<Vec<u8>> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if !data.starts_with(SIGNATURE) {
+        return None;
+    }
+
+    let mut output = Vec::with_capacity(data.len());
+    output.extend_from_slice(SIGNATURE);
+    let mut pos = SIGNATURE.len();
+    while pos < data.len() {
+        let length = u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?) as usize;
+        let chunk_type = data.get(pos + 4..pos + 8)?;
+        // Length, type, data and CRC
+        let end = pos.checked_add(12)?.checked_add(length)?;
+        if end > data.len() {
+            return None;
+        }
+
+        if !matches!(chunk_type, b"eXIf" | b"tEXt" | b"zTXt" | b"iTXt" | b"tIME") {
+            output.extend_from_slice(&data[pos..end]);
+        }
+        pos = end;
+    }
+
+    Some(output)
+}
+
+/// Drop `EXIF` and `XMP ` chunks and clear their flags in the `VP8X` header
+fn strip_webp(data: &[u8]) -> Option<Vec<u8>> {
+    if data.len() < 12 || &data[..4] != b"RIFF" || &data[8..12] != b"WEBP" {
+        return None;
+    }
+
+    let mut output = Vec::with_capacity(data.len());
+    output.extend_from_slice(&data[..12]);
+    let mut pos = 12;
+    while pos < data.len() {
+        let fourcc = data.get(pos..pos + 4)?;
+        let size = u32::from_le_bytes(data.get(pos + 4..pos + 8)?.try_into().ok()?) as usize;
+        // Chunks are padded to an even size
+        let end = pos.checked_add(8)?.checked_add(size + (size & 1))?;
+        if end > data.len() {
+            return None;
+        }
+
+        match fourcc {
+            b"EXIF" | b"XMP " => {}
+            b"VP8X" if size >= 1 => {
+                let start = output.len();
+                output.extend_from_slice(&data[pos..end]);
+                // Flags byte: 0x08 = EXIF present, 0x04 = XMP present
+                output[start + 8] &= !0x0C;
+            }
+            _ => output.extend_from_slice(&data[pos..end]),
+        }
+        pos = end;
+    }
+
+    let riff_size = u32::try_from(output.len() - 8).ok()?;
+    output[4..8].copy_from_slice(&riff_size.to_le_bytes());
+
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage, Rgba, RgbaImage};
+
+    const GPS_MARKER: &[u8] = b"GPSLatitude=48.8584";
+
+    fn options() -> ImageOptions {
+        ImageOptions {
+            thumbnail_size: 50,
+            variant_widths: vec![100, 200, 800],
+            webp: true,
+            strip_metadata: true,
+        }
+    }
+
+    fn jpeg(width: u32, height: u32) -> Vec<u8> {
+        let image =
+            DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb([200, 80, 40])));
+        encode(&image, VariantFormat::Jpeg, 90).unwrap()
+    }
+
+    /// Insert an APP1 EXIF segment carrying fake GPS data after the SOI marker
+    fn with_exif(jpeg: &[u8]) -> Vec<u8> {
+        let mut payload = b"Exif\0\0".to_vec();
+        payload.extend_from_slice(GPS_MARKER);
+        let length = (payload.len() + 2) as u16;
+
+        let mut output = jpeg[..2].to_vec();
+        output.extend_from_slice(&[0xFF, 0xE1]);
+        output.extend_from_slice(&length.to_be_bytes());
+        output.extend_from_slice(&payload);
+        output.extend_from_slice(&jpeg[2..]);
+        output
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack
+            .windows(needle.len())
+            .any(|window| window == needle)
+    }
+
+    #[test]
+    fn options_fall_back_to_defaults() {
+        assert_eq!(
+            ImageOptions::from_map(&HashMap::new()),
+            ImageOptions::default()
+        );
+
+        let map: HashMap<String, String> = [
+            ("image_thumbnail_size", "abc"),
+            ("image_variant_widths", "1280, 640, x, 0, 640"),
+            ("image_webp_enabled", "false"),
+        ]
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+        let options = ImageOptions::from_map(&map);
+
+        assert_eq!(options.thumbnail_size, 320);
+        assert_eq!(options.variant_widths, vec![640, 1280]);
+        assert!(!options.webp);
+        assert!(options.strip_metadata);
+    }
+
+    #[test]
+    fn jpeg_gets_dimensions_and_variants() {
+        let processed = process(&jpeg(400, 200), &options()).unwrap().unwrap();
+
+        assert_eq!(processed.content_type, "image/jpeg");
+        assert_eq!((processed.width, processed.height), (400, 200));
+        assert!(processed.original.is_none());
+
+        let summary: Vec<_> = processed
+            .variants
+            .iter()
+            .map(|v| (v.kind, v.format, v.width, v.height))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (VariantKind::Thumbnail, VariantFormat::Jpeg, 50, 25),
+                (VariantKind::Thumbnail, VariantFormat::WebP, 50, 25),
+                (VariantKind::Responsive, VariantFormat::Jpeg, 100, 50),
+                (VariantKind::Responsive, VariantFormat::WebP, 100, 50),
+                (VariantKind::Responsive, VariantFormat::Jpeg, 200, 100),
+                (VariantKind::Responsive, VariantFormat::WebP, 200, 100),
+            ]
+        );
+        assert!(processed.variants[1].data.starts_with(b"RIFF"));
+    }
+
+    #[test]
+    fn exif_is_stripped_from_original() {
+        let data = with_exif(&jpeg(64, 64));
+        assert!(contains(&data, GPS_MARKER));
+
+        let processed = process(&data, &options()).unwrap().unwrap();
+        let original = processed.original.unwrap();
+        assert!(!contains(&original, GPS_MARKER));
+        assert_eq!(original.len(), data.len() - (GPS_MARKER.len() + 10));
+        assert!(process(&original, &options()).unwrap().is_some());
+
+        let keep = ImageOptions {
+            strip_metadata: false,
+            ..options()
+        };
+        assert!(process(&data, &keep).unwrap().unwrap().original.is_none());
+    }
+
+    #[test]
+    fn png_text_chunks_are_stripped_and_alpha_kept() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(120, 60, Rgba([0, 0, 0, 128])));
+        let png = encode(&image, VariantFormat::Png, 0).unwrap();
+
+        // tEXt chunk right after IHDR (8 byte signature + 25 byte IHDR)
+        let text = b"Comment\0secret";
+        let mut data = png[..33].to_vec();
+        data.extend_from_slice(&(text.len() as u32).to_be_bytes());
+        data.extend_from_slice(b"tEXt");
+        data.extend_from_slice(text);
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        data.extend_from_slice(&png[33..]);
+
+        assert_eq!(strip_png(&data).unwrap(), png);
+
+        let processed = process(&data, &options()).unwrap().unwrap();
+        assert_eq!(processed.original.unwrap(), png);
+        assert_eq!(processed.variants[0].format, VariantFormat::Png);
+        assert_eq!(processed.variants[0].width, 50);
+    }
+
+    #[test]
+    fn webp_metadata_chunks_are_stripped() {
+        let mut data = b"RIFF\0\0\0\0WEBP".to_vec();
+        data.extend_from_slice(b"VP8X");
+        data.extend_from_slice(&10u32.to_le_bytes());
+        data.extend_from_slice(&[0x0C, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        data.extend_from_slice(b"EXIF");
+        data.extend_from_slice(&(GPS_MARKER.len() as u32).to_le_bytes());
+        data.extend_from_slice(GPS_MARKER);
+        data.push(0);
+        let size = (data.len() - 8) as u32;
+        data[4..8].copy_from_slice(&size.to_le_bytes());
+
+        let stripped = strip_webp(&data).unwrap();
+        assert!(!contains(&stripped, GPS_MARKER));
+        assert_eq!(stripped[20], 0);
+        assert_eq!(
+            u32::from_le_bytes(stripped[4..8].try_into().unwrap()) as usize,
+            stripped.len() - 8
+        );
+    }
+
+    #[test]
+    fn non_images_are_passed_through() {
+        assert!(process(b"plain text", &options()).unwrap().is_none());
+        assert!(
+            process(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>", &options())
+                .unwrap()
+                .is_none()
+        );
+        assert!(process(&[0xFF, 0xD8, 0xFF, 0xE0, 0, 2], &options()).is_err());
+    }
+}
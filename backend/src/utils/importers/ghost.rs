@@ -0,0 +1,261 @@
+//! Ghost JSON export importer
+//!
+//! Handles both the current `{"db": [{"data": ...}]}` layout and the older
+//! `{"data": ...}` one. Ghost has tags but no categories, so imported posts
+//! only get tags; internal tags (`#name`) are left out. Markdown is used when
+//! the export has it, otherwise the rendered HTML.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::{non_empty, normalize_slug, parse_date, slug_from_title};
+use crate::error::ApiError;
+use crate::models::import::{ImportedPost, ParsedImport};
+
+/// Parse a Ghost export
+pub fn parse(json: &str) -> Result<ParsedImport, ApiError> {
+    let root: Value = serde_json::from_str(json)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid Ghost export: {}", e)))?;
+    let data = root
+        .pointer("/db/0/data")
+        .or_else(|| root.get("data"))
+        .ok_or_else(|| {
+            ApiError::BadRequest("Not a Ghost export: no data section found".to_string())
+        })?;
+    let rows = |table: &str| {
+        data.get(table)
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default()
+    };
+
+    let tag_names: HashMap<String, String> = rows("tags")
+        .iter()
+        .filter_map(|tag| Some((id_of(tag.get("id")?)?, text(tag, "name")?)))
+        .filter(|(_, name)| !name.starts_with('#'))
+        .collect();
+    let user_names: HashMap<String, String> = rows("users")
+        .iter()
+        .filter_map(|user| Some((id_of(user.get("id")?)?, text(user, "name")?)))
+        .collect();
+
+    let mut post_tags: HashMap<String, Vec<(i64, String)>> = HashMap::new();
+    for link in rows("posts_tags") {
+        let (Some(post_id), Some(tag_id)) = (
+            link.get("post_id").and_then(id_of),
+            link.get("tag_id").and_then(id_of),
+        ) else {
+            continue;
+        };
+        if let Some(name) = tag_names.get(&tag_id) {
+            let order = link.get("sort_order").and_then(Value::as_i64).unwrap_or(0);
+            post_tags
+                .entry(post_id)
+                .or_default()
+                .push((order, name.clone()));
+        }
+    }
+    let mut post_authors: HashMap<String, (i64, String)> = HashMap::new();
+    for link in rows("posts_authors") {
+        let (Some(post_id), Some(author_id)) = (
+            link.get("post_id").and_then(id_of),
+            link.get("author_id").and_then(id_of),
+        ) else {
+            continue;
+        };
+        let order = link.get("sort_order").and_then(Value::as_i64).unwrap_or(0);
+        if post_authors
+            .get(&post_id)
+            .is_none_or(|(current, _)| order < *current)
+        {
+            post_authors.insert(post_id, (order, author_id));
+        }
+    }
+
+    let mut parsed = ParsedImport::default();
+    let mut skipped_pages = 0;
+    for post in rows("posts") {
+        let is_page = post.get("type").and_then(Value::as_str) == Some("page")
+            || post.get("page").and_then(Value::as_bool) == Some(true);
+        if is_page {
+            skipped_pages += 1;
+            continue;
+        }
+
+        let id = post.get("id").and_then(id_of).unwrap_or_default();
+        let title = text(&post, "title").unwrap_or_default();
+        let status = text(&post, "status").unwrap_or_default();
+        if status == "scheduled" {
+            parsed
+                .warnings
+                .push(format!("Scheduled post '{}' is imported as a draft", title));
+        }
+
+        let mut tags = post_tags.remove(&id).unwrap_or_default();
+        tags.sort();
+        let author_id = post_authors
+            .remove(&id)
+            .map(|(_, author_id)| author_id)
+            .or_else(|| post.get("author_id").and_then(id_of));
+
+        parsed.posts.push(ImportedPost {
+            slug: text(&post, "slug")
+                .and_then(|slug| normalize_slug(&slug))
+                .or_else(|| slug_from_title(&title)),
+            content: content_of(&post),
+            summary: text(&post, "custom_excerpt").or_else(|| text(&post, "meta_description")),
+            thumbnail: text(&post, "feature_image").or_else(|| text(&post, "image")),
+            author: author_id.and_then(|id| user_names.get(&id).cloned()),
+            category: None,
+            tags: tags.into_iter().map(|(_, name)| name).collect(),
+            is_published: status == "published",
+            created_at: date(&post, "published_at").or_else(|| date(&post, "created_at")),
+            updated_at: date(&post, "updated_at"),
+            references: None,
+            title,
+        });
+    }
+
+    if skipped_pages > 0 {
+        parsed
+            .warnings
+            .push(format!("Skipped {} page(s)", skipped_pages));
+    }
+
+    Ok(parsed)
+}
+
+/// Markdown if the export kept it, else the rendered HTML, else plain text
+fn content_of(post: &Value) -> String {
+    if let Some(markdown) = text(post, "markdown") {
+        return markdown;
+    }
+
+    // Ghost 1.x and 2.x stored Markdown posts as a single Mobiledoc card
+    let mobiledoc_markdown = text(post, "mobiledoc")
+        .and_then(|doc| serde_json::from_str::<Value>(&doc).ok())
+        .and_then(|doc| {
+            let cards = doc.get("cards")?.as_array()?;
+            match cards.as_slice() {
+                [card] if card.get(0)?.as_str()? == "markdown" => {
+                    let payload = card.get(1)?;
+                    payload
+                        .get("markdown")
+                        .or_else(|| payload.get("cardSource"))
+                        .and_then(Value::as_str)
+                        .and_then(non_empty)
+                }
+                _ => None,
+            }
+        });
+
+    mobiledoc_markdown
+        .or_else(|| text(post, "html"))
+        .or_else(|| text(post, "plaintext"))
+        .unwrap_or_default()
+}
+
+fn text(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).and_then(non_empty)
+}
+
+fn date(value: &Value, key: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    text(value, key).and_then(|date| parse_date(&date))
+}
+
+/// Ghost IDs are numbers in old exports and object ID strings in new ones
+fn id_of(value: &Value) -> Option<String> {
+    match value {
+        Value::String(id) => non_empty(id),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn posts_are_read_with_tags_and_authors() {
+        let export = serde_json::json!({
+            "db": [{
+                "meta": { "version": "5.0.0" },
+                "data": {
+                    "posts": [
+                        {
+                            "id": "p1", "title": "Hello Ghost", "slug": "hello-ghost",
+                            "html": "<p>Hi</p>", "status": "published", "type": "post",
+                            "feature_image": "https://example.com/cover.jpg",
+                            "custom_excerpt": "Intro",
+                            "created_at": "2020-01-01T00:00:00.000Z",
+                            "published_at": "2020-01-02T00:00:00.000Z",
+                            "updated_at": "2020-01-03T00:00:00.000Z"
+                        },
+                        {
+                            "id": "p2", "title": "Old markdown", "slug": "old",
+                            "mobiledoc": "{\"cards\":[[\"card-markdown\",{}]]}",
+                            "markdown": "# Old", "status": "draft", "type": "post"
+                        },
+                        { "id": "p3", "title": "About", "slug": "about", "type": "page" }
+                    ],
+                    "tags": [
+                        { "id": "t1", "name": "Rust" },
+                        { "id": "t2", "name": "Web" },
+                        { "id": "t3", "name": "#internal" }
+                    ],
+                    "posts_tags": [
+                        { "post_id": "p1", "tag_id": "t2", "sort_order": 1 },
+                        { "post_id": "p1", "tag_id": "t1", "sort_order": 0 },
+                        { "post_id": "p1", "tag_id": "t3", "sort_order": 2 }
+                    ],
+                    "users": [{ "id": "u1", "name": "Jane" }],
+                    "posts_authors": [{ "post_id": "p1", "author_id": "u1", "sort_order": 0 }]
+                }
+            }]
+        });
+
+        let parsed = parse(&export.to_string()).unwrap();
+        assert_eq!(parsed.posts.len(), 2);
+        assert!(parsed.warnings.iter().any(|w| w.contains("1 page")));
+
+        let post = &parsed.posts[0];
+        assert_eq!(post.slug.as_deref(), Some("hello-ghost"));
+        assert_eq!(post.content, "<p>Hi</p>");
+        assert_eq!(post.tags, vec!["Rust".to_string(), "Web".to_string()]);
+        assert_eq!(post.author.as_deref(), Some("Jane"));
+        assert_eq!(post.summary.as_deref(), Some("Intro"));
+        assert!(post.is_published);
+        assert_eq!(post.created_at, parse_date("2020-01-02T00:00:00Z"));
+
+        let draft = &parsed.posts[1];
+        assert_eq!(draft.content, "# Old");
+        assert!(!draft.is_published);
+    }
+
+    #[test]
+    fn legacy_layout_and_mobiledoc_markdown() {
+        let export = serde_json::json!({
+            "data": {
+                "posts": [{
+                    "id": 1, "title": "Legacy", "page": false, "status": "published",
+                    "mobiledoc": "{\"cards\":[[\"markdown\",{\"markdown\":\"**bold**\"}]]}",
+                    "author_id": 1
+                }],
+                "users": [{ "id": 1, "name": "Old Admin" }]
+            }
+        });
+
+        let parsed = parse(&export.to_string()).unwrap();
+        assert_eq!(parsed.posts[0].content, "**bold**");
+        assert_eq!(parsed.posts[0].slug.as_deref(), Some("legacy"));
+        assert_eq!(parsed.posts[0].author.as_deref(), Some("Old Admin"));
+    }
+
+    #[test]
+    fn other_json_is_rejected() {
+        assert!(parse("{\"categories\": []}").is_err());
+        assert!(parse("not json").is_err());
+    }
+}
@@ -0,0 +1,372 @@
+//! Front matter Markdown importer
+//!
+//! Reads a zip of Hexo, Hugo or Jekyll sources. Every `.md` / `.markdown`
+//! file with YAML (`---`) or TOML (`+++`) front matter becomes a post; the
+//! field names of all three generators are understood. Jekyll's
+//! `YYYY-MM-DD-slug.md` file names and Hugo's `slug/index.md` bundles supply
+//! the date and slug when the front matter doesn't.
+
+use std::io::{Cursor, Read};
+
+use serde_json::Value;
+use yaml_rust::{Yaml, YamlLoader};
+
+use super::{non_empty, normalize_slug, parse_date, slug_from_title};
+use crate::error::ApiError;
+use crate::models::import::{ImportedPost, ParsedImport};
+
+/// Largest single Markdown file read from an archive
+const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Largest total size of the Markdown files read from an archive
+const MAX_TOTAL_SIZE: u64 = 200 * 1024 * 1024;
+
+/// Parse a zip archive of Markdown files
+pub fn parse_archive(data: &[u8]) -> Result<ParsedImport, ApiError> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data))
+        .map_err(|e| ApiError::BadRequest(format!("Invalid zip archive: {}", e)))?;
+
+    let mut parsed = ParsedImport::default();
+    let mut total_size = 0;
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid zip archive: {}", e)))?;
+        let path = entry
+            .name()
+            .map_err(|e| ApiError::BadRequest(format!("Invalid zip archive: {}", e)))?
+            .into_owned();
+        if entry.is_dir() || !is_markdown_path(&path) {
+            continue;
+        }
+        if entry.size() > MAX_FILE_SIZE {
+            parsed
+                .warnings
+                .push(format!("Skipped {}: larger than 10 MB", path));
+            continue;
+        }
+        total_size += entry.size();
+        if total_size > MAX_TOTAL_SIZE {
+            return Err(ApiError::BadRequest(
+                "Archive has more than 200 MB of Markdown".to_string(),
+            ));
+        }
+
+        let mut bytes = Vec::new();
+        (&mut entry)
+            .take(MAX_FILE_SIZE)
+            .read_to_end(&mut bytes)
+            .map_err(|e| ApiError::BadRequest(format!("Failed to read {}: {}", path, e)))?;
+        let Ok(text) = String::from_utf8(bytes) else {
+            parsed.warnings.push(format!("Skipped {}: not UTF-8", path));
+            continue;
+        };
+
+        match parse_file(&path, &text) {
+            Ok(post) => parsed.posts.push(post),
+            Err(reason) => parsed
+                .warnings
+                .push(format!("Skipped {}: {}", path, reason)),
+        }
+    }
+
+    if parsed.posts.is_empty() && parsed.warnings.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Archive contains no Markdown files".to_string(),
+        ));
+    }
+
+    Ok(parsed)
+}
+
+/// Markdown sources, leaving out macOS metadata, hidden files and Hugo section pages
+fn is_markdown_path(path: &str) -> bool {
+    let segments: Vec<&str> = path.split('/').collect();
+    let Some(name) = segments.last() else {
+        return false;
+    };
+    let lower = name.to_ascii_lowercase();
+
+    (lower.ends_with(".md") || lower.ends_with(".markdown"))
+        && lower != "_index.md"
+        && !segments
+            .iter()
+            .any(|segment| segment.starts_with('.') || *segment == "__MACOSX")
+}
+
+/// Parse one Markdown file; the error says why it isn't a post
+pub fn parse_file(path: &str, text: &str) -> Result<ImportedPost, String> {
+    let (front_matter, body) =
+        split_front_matter(text).ok_or_else(|| "no front matter".to_string())?;
+
+    // Pages and documents (e.g. from a site export) aren't posts
+    let kind = string(&front_matter, &["type", "layout"]);
+    if let Some(kind) = kind.filter(|kind| matches!(kind.as_str(), "page" | "doc")) {
+        return Err(format!("{} files are not posts", kind));
+    }
+
+    let (file_date, file_slug) = name_parts(path);
+    let title = string(&front_matter, &["title"])
+        .or_else(|| file_slug.clone())
+        .ok_or_else(|| "no title".to_string())?;
+
+    let is_draft = front_matter.get("draft").and_then(Value::as_bool) == Some(true)
+        || front_matter.get("published").and_then(Value::as_bool) == Some(false)
+        || path.split('/').any(|segment| segment == "_drafts");
+
+    let categories = list(&front_matter, &["categories", "category"]);
+    let mut tags = list(&front_matter, &["tags", "tag"]);
+    tags.dedup();
+
+    Ok(ImportedPost {
+        slug: string(&front_matter, &["slug"])
+            .or_else(|| string(&front_matter, &["url", "permalink"]))
+            .and_then(|slug| normalize_slug(&slug))
+            .or(file_slug)
+            .or_else(|| slug_from_title(&title)),
+        content: body.trim().to_string(),
+        summary: string(&front_matter, &["description", "excerpt", "summary"]),
+        thumbnail: string(
+            &front_matter,
+            &[
+                "cover",
+                "image",
+                "thumbnail",
+                "featured_image",
+                "feature_image",
+            ],
+        )
+        .or_else(|| list(&front_matter, &["images"]).into_iter().next()),
+        author: string(&front_matter, &["author"]),
+        category: categories.into_iter().next(),
+        tags,
+        is_published: !is_draft,
+        created_at: string(&front_matter, &["date", "publishDate"])
+            .and_then(|date| parse_date(&date))
+            .or(file_date),
+        updated_at: string(&front_matter, &["updated", "lastmod", "last_modified_at"])
+            .and_then(|date| parse_date(&date)),
+        references: front_matter
+            .get("references")
+            .filter(|references| references.is_object())
+            .cloned(),
+        title,
+    })
+}
+
+/// Front matter as a JSON object and the Markdown body after it
+fn split_front_matter(text: &str) -> Option<(Value, &str)> {
+    let text = text.trim_start_matches('\u{feff}');
+    let fence = if text.starts_with("---") {
+        "---"
+    } else if text.starts_with("+++") {
+        "+++"
+    } else {
+        return None;
+    };
+
+    let after_open = text[fence.len()..]
+        .strip_prefix('\r')
+        .unwrap_or(&text[fence.len()..]);
+    let after_open = after_open.strip_prefix('\n')?;
+
+    let mut offset = 0;
+    let (raw, body) = loop {
+        let line_end = after_open[offset..]
+            .find('\n')
+            .map_or(after_open.len(), |i| offset + i + 1);
+        let line = after_open[offset..line_end].trim_end();
+        if line == fence || (fence == "---" && line == "...") {
+            break (&after_open[..offset], &after_open[line_end..]);
+        }
+        if line_end == after_open.len() {
+            return None;
+        }
+        offset = line_end;
+    };
+
+    let value = if fence == "---" {
+        let docs = YamlLoader::load_from_str(raw).ok()?;
+        docs.first().map_or(Value::Null, yaml_to_json)
+    } else {
+        toml_to_json(toml::Value::Table(raw.parse::<toml::Table>().ok()?))
+    };
+    let value = if value.is_null() {
+        Value::Object(Default::default())
+    } else {
+        value
+    };
+
+    value.is_object().then_some((value, body))
+}
+
+fn yaml_to_json(yaml: &Yaml) -> Value {
+    match yaml {
+        Yaml::String(s) | Yaml::Real(s) => Value::String(s.clone()),
+        Yaml::Integer(i) => Value::from(*i),
+        Yaml::Boolean(b) => Value::Bool(*b),
+        Yaml::Array(items) => Value::Array(items.iter().map(yaml_to_json).collect()),
+        Yaml::Hash(hash) => Value::Object(
+            hash.iter()
+                .filter_map(|(key, value)| {
+                    let key = match key {
+                        Yaml::String(s) | Yaml::Real(s) => s.clone(),
+                        Yaml::Integer(i) => i.to_string(),
+                        Yaml::Boolean(b) => b.to_string(),
+                        _ => return None,
+                    };
+                    Some((key, yaml_to_json(value)))
+                })
+                .collect(),
+        ),
+        _ => Value::Null,
+    }
+}
+
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => Value::from(f),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(date) => Value::String(date.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// First non-empty scalar among the given keys
+fn string(front_matter: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|key| front_matter.get(*key))
+        .find_map(scalar)
+}
+
+/// Values of the first key present; lists may be nested (Hexo) or a comma separated string
+fn list(front_matter: &Value, keys: &[&str]) -> Vec<String> {
+    fn flatten(value: &Value, out: &mut Vec<String>) {
+        match value {
+            Value::Array(items) => items.iter().for_each(|item| flatten(item, out)),
+            Value::String(s) => out.extend(s.split(',').filter_map(non_empty)),
+            other => out.extend(scalar(other)),
+        }
+    }
+
+    let mut values = Vec::new();
+    if let Some(value) = keys.iter().find_map(|key| front_matter.get(*key)) {
+        flatten(value, &mut values);
+    }
+    values
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => non_empty(s),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Date and slug encoded in the path: Jekyll `2020-01-02-slug.md`, Hugo `slug/index.md`
+fn name_parts(path: &str) -> (Option<chrono::DateTime<chrono::Utc>>, Option<String>) {
+    let mut segments = path.rsplit('/');
+    let file = segments.next().unwrap_or_default();
+    let stem = file.rsplit_once('.').map_or(file, |(stem, _)| stem);
+    let stem = if stem.eq_ignore_ascii_case("index") {
+        segments.next().unwrap_or_default()
+    } else {
+        stem
+    };
+
+    match stem.get(..10).and_then(parse_date) {
+        Some(date) if stem.len() > 11 && stem.as_bytes()[10] == b'-' => {
+            (Some(date), non_empty(&stem[11..]))
+        }
+        _ => (None, non_empty(stem)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn hexo_yaml_front_matter() {
+        let text = "---\ntitle: Hello Hexo\ndate: 2020-05-06 07:08:09\ncategories:\n  - [Tech, Rust]\ntags: [rust, web]\ncover: /img/a.png\n---\n\n# Body\n";
+        let post = parse_file("source/_posts/hello.md", text).unwrap();
+
+        assert_eq!(post.title, "Hello Hexo");
+        assert_eq!(post.slug.as_deref(), Some("hello"));
+        assert_eq!(post.category.as_deref(), Some("Tech"));
+        assert_eq!(post.tags, vec!["rust".to_string(), "web".to_string()]);
+        assert_eq!(post.thumbnail.as_deref(), Some("/img/a.png"));
+        assert_eq!(post.content, "# Body");
+        assert_eq!(post.created_at, parse_date("2020-05-06 07:08:09"));
+        assert!(post.is_published);
+    }
+
+    #[test]
+    fn hugo_toml_front_matter_in_bundle() {
+        let text = "+++\ntitle = \"Hugo post\"\ndate = 2021-01-02T03:04:05Z\ndraft = true\ntags = [\"go\"]\nsummary = \"Short\"\n+++\nBody\n";
+        let post = parse_file("content/posts/my-bundle/index.md", text).unwrap();
+
+        assert_eq!(post.slug.as_deref(), Some("my-bundle"));
+        assert_eq!(post.tags, vec!["go".to_string()]);
+        assert_eq!(post.summary.as_deref(), Some("Short"));
+        assert_eq!(post.created_at, parse_date("2021-01-02T03:04:05Z"));
+        assert!(!post.is_published);
+    }
+
+    #[test]
+    fn jekyll_file_names_supply_date_and_slug() {
+        let text =
+            "---\ntitle: Jekyll\ntags: ruby, static\npermalink: /blog/custom-slug/\n---\nBody";
+        let post = parse_file("_posts/2019-12-31-new-year.md", text).unwrap();
+        assert_eq!(post.slug.as_deref(), Some("custom-slug"));
+        assert_eq!(post.created_at, parse_date("2019-12-31"));
+        assert_eq!(post.tags, vec!["ruby".to_string(), "static".to_string()]);
+
+        let draft = parse_file("_drafts/2019-12-31-idea.md", "---\ntitle: Idea\n---\n").unwrap();
+        assert_eq!(draft.slug.as_deref(), Some("idea"));
+        assert!(!draft.is_published);
+    }
+
+    #[test]
+    fn files_without_front_matter_are_rejected() {
+        assert!(parse_file("README.md", "# Readme").is_err());
+        assert!(parse_file("a.md", "---\ntitle: unterminated\n").is_err());
+    }
+
+    #[test]
+    fn archives_skip_non_posts() {
+        let mut buffer = Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut buffer);
+            let options = zip::write::SimpleFileOptions::default();
+            for (name, body) in [
+                ("posts/first.md", "---\ntitle: First\n---\nHi"),
+                ("posts/notes.md", "no front matter"),
+                ("posts/_index.md", "---\ntitle: Section\n---\n"),
+                ("__MACOSX/posts/._first.md", "junk"),
+                ("posts/image.png", "png"),
+            ] {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(body.as_bytes()).unwrap();
+            }
+            zip.finish().unwrap();
+        }
+
+        let parsed = parse_archive(buffer.get_ref()).unwrap();
+        assert_eq!(parsed.posts.len(), 1);
+        assert_eq!(parsed.posts[0].title, "First");
+        assert_eq!(parsed.warnings.len(), 1);
+        assert!(parsed.warnings[0].contains("posts/notes.md"));
+    }
+}
@@ -0,0 +1,113 @@
+//! Parsers for content exported by other blog engines
+//!
+//! Each parser turns an export into `ImportedPost`s. Matching them against
+//! existing categories, tags and slugs and writing them is up to
+//! `ImportService`.
+
+pub mod ghost;
+pub mod markdown;
+pub mod wordpress;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+/// Parse the date formats found in exports; dates without an offset are taken as UTC
+pub(crate) fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    const DATETIME_FORMATS: [&str; 4] = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+    ];
+
+    let value = value.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+    if let Ok(date) = DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S %z") {
+        return Some(date.with_timezone(&Utc));
+    }
+    if let Ok(date) = DateTime::parse_from_rfc2822(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+    for format in DATETIME_FORMATS {
+        if let Ok(date) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(date.and_utc());
+        }
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date| date.and_utc())
+}
+
+/// Slug from a slug field or permalink, e.g. `/2020/01/hello-world/` -> `hello-world`
+pub(crate) fn normalize_slug(value: &str) -> Option<String> {
+    value
+        .trim()
+        .trim_matches('/')
+        .rsplit('/')
+        .next()
+        .map(str::trim)
+        .filter(|slug| !slug.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+/// ASCII slug derived from a title, `None` if the title has no ASCII letters or digits
+pub(crate) fn slug_from_title(title: &str) -> Option<String> {
+    let mut slug = String::new();
+    for c in title.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+
+    (!slug.is_empty()).then(|| slug.to_string())
+}
+
+/// Trimmed value, `None` when blank
+pub(crate) fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates_in_export_formats_are_parsed() {
+        let expected = "2021-03-04T05:06:07Z".parse::<DateTime<Utc>>().unwrap();
+
+        assert_eq!(parse_date("2021-03-04T05:06:07Z"), Some(expected));
+        assert_eq!(parse_date("2021-03-04T13:06:07+08:00"), Some(expected));
+        assert_eq!(parse_date("2021-03-04 05:06:07"), Some(expected));
+        assert_eq!(parse_date("2021-03-04 13:06:07 +0800"), Some(expected));
+        assert_eq!(
+            parse_date("Thu, 04 Mar 2021 05:06:07 +0000"),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_date("2021-03-04"),
+            Some("2021-03-04T00:00:00Z".parse().unwrap())
+        );
+        assert_eq!(parse_date("0000-00-00 00:00:00"), None);
+        assert_eq!(parse_date("soon"), None);
+    }
+
+    #[test]
+    fn slugs_come_from_fields_or_titles() {
+        assert_eq!(
+            normalize_slug("/2020/01/hello-world/").as_deref(),
+            Some("hello-world")
+        );
+        assert_eq!(normalize_slug(" /// "), None);
+        assert_eq!(
+            slug_from_title("Hello, World! Rust 2024").as_deref(),
+            Some("hello-world-rust-2024")
+        );
+        assert_eq!(slug_from_title("你好"), None);
+    }
+}
@@ -0,0 +1,326 @@
+//! WordPress WXR (WordPress eXtended RSS) importer
+//!
+//! Reads the `<item>` elements of an export: posts are imported, attachments
+//! only provide featured image URLs, and pages, menus and other item types are
+//! skipped. Post content stays HTML, which Markdown rendering passes through.
+
+use std::collections::HashMap;
+
+use quick_xml::escape::resolve_xml_entity;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use super::{non_empty, normalize_slug, parse_date, slug_from_title};
+use crate::error::ApiError;
+use crate::models::import::{ImportedPost, ParsedImport};
+
+/// Date WordPress writes for posts that were never published
+const ZERO_DATE: &str = "0000-00-00 00:00:00";
+
+/// Elements of one `<item>`
+#[derive(Debug, Default)]
+struct Item {
+    fields: HashMap<String, String>,
+    /// `(domain, name)` of each `<category>`
+    categories: Vec<(String, String)>,
+    meta: HashMap<String, String>,
+}
+
+impl Item {
+    fn field(&self, name: &str) -> Option<String> {
+        self.fields.get(name).and_then(|value| non_empty(value))
+    }
+
+    fn date(&self, gmt: &str, local: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+        [gmt, local]
+            .into_iter()
+            .filter_map(|name| self.field(name))
+            .find(|value| value != ZERO_DATE)
+            .and_then(|value| parse_date(&value))
+    }
+}
+
+/// Parse a WXR export
+pub fn parse(xml: &str) -> Result<ParsedImport, ApiError> {
+    let items = read_items(xml)?;
+
+    let attachments: HashMap<String, String> = items
+        .iter()
+        .filter(|item| item.field("wp:post_type").as_deref() == Some("attachment"))
+        .filter_map(|item| Some((item.field("wp:post_id")?, item.field("wp:attachment_url")?)))
+        .collect();
+
+    let mut parsed = ParsedImport::default();
+    let mut skipped_types: HashMap<String, usize> = HashMap::new();
+    for item in &items {
+        let post_type = item.field("wp:post_type").unwrap_or_else(|| "post".into());
+        if post_type != "post" {
+            if post_type != "attachment" {
+                *skipped_types.entry(post_type).or_default() += 1;
+            }
+            continue;
+        }
+
+        let title = item.field("title").unwrap_or_default();
+        let status = item.field("wp:status").unwrap_or_default();
+        if matches!(status.as_str(), "trash" | "auto-draft") {
+            parsed
+                .warnings
+                .push(format!("Skipped {} post '{}'", status, title));
+            continue;
+        }
+
+        let mut categories = item
+            .categories
+            .iter()
+            .filter(|(domain, _)| domain == "category")
+            .map(|(_, name)| name.clone());
+        let category = categories.next();
+        let extra: Vec<String> = categories.collect();
+        if !extra.is_empty() {
+            parsed.warnings.push(format!(
+                "Post '{}' is in several categories, only '{}' is kept (dropped: {})",
+                title,
+                category.as_deref().unwrap_or_default(),
+                extra.join(", ")
+            ));
+        }
+
+        parsed.posts.push(ImportedPost {
+            slug: item
+                .field("wp:post_name")
+                .and_then(|slug| normalize_slug(&slug))
+                .or_else(|| slug_from_title(&title)),
+            content: item.field("content:encoded").unwrap_or_default(),
+            summary: item.field("excerpt:encoded"),
+            thumbnail: item
+                .meta
+                .get("_thumbnail_id")
+                .and_then(|id| attachments.get(id.trim()))
+                .cloned(),
+            author: item.field("dc:creator"),
+            category,
+            tags: item
+                .categories
+                .iter()
+                .filter(|(domain, _)| domain == "post_tag")
+                .map(|(_, name)| name.clone())
+                .collect(),
+            is_published: status == "publish",
+            created_at: item.date("wp:post_date_gmt", "wp:post_date"),
+            updated_at: item.date("wp:post_modified_gmt", "wp:post_modified"),
+            references: None,
+            title,
+        });
+    }
+
+    let mut skipped_types: Vec<_> = skipped_types.into_iter().collect();
+    skipped_types.sort();
+    for (post_type, count) in skipped_types {
+        parsed
+            .warnings
+            .push(format!("Skipped {} item(s) of type '{}'", count, post_type));
+    }
+
+    Ok(parsed)
+}
+
+/// Collect the elements of every `<item>` in the export
+fn read_items(xml: &str) -> Result<Vec<Item>, ApiError> {
+    let invalid =
+        |e: &dyn std::fmt::Display| ApiError::BadRequest(format!("Invalid WXR file: {}", e));
+
+    let mut reader = Reader::from_str(xml);
+    let mut items = Vec::new();
+    let mut item: Option<Item> = None;
+    // Element names below the current `<item>`
+    let mut path: Vec<String> = Vec::new();
+    let mut text = String::new();
+    let mut category_domain = String::new();
+    let mut meta_key = String::new();
+
+    loop {
+        match reader.read_event().map_err(|e| invalid(&e))? {
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                if name == "item" {
+                    item = Some(Item::default());
+                    path.clear();
+                    continue;
+                }
+                if item.is_none() {
+                    continue;
+                }
+                if name == "category" {
+                    category_domain = e
+                        .try_get_attribute("domain")
+                        .ok()
+                        .flatten()
+                        .and_then(|attr| attr.unescape_value().ok())
+                        .map(|value| value.into_owned())
+                        .unwrap_or_default();
+                }
+                path.push(name);
+                text.clear();
+            }
+            Event::Text(e) if item.is_some() => {
+                text.push_str(&e.xml_content().map_err(|e| invalid(&e))?);
+            }
+            Event::CData(e) if item.is_some() => {
+                text.push_str(&e.decode().map_err(|e| invalid(&e))?);
+            }
+            Event::GeneralRef(e) if item.is_some() => {
+                if let Some(c) = e.resolve_char_ref().map_err(|e| invalid(&e))? {
+                    text.push(c);
+                } else {
+                    let entity = e.decode().map_err(|e| invalid(&e))?;
+                    match resolve_xml_entity(&entity) {
+                        Some(value) => text.push_str(value),
+                        None => {
+                            text.push('&');
+                            text.push_str(&entity);
+                            text.push(';');
+                        }
+                    }
+                }
+            }
+            Event::End(e) => {
+                let Some(current) = item.as_mut() else {
+                    continue;
+                };
+                let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                if name == "item" && path.is_empty() {
+                    items.extend(item.take());
+                    continue;
+                }
+
+                match name.as_str() {
+                    "category" => current.categories.push((
+                        std::mem::take(&mut category_domain),
+                        text.trim().to_string(),
+                    )),
+                    "wp:meta_key" => meta_key = text.trim().to_string(),
+                    "wp:meta_value" => {
+                        current
+                            .meta
+                            .insert(std::mem::take(&mut meta_key), text.clone());
+                    }
+                    // Only direct children of `<item>` are post fields
+                    _ if path.len() == 1 => {
+                        current.fields.insert(name, std::mem::take(&mut text));
+                    }
+                    _ => {}
+                }
+                path.pop();
+                text.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if items.is_empty() && !xml.contains("<rss") {
+        return Err(ApiError::BadRequest(
+            "Not a WordPress export: no <rss> element found".to_string(),
+        ));
+    }
+
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WXR: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/"
+     xmlns:excerpt="http://wordpress.org/export/1.2/excerpt/"
+     xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:wp="http://wordpress.org/export/1.2/">
+<channel>
+  <title>My Blog</title>
+  <item>
+    <title>Hello &amp; Welcome</title>
+    <dc:creator><![CDATA[admin]]></dc:creator>
+    <content:encoded><![CDATA[<p>First <b>post</b></p>]]></content:encoded>
+    <excerpt:encoded><![CDATA[Short intro]]></excerpt:encoded>
+    <wp:post_id>10</wp:post_id>
+    <wp:post_date><![CDATA[2021-03-04 13:06:07]]></wp:post_date>
+    <wp:post_date_gmt><![CDATA[2021-03-04 05:06:07]]></wp:post_date_gmt>
+    <wp:post_name><![CDATA[hello-welcome]]></wp:post_name>
+    <wp:status><![CDATA[publish]]></wp:status>
+    <wp:post_type><![CDATA[post]]></wp:post_type>
+    <category domain="category" nicename="news"><![CDATA[News]]></category>
+    <category domain="category" nicename="misc"><![CDATA[Misc]]></category>
+    <category domain="post_tag" nicename="rust"><![CDATA[Rust]]></category>
+    <wp:postmeta>
+      <wp:meta_key><![CDATA[_thumbnail_id]]></wp:meta_key>
+      <wp:meta_value><![CDATA[11]]></wp:meta_value>
+    </wp:postmeta>
+  </item>
+  <item>
+    <title>cover.png</title>
+    <wp:post_id>11</wp:post_id>
+    <wp:post_type><![CDATA[attachment]]></wp:post_type>
+    <wp:attachment_url><![CDATA[https://example.com/wp-content/uploads/cover.png]]></wp:attachment_url>
+  </item>
+  <item>
+    <title>Work in progress</title>
+    <content:encoded><![CDATA[draft]]></content:encoded>
+    <wp:post_date><![CDATA[2022-01-01 08:00:00]]></wp:post_date>
+    <wp:post_date_gmt><![CDATA[0000-00-00 00:00:00]]></wp:post_date_gmt>
+    <wp:post_name><![CDATA[]]></wp:post_name>
+    <wp:status><![CDATA[draft]]></wp:status>
+    <wp:post_type><![CDATA[post]]></wp:post_type>
+  </item>
+  <item>
+    <title>About</title>
+    <wp:post_type><![CDATA[page]]></wp:post_type>
+  </item>
+</channel>
+</rss>"#;
+
+    #[test]
+    fn posts_are_read_with_taxonomy_and_dates() {
+        let parsed = parse(WXR).unwrap();
+        assert_eq!(parsed.posts.len(), 2);
+
+        let post = &parsed.posts[0];
+        assert_eq!(post.title, "Hello & Welcome");
+        assert_eq!(post.slug.as_deref(), Some("hello-welcome"));
+        assert_eq!(post.content, "<p>First <b>post</b></p>");
+        assert_eq!(post.summary.as_deref(), Some("Short intro"));
+        assert_eq!(post.author.as_deref(), Some("admin"));
+        assert_eq!(post.category.as_deref(), Some("News"));
+        assert_eq!(post.tags, vec!["Rust".to_string()]);
+        assert_eq!(
+            post.thumbnail.as_deref(),
+            Some("https://example.com/wp-content/uploads/cover.png")
+        );
+        assert!(post.is_published);
+        assert_eq!(post.created_at, parse_date("2021-03-04T05:06:07Z"));
+    }
+
+    #[test]
+    fn drafts_fall_back_to_local_dates_and_title_slugs() {
+        let parsed = parse(WXR).unwrap();
+        let draft = &parsed.posts[1];
+
+        assert!(!draft.is_published);
+        assert_eq!(draft.slug.as_deref(), Some("work-in-progress"));
+        assert_eq!(draft.created_at, parse_date("2022-01-01 08:00:00"));
+    }
+
+    #[test]
+    fn skipped_items_are_reported() {
+        let warnings = parse(WXR).unwrap().warnings;
+
+        assert!(warnings.iter().any(|w| w.contains("dropped: Misc")));
+        assert!(warnings.iter().any(|w| w.contains("type 'page'")));
+    }
+
+    #[test]
+    fn non_wxr_input_is_rejected() {
+        assert!(parse("<html><body>nope</body></html>").is_err());
+        assert!(parse("<rss><channel><item><title>x</wrong></item></channel></rss>").is_err());
+    }
+}
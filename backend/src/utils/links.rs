@@ -0,0 +1,90 @@
+//! HTML link extraction utilities
+
+use reqwest::Url;
+use std::sync::OnceLock;
+
+static HREF_RE: OnceLock<regex::Regex> = OnceLock::new();
+
+/// Extract the `href` of every `<a>` tag, resolved against the page URL
+///
+/// Relative links are joined onto `base`; anything that isn't http(s) is skipped.
+pub fn extract_links(html: &str, base: &Url) -> Vec<Url> {
+    let re = HREF_RE.get_or_init(|| {
+        regex::Regex::new(r#"(?is)<a\s[^>]*?href\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#).unwrap()
+    });
+
+    re.captures_iter(html)
+        .filter_map(|cap| cap.get(1).or(cap.get(2)).or(cap.get(3)))
+        .filter_map(|href| base.join(href.as_str().trim()).ok())
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .collect()
+}
+
+/// Whether the page links to `site_url`'s host (a `www.` prefix is ignored on both sides)
+pub fn links_to_site(html: &str, page_url: &Url, site_url: &Url) -> bool {
+    let Some(site_host) = site_url.host_str().map(normalize_host) else {
+        return false;
+    };
+
+    extract_links(html, page_url)
+        .iter()
+        .filter_map(|url| url.host_str())
+        .any(|host| normalize_host(host) == site_host)
+}
+
+fn normalize_host(host: &str) -> String {
+    let host = host.to_ascii_lowercase();
+    host.strip_prefix("www.")
+        .map(str::to_string)
+        .unwrap_or(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn extract_links_resolves_relative_and_skips_non_http() {
+        let html = r#"
+            <a href="/about">About</a>
+            <A class="x" HREF='https://other.com/page'>Other</A>
+            <a href=plain.html>Plain</a>
+            <a href="mailto:me@example.com">Mail</a>
+            <link href="https://style.com/a.css">
+        "#;
+        let links: Vec<String> = extract_links(html, &url("https://friend.dev/links/"))
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        assert_eq!(
+            links,
+            vec![
+                "https://friend.dev/about",
+                "https://other.com/page",
+                "https://friend.dev/links/plain.html",
+            ]
+        );
+    }
+
+    #[test]
+    fn links_to_site_ignores_www_and_case() {
+        let page = url("https://friend.dev/links");
+        let site = url("https://www.Example.com");
+
+        assert!(links_to_site(
+            r#"<a href="https://example.com/blog/1">Example</a>"#,
+            &page,
+            &site
+        ));
+        assert!(!links_to_site(
+            r#"<a href="https://example.com.evil.io/">Fake</a> example.com"#,
+            &page,
+            &site
+        ));
+    }
+}
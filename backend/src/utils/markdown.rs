@@ -245,10 +245,38 @@ pub fn render_markdown_simple(markdown: &str) -> String {
     MarkdownRenderer::render_simple(markdown)
 }
 
+/// Collect the absolute http(s) link targets of a markdown document, deduplicated in order
+pub fn extract_link_urls(markdown: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+
+    for event in Parser::new_ext(markdown, Options::all()) {
+        if let Event::Start(Tag::Link { dest_url, .. }) = event {
+            let url = dest_url.trim();
+            let is_http = url.starts_with("http://") || url.starts_with("https://");
+            if is_http && !urls.iter().any(|u| u == url) {
+                urls.push(url.to_string());
+            }
+        }
+    }
+
+    urls
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_extract_link_urls() {
+        let md = "See [Rust](https://www.rust-lang.org) and <https://docs.rs/>.\n\n\
+                  [again](https://www.rust-lang.org) [local](/about) [mail](mailto:a@b.c)\n\n\
+                  ```\n[in code](https://ignored.example)\n```";
+        assert_eq!(
+            extract_link_urls(md),
+            vec!["https://www.rust-lang.org", "https://docs.rs/"]
+        );
+    }
+
     #[test]
     fn test_simple_markdown() {
         let md = "# Hello World\n\nThis is a paragraph.";
@@ -38,8 +38,81 @@ const MIGRATIONS: &[(&str, &str)] = &[
         "008_blog_global_summary",
         include_str!("../../migrations/008_blog_global_summary.sql"),
     ),
+    (
+        "009_site_url",
+        include_str!("../../migrations/009_site_url.sql"),
+    ),
+    (
+        "010_sitemap_robots",
+        include_str!("../../migrations/010_sitemap_robots.sql"),
+    ),
+    (
+        "011_blog_publish_at",
+        include_str!("../../migrations/011_blog_publish_at.sql"),
+    ),
+    (
+        "012_content_revisions",
+        include_str!("../../migrations/012_content_revisions.sql"),
+    ),
+    (
+        "013_comments",
+        include_str!("../../migrations/013_comments.sql"),
+    ),
+    (
+        "014_friend_link_backlink",
+        include_str!("../../migrations/014_friend_link_backlink.sql"),
+    ),
+    (
+        "015_link_checks",
+        include_str!("../../migrations/015_link_checks.sql"),
+    ),
+    (
+        "016_text_content_encryption",
+        include_str!("../../migrations/016_text_content_encryption.sql"),
+    ),
+    (
+        "017_user_roles",
+        include_str!("../../migrations/017_user_roles.sql"),
+    ),
+    (
+        "018_mcp_tokens",
+        include_str!("../../migrations/018_mcp_tokens.sql"),
+    ),
+    (
+        "019_two_factor",
+        include_str!("../../migrations/019_two_factor.sql"),
+    ),
+    (
+        "020_login_events",
+        include_str!("../../migrations/020_login_events.sql"),
+    ),
+    (
+        "021_site_config_secrets",
+        include_str!("../../migrations/021_site_config_secrets.sql"),
+    ),
+    (
+        "022_file_variants",
+        include_str!("../../migrations/022_file_variants.sql"),
+    ),
+    (
+        "023_storage_backends",
+        include_str!("../../migrations/023_storage_backends.sql"),
+    ),
+    (
+        "024_file_dedup",
+        include_str!("../../migrations/024_file_dedup.sql"),
+    ),
+    (
+        "025_direct_uploads",
+        include_str!("../../migrations/025_direct_uploads.sql"),
+    ),
 ];
 
+/// Name of the newest embedded migration
+pub fn latest_migration() -> &'static str {
+    MIGRATIONS.last().map_or("", |(name, _)| name)
+}
+
 /// Run all pending migrations
 pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
     // Create migrations tracking table if not exists
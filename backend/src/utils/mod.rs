@@ -1,5 +1,14 @@
 //! Utility functions and helpers
 
+pub mod crypto;
+pub mod diff;
+pub mod feed;
+pub mod imaging;
+pub mod importers;
+pub mod links;
 pub mod markdown;
 pub mod migration;
 pub mod pagination;
+pub mod site_export;
+pub mod sitemap;
+pub mod totp;
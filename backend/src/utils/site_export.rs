@@ -0,0 +1,471 @@
+//! Static-site export layout and front matter
+//!
+//! The archive layout is:
+//!
+//! - `posts/<slug>.md` - every blog, drafts marked with `draft: true`
+//! - `docs/<directory>/.../<document>.md` - documents in their directory tree,
+//!   each directory with an `_index.md` section page
+//! - `assets/` - uploads referenced from content, when requested
+//! - `skipped-assets.txt` - referenced uploads that were left out, if any
+//!
+//! Front matter uses the field names Hugo and Hexo both read (`title`, `date`,
+//! `tags`, `categories`, `draft`), plus `lastmod` / `updated` for the
+//! modification time. Asset links are rewritten to `/assets/...`, so the folder
+//! goes to `static/` in Hugo or `source/` in Hexo.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::Value as JsonValue;
+use yaml_rust::yaml::Hash;
+use yaml_rust::{Yaml, YamlEmitter};
+
+use crate::models::directory::Directory;
+use crate::models::document::Document;
+use crate::models::site_export::SiteExportBlog;
+use crate::services::storage::UPLOADS_PREFIX;
+
+pub const POSTS_DIR: &str = "posts";
+pub const DOCS_DIR: &str = "docs";
+pub const ASSETS_DIR: &str = "assets";
+pub const SKIPPED_ASSETS_FILE: &str = "skipped-assets.txt";
+
+/// Markdown file of a blog: YAML front matter followed by the content
+pub fn blog_markdown(blog: &SiteExportBlog) -> String {
+    let mut fields = FrontMatter::default();
+    fields.string("title", Some(&blog.title));
+    fields.string("slug", blog.slug.as_deref());
+    fields.date("date", blog.created_at);
+    fields.date("lastmod", blog.updated_at);
+    fields.date("updated", blog.updated_at);
+    fields.string("author", blog.author.as_deref());
+    fields.list("categories", blog.category.iter());
+    fields.list("tags", blog.tags.iter());
+    fields.string("summary", blog.summary.as_deref());
+    fields.string("cover", blog.thumbnail.as_deref());
+    if !blog.is_published {
+        fields.insert("draft", Yaml::Boolean(true));
+        fields.insert("published", Yaml::Boolean(false));
+        fields.date("publishDate", blog.publish_at);
+    }
+    fields.json("references", blog.references.as_ref());
+
+    fields.render(&blog.content)
+}
+
+/// Markdown file of a document; `type: doc` keeps it out of post imports
+pub fn document_markdown(document: &Document) -> String {
+    let mut fields = FrontMatter::default();
+    fields.string("title", Some(&document.name));
+    fields.date("date", document.created_at);
+    fields.date("lastmod", document.updated_at);
+    fields.date("updated", document.updated_at);
+    fields.insert("weight", Yaml::Integer(document.sort_order.into()));
+    fields.insert("type", Yaml::String("doc".to_string()));
+    fields.json("references", document.references.as_ref());
+
+    fields.render(&document.content)
+}
+
+/// Section page describing a directory
+pub fn directory_index(directory: &Directory) -> String {
+    let mut fields = FrontMatter::default();
+    fields.string("title", Some(&directory.name));
+    fields.string("description", directory.intro.as_deref());
+    fields.insert("weight", Yaml::Integer(directory.sort_order.into()));
+    fields.date("date", directory.created_at);
+
+    fields.render("")
+}
+
+/// Archive path of every blog, unique even when sanitized slugs collide
+pub fn blog_paths(blogs: &[SiteExportBlog]) -> Vec<String> {
+    let mut taken = HashSet::new();
+    blogs
+        .iter()
+        .map(|blog| {
+            let name = blog
+                .slug
+                .as_deref()
+                .map(safe_segment)
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| format!("post-{}", blog.id));
+            format!("{}/{}", POSTS_DIR, unique_file(&mut taken, &name, blog.id))
+        })
+        .collect()
+}
+
+/// Archive directory of every directory, e.g. `docs/Guide/Install`
+///
+/// Directories whose parent is missing (or part of a cycle) are placed at the
+/// top level rather than dropped.
+pub fn directory_paths(directories: &[Directory]) -> HashMap<i64, String> {
+    let by_id: HashMap<i64, &Directory> = directories.iter().map(|dir| (dir.id, dir)).collect();
+    let mut paths: HashMap<i64, String> = HashMap::new();
+    let mut taken: HashSet<String> = HashSet::new();
+
+    // Parents are resolved before children by walking up from each directory
+    for directory in directories {
+        let mut chain = vec![directory.id];
+        let mut current = directory.parent_id;
+        while let Some(id) = current {
+            if paths.contains_key(&id) || chain.contains(&id) {
+                break;
+            }
+            match by_id.get(&id) {
+                Some(parent) => {
+                    chain.push(id);
+                    current = parent.parent_id;
+                }
+                None => break,
+            }
+        }
+
+        for id in chain.into_iter().rev() {
+            if paths.contains_key(&id) {
+                continue;
+            }
+            let dir = by_id[&id];
+            let parent_path = dir
+                .parent_id
+                .and_then(|parent| paths.get(&parent).cloned())
+                .unwrap_or_else(|| DOCS_DIR.to_string());
+            let name = safe_segment(&dir.name);
+            let name = if name.is_empty() {
+                format!("directory-{}", dir.id)
+            } else {
+                name
+            };
+            let mut path = format!("{}/{}", parent_path, name);
+            if !taken.insert(path.to_lowercase()) {
+                path = format!("{}-{}", path, dir.id);
+                taken.insert(path.to_lowercase());
+            }
+            paths.insert(id, path);
+        }
+    }
+
+    paths
+}
+
+/// Archive path of every document inside its directory
+pub fn document_paths(documents: &[Document], directories: &HashMap<i64, String>) -> Vec<String> {
+    let mut taken = HashSet::new();
+    documents
+        .iter()
+        .map(|document| {
+            let dir = document
+                .directory_id
+                .and_then(|id| directories.get(&id))
+                .map_or(DOCS_DIR, String::as_str);
+            let name = document
+                .filename
+                .as_deref()
+                .map(|filename| {
+                    safe_segment(
+                        filename
+                            .strip_suffix(".md")
+                            .or_else(|| filename.strip_suffix(".markdown"))
+                            .unwrap_or(filename),
+                    )
+                })
+                .filter(|name| !name.is_empty())
+                .or_else(|| Some(safe_segment(&document.name)).filter(|name| !name.is_empty()))
+                .unwrap_or_else(|| format!("document-{}", document.id));
+
+            unique_file(&mut taken, &format!("{}/{}", dir, name), document.id)
+        })
+        .collect()
+}
+
+/// Archive path an upload is copied to, e.g. `uploads/<uuid>/thumbnail.jpg` -> `assets/<uuid>-thumbnail.jpg`
+pub fn asset_path(object_key: &str) -> String {
+    let name = object_key
+        .strip_prefix(UPLOADS_PREFIX)
+        .unwrap_or(object_key)
+        .split('/')
+        .map(safe_segment)
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    format!("{}/{}", ASSETS_DIR, name)
+}
+
+/// Replace upload URLs with their asset links, longest URL first so no URL
+/// is rewritten through a shorter one it starts with
+pub fn rewrite_links(text: &str, links: &[(String, String)]) -> String {
+    let mut links: Vec<&(String, String)> = links
+        .iter()
+        .filter(|(url, _)| text.contains(url.as_str()))
+        .collect();
+    if links.is_empty() {
+        return text.to_string();
+    }
+    links.sort_by_key(|(url, _)| std::cmp::Reverse(url.len()));
+
+    let mut text = text.to_string();
+    for (url, link) in links {
+        text = text.replace(url.as_str(), link);
+    }
+    text
+}
+
+/// Path segment safe on every file system, keeping non-ASCII names
+pub fn safe_segment(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| {
+            if c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    cleaned.trim().trim_matches('.').trim().to_string()
+}
+
+/// `<path>.md`, or `<path>-<id>.md` when the name is taken (ignoring case)
+fn unique_file(taken: &mut HashSet<String>, path: &str, id: i64) -> String {
+    let file = format!("{}.md", path);
+    if taken.insert(file.to_lowercase()) {
+        return file;
+    }
+    let file = format!("{}-{}.md", path, id);
+    taken.insert(file.to_lowercase());
+    file
+}
+
+/// Ordered front matter fields, skipping empty values
+#[derive(Default)]
+struct FrontMatter(Hash);
+
+impl FrontMatter {
+    fn insert(&mut self, key: &str, value: Yaml) {
+        self.0.insert(Yaml::String(key.to_string()), value);
+    }
+
+    fn string(&mut self, key: &str, value: Option<&str>) {
+        if let Some(value) = value.filter(|value| !value.trim().is_empty()) {
+            self.insert(key, Yaml::String(value.to_string()));
+        }
+    }
+
+    fn date(&mut self, key: &str, value: Option<DateTime<Utc>>) {
+        if let Some(value) = value {
+            self.insert(
+                key,
+                Yaml::String(value.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            );
+        }
+    }
+
+    fn list<'a>(&mut self, key: &str, values: impl Iterator<Item = &'a String>) {
+        let values: Vec<Yaml> = values.map(|value| Yaml::String(value.clone())).collect();
+        if !values.is_empty() {
+            self.insert(key, Yaml::Array(values));
+        }
+    }
+
+    fn json(&mut self, key: &str, value: Option<&JsonValue>) {
+        match value {
+            None | Some(JsonValue::Null) => {}
+            Some(JsonValue::Object(map)) if map.is_empty() => {}
+            Some(value) => self.insert(key, json_to_yaml(value)),
+        }
+    }
+
+    fn render(self, content: &str) -> String {
+        let mut yaml = String::new();
+        // Writing to a String can't fail
+        let _ = YamlEmitter::new(&mut yaml).dump(&Yaml::Hash(self.0));
+
+        let mut markdown = yaml;
+        markdown.push_str("\n---\n");
+        let content = content.trim();
+        if !content.is_empty() {
+            markdown.push('\n');
+            markdown.push_str(content);
+            markdown.push('\n');
+        }
+        markdown
+    }
+}
+
+fn json_to_yaml(value: &JsonValue) -> Yaml {
+    match value {
+        JsonValue::Null => Yaml::Null,
+        JsonValue::Bool(b) => Yaml::Boolean(*b),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) => Yaml::Integer(i),
+            None => Yaml::Real(n.to_string()),
+        },
+        JsonValue::String(s) => Yaml::String(s.clone()),
+        JsonValue::Array(items) => Yaml::Array(items.iter().map(json_to_yaml).collect()),
+        JsonValue::Object(map) => Yaml::Hash(
+            map.iter()
+                .map(|(key, value)| (Yaml::String(key.clone()), json_to_yaml(value)))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::importers::markdown::parse_file;
+    use crate::utils::importers::parse_date;
+
+    fn blog(id: i64, slug: Option<&str>) -> SiteExportBlog {
+        SiteExportBlog {
+            id,
+            title: "Hello: World".to_string(),
+            slug: slug.map(ToOwned::to_owned),
+            author: Some("Jane".to_string()),
+            content: "# Heading\n\nBody".to_string(),
+            summary: Some("Line one\nline two".to_string()),
+            thumbnail: None,
+            category: Some("Rust".to_string()),
+            tags: vec!["web".to_string(), "true".to_string()],
+            is_published: false,
+            created_at: parse_date("2021-03-04T05:06:07Z"),
+            updated_at: parse_date("2021-03-05T00:00:00Z"),
+            publish_at: None,
+            references: Some(serde_json::json!({
+                "ref-1": { "id": "ref-1", "title": "Source", "content": "Quote" }
+            })),
+        }
+    }
+
+    fn directory(id: i64, name: &str, parent_id: Option<i64>) -> Directory {
+        Directory {
+            id,
+            name: name.to_string(),
+            intro: None,
+            parent_id,
+            sort_order: 0,
+            created_at: None,
+        }
+    }
+
+    fn document(
+        id: i64,
+        name: &str,
+        filename: Option<&str>,
+        directory_id: Option<i64>,
+    ) -> Document {
+        Document {
+            id,
+            name: name.to_string(),
+            filename: filename.map(ToOwned::to_owned),
+            content: "Doc".to_string(),
+            directory_id,
+            sort_order: 0,
+            created_at: None,
+            updated_at: None,
+            references: None,
+        }
+    }
+
+    #[test]
+    fn exported_blogs_import_back_unchanged() {
+        let blog = blog(1, Some("hello-world"));
+        let markdown = blog_markdown(&blog);
+        assert!(markdown.starts_with("---\n"));
+
+        let post = parse_file("posts/hello-world.md", &markdown).unwrap();
+        assert_eq!(post.title, blog.title);
+        assert_eq!(post.slug.as_deref(), Some("hello-world"));
+        assert_eq!(post.content, blog.content);
+        assert_eq!(post.summary, blog.summary);
+        assert_eq!(post.category, blog.category);
+        assert_eq!(post.tags, blog.tags);
+        assert_eq!(post.created_at, blog.created_at);
+        assert_eq!(post.updated_at, blog.updated_at);
+        assert_eq!(post.references, blog.references);
+        assert!(!post.is_published);
+    }
+
+    #[test]
+    fn documents_are_not_imported_as_posts() {
+        let markdown = document_markdown(&document(1, "Install", None, None));
+        assert!(markdown.contains("type: doc"));
+        assert!(parse_file("docs/Install.md", &markdown).is_err());
+    }
+
+    #[test]
+    fn blog_paths_fall_back_to_ids_and_stay_unique() {
+        let blogs = vec![
+            blog(1, Some("a/b")),
+            blog(2, Some("a-b")),
+            blog(3, None),
+            blog(4, Some("..")),
+        ];
+        assert_eq!(
+            blog_paths(&blogs),
+            vec![
+                "posts/a-b.md".to_string(),
+                "posts/a-b-2.md".to_string(),
+                "posts/post-3.md".to_string(),
+                "posts/post-4.md".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn documents_follow_the_directory_tree() {
+        // Children may come before their parents
+        let directories = vec![
+            directory(3, "Linux", Some(2)),
+            directory(2, "Install", Some(1)),
+            directory(1, "指南", None),
+            directory(4, "Orphan", Some(99)),
+            directory(5, "install", Some(1)),
+        ];
+        let paths = directory_paths(&directories);
+        assert_eq!(paths[&1], "docs/指南");
+        assert_eq!(paths[&3], "docs/指南/Install/Linux");
+        assert_eq!(paths[&4], "docs/Orphan");
+        assert_eq!(paths[&5], "docs/指南/install-5");
+
+        let documents = vec![
+            document(10, "Setup", Some("setup.md"), Some(3)),
+            document(11, "Setup", None, Some(3)),
+            document(12, "Readme", None, None),
+        ];
+        assert_eq!(
+            document_paths(&documents, &paths),
+            vec![
+                "docs/指南/Install/Linux/setup.md".to_string(),
+                "docs/指南/Install/Linux/Setup-11.md".to_string(),
+                "docs/Readme.md".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn links_are_rewritten_to_assets() {
+        let key = "uploads/0b6f.png";
+        let variant = "uploads/0b6f/thumbnail.jpg";
+        assert_eq!(asset_path(key), "assets/0b6f.png");
+        assert_eq!(asset_path(variant), "assets/0b6f-thumbnail.jpg");
+
+        let links = vec![
+            (
+                "https://cdn.example.com/uploads/0b6f.png".to_string(),
+                "/assets/0b6f.png".to_string(),
+            ),
+            (
+                "https://cdn.example.com/uploads/0b6f.png?x=1".to_string(),
+                "/assets/other.png".to_string(),
+            ),
+        ];
+        let text = "![a](https://cdn.example.com/uploads/0b6f.png) ![b](https://cdn.example.com/uploads/0b6f.png?x=1)";
+        assert_eq!(
+            rewrite_links(text, &links),
+            "![a](/assets/0b6f.png) ![b](/assets/other.png)"
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Sitemap and robots.txt rendering utilities
+//!
+//! Follows the sitemaps.org 0.9 protocol.
+
+use chrono::{DateTime, Utc};
+
+use super::feed::xml_escape;
+
+/// A rendered `<url>` or `<sitemap>` entry
+#[derive(Debug, Clone)]
+pub struct SitemapEntry {
+    pub loc: String,
+    pub lastmod: Option<DateTime<Utc>>,
+}
+
+/// Render a sitemap index pointing at sub-sitemaps
+pub fn render_sitemap_index(entries: &[SitemapEntry]) -> String {
+    render_entries("sitemapindex", "sitemap", entries)
+}
+
+/// Render a `<urlset>` sitemap
+pub fn render_urlset(entries: &[SitemapEntry]) -> String {
+    render_entries("urlset", "url", entries)
+}
+
+fn render_entries(root: &str, element: &str, entries: &[SitemapEntry]) -> String {
+    let mut xml = String::new();
+    xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    xml.push('\n');
+    xml.push_str(&format!(
+        r#"<{} xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#,
+        root
+    ));
+    for entry in entries {
+        xml.push_str(&format!(
+            "<{}><loc>{}</loc>",
+            element,
+            xml_escape(&entry.loc)
+        ));
+        if let Some(lastmod) = entry.lastmod {
+            xml.push_str(&format!(
+                "<lastmod>{}</lastmod>",
+                lastmod.format("%Y-%m-%dT%H:%M:%SZ")
+            ));
+        }
+        xml.push_str(&format!("</{}>", element));
+    }
+    xml.push_str(&format!("</{}>", root));
+    xml
+}
+
+/// Render robots.txt
+///
+/// `disallow` is a newline-separated list of paths, `extra` is appended verbatim.
+pub fn render_robots(disallow: &str, extra: &str, sitemap_url: Option<&str>) -> String {
+    let mut lines = vec!["User-agent: *".to_string()];
+
+    let paths: Vec<&str> = disallow
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    if paths.is_empty() {
+        lines.push("Disallow:".to_string());
+    } else {
+        lines.extend(paths.iter().map(|path| format!("Disallow: {}", path)));
+    }
+
+    let extra = extra.trim();
+    if !extra.is_empty() {
+        lines.push(String::new());
+        lines.push(extra.to_string());
+    }
+
+    if let Some(url) = sitemap_url.filter(|url| !url.is_empty()) {
+        lines.push(String::new());
+        lines.push(format!("Sitemap: {}", url));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn urlset_escapes_loc_and_formats_lastmod() {
+        let xml = render_urlset(&[SitemapEntry {
+            loc: "https://example.com/blog/a&b".to_string(),
+            lastmod: Some(Utc.with_ymd_and_hms(2024, 5, 6, 7, 8, 9).unwrap()),
+        }]);
+
+        assert!(xml.contains(r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#));
+        assert!(xml.contains(
+            "<url><loc>https://example.com/blog/a&amp;b</loc><lastmod>2024-05-06T07:08:09Z</lastmod></url>"
+        ));
+    }
+
+    #[test]
+    fn sitemap_index_omits_missing_lastmod() {
+        let xml = render_sitemap_index(&[SitemapEntry {
+            loc: "https://example.com/sitemaps/tags/1.xml".to_string(),
+            lastmod: None,
+        }]);
+
+        assert!(
+            xml.contains("<sitemap><loc>https://example.com/sitemaps/tags/1.xml</loc></sitemap>")
+        );
+        assert!(xml.ends_with("</sitemapindex>"));
+    }
+
+    #[test]
+    fn robots_lists_disallow_paths_and_sitemap() {
+        let robots = render_robots(
+            "/admin\n\n /api ",
+            "",
+            Some("https://example.com/sitemap.xml"),
+        );
+
+        assert_eq!(
+            robots,
+            "User-agent: *\nDisallow: /admin\nDisallow: /api\n\nSitemap: https://example.com/sitemap.xml\n"
+        );
+    }
+
+    #[test]
+    fn robots_allows_everything_without_rules() {
+        assert_eq!(render_robots("", "", None), "User-agent: *\nDisallow:\n");
+    }
+}
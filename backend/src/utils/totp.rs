@@ -0,0 +1,205 @@
+//! Time-based one-time passwords (RFC 6238)
+//!
+//! HMAC-SHA1, 6 digits and 30 second steps, the parameters every authenticator
+//! app understands. Verification takes the current time as an argument so tests
+//! can use a fixed clock.
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+
+/// Seconds per TOTP step
+pub const STEP_SECONDS: i64 = 30;
+
+/// Digits per code
+const DIGITS: u32 = 6;
+
+/// Steps accepted on either side of the current one to absorb clock drift
+const ALLOWED_DRIFT_STEPS: i64 = 1;
+
+/// Secret size in bytes (160 bits, as recommended by RFC 4226)
+const SECRET_BYTES: usize = 20;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Generate a new random secret, base32 encoded
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; SECRET_BYTES];
+    OsRng.fill_bytes(&mut secret);
+    base32_encode(&secret)
+}
+
+/// `otpauth://` URI to render as a QR code in an authenticator app
+pub fn provisioning_uri(issuer: &str, account: &str, secret: &str) -> String {
+    let issuer = percent_encode(issuer);
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        issuer,
+        percent_encode(account),
+        secret,
+        issuer,
+        DIGITS,
+        STEP_SECONDS
+    )
+}
+
+/// Code for the step containing `unix_time`
+#[cfg(test)]
+pub fn code_at(secret: &str, unix_time: i64) -> Option<String> {
+    let key = base32_decode(secret)?;
+    Some(format_code(hotp(&key, unix_time.div_euclid(STEP_SECONDS))))
+}
+
+/// Check a code against the steps around `unix_time`
+///
+/// Returns the matched step so callers can refuse to accept it twice.
+pub fn verify(secret: &str, code: &str, unix_time: i64) -> Option<i64> {
+    let code = code.trim().replace(' ', "");
+    if code.len() != DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let key = base32_decode(secret)?;
+    let current = unix_time.div_euclid(STEP_SECONDS);
+    (current - ALLOWED_DRIFT_STEPS..=current + ALLOWED_DRIFT_STEPS)
+        .find(|&step| constant_time_eq(format_code(hotp(&key, step)).as_bytes(), code.as_bytes()))
+}
+
+fn hotp(key: &[u8], counter: i64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(&(counter as u64).to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    // Dynamic truncation (RFC 4226 section 5.3)
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+
+    binary % 10u32.pow(DIGITS)
+}
+
+fn format_code(value: u32) -> String {
+    format!("{:0width$}", value, width = DIGITS as usize)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// RFC 4648 base32 without padding
+pub fn base32_encode(data: &[u8]) -> String {
+    let mut output = String::with_capacity(data.len().div_ceil(5) * 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        output.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+
+    output
+}
+
+/// Decode base32, ignoring case, spaces and padding
+pub fn base32_decode(input: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for c in input.chars().filter(|c| *c != ' ' && *c != '=') {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a as char == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+
+    (!output.is_empty()).then_some(output)
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RFC 6238 appendix B test secret ("12345678901234567890")
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn matches_rfc_6238_vectors() {
+        // The RFC lists 8 digit codes; the last 6 digits are the 6 digit codes
+        assert_eq!(code_at(RFC_SECRET, 59).unwrap(), "287082");
+        assert_eq!(code_at(RFC_SECRET, 1111111109).unwrap(), "081804");
+        assert_eq!(code_at(RFC_SECRET, 1234567890).unwrap(), "005924");
+        assert_eq!(code_at(RFC_SECRET, 2000000000).unwrap(), "279037");
+    }
+
+    #[test]
+    fn verify_accepts_one_step_of_drift() {
+        let now = 1_700_000_000;
+        let step = now / STEP_SECONDS;
+        let code = code_at(RFC_SECRET, now).unwrap();
+
+        assert_eq!(verify(RFC_SECRET, &code, now), Some(step));
+        assert_eq!(verify(RFC_SECRET, &code, now + STEP_SECONDS), Some(step));
+        assert_eq!(verify(RFC_SECRET, &code, now - STEP_SECONDS), Some(step));
+        assert_eq!(verify(RFC_SECRET, &code, now + 2 * STEP_SECONDS), None);
+    }
+
+    #[test]
+    fn verify_rejects_malformed_codes() {
+        let now = 1_700_000_000;
+        assert_eq!(verify(RFC_SECRET, "12345", now), None);
+        assert_eq!(verify(RFC_SECRET, "abcdef", now), None);
+        assert_eq!(verify("not base32!", "123456", now), None);
+    }
+
+    #[test]
+    fn base32_round_trips() {
+        assert_eq!(base32_encode(b"12345678901234567890"), RFC_SECRET);
+        assert_eq!(
+            base32_decode(&RFC_SECRET.to_lowercase()).unwrap(),
+            b"12345678901234567890"
+        );
+        assert_eq!(base32_encode(b"f"), "MY");
+        assert_eq!(base32_decode("MY======").unwrap(), b"f");
+
+        let secret = generate_secret();
+        assert_eq!(secret.len(), 32);
+        assert_eq!(base32_decode(&secret).unwrap().len(), SECRET_BYTES);
+    }
+
+    #[test]
+    fn provisioning_uri_escapes_labels() {
+        assert_eq!(
+            provisioning_uri("My Blog", "admin", "ABC"),
+            "otpauth://totp/My%20Blog:admin?secret=ABC&issuer=My%20Blog&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+}
@@ -40,6 +40,7 @@ import { toast } from "sonner";
 
 export default function DataManagementPage() {
     const [isExporting, setIsExporting] = useState(false);
+    const [isExportingNdjson, setIsExportingNdjson] = useState(false);
     const [isImporting, setIsImporting] = useState(false);
     const [isImportingSql, setIsImportingSql] = useState(false);
     const [importResult, setImportResult] = useState<ImportResult | null>(null);
@@ -71,6 +72,25 @@ export default function DataManagementPage() {
         }
     };
 
+    const handleNdjsonExport = async () => {
+        setIsExportingNdjson(true);
+        try {
+            const blob = await dataApi.exportNdjson();
+            const url = URL.createObjectURL(blob);
+            const a = document.createElement("a");
+            a.href = url;
+            a.download = `blog-backup-${new Date().toISOString().split("T")[0]}.ndjson`;
+            document.body.appendChild(a);
+            a.click();
+            document.body.removeChild(a);
+            URL.revokeObjectURL(url);
+            toast.success("备份导出成功");
+        } catch (err) {
+            toast.error(err instanceof Error ? err.message : "导出失败");
+        } finally {
+            setIsExportingNdjson(false);
+        }
+    };
 
     const handleJsonImport = async (event: React.ChangeEvent<HTMLInputElement>) => {
         const file = event.target.files?.[0];
@@ -78,6 +98,12 @@ export default function DataManagementPage() {
         setIsImporting(true);
         setImportResult(null);
         try {
+            if (file.name.endsWith(".ndjson")) {
+                const result = await dataApi.importNdjson(file);
+                setImportResult(result);
+                toast.success("备份导入完成");
+                return;
+            }
             const text = await file.text();
             const data = JSON.parse(text) as Partial<ExportData>;
             if (data.blogs) {
@@ -136,21 +162,23 @@ export default function DataManagementPage() {
         }
     };
 
-    const renderStats = (stats: ImportResult[keyof ImportResult], label: string) => (
-        <TableRow key={label}>
-            <TableCell className="font-medium">{label}</TableCell>
-            <TableCell>{stats.total}</TableCell>
-            <TableCell><Badge variant="default" className="bg-green-500">{stats.success}</Badge></TableCell>
-            <TableCell>
-                {stats.failed > 0 ? <Badge variant="destructive">{stats.failed}</Badge> : <Badge variant="secondary">0</Badge>}
-            </TableCell>
-            <TableCell>
-                {stats.errors.length > 0 ? (
-                    <span className="text-xs text-destructive">{stats.errors.slice(0, 2).join("; ")}{stats.errors.length > 2 && `... +${stats.errors.length - 2}`}</span>
-                ) : <span className="text-muted-foreground">-</span>}
-            </TableCell>
-        </TableRow>
-    );
+    const tableLabels: Record<string, string> = {
+        categories: "分类",
+        tags: "标签",
+        files: "文件",
+        file_variants: "文件变体",
+        blogs: "博客",
+        blog_tags: "博客标签",
+        blog_revisions: "博客修订",
+        friend_links: "友链",
+        projects: "项目",
+        directories: "目录",
+        documents: "文档",
+        document_revisions: "文档修订",
+        comments: "评论",
+        texts: "文本",
+        site_config: "站点配置",
+    };
 
 
     return (
@@ -167,12 +195,17 @@ export default function DataManagementPage() {
                         <Download className="h-5 w-5" />
                         数据导出
                     </CardTitle>
-                    <CardDescription>将所有数据导出为 JSON 文件</CardDescription>
+                    <CardDescription>导出为 JSON 文件，或导出包含全部字段的 NDJSON 完整备份</CardDescription>
                 </CardHeader>
                 <CardContent>
-                    <Button onClick={handleExport} disabled={isExporting}>
-                        {isExporting ? <><Loader2 className="mr-2 h-4 w-4 animate-spin" />导出中...</> : <><FileJson className="mr-2 h-4 w-4" />导出数据</>}
-                    </Button>
+                    <div className="flex gap-2">
+                        <Button onClick={handleExport} disabled={isExporting}>
+                            {isExporting ? <><Loader2 className="mr-2 h-4 w-4 animate-spin" />导出中...</> : <><FileJson className="mr-2 h-4 w-4" />导出数据</>}
+                        </Button>
+                        <Button variant="outline" onClick={handleNdjsonExport} disabled={isExportingNdjson}>
+                            {isExportingNdjson ? <><Loader2 className="mr-2 h-4 w-4 animate-spin" />导出中...</> : <><Download className="mr-2 h-4 w-4" />完整备份 (NDJSON)</>}
+                        </Button>
+                    </div>
                     {exportData && (
                         <p className="mt-2 text-sm text-muted-foreground">
                             导出: {exportData.blogs.length} 博客, {exportData.categories.length} 分类, {exportData.tags.length} 标签
@@ -230,11 +263,11 @@ export default function DataManagementPage() {
                         </TabsContent>
 
                         <TabsContent value="json" className="space-y-4">
-                            <input ref={fileInputRef} type="file" accept=".json" onChange={handleJsonImport} className="hidden" />
+                            <input ref={fileInputRef} type="file" accept=".json,.ndjson" onChange={handleJsonImport} className="hidden" />
                             <Button variant="outline" onClick={() => fileInputRef.current?.click()} disabled={isImporting}>
-                                {isImporting ? <><Loader2 className="mr-2 h-4 w-4 animate-spin" />导入中...</> : <><FileJson className="mr-2 h-4 w-4" />选择 JSON 文件</>}
+                                {isImporting ? <><Loader2 className="mr-2 h-4 w-4 animate-spin" />导入中...</> : <><FileJson className="mr-2 h-4 w-4" />选择 JSON / NDJSON 文件</>}
                             </Button>
-                            <p className="text-xs text-muted-foreground">导入的博客默认设置为已发布状态</p>
+                            <p className="text-xs text-muted-foreground">导入在单个事务中执行，任何一行失败都会整体回滚；已存在的数据会被更新</p>
                         </TabsContent>
                     </Tabs>
                 </CardContent>
@@ -271,30 +304,37 @@ export default function DataManagementPage() {
                 <Card>
                     <CardHeader>
                         <CardTitle className="flex items-center gap-2">
-                            {Object.values(importResult).every(s => s.failed === 0) ? <CheckCircle2 className="h-5 w-5 text-green-500" /> : <XCircle className="h-5 w-5 text-yellow-500" />}
-                            JSON 导入结果
+                            {importResult.warnings.length === 0 ? <CheckCircle2 className="h-5 w-5 text-green-500" /> : <AlertTriangle className="h-5 w-5 text-yellow-500" />}
+                            导入结果
+                            {importResult.schema_version && <Badge variant="secondary">v{importResult.schema_version}</Badge>}
                         </CardTitle>
                     </CardHeader>
-                    <CardContent>
+                    <CardContent className="space-y-4">
                         <Table>
                             <TableHeader>
                                 <TableRow>
                                     <TableHead>数据类型</TableHead>
-                                    <TableHead>总数</TableHead>
-                                    <TableHead>成功</TableHead>
-                                    <TableHead>失败</TableHead>
-                                    <TableHead>错误信息</TableHead>
+                                    <TableHead>新增</TableHead>
+                                    <TableHead>更新</TableHead>
+                                    <TableHead>跳过</TableHead>
                                 </TableRow>
                             </TableHeader>
                             <TableBody>
-                                {renderStats(importResult.categories, "分类")}
-                                {renderStats(importResult.tags, "标签")}
-                                {renderStats(importResult.blogs, "博客")}
-                                {renderStats(importResult.blog_tags, "博客标签")}
-                                {renderStats(importResult.friend_links, "友链")}
-                                {renderStats(importResult.projects, "项目")}
+                                {importResult.tables.map((changes) => (
+                                    <TableRow key={changes.table}>
+                                        <TableCell className="font-medium">{tableLabels[changes.table] ?? changes.table}</TableCell>
+                                        <TableCell><Badge variant="default" className="bg-green-500">{changes.inserted}</Badge></TableCell>
+                                        <TableCell><Badge variant="secondary">{changes.updated}</Badge></TableCell>
+                                        <TableCell><Badge variant="outline">{changes.skipped}</Badge></TableCell>
+                                    </TableRow>
+                                ))}
                             </TableBody>
                         </Table>
+                        {importResult.warnings.length > 0 && (
+                            <div className="bg-yellow-50 dark:bg-yellow-950 p-3 rounded text-xs text-yellow-800 dark:text-yellow-200">
+                                {importResult.warnings.map((warning, i) => <div key={i}>{warning}</div>)}
+                            </div>
+                        )}
                     </CardContent>
                 </Card>
             )}
@@ -15,10 +15,12 @@ export default function LoginPage() {
     const [username, setUsername] = useState("");
     const [password, setPassword] = useState("");
     const [confirmPassword, setConfirmPassword] = useState("");
+    const [challengeToken, setChallengeToken] = useState<string | null>(null);
+    const [code, setCode] = useState("");
     const [isLoading, setIsLoading] = useState(false);
     const [isChecking, setIsChecking] = useState(true);
     const [needSetup, setNeedSetup] = useState(false);
-    const { login } = useAuth();
+    const { login, loginTwoFactor } = useAuth();
     const router = useRouter();
 
     useEffect(() => {
@@ -45,7 +47,11 @@ export default function LoginPage() {
         }
         setIsLoading(true);
         try {
-            await login({ username, password });
+            const response = await login({ username, password });
+            if ("two_factor_required" in response) {
+                setChallengeToken(response.challenge_token);
+                return;
+            }
             toast.success("登录成功");
             router.push("/admin");
         } catch (error) {
@@ -59,6 +65,33 @@ export default function LoginPage() {
         }
     };
 
+    const handleTwoFactor = async (e: React.FormEvent) => {
+        e.preventDefault();
+        if (!challengeToken) return;
+        if (!code.trim()) {
+            toast.error("请输入验证码");
+            return;
+        }
+        setIsLoading(true);
+        try {
+            await loginTwoFactor({ challenge_token: challengeToken, code });
+            toast.success("登录成功");
+            router.push("/admin");
+        } catch (error) {
+            if (error instanceof ApiError) {
+                toast.error(error.message || "验证失败");
+                // The challenge expired; start over from the password step
+                if (error.message.includes("challenge")) {
+                    setChallengeToken(null);
+                    setCode("");
+                }
+            } else {
+                toast.error("验证失败，请稍后重试");
+            }
+        } finally {
+            setIsLoading(false);
+        }
+    };
 
     const handleSetup = async (e: React.FormEvent) => {
         e.preventDefault();
@@ -114,7 +147,9 @@ export default function LoginPage() {
                     <CardDescription className="text-center">
                         {needSetup
                             ? "首次使用，请创建管理员账号"
-                            : "请输入您的账号密码登录"}
+                            : challengeToken
+                              ? "请输入验证器应用中的验证码或恢复码"
+                              : "请输入您的账号密码登录"}
                     </CardDescription>
                 </CardHeader>
                 <CardContent>
@@ -164,6 +199,30 @@ export default function LoginPage() {
                                 )}
                             </Button>
                         </form>
+                    ) : challengeToken ? (
+                        <form onSubmit={handleTwoFactor} className="space-y-4">
+                            <div className="space-y-2">
+                                <Label htmlFor="code">验证码</Label>
+                                <Input
+                                    id="code"
+                                    type="text"
+                                    inputMode="text"
+                                    placeholder="6 位验证码或恢复码"
+                                    value={code}
+                                    onChange={(e) => setCode(e.target.value)}
+                                    disabled={isLoading}
+                                    autoComplete="one-time-code"
+                                    autoFocus
+                                />
+                            </div>
+                            <Button type="submit" className="w-full" disabled={isLoading}>
+                                {isLoading ? (
+                                    <><Loader2 className="mr-2 h-4 w-4 animate-spin" />验证中...</>
+                                ) : (
+                                    <><LogIn className="mr-2 h-4 w-4" />验证并登录</>
+                                )}
+                            </Button>
+                        </form>
                     ) : (
                         <form onSubmit={handleLogin} className="space-y-4">
                             <div className="space-y-2">
@@ -5,13 +5,14 @@ import { useRouter, usePathname } from "next/navigation";
 import {
     isAuthenticated,
     login as authLogin,
+    loginTwoFactor as authLoginTwoFactor,
     logout as authLogout,
     getCurrentUser,
     ensureValidToken,
     refreshToken,
     isTokenExpired,
 } from "@/lib/auth";
-import type { LoginRequest, LoginResponse } from "@/types";
+import type { LoginOutcome, LoginRequest, LoginResponse, TwoFactorLoginRequest } from "@/types";
 
 interface AuthUser {
     id: number;
@@ -22,7 +23,8 @@ interface AuthContextType {
     user: AuthUser | null;
     isLoading: boolean;
     isLoggedIn: boolean;
-    login: (credentials: LoginRequest) => Promise<LoginResponse>;
+    login: (credentials: LoginRequest) => Promise<LoginOutcome>;
+    loginTwoFactor: (request: TwoFactorLoginRequest) => Promise<LoginResponse>;
     logout: () => void;
 }
 
@@ -107,10 +109,17 @@ export function AuthProvider({ children }: AuthProviderProps) {
         }
     }, [isLoading, user, pathname, router]);
 
-    const login = useCallback(async (credentials: LoginRequest): Promise<LoginResponse> => {
+    const login = useCallback(async (credentials: LoginRequest): Promise<LoginOutcome> => {
         const response = await authLogin(credentials);
-        const currentUser = getCurrentUser();
-        setUser(currentUser);
+        if (!("two_factor_required" in response)) {
+            setUser(getCurrentUser());
+        }
+        return response;
+    }, []);
+
+    const loginTwoFactor = useCallback(async (request: TwoFactorLoginRequest): Promise<LoginResponse> => {
+        const response = await authLoginTwoFactor(request);
+        setUser(getCurrentUser());
         return response;
     }, []);
 
@@ -125,6 +134,7 @@ export function AuthProvider({ children }: AuthProviderProps) {
         isLoading,
         isLoggedIn: !!user,
         login,
+        loginTwoFactor,
         logout,
     };
 
@@ -29,6 +29,8 @@ import type {
   VerifyTextRequest,
   LoginRequest,
   LoginResponse,
+  LoginOutcome,
+  TwoFactorLoginRequest,
   RefreshTokenRequest,
   ArchiveResponse,
   SearchResult,
@@ -470,7 +472,13 @@ export interface SetupAdminRequest {
 
 export const authApi = {
   login: (data: LoginRequest) =>
-    request<LoginResponse>("/auth/login", {
+    request<LoginOutcome>("/auth/login", {
+      method: "POST",
+      body: JSON.stringify(data),
+    }),
+
+  loginTwoFactor: (data: TwoFactorLoginRequest) =>
+    request<LoginResponse>("/auth/login/2fa", {
       method: "POST",
       body: JSON.stringify(data),
     }),
@@ -481,6 +489,12 @@ export const authApi = {
       body: JSON.stringify(data),
     }),
 
+  logout: (data: RefreshTokenRequest) =>
+    request<void>("/auth/logout", {
+      method: "POST",
+      body: JSON.stringify(data),
+    }),
+
   checkAdmin: () => request<{ exists: boolean }>("/auth/check-admin"),
 
   setup: (data: SetupAdminRequest) =>
@@ -550,20 +564,22 @@ export interface ExportData {
   }>;
 }
 
-export interface ImportStats {
-  total: number;
-  success: number;
-  failed: number;
-  errors: string[];
+export type ConflictPolicy = "upsert" | "skip" | "replace";
+
+export interface TableChanges {
+  table: string;
+  inserted: number;
+  updated: number;
+  skipped: number;
 }
 
 export interface ImportResult {
-  categories: ImportStats;
-  tags: ImportStats;
-  blogs: ImportStats;
-  blog_tags: ImportStats;
-  friend_links: ImportStats;
-  projects: ImportStats;
+  // Absent for legacy JSON backups
+  schema_version?: number;
+  policy: ConflictPolicy;
+  since?: string;
+  tables: TableChanges[];
+  warnings: string[];
 }
 
 export interface SqlImportResult {
@@ -572,20 +588,121 @@ export interface SqlImportResult {
   errors: string[];
 }
 
+export type PostImportSource = "wordpress" | "markdown" | "ghost";
+
+export interface ImportedPostResult {
+  title: string;
+  slug?: string;
+  category?: string;
+  tags: string[];
+  is_published: boolean;
+  created_at?: string;
+  action: "create" | "skip";
+  conflict?: string;
+  blog_id?: number;
+}
+
+export interface PostImportReport {
+  source: PostImportSource;
+  dry_run: boolean;
+  posts: ImportedPostResult[];
+  new_categories: string[];
+  new_tags: string[];
+  created: number;
+  skipped: number;
+  warnings: string[];
+}
+
 export const dataApi = {
   export: () => request<ExportData>("/admin/data/export"),
 
-  import: (data: Partial<ExportData>) =>
-    request<ImportResult>("/admin/data/import", {
+  // Zip of Markdown files with front matter, for Hugo/Hexo or re-import
+  exportSite: async (assets = false): Promise<Blob> => {
+    const token = await getAuthToken();
+    const response = await fetch(
+      `${API_BASE_URL}/admin/data/export/site?assets=${assets}`,
+      { headers: token ? { Authorization: `Bearer ${token}` } : {} }
+    );
+
+    if (!response.ok) {
+      const data: ApiResponse<null> = await response.json();
+      throw new ApiError(data.code, data.message);
+    }
+    return response.blob();
+  },
+
+  // Versioned NDJSON backup; `since` exports only rows changed from then on
+  exportNdjson: async (since?: string): Promise<Blob> => {
+    const token = await getAuthToken();
+    const query = since ? `?since=${encodeURIComponent(since)}` : "";
+    const response = await fetch(`${API_BASE_URL}/admin/data/export/ndjson${query}`, {
+      headers: token ? { Authorization: `Bearer ${token}` } : {},
+    });
+
+    if (!response.ok) {
+      const data: ApiResponse<null> = await response.json();
+      throw new ApiError(data.code, data.message);
+    }
+    return response.blob();
+  },
+
+  import: (data: Partial<ExportData>, policy: ConflictPolicy = "upsert") =>
+    request<ImportResult>(`/admin/data/import?policy=${policy}`, {
       method: "POST",
       body: JSON.stringify(data),
     }),
 
+  importNdjson: async (
+    file: Blob,
+    policy: ConflictPolicy = "upsert"
+  ): Promise<ImportResult> => {
+    const token = await getAuthToken();
+    const response = await fetch(`${API_BASE_URL}/admin/data/import?policy=${policy}`, {
+      method: "POST",
+      headers: {
+        "Content-Type": "application/x-ndjson",
+        ...(token ? { Authorization: `Bearer ${token}` } : {}),
+      },
+      body: file,
+    });
+
+    const data: ApiResponse<ImportResult> = await response.json();
+    if (!response.ok || data.code !== 0) {
+      throw new ApiError(data.code, data.message);
+    }
+    return data.data;
+  },
+
   importSql: (sql: string) =>
     request<SqlImportResult>("/admin/data/import-sql", {
       method: "POST",
       body: JSON.stringify({ sql }),
     }),
+
+  importPosts: async (
+    source: PostImportSource,
+    file: File,
+    dryRun = true
+  ): Promise<PostImportReport> => {
+    const token = await getAuthToken();
+    const formData = new FormData();
+    formData.append("file", file);
+
+    const response = await fetch(
+      `${API_BASE_URL}/admin/data/import/${source}?dry_run=${dryRun}`,
+      {
+        method: "POST",
+        headers: token ? { Authorization: `Bearer ${token}` } : {},
+        body: formData,
+      }
+    );
+
+    const data: ApiResponse<PostImportReport> = await response.json();
+    if (!response.ok || data.code !== 0) {
+      throw new ApiError(data.code, data.message);
+    }
+    return data.data;
+  },
 };
 
 // Site Config API
@@ -595,6 +712,8 @@ export interface SiteConfig {
   config_value: string | null;
   config_type: string | null;
   description: string | null;
+  // Sensitive values come back masked and are only replaced when a new value is sent
+  is_sensitive: boolean;
   created_at: string;
   updated_at: string;
 }
@@ -1,7 +1,12 @@
 "use client";
 
 import { authApi } from "./api";
-import type { LoginRequest, LoginResponse } from "@/types";
+import type {
+    LoginOutcome,
+    LoginRequest,
+    LoginResponse,
+    TwoFactorLoginRequest,
+} from "@/types";
 
 const ACCESS_TOKEN_KEY = "access_token";
 const REFRESH_TOKEN_KEY = "refresh_token";
@@ -55,15 +60,29 @@ export function isAuthenticated(): boolean {
     return !isTokenExpired(0); // Check if actually expired, not just near expiry
 }
 
-// Login function
-export async function login(credentials: LoginRequest): Promise<LoginResponse> {
+// Login function; returns a challenge instead of tokens when 2FA is enabled
+export async function login(credentials: LoginRequest): Promise<LoginOutcome> {
     const response = await authApi.login(credentials);
+    if (!("two_factor_required" in response)) {
+        setTokens(response);
+    }
+    return response;
+}
+
+// Second login step with an authenticator or recovery code
+export async function loginTwoFactor(request: TwoFactorLoginRequest): Promise<LoginResponse> {
+    const response = await authApi.loginTwoFactor(request);
     setTokens(response);
     return response;
 }
 
 // Logout function
 export function logout(): void {
+    const refresh = getRefreshToken();
+    if (refresh) {
+        // End the server-side session; local tokens are cleared regardless
+        authApi.logout({ refresh_token: refresh }).catch(() => {});
+    }
     clearTokens();
 }
 
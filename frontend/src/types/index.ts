@@ -210,9 +210,22 @@ export interface FileInfo {
     height?: number;
     bucket_name?: string;
     object_key?: string;
+    storage_backend?: string;
+    content_hash?: string;
+    ref_count?: number;
+    variants?: FileVariant[];
     created_at: string;
 }
 
+export interface FileVariant {
+    kind: 'thumbnail' | 'responsive';
+    format: 'jpeg' | 'png' | 'webp';
+    width: number;
+    height: number;
+    file_size: number;
+    url: string;
+}
+
 // Friend Link Types
 export interface FriendLink {
     id: number;
@@ -316,6 +329,20 @@ export interface LoginResponse {
     user: UserResponse;
 }
 
+export interface TwoFactorChallenge {
+    two_factor_required: true;
+    challenge_token: string;
+    expires_in: number;
+}
+
+// Users with two-factor authentication get a challenge instead of tokens
+export type LoginOutcome = LoginResponse | TwoFactorChallenge;
+
+export interface TwoFactorLoginRequest {
+    challenge_token: string;
+    code: string;
+}
+
 export interface RefreshTokenRequest {
     refresh_token: string;
 }